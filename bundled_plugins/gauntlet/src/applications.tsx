@@ -2,7 +2,7 @@ import { GeneratedCommand, GeneratorProps } from "@project-gauntlet/api/helpers"
 import { walk, WalkOptions } from "@std/fs/walk";
 import { debounce } from "@std/async/debounce";
 import { current_os, wayland } from "gauntlet:bridge/internal-all";
-import { linux_app_from_path, linux_application_dirs, linux_open_application, } from "gauntlet:bridge/internal-linux";
+import { linux_app_from_path, linux_application_dirs, linux_open_application_action } from "gauntlet:bridge/internal-linux";
 import {
     macos_app_from_arbitrary_path,
     macos_app_from_path,
@@ -15,7 +15,14 @@ import {
     macos_settings_pre_13,
     macos_system_applications
 } from "gauntlet:bridge/internal-macos";
-import { applicationAccessories, applicationActions } from "./window/shared";
+import {
+    applicationAccessories,
+    applicationActions,
+    applicationSecondaryActions,
+    linuxDesktopFilePaths,
+    linuxTerminal,
+    openLinuxApplication
+} from "./window/shared";
 import { applicationEventLoopX11, focusX11Window } from "./window/x11";
 import { applicationEventLoopWayland, focusWaylandWindow } from "./window/wayland";
 import { windows_app_from_path, windows_application_dirs, windows_open_application } from "gauntlet:bridge/internal-windows";
@@ -28,17 +35,31 @@ export default async function Applications({ add, remove, get, getAll }: Generat
                 linux_application_dirs(),
                 path => linux_app_from_path(path),
                 (id, data) => {
+                    linuxDesktopFilePaths[id] = data.desktop_file_path;
+
+                    applicationSecondaryActions[id] = data.actions.map(action => ({
+                        label: action.name,
+                        run: () => {
+                            linux_open_application_action(data.desktop_file_path, action.id, linuxTerminal())
+                        },
+                    }));
+
+                    const keywords = [
+                        ...data.keywords,
+                        ...(data.generic_name ? [data.generic_name] : []),
+                        ...(data.comment ? [data.comment] : []),
+                    ];
+
                     if (wayland()) {
                         return {
                             name: data.name,
                             actions: applicationActions(
                                 id,
-                                () => {
-                                    linux_open_application(id)
-                                },
+                                openLinuxApplication(id),
                                 focusWaylandWindow,
                             ),
                             accessories: applicationAccessories(id),
+                            keywords,
                             icon: data.icon, // TODO lazy icons
                             "__linux__": {
                                 startupWmClass: data.startup_wm_class,
@@ -50,12 +71,11 @@ export default async function Applications({ add, remove, get, getAll }: Generat
                             name: data.name,
                             actions: applicationActions(
                                 id,
-                                () => {
-                                    linux_open_application(id)
-                                },
+                                openLinuxApplication(id),
                                 focusX11Window,
                             ),
                             accessories: applicationAccessories(id),
+                            keywords,
                             icon: data.icon, // TODO lazy icons
                             "__linux__": {
                                 startupWmClass: data.startup_wm_class,
@@ -0,0 +1,106 @@
+import { Action, ActionPanel, Detail, List } from "@project-gauntlet/api/components";
+import { ReactElement, useState } from "react";
+import { Clipboard, showHud } from "@project-gauntlet/api/helpers";
+import { useNavigation } from "@project-gauntlet/api/hooks";
+import { numbat_clear_history, numbat_history, numbat_remove_history_entry } from "gauntlet:bridge/internal-all";
+
+export default function CalculatorHistory(): ReactElement {
+    const [history, setHistory] = useState(() => numbat_history());
+    const { pushView, popView } = useNavigation();
+
+    return (
+        <List
+            actions={
+                <ActionPanel>
+                    <Action
+                        label={"Clear history"}
+                        onAction={() => {
+                            numbat_clear_history()
+                            setHistory([])
+                        }}
+                    />
+                </ActionPanel>
+            }
+        >
+            {
+                history.length == 0
+                    ? <List.EmptyView title={"No calculations yet"} description={"Results of calculations are saved here when they are copied"}/>
+                    : history.map(entry => (
+                        <List.Item
+                            key={entry.expression}
+                            title={entry.expression}
+                            subtitle={entry.result}
+                            onClick={() => {
+                                pushView(
+                                    <HistoryEntry
+                                        entry={entry}
+                                        onRemove={() => {
+                                            numbat_remove_history_entry(entry.expression)
+                                            setHistory(numbat_history())
+                                            popView()
+                                        }}
+                                    />
+                                )
+                            }}
+                        />
+                    ))
+            }
+        </List>
+    )
+}
+
+function HistoryEntry({ entry, onRemove }: { entry: NumbatHistoryEntry, onRemove: () => void }): ReactElement {
+    const copy = async (value: string, hud: string) => {
+        await Clipboard.writeText(value)
+        showHud(hud)
+    };
+
+    const number = entry.number;
+    const scientific = entry.scientific;
+
+    return (
+        <Detail
+            actions={
+                <ActionPanel>
+                    <Action
+                        label={"Copy result"}
+                        onAction={() => copy(entry.result, "Result copied")}
+                    />
+                    {
+                        number ? (
+                            <Action
+                                label={"Copy number"}
+                                onAction={() => copy(number, "Number copied")}
+                            />
+                        ) : undefined
+                    }
+                    {
+                        scientific ? (
+                            <Action
+                                label={"Copy in scientific notation"}
+                                onAction={() => copy(scientific, "Result copied in scientific notation")}
+                            />
+                        ) : undefined
+                    }
+                    <Action
+                        label={"Copy expression"}
+                        onAction={() => copy(entry.expression, "Expression copied")}
+                    />
+                    <Action
+                        label={"Remove from history"}
+                        onAction={onRemove}
+                    />
+                </ActionPanel>
+            }
+        >
+            <Detail.Content>
+                <Detail.Content.CodeBlock>
+                    {entry.expression}
+                </Detail.Content.CodeBlock>
+                <Detail.Content.H3>
+                    {entry.result}
+                </Detail.Content.H3>
+            </Detail.Content>
+        </Detail>
+    )
+}
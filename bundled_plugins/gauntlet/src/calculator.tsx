@@ -1,7 +1,7 @@
 import { Action, ActionPanel, Content, Icons, Inline } from "@project-gauntlet/api/components";
 import { ReactNode } from "react";
 import { Clipboard, showHud } from "@project-gauntlet/api/helpers";
-import { run_numbat } from "gauntlet:bridge/internal-all";
+import { numbat_add_history_entry, numbat_save_variables, run_numbat } from "gauntlet:bridge/internal-all";
 
 export default function Calculator(props: { text: string }): ReactNode | undefined {
     const text = props.text;
@@ -15,42 +15,121 @@ export default function Calculator(props: { text: string }): ReactNode | undefin
     try {
          result = run_numbat(text);
     } catch (e) {
-        // this view is executed on every key press in main search bar
-        // when numbat run fails it means expression is not valid so we return here and do not show inline view
+        console.error("Unable to run calculator", e)
         return undefined
     }
 
-    const { left, right } = result;
+    switch (result.type) {
+        case "NotCalculation": {
+            // this view is executed on every key press in main search bar
+            // so text which is not a calculation at all doesn't show inline view
+            return undefined
+        }
+        case "Error": {
+            return (
+                <Inline>
+                    <Inline.Left>
+                        <Content.H3>
+                            {result.left}
+                        </Content.H3>
+                    </Inline.Left>
+                    <Inline.Separator icon={Icons.ArrowRight}/>
+                    <Inline.Right>
+                        <Content.Paragraph>
+                            {result.message}
+                        </Content.Paragraph>
+                        {
+                            result.spans.map(span => (
+                                <Content.CodeBlock key={`${span.start}-${span.end}`}>
+                                    {`${span.start + 1}:${span.end} "${span.text}"${span.message ? ` - ${span.message}` : ""}`}
+                                </Content.CodeBlock>
+                            ))
+                        }
+                    </Inline.Right>
+                </Inline>
+            )
+        }
+        case "Value": {
+            const { left, right, number, scientific } = result;
 
-    if (left == right) {
-        return undefined
+            if (left == right) {
+                return undefined
+            }
+
+            const entry: NumbatHistoryEntry = {
+                expression: left,
+                result: right,
+                number: number ?? undefined,
+                scientific: scientific ?? undefined,
+            };
+
+            const copy = async (value: string, hud: string) => {
+                addToHistory(entry)
+                await Clipboard.writeText(value)
+                showHud(hud)
+            };
+
+            return (
+                <Inline
+                    actions={
+                        <ActionPanel>
+                            <Action
+                                label={"Copy result"}
+                                onAction={() => copy(right, "Result copied")}
+                            />
+                            {
+                                number ? (
+                                    <Action
+                                        label={"Copy number"}
+                                        onAction={() => copy(number, "Number copied")}
+                                    />
+                                ) : undefined
+                            }
+                            {
+                                scientific ? (
+                                    <Action
+                                        label={"Copy in scientific notation"}
+                                        onAction={() => copy(scientific, "Result copied in scientific notation")}
+                                    />
+                                ) : undefined
+                            }
+                            <Action
+                                label={"Save as ans and keep variables"}
+                                onAction={() => {
+                                    try {
+                                        numbat_save_variables(text)
+                                        addToHistory(entry)
+                                        showHud("Saved, use ans or defined variables in following calculations")
+                                    } catch (e) {
+                                        console.error("Unable to save calculator variables", e)
+                                        showHud("Unable to save calculation")
+                                    }
+                                }}
+                            />
+                        </ActionPanel>
+                    }
+                >
+                    <Inline.Left>
+                        <Content.H3>
+                            {left}
+                        </Content.H3>
+                    </Inline.Left>
+                    <Inline.Separator icon={Icons.ArrowRight}/>
+                    <Inline.Right>
+                        <Content.H3>
+                            {right}
+                        </Content.H3>
+                    </Inline.Right>
+                </Inline>
+            )
+        }
     }
+}
 
-    return (
-        <Inline
-            actions={
-                <ActionPanel>
-                    <Action
-                        label={"Copy result"}
-                        onAction={async () => {
-                            await Clipboard.writeText(right)
-                            showHud("Result copied")
-                        }}
-                    />
-                </ActionPanel>
-            }
-        >
-            <Inline.Left>
-                <Content.H3>
-                    {left}
-                </Content.H3>
-            </Inline.Left>
-            <Inline.Separator icon={Icons.ArrowRight}/>
-            <Inline.Right>
-                <Content.H3>
-                    {right}
-                </Content.H3>
-            </Inline.Right>
-        </Inline>
-    )
+function addToHistory(entry: NumbatHistoryEntry) {
+    try {
+        numbat_add_history_entry(entry)
+    } catch (e) {
+        console.error("Unable to save calculator history", e)
+    }
 }
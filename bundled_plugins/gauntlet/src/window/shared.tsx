@@ -1,4 +1,4 @@
-import { GeneratedCommand, GeneratedCommandAccessory, GeneratedCommandAction } from "@project-gauntlet/api/helpers";
+import { GeneratedCommand, GeneratedCommandAccessory, GeneratedCommandAction, pluginPreferences } from "@project-gauntlet/api/helpers";
 import { List } from "@project-gauntlet/api/components";
 import { linux_open_application } from "gauntlet:bridge/internal-linux";
 
@@ -10,6 +10,12 @@ export type OpenWindowData = {
 
 export const openWindows: Record<string, OpenWindowData> = {};
 
+// paths to .desktop files of applications on linux
+export const linuxDesktopFilePaths: Record<string, string> = {};
+
+// secondary actions of application, e.g. "New Private Window" from .desktop file on linux
+export const applicationSecondaryActions: Record<string, GeneratedCommandAction[]> = {};
+
 export function applicationActions(
     id: string,
     openApplication: () => void,
@@ -18,6 +24,8 @@ export function applicationActions(
     const appWindows = Object.entries(openWindows)
         .filter(([_, windowData]) => windowData.appId == id)
 
+    const secondaryActions = applicationSecondaryActions[id] ?? [];
+
     // TODO ability to close window
 
     if (appWindows.length == 0) {
@@ -27,7 +35,8 @@ export function applicationActions(
                 run: () => {
                     openApplication()
                 },
-            }
+            },
+            ...secondaryActions
         ]
     } else if (appWindows.length == 1) {
         return [
@@ -38,7 +47,8 @@ export function applicationActions(
                     let [windowId, _] = appWindow!!;
                     focusWindow(windowId)
                 },
-            }
+            },
+            ...secondaryActions
         ]
     } else if (appWindows.length > 1) {
         return [
@@ -62,7 +72,8 @@ export function applicationActions(
                         </List>
                     )
                 }
-            }
+            },
+            ...secondaryActions
         ]
     } else {
         return []
@@ -133,6 +144,17 @@ export function deleteOpenWindow(
 
 export function openLinuxApplication(appId: string) {
     return () => {
-        linux_open_application(appId)
+        const desktopFilePath = linuxDesktopFilePaths[appId];
+        if (!desktopFilePath) {
+            throw new Error(`Desktop file of application is not known: ${appId}`)
+        }
+
+        linux_open_application(desktopFilePath, [], linuxTerminal())
     }
+}
+
+export function linuxTerminal(): string | undefined {
+    const { linuxTerminal } = pluginPreferences<{ linuxTerminal: string }>();
+
+    return linuxTerminal || undefined
 }
\ No newline at end of file
@@ -1,6 +1,7 @@
 // @ts-ignore TODO how to add declaration for this?
 import { getAssetData, getAssetDataSync, getPluginPreferences, getEntrypointPreferences, showHudWindow } from "ext:gauntlet/renderer.js";
 import {
+    applications_open,
     clipboard_clear,
     clipboard_read,
     clipboard_read_text,
@@ -9,7 +10,13 @@ import {
     environment_gauntlet_version,
     environment_is_development,
     environment_plugin_cache_dir,
-    environment_plugin_data_dir
+    environment_plugin_data_dir,
+    window_management_capabilities,
+    window_management_close,
+    window_management_focus,
+    window_management_list,
+    window_management_minimize,
+    window_management_pending_event,
 } from "ext:core/ops";
 import type { FC } from "react";
 
@@ -38,6 +45,7 @@ export interface GeneratedCommand {
     actions: GeneratedCommandAction[]
     icon?: ArrayBuffer
     accessories?: GeneratedCommandAccessory[]
+    keywords?: string[]
 }
 
 export type GeneratedCommandAction = GeneratedCommandActionRun | GeneratedCommandActionView
@@ -125,6 +133,124 @@ export interface Clipboard {
     clear(): Promise<void>;
 }
 
+export interface WindowInfo {
+    id: string
+    title: string
+    appId?: string
+    workspace?: string
+    pid?: number
+}
+
+export type WindowEvent = WindowOpenedEvent | WindowClosedEvent | WindowTitleChangedEvent
+
+export interface WindowOpenedEvent {
+    type: "opened"
+    window: WindowInfo
+}
+
+export interface WindowClosedEvent {
+    type: "closed"
+    windowId: string
+}
+
+export interface WindowTitleChangedEvent {
+    type: "titleChanged"
+    windowId: string
+    title: string
+}
+
+export interface WindowCapabilities {
+    backend: "x11" | "wlr" | "cosmic" | "fake"
+    workspace: boolean
+    pid: boolean
+    focus: boolean
+    close: boolean
+    minimize: boolean
+}
+
+const windowListeners = new Set<(event: WindowEvent) => void>();
+let windowEventLoopStarted = false;
+
+function toWindowInfo(window: RuntimeWindow): WindowInfo {
+    return {
+        id: window.id,
+        title: window.title,
+        appId: window.app_id ?? undefined,
+        workspace: window.workspace ?? undefined,
+        pid: window.pid ?? undefined,
+    }
+}
+
+function toWindowEvent(event: RuntimeWindowEvent): WindowEvent {
+    switch (event.type) {
+        case "WindowOpened":
+            return { type: "opened", window: toWindowInfo(event.window) }
+        case "WindowClosed":
+            return { type: "closed", windowId: event.window_id }
+        case "WindowTitleChanged":
+            return { type: "titleChanged", windowId: event.window_id, title: event.title }
+    }
+}
+
+// single loop is shared by all listeners, events are only collected by runtime after first listener is added
+async function runWindowEventLoop() {
+    while (true) {
+        const event = toWindowEvent(await window_management_pending_event());
+
+        for (const listener of windowListeners) {
+            try {
+                listener(event)
+            } catch (e) {
+                console.error("Error occurred when handling window event", e)
+            }
+        }
+    }
+}
+
+export const Windows: Windows = {
+    capabilities: function (): WindowCapabilities {
+        return window_management_capabilities()
+    },
+    list: function (): WindowInfo[] {
+        return window_management_list().map(toWindowInfo)
+    },
+    focus: function (windowId: string): void {
+        window_management_focus(windowId)
+    },
+    close: function (windowId: string): void {
+        window_management_close(windowId)
+    },
+    minimize: function (windowId: string): void {
+        window_management_minimize(windowId)
+    },
+    addListener: function (listener: (event: WindowEvent) => void): () => void {
+        windowListeners.add(listener);
+
+        if (!windowEventLoopStarted) {
+            windowEventLoopStarted = true;
+
+            runWindowEventLoop()
+                .catch(e => {
+                    windowEventLoopStarted = false;
+                    console.error("Window event loop has stopped", e)
+                })
+        }
+
+        return () => {
+            windowListeners.delete(listener)
+        }
+    },
+}
+
+export interface Windows {
+    capabilities(): WindowCapabilities;
+    list(): WindowInfo[];
+    focus(windowId: string): void;
+    close(windowId: string): void;
+    minimize(windowId: string): void;
+    addListener(listener: (event: WindowEvent) => void): () => void;
+}
+
 export const Environment: Environment = {
     get gauntletVersion(): number {
         return environment_gauntlet_version()
@@ -147,3 +273,12 @@ export interface Environment {
     get pluginCacheDir(): string;
 }
 
+export const Applications: Applications = {
+    open: function (desktopFilePath: string, uris?: string[]): void {
+        applications_open(desktopFilePath, uris ?? [])
+    },
+}
+
+export interface Applications {
+    open(desktopFilePath: string, uris?: string[]): void;
+}
@@ -1,6 +1,5 @@
 import type { FC } from "react";
 import { runEntrypointGenerators, runGeneratedCommand, runGeneratedCommandAction } from "./entrypoint-generator";
-import { reloadSearchIndex } from "./search-index";
 import { closeView, handleEvent, handlePluginViewKeyboardEvent, renderInlineView, renderView } from "./render";
 import {
     entrypoint_preferences_required, op_entrypoint_names,
@@ -134,11 +133,6 @@ export async function runPluginLoop() {
                 runEntrypointGenerators()
                 break;
             }
-            case "RefreshSearchIndex": {
-                // noinspection ES6MissingAwait
-                reloadSearchIndex(false)
-                break;
-            }
         }
     }
 }
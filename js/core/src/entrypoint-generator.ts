@@ -5,7 +5,7 @@ import {
     op_log_debug,
     update_loading_bar
 } from "ext:core/ops";
-import { reloadSearchIndex } from "./search-index";
+import { reloadSearchIndex, scheduleSearchIndexUpdate, updateSearchIndex } from "./search-index";
 import type { FC } from "react";
 import { renderView } from "./render";
 
@@ -14,6 +14,7 @@ interface GeneratedCommand { // TODO is it possible to import api here
     actions: GeneratedCommandAction[]
     icon?: ArrayBuffer
     accessories?: GeneratedCommandAccessory[]
+    keywords?: string[]
 }
 
 type GeneratedCommandAction = GeneratedCommandActionRun | GeneratedCommandActionView
@@ -65,6 +66,9 @@ interface GeneratedCommandDerivedActionView {
 
 
 type ProcessedGeneratedCommands = { [lookupEntrypointId: string]: ProcessedGeneratedCommand };
+
+// generator that never finishes its initial run shouldn't prevent search index from being reloaded
+const GENERATOR_INITIAL_RUN_TIMEOUT_MS = 30 * 1000;
 type GeneratorCleanups = { [generatorEntrypointId: string]: () => (void | Promise<void>) };
 
 let storedGeneratedCommands: ProcessedGeneratedCommands = {}
@@ -82,7 +86,11 @@ export async function runEntrypointGenerators(): Promise<void> {
     storedGeneratedCommands = {}
     generatorCleanups = {}
 
-    await reloadSearchIndex(true)
+    // only updates builtin entrypoints, generated entries from previous run are kept
+    // until generators finish their initial run, so that they are searchable right away
+    await updateSearchIndex([], [], true)
+
+    const initialRuns: Promise<void>[] = []
 
     const entrypointIds = await get_entrypoint_generator_entrypoint_ids();
     for (const generatorEntrypointId of entrypointIds) {
@@ -140,7 +148,7 @@ export async function runEntrypointGenerators(): Promise<void> {
                     derivedActions,
                 }
 
-                reloadSearchIndex(true)
+                scheduleSearchIndexUpdate([lookupId], [])
             }
             const remove = (id: string) => {
                 op_log_info("entrypoint_generator", `Removing entry '${id}' by entrypoint generator entrypoint '${generatorEntrypointId}'`)
@@ -148,7 +156,7 @@ export async function runEntrypointGenerators(): Promise<void> {
 
                 delete storedGeneratedCommands[lookupId]
 
-                reloadSearchIndex(true)
+                scheduleSearchIndexUpdate([], [lookupId])
             }
 
             const get = (id: string) => {
@@ -172,8 +180,7 @@ export async function runEntrypointGenerators(): Promise<void> {
                 )
             }
 
-            // noinspection ES6MissingAwait
-            (async () => {
+            const initialRun = (async () => {
                 try {
                     update_loading_bar(generatorEntrypointId, true)
                     let cleanup = await generator({ add, remove, get, getAll })
@@ -184,15 +191,49 @@ export async function runEntrypointGenerators(): Promise<void> {
                 } catch (e) {
                     console.error(`Error occurred when calling entrypoint generator for entrypoint: ${generatorEntrypointId}`, e)
                 }
-            })()
+            })();
+
+            initialRuns.push(withTimeout(initialRun, GENERATOR_INITIAL_RUN_TIMEOUT_MS, () => {
+                // entries added after the timeout are still added to search index, just one by one
+                console.error(`Entrypoint generator for entrypoint: ${generatorEntrypointId} didn't finish initial run in ${GENERATOR_INITIAL_RUN_TIMEOUT_MS / 1000} seconds`)
+            }))
         } catch (e) {
             console.error(`Error occurred when importing entrypoint generator for entrypoint: ${generatorEntrypointId}`, e)
         }
     }
+
+    // replace whole index when all generators are done, to remove entries that were not generated this time
+    // noinspection ES6MissingAwait
+    Promise.all(initialRuns)
+        .then(() => reloadSearchIndex(true))
+}
+
+function withTimeout(promise: Promise<void>, timeoutMs: number, onTimeout: () => void): Promise<void> {
+    let timeout: ReturnType<typeof setTimeout> | undefined;
+
+    const timeoutPromise = new Promise<void>(resolve => {
+        timeout = setTimeout(() => {
+            onTimeout()
+            resolve()
+        }, timeoutMs)
+    });
+
+    return Promise.race([promise, timeoutPromise])
+        .finally(() => clearTimeout(timeout))
 }
 
 export function generatedCommandSearchIndex(): GeneratedSearchItem[] {
-    return Object.entries(storedGeneratedCommands).map(([entrypointLookupId, value]) => ({
+    return Object.entries(storedGeneratedCommands).map(([entrypointLookupId, value]) => generatedCommandSearchItem(entrypointLookupId, value))
+}
+
+export function generatedCommandSearchItems(entrypointLookupIds: string[]): GeneratedSearchItem[] {
+    return entrypointLookupIds
+        .filter(entrypointLookupId => entrypointLookupId in storedGeneratedCommands)
+        .map(entrypointLookupId => generatedCommandSearchItem(entrypointLookupId, storedGeneratedCommands[entrypointLookupId]))
+}
+
+function generatedCommandSearchItem(entrypointLookupId: string, value: ProcessedGeneratedCommand): GeneratedSearchItem {
+    return {
         generator_entrypoint_id: value.generatorEntrypointId,
         entrypoint_id: entrypointLookupId,
         entrypoint_uuid: value.uuid,
@@ -204,8 +245,9 @@ export function generatedCommandSearchIndex(): GeneratedSearchItem[] {
                 action_type: action.type,
                 label: action.label
             })),
-        entrypoint_accessories: value.command.accessories || []
-    }))
+        entrypoint_accessories: value.command.accessories || [],
+        entrypoint_keywords: value.command.keywords || []
+    }
 }
 
 export async function runGeneratedCommandAction(entrypointId: string, key: string, modifierShift: boolean, modifierControl: boolean, modifierAlt: boolean, modifierMeta: boolean) {
@@ -1,5 +1,10 @@
 export {
     run_numbat,
+    numbat_save_variables,
+    numbat_history,
+    numbat_add_history_entry,
+    numbat_remove_history_entry,
+    numbat_clear_history,
     open_settings,
     current_os,
     wayland,
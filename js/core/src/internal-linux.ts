@@ -2,6 +2,7 @@ export {
     linux_app_from_path,
     linux_application_dirs,
     linux_open_application,
+    linux_open_application_action,
     linux_x11_focus_window,
     linux_wayland_focus_window,
     application_x11_pending_event,
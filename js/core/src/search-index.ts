@@ -1,6 +1,57 @@
-import { generatedCommandSearchIndex } from "./entrypoint-generator";
-import { reload_search_index } from "ext:core/ops";
+import { generatedCommandSearchIndex, generatedCommandSearchItems } from "./entrypoint-generator";
+import { reload_search_index, update_search_index } from "ext:core/ops";
+
+// generators usually add a lot of entries at once, e.g. applications plugin adds several hundreds of them on start,
+// every update reloads plugin data and commits search index, so changes made in short period of time are sent together
+const UPDATE_BATCH_DELAY_MS = 100;
+
+let pendingUpdatedEntrypointIds = new Set<string>()
+let pendingRemovedEntrypointIds = new Set<string>()
+let updateScheduled = false
+
+// updates are sent one after another, so that older update doesn't overwrite newer one
+let searchIndexQueue: Promise<void> = Promise.resolve()
+
+function enqueue(update: () => Promise<void>): Promise<void> {
+    const result = searchIndexQueue.then(update)
+    searchIndexQueue = result.catch(() => {})
+    return result
+}
 
 export async function reloadSearchIndex(refreshSearchList: boolean) {
-    await reload_search_index(generatedCommandSearchIndex(), refreshSearchList);
-}
\ No newline at end of file
+    await enqueue(() => reload_search_index(generatedCommandSearchIndex(), refreshSearchList));
+}
+
+export async function updateSearchIndex(updatedEntrypointIds: string[], removedEntrypointIds: string[], refreshSearchList: boolean) {
+    await enqueue(() => update_search_index(generatedCommandSearchItems(updatedEntrypointIds), removedEntrypointIds, refreshSearchList));
+}
+
+export function scheduleSearchIndexUpdate(updatedEntrypointIds: string[], removedEntrypointIds: string[]) {
+    for (const entrypointId of updatedEntrypointIds) {
+        pendingRemovedEntrypointIds.delete(entrypointId)
+        pendingUpdatedEntrypointIds.add(entrypointId)
+    }
+
+    for (const entrypointId of removedEntrypointIds) {
+        pendingUpdatedEntrypointIds.delete(entrypointId)
+        pendingRemovedEntrypointIds.add(entrypointId)
+    }
+
+    if (updateScheduled) {
+        return
+    }
+
+    updateScheduled = true
+
+    setTimeout(() => {
+        const updated = [...pendingUpdatedEntrypointIds]
+        const removed = [...pendingRemovedEntrypointIds]
+
+        pendingUpdatedEntrypointIds = new Set()
+        pendingRemovedEntrypointIds = new Set()
+        updateScheduled = false
+
+        updateSearchIndex(updated, removed, true)
+            .catch(err => console.error("Error occurred when updating search index", err))
+    }, UPDATE_BATCH_DELAY_MS)
+}
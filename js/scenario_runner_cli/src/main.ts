@@ -2,6 +2,7 @@ import { Command } from 'commander';
 import { spawnSync } from "node:child_process";
 import path from "node:path";
 import { existsSync, readdirSync, rmSync } from "node:fs";
+import { fileURLToPath } from "node:url";
 
 const program = new Command();
 
@@ -22,6 +23,13 @@ program.command('run-screenshot-gen')
         await runScreenshotGen(plugin, entrypoint)
     });
 
+program.command('run-plugin-tests')
+    .argument('<plugin-dir>', 'directory of the plugin, expected to be already built')
+    .argument('<tests-dir>', 'directory with plugin test json files')
+    .action(async (pluginDir, testsDir) => {
+        await runPluginTests(pluginDir, testsDir)
+    });
+
 await program.parseAsync(process.argv);
 
 async function sleep(ms: number) {
@@ -76,6 +84,39 @@ async function runScenarios(expectedPlugin: string | undefined) {
     }
 }
 
+async function runPluginTests(pluginDir: string, testsDir: string) {
+    // script is located in js/scenario_runner_cli/dist, plugin authors can run it from any directory
+    const projectRoot = path.resolve(path.dirname(fileURLToPath(import.meta.url)), '..', '..', '..');
+    // npm runs scripts in package directory, paths given by user are relative to directory npm was started in
+    const userCwd = process.env.INIT_CWD ?? process.cwd();
+    const testsRun = path.join(projectRoot, "scenarios", "run-plugin-tests");
+
+    console.log("Starting plugin test runner")
+
+    const backendProcess = spawnSync('cargo',  ['run', '--features', 'scenario_runner'], {
+        stdio: "inherit",
+        cwd: projectRoot,
+        env: Object.assign(process.env, {
+            RUST_LOG: "gauntlet-server=INFO",
+            GAUNTLET_SCENARIO_RUNNER_TYPE: "plugin_test",
+            GAUNTLET_PLUGIN_TEST_PLUGIN_DIR: path.resolve(userCwd, pluginDir),
+            GAUNTLET_PLUGIN_TEST_DIR: path.resolve(userCwd, testsDir),
+            XDG_DATA_HOME: path.join(testsRun, "data"),
+            XDG_CONFIG_HOME: path.join(testsRun, "config"),
+            XDG_CACHE_HOME: path.join(testsRun, "cache"),
+            XDG_STATE_HOME: path.join(testsRun, "state"),
+        })
+    })
+
+    if (existsSync(testsRun)) {
+        rmSync(testsRun, { recursive: true })
+    }
+
+    if (backendProcess.status !== 0) {
+        throw new Error(`Plugin tests failed, status: ${backendProcess.status}`);
+    }
+}
+
 async function runScreenshotGen(expectedPlugin: string | undefined, expectedEntrypoint: string | undefined) {
     const projectRoot = path.resolve(process.cwd(), '..', '..');
     const scenarios = path.join(projectRoot, "scenarios");
@@ -19,6 +19,15 @@ type LinuxDesktopApplicationData = {
     icon: ArrayBuffer | undefined,
     desktop_file_path: string,
     startup_wm_class: string | undefined,
+    generic_name: string | undefined,
+    comment: string | undefined,
+    keywords: string[],
+    actions: LinuxDesktopApplicationAction[],
+}
+
+type LinuxDesktopApplicationAction = {
+    id: string,
+    name: string,
 }
 
 type MacOSDesktopApplicationData = {
@@ -45,7 +54,7 @@ type MacOSDesktopSettings13AndPostData = {
     icon: ArrayBuffer | undefined,
 }
 
-type PluginEvent = ViewEvent | NotReactsKeyboardEvent | RunCommand | RunGeneratedCommand | OpenView | CloseView | OpenInlineView | ReloadSearchIndex | RefreshSearchIndex
+type PluginEvent = ViewEvent | NotReactsKeyboardEvent | RunCommand | RunGeneratedCommand | OpenView | CloseView | OpenInlineView | ReloadSearchIndex
 type RenderLocation = "InlineView" | "View"
 
 type ViewEvent = {
@@ -98,10 +107,6 @@ type ReloadSearchIndex = {
     type: "ReloadSearchIndex"
 }
 
-type RefreshSearchIndex = {
-    type: "RefreshSearchIndex"
-}
-
 type PropertyValue = PropertyValueString | PropertyValueNumber | PropertyValueBool | PropertyValueUndefined
 type PropertyValueString = { type: "String", value: string }
 type PropertyValueNumber = { type: "Number", value: number }
@@ -138,6 +143,7 @@ type GeneratedSearchItem = {
     entrypoint_icon: ArrayBuffer | undefined,
     entrypoint_actions: GeneratedSearchItemAction[],
     entrypoint_accessories: GeneratedCommandAccessory[],
+    entrypoint_keywords: string[],
 }
 
 type GeneratedSearchItemAction = {
@@ -146,15 +152,56 @@ type GeneratedSearchItemAction = {
     label: string,
 }
 
+type NumbatResult = NumbatResultValue | NumbatResultError | NumbatResultNotCalculation
+
+type NumbatResultValue = {
+    type: "Value",
+    left: string,
+    right: string,
+    number?: string,
+    scientific?: string,
+}
+
+type NumbatResultError = {
+    type: "Error",
+    left: string,
+    message: string,
+    spans: NumbatErrorSpan[],
+}
+
+type NumbatResultNotCalculation = {
+    type: "NotCalculation",
+}
+
+type NumbatErrorSpan = {
+    start: number,
+    end: number,
+    text: string,
+    message: string,
+}
+
+type NumbatHistoryEntry = {
+    expression: string,
+    result: string,
+    number?: string,
+    scientific?: string,
+}
+
 declare module "gauntlet:bridge/internal-all" {
     function open_settings(): void
-    function run_numbat(input: string): { left: string, right: string }
+    function run_numbat(input: string): NumbatResult
+    function numbat_save_variables(input: string): void
+    function numbat_history(): NumbatHistoryEntry[]
+    function numbat_add_history_entry(entry: NumbatHistoryEntry): void
+    function numbat_remove_history_entry(expression: string): void
+    function numbat_clear_history(): void
     function current_os(): string
     function wayland(): boolean
 }
 
 declare module "gauntlet:bridge/internal-linux" {
-    function linux_open_application(desktop_id: string): void
+    function linux_open_application(desktop_file_path: string, uris: string[], terminal: string | undefined): void
+    function linux_open_application_action(desktop_file_path: string, action_id: string, terminal: string | undefined): void
     function linux_x11_focus_window(window_id: string): void
     function linux_wayland_focus_window(window_id: string): void
     function linux_application_dirs(): string[]
@@ -185,14 +232,20 @@ declare module "gauntlet:bridge/internal-windows" {
 
 declare module "ext:core/ops" {
     function open_settings(): void
-    function run_numbat(input: string): { left: string, right: string }
+    function run_numbat(input: string): NumbatResult
+    function numbat_save_variables(input: string): void
+    function numbat_history(): NumbatHistoryEntry[]
+    function numbat_add_history_entry(entry: NumbatHistoryEntry): void
+    function numbat_remove_history_entry(expression: string): void
+    function numbat_clear_history(): void
 
     function current_os(): string
     function wayland(): boolean
     function application_x11_pending_event(): Promise<X11ApplicationEvent>
     function application_wayland_pending_event(): Promise<WaylandApplicationEvent>
 
-    function linux_open_application(desktop_id: string): void
+    function linux_open_application(desktop_file_path: string, uris: string[], terminal: string | undefined): void
+    function linux_open_application_action(desktop_file_path: string, action_id: string, terminal: string | undefined): void
     function linux_x11_focus_window(window_id: string): void
     function linux_wayland_focus_window(window_id: string): void
     function linux_application_dirs(): string[]
@@ -238,6 +291,7 @@ declare module "ext:core/ops" {
     function show_preferences_required_view(entrypointId: string, pluginPreferencesRequired: boolean, entrypointPreferencesRequired: boolean): void;
 
     function reload_search_index(searchItems: GeneratedSearchItem[], refreshSearchList: boolean): Promise<void>;
+    function update_search_index(updatedSearchItems: GeneratedSearchItem[], removedEntrypointIds: string[], refreshSearchList: boolean): Promise<void>;
 
     function show_hud(display: string): void;
     function update_loading_bar(entrypoint_id: string, show: boolean): void;
@@ -253,10 +307,50 @@ declare module "ext:core/ops" {
     function clipboard_write_text(data: string): Promise<void>;
     function clipboard_clear(): Promise<void>;
 
+    function window_management_capabilities(): RuntimeWindowCapabilities;
+    function window_management_list(): RuntimeWindow[];
+    function window_management_focus(windowId: string): void;
+    function window_management_close(windowId: string): void;
+    function window_management_minimize(windowId: string): void;
+    function window_management_pending_event(): Promise<RuntimeWindowEvent>;
+
     function environment_gauntlet_version(): number;
     function environment_is_development(): boolean;
     function environment_plugin_data_dir(): string;
     function environment_plugin_cache_dir(): string;
+
+    function applications_open(desktopFilePath: string, uris: string[]): void;
+}
+
+// windows types
+
+type RuntimeWindow = {
+    id: string,
+    title: string,
+    app_id?: string,
+    workspace?: string,
+    pid?: number,
+}
+
+type RuntimeWindowEvent = {
+    type: "WindowOpened",
+    window: RuntimeWindow,
+} | {
+    type: "WindowClosed",
+    window_id: string,
+} | {
+    type: "WindowTitleChanged",
+    window_id: string,
+    title: string,
+}
+
+type RuntimeWindowCapabilities = {
+    backend: "x11" | "wlr" | "cosmic" | "fake",
+    workspace: boolean,
+    pid: boolean,
+    focus: boolean,
+    close: boolean,
+    minimize: boolean,
 }
 
 // component model types
@@ -1,8 +1,10 @@
 use anyhow::{anyhow, Context};
 use clap::Parser;
 use gauntlet_client::open_window;
+use gauntlet_common::model::{EntrypointId, PluginId};
+use gauntlet_common::rpc::backend_api::BackendApi;
 use gauntlet_management_client::start_management_client;
-use gauntlet_server::start;
+use gauntlet_server::{plugin_content_hash, start};
 
 #[derive(Debug, clap::Parser)]
 struct Cli {
@@ -17,6 +19,43 @@ struct Cli {
 enum Commands {
     Open,
     Settings,
+    /// Inspect or reset learned search ranking data
+    Frecency {
+        #[command(subcommand)]
+        command: FrecencyCommands,
+    },
+    /// Replace aliases of the entrypoint, also works for generated entrypoints which are not listed in settings
+    Aliases {
+        plugin_id: String,
+        entrypoint_id: String,
+        aliases: Vec<String>,
+    },
+    /// Install plugin from .tar.gz/.zip archive or directory containing built plugin
+    Install {
+        path: std::path::PathBuf,
+    },
+    /// Print content hash of built plugin directory, which is signed to produce plugin signature
+    PluginContentHash {
+        path: std::path::PathBuf,
+    },
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum FrecencyCommands {
+    /// List frecency of entrypoints of the plugin
+    List {
+        plugin_id: String,
+
+        #[arg(long)]
+        entrypoint_id: Option<String>,
+    },
+    /// Reset frecency of a single entrypoint
+    Reset {
+        plugin_id: String,
+        entrypoint_id: String,
+    },
+    /// Reset frecency of all entrypoints of all plugins
+    ResetAll,
 }
 
 pub fn init() {
@@ -45,11 +84,121 @@ pub fn init() {
             match command {
                 Commands::Open => open_window(),
                 Commands::Settings => start_management_client(),
+                Commands::Frecency { command } => {
+                    if let Err(err) = run_frecency_command(command) {
+                        tracing::error!("{:#}", err);
+                        std::process::exit(1)
+                    }
+                }
+                Commands::Aliases { plugin_id, entrypoint_id, aliases } => {
+                    if let Err(err) = run_aliases_command(plugin_id, entrypoint_id, aliases) {
+                        tracing::error!("{:#}", err);
+                        std::process::exit(1)
+                    }
+                }
+                Commands::Install { path } => {
+                    if let Err(err) = run_install_command(path) {
+                        tracing::error!("{:#}", err);
+                        std::process::exit(1)
+                    }
+                }
+                Commands::PluginContentHash { path } => {
+                    match plugin_content_hash(path) {
+                        Ok(content_hash) => println!("{}", content_hash),
+                        Err(err) => {
+                            tracing::error!("{:#}", err);
+                            std::process::exit(1)
+                        }
+                    }
+                }
             };
         }
     }
 }
 
+fn run_frecency_command(command: &FrecencyCommands) -> anyhow::Result<()> {
+    cli_runtime()?
+        .block_on(async {
+            let mut backend_api = connect_to_server().await?;
+
+            match command {
+                FrecencyCommands::List { plugin_id, entrypoint_id } => {
+                    let plugin_id = PluginId::from_string(plugin_id);
+                    let entrypoint_id = entrypoint_id.as_ref().map(|id| EntrypointId::from_string(id));
+
+                    let stats = backend_api.frecency_stats(plugin_id, entrypoint_id)
+                        .await?;
+
+                    for stats in stats {
+                        println!(
+                            "{}\tfrecency: {:.4}\taccesses: {}\tlast accessed: {:.0}",
+                            stats.entrypoint_id.to_string(),
+                            stats.frecency,
+                            stats.num_accesses,
+                            stats.last_accessed_secs
+                        );
+                    }
+                }
+                FrecencyCommands::Reset { plugin_id, entrypoint_id } => {
+                    backend_api.reset_entrypoint_frecency(PluginId::from_string(plugin_id), EntrypointId::from_string(entrypoint_id))
+                        .await?;
+                }
+                FrecencyCommands::ResetAll => {
+                    backend_api.reset_all_frecency()
+                        .await?;
+                }
+            }
+
+            Ok(())
+        })
+}
+
+fn run_aliases_command(plugin_id: &str, entrypoint_id: &str, aliases: &[String]) -> anyhow::Result<()> {
+    cli_runtime()?
+        .block_on(async {
+            let mut backend_api = connect_to_server().await?;
+
+            backend_api.set_entrypoint_aliases(PluginId::from_string(plugin_id), EntrypointId::from_string(entrypoint_id), aliases.to_vec())
+                .await?;
+
+            Ok(())
+        })
+}
+
+fn run_install_command(path: &std::path::Path) -> anyhow::Result<()> {
+    // server has a different working directory
+    let path = std::fs::canonicalize(path)
+        .context(format!("Unable to find {:?}", path))?
+        .into_os_string()
+        .into_string()
+        .map_err(|_| anyhow!("non uft8 paths are not supported"))?;
+
+    cli_runtime()?
+        .block_on(async {
+            let mut backend_api = connect_to_server().await?;
+
+            let plugin_id = backend_api.install_plugin(path)
+                .await?;
+
+            println!("Installed plugin: {}", plugin_id.to_string());
+
+            Ok(())
+        })
+}
+
+fn cli_runtime() -> anyhow::Result<tokio::runtime::Runtime> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("unable to start tokio runtime")
+}
+
+async fn connect_to_server() -> anyhow::Result<BackendApi> {
+    BackendApi::new()
+        .await
+        .context("Unable to connect to server. Please check if you have Gauntlet running on your PC")
+}
+
 #[cfg(target_os = "macos")]
 fn setup_auto_launch_macos() -> anyhow::Result<()> {
     let app_path = std::env::current_exe()
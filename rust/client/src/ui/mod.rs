@@ -13,7 +13,7 @@ use iced::widget::text_input::focus;
 use iced::widget::{button, column, container, horizontal_rule, horizontal_space, row, scrollable, text, text_input, Space};
 use iced::window::{Level, Mode, Position, Screenshot};
 use iced::{event, executor, font, futures, keyboard, stream, window, Alignment, Event, Font, Length, Padding, Pixels, Renderer, Settings, Size, Subscription, Task};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fs;
 use std::path::Path;
 use std::rc::Rc;
@@ -24,12 +24,12 @@ use serde::Deserialize;
 use tokio::sync::{Mutex as TokioMutex, RwLock as TokioRwLock};
 
 use client_context::ClientContext;
-use gauntlet_common::model::{BackendRequestData, BackendResponseData, EntrypointId, UiTheme, KeyboardEventOrigin, PhysicalKey, PhysicalShortcut, PluginId, RootWidget, RootWidgetMembers, SearchResult, SearchResultEntrypointAction, SearchResultEntrypointActionType, SearchResultEntrypointType, UiRenderLocation, UiRequestData, UiResponseData, UiSetupData, UiWidgetId};
+use gauntlet_common::model::{BackendRequestData, BackendResponseData, EntrypointId, UiTheme, KeyboardEventOrigin, PermissionPromptResult, PhysicalKey, PhysicalShortcut, PluginId, RootWidget, RootWidgetMembers, SearchResult, SearchResultEntrypointAction, SearchResultEntrypointActionType, SearchResultEntrypointType, UiRenderLocation, UiRequestData, UiResponseData, UiSetupData, UiWidgetId};
 use gauntlet_common::rpc::backend_api::{BackendApi, BackendForFrontendApi, BackendForFrontendApiError};
 use gauntlet_common::scenario_convert::{ui_render_location_from_scenario};
 use gauntlet_common::scenario_model::{ScenarioFrontendEvent, ScenarioUiRenderLocation};
 use gauntlet_common_ui::physical_key_model;
-use gauntlet_utils::channel::{RequestReceiver, RequestSender, Responder};
+use gauntlet_utils::channel::{RequestReceiver, RequestSender, Responder, REQUEST_TIMEOUT};
 
 use crate::model::UiViewEvent;
 use crate::ui::search_list::search_list;
@@ -80,7 +80,15 @@ pub struct AppModel {
     global_state: GlobalState,
     search_results: Vec<SearchResult>,
     loading_bar_state: HashMap<(PluginId, EntrypointId), ()>,
-    hud_display: Option<String>
+    hud_display: Option<String>,
+    permission_prompts: VecDeque<PermissionPrompt>,
+}
+
+struct PermissionPrompt {
+    plugin_name: String,
+    permission: String,
+    value: String,
+    responder: Arc<Mutex<Option<Responder<UiResponseData>>>>,
 }
 
 #[cfg(target_os = "linux")]
@@ -168,7 +176,8 @@ pub enum AppMsg {
     ShowPluginErrorView {
         plugin_id: PluginId,
         entrypoint_id: EntrypointId,
-        render_location: UiRenderLocation
+        render_location: UiRenderLocation,
+        reason: Option<String>,
     },
     Screenshot {
         save_path: String
@@ -221,6 +230,17 @@ pub enum AppMsg {
     SetTheme {
         theme: UiTheme
     },
+    ShowPermissionPrompt {
+        plugin_id: PluginId,
+        plugin_name: String,
+        permission: String,
+        value: String,
+        responder: Arc<Mutex<Option<Responder<UiResponseData>>>>
+    },
+    PermissionPromptAnswered {
+        result: PermissionPromptResult
+    },
+    PermissionPromptTimedOut,
 }
 
 #[cfg(target_os = "linux")]
@@ -499,6 +519,7 @@ fn new(
                 let error_view = ErrorViewData::PluginError {
                     plugin_id: PluginId::from_string("__SCREENSHOT_GEN___"),
                     entrypoint_id: EntrypointId::from_string(entrypoint_id),
+                    reason: None,
                 };
 
                 GlobalState::new_error(error_view)
@@ -531,6 +552,7 @@ fn new(
             search_results: vec![],
             loading_bar_state: HashMap::new(),
             hud_display: None,
+            permission_prompts: VecDeque::new(),
         },
         Task::batch(tasks),
     )
@@ -619,7 +641,18 @@ fn update(state: &mut AppModel, message: AppMsg) -> Task<AppMsg> {
             ])
         }
         AppMsg::RunSearchItemAction(search_result, action_index) => {
-            match search_result.entrypoint_type {
+            let runs_entrypoint = match search_result.entrypoint_type {
+                SearchResultEntrypointType::Command | SearchResultEntrypointType::View => action_index == 0,
+                SearchResultEntrypointType::Generated => true,
+            };
+
+            let select_task = if runs_entrypoint {
+                state.search_result_selected(state.prompt.clone(), search_result.plugin_id.clone(), search_result.entrypoint_id.clone())
+            } else {
+                Task::none()
+            };
+
+            let run_task = match search_result.entrypoint_type {
                 SearchResultEntrypointType::Command => {
                     if action_index == 0 {
                         Task::done(AppMsg::RunCommand {
@@ -663,7 +696,12 @@ fn update(state: &mut AppModel, message: AppMsg) -> Task<AppMsg> {
                         }
                     }
                 },
-            }
+            };
+
+            Task::batch([
+                select_task,
+                run_task,
+            ])
         }
         AppMsg::PromptChanged(mut new_prompt) => {
             if cfg!(feature = "scenario_runner") {
@@ -788,6 +826,14 @@ fn update(state: &mut AppModel, message: AppMsg) -> Task<AppMsg> {
             match event {
                 keyboard::Event::KeyPressed { key, modifiers, physical_key, text, .. } => {
                     tracing::debug!("Key pressed: {:?}. shift: {:?} control: {:?} alt: {:?} meta: {:?}", key, modifiers.shift(), modifiers.control(), modifiers.alt(), modifiers.logo());
+
+                    if !state.permission_prompts.is_empty() {
+                        return match key {
+                            Key::Named(Named::Escape) => Task::done(AppMsg::PermissionPromptAnswered { result: PermissionPromptResult::Deny }),
+                            _ => Task::none()
+                        }
+                    }
+
                     match key {
                         Key::Named(Named::ArrowUp) => state.global_state.up(&state.client_context, &state.search_results),
                         Key::Named(Named::ArrowDown) => state.global_state.down(&state.client_context, &state.search_results),
@@ -1007,12 +1053,13 @@ fn update(state: &mut AppModel, message: AppMsg) -> Task<AppMsg> {
                 },
             )
         }
-        AppMsg::ShowPluginErrorView { plugin_id, entrypoint_id, .. } => {
+        AppMsg::ShowPluginErrorView { plugin_id, entrypoint_id, reason, .. } => {
             GlobalState::error(
                 &mut state.global_state,
                 ErrorViewData::PluginError {
                     plugin_id,
                     entrypoint_id,
+                    reason,
                 },
             )
         }
@@ -1353,11 +1400,70 @@ fn update(state: &mut AppModel, message: AppMsg) -> Task<AppMsg> {
 
             GauntletComplexTheme::update_global(state.theme.clone());
 
+            Task::none()
+        }
+        AppMsg::ShowPermissionPrompt { plugin_id, plugin_name, permission, value, responder } => {
+            tracing::info!("Showing permission prompt for plugin {:?}: '{}' access to {:?}", plugin_id, permission, value);
+
+            state.permission_prompts.push_back(PermissionPrompt {
+                plugin_name,
+                permission,
+                value,
+                responder,
+            });
+
+            // backend stops waiting for the answer after request timeout, remove prompt when that happens
+            let timeout = Task::perform(async move {
+                tokio::time::sleep(REQUEST_TIMEOUT).await;
+
+                AppMsg::PermissionPromptTimedOut
+            }, std::convert::identity);
+
+            // do not reset state of already opened window, e.g. plugin view which triggered the prompt
+            if state.focused {
+                timeout
+            } else {
+                Task::batch([state.show_window(), timeout])
+            }
+        }
+        AppMsg::PermissionPromptAnswered { result } => {
+            if let Some(prompt) = state.permission_prompts.pop_front() {
+                // responder is not clone and send, and we need to consume it
+                // so we wrap it in arc mutex option
+                let responder = prompt.responder
+                    .lock()
+                    .expect("lock is poisoned")
+                    .take()
+                    .expect("there should always be a responder here");
+
+                if responder.try_respond(UiResponseData::PermissionPrompt { result }).is_err() {
+                    tracing::warn!("Permission prompt was answered after it timed out");
+                }
+            }
+
+            remove_timed_out_permission_prompts(state);
+
+            Task::none()
+        }
+        AppMsg::PermissionPromptTimedOut => {
+            remove_timed_out_permission_prompts(state);
+
             Task::none()
         }
     }
 }
 
+fn remove_timed_out_permission_prompts(state: &mut AppModel) {
+    // backend stopped waiting for these, plugin already got denial
+    state.permission_prompts.retain(|prompt| {
+        prompt.responder
+            .lock()
+            .expect("lock is poisoned")
+            .as_ref()
+            .is_some_and(|responder| !responder.is_closed())
+    });
+}
+
 fn view(state: &AppModel, window: window::Id) -> Element<'_, AppMsg> {
     if window != state.main_window_id {
         view_hud(state)
@@ -1403,7 +1509,72 @@ fn view_hud(state: &AppModel) -> Element<'_, AppMsg> {
     }
 }
 
+fn view_permission_prompt(prompt: &PermissionPrompt) -> Element<'_, AppMsg> {
+    let description: Element<_> = text(format!("Plugin \"{}\" requests {} access", prompt.plugin_name, prompt.permission))
+        .shaping(Shaping::Advanced)
+        .into();
+
+    let description = container(description)
+        .width(Length::Fill)
+        .align_x(Horizontal::Center)
+        .themed(ContainerStyle::PluginErrorViewTitle);
+
+    let sub_description: Element<_> = text(&prompt.value)
+        .shaping(Shaping::Advanced)
+        .into();
+
+    let sub_description = container(sub_description)
+        .width(Length::Fill)
+        .align_x(Horizontal::Center)
+        .themed(ContainerStyle::PluginErrorViewDescription);
+
+    let buttons = [
+        ("Allow once", PermissionPromptResult::AllowOnce),
+        ("Allow always", PermissionPromptResult::AllowAlways),
+        ("Deny", PermissionPromptResult::Deny),
+    ];
+
+    let buttons: Vec<Element<_>> = buttons.into_iter()
+        .map(|(label, result)| {
+            let button_label: Element<_> = text(label)
+                .into();
+
+            button(button_label)
+                .on_press(AppMsg::PermissionPromptAnswered { result })
+                .into()
+        })
+        .collect();
+
+    let buttons: Element<_> = row(buttons)
+        .spacing(8)
+        .into();
+
+    let buttons = container(buttons)
+        .width(Length::Fill)
+        .align_x(Horizontal::Center)
+        .into();
+
+    let content: Element<_> = column([
+        description,
+        sub_description,
+        buttons
+    ]).into();
+
+    let content: Element<_> = container(content)
+        .align_x(Horizontal::Center)
+        .align_y(Vertical::Center)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .themed(ContainerStyle::Main);
+
+    content
+}
+
 fn view_main(state: &AppModel) -> Element<'_, AppMsg> {
+    if let Some(prompt) = state.permission_prompts.front() {
+        return view_permission_prompt(prompt)
+    }
+
     match &state.global_state {
         GlobalState::ErrorView { error_view } => {
             match error_view {
@@ -1467,7 +1638,7 @@ fn view_main(state: &AppModel) -> Element<'_, AppMsg> {
 
                     content
                 }
-                ErrorViewData::PluginError { .. } => {
+                ErrorViewData::PluginError { reason, .. } => {
                     let description: Element<_> = text("Error occurred in plugin when trying to show the view")
                         .into();
 
@@ -1476,8 +1647,10 @@ fn view_main(state: &AppModel) -> Element<'_, AppMsg> {
                         .align_x(Horizontal::Center)
                         .themed(ContainerStyle::PluginErrorViewTitle);
 
-                    let sub_description: Element<_> = text("Please report this to plugin author")
-                        .into();
+                    let sub_description: Element<_> = match reason {
+                        Some(reason) => text(reason).shaping(Shaping::Advanced).into(),
+                        None => text("Please report this to plugin author").into()
+                    };
 
                     let sub_description = container(sub_description)
                         .width(Length::Fill)
@@ -2069,6 +2242,17 @@ impl AppModel {
         }, |result| handle_backend_error(result, |()| AppMsg::Noop))
     }
 
+    fn search_result_selected(&self, query: String, plugin_id: PluginId, entrypoint_id: EntrypointId) -> Task<AppMsg> {
+        let mut backend_client = self.backend_api.clone();
+
+        Task::perform(async move {
+            backend_client.search_result_selected(query, plugin_id, entrypoint_id)
+                .await?;
+
+            Ok(())
+        }, |result| handle_backend_error(result, |()| AppMsg::Noop))
+    }
+
     fn run_command(&self, plugin_id: PluginId, entrypoint_id: EntrypointId) -> Task<AppMsg> {
         let mut backend_client = self.backend_api.clone();
 
@@ -2307,13 +2491,14 @@ async fn request_loop(
                         entrypoint_preferences_required
                     }
                 }
-                UiRequestData::ShowPluginErrorView { plugin_id, entrypoint_id, render_location } => {
+                UiRequestData::ShowPluginErrorView { plugin_id, entrypoint_id, render_location, reason } => {
                     responder.respond(UiResponseData::Nothing);
 
                     AppMsg::ShowPluginErrorView {
                         plugin_id,
                         entrypoint_id,
                         render_location,
+                        reason,
                     }
                 }
                 UiRequestData::RequestSearchResultUpdate => {
@@ -2350,6 +2535,15 @@ async fn request_loop(
                         theme,
                     }
                 }
+                UiRequestData::RequestPermission { plugin_id, plugin_name, permission, value } => {
+                    AppMsg::ShowPermissionPrompt {
+                        plugin_id,
+                        plugin_name,
+                        permission,
+                        value,
+                        responder: Arc::new(Mutex::new(Some(responder)))
+                    }
+                }
             }
         };
 
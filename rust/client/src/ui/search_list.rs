@@ -15,7 +15,8 @@ use iced::widget::row;
 use iced::widget::text;
 use iced::widget::text::Shaping;
 use iced::widget::{column, container, horizontal_space};
-use iced::{Alignment, Length};
+use iced::font::Weight;
+use iced::{Alignment, Font, Length};
 
 pub fn search_list<'a>(
     search_results: &'a [SearchResult],
@@ -25,9 +26,7 @@ pub fn search_list<'a>(
         .iter()
         .enumerate()
         .map(|(index, search_result)| {
-            let main_text: Element<_> = text(&search_result.entrypoint_name)
-                .shaping(Shaping::Advanced)
-                .into();
+            let main_text = render_entrypoint_name(&search_result.entrypoint_name, &search_result.entrypoint_name_match_indices);
             let main_text: Element<_> = container(main_text)
                 .themed(ContainerStyle::MainListItemText);
 
@@ -126,3 +125,41 @@ pub fn search_list<'a>(
 
     column(items).into()
 }
+
+fn render_entrypoint_name<'a>(entrypoint_name: &str, match_indices: &[usize]) -> Element<'a, SearchResult> {
+    if match_indices.is_empty() {
+        return text(entrypoint_name.to_string())
+            .shaping(Shaping::Advanced)
+            .into();
+    }
+
+    // split name into runs of matched and not matched chars, matched runs are rendered in bold
+    let mut runs: Vec<(bool, String)> = vec![];
+    for (index, char) in entrypoint_name.chars().enumerate() {
+        let matched = match_indices.contains(&index);
+
+        match runs.last_mut() {
+            Some((last_matched, run)) if *last_matched == matched => run.push(char),
+            _ => runs.push((matched, char.to_string())),
+        }
+    }
+
+    let runs: Vec<Element<_>> = runs.into_iter()
+        .map(|(matched, run)| {
+            let mut text = text(run)
+                .shaping(Shaping::Advanced);
+
+            if matched {
+                text = text
+                    .font(Font {
+                        weight: Weight::Bold,
+                        ..Font::DEFAULT
+                    })
+            }
+
+            text.into()
+        })
+        .collect();
+
+    row(runs).into()
+}
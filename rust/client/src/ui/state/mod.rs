@@ -55,6 +55,7 @@ pub enum ErrorViewData {
     PluginError {
         plugin_id: PluginId,
         entrypoint_id: EntrypointId,
+        reason: Option<String>,
     },
     BackendTimeout,
     UnknownError {
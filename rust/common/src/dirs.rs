@@ -68,12 +68,22 @@ impl Dirs {
         self.cache_dir().join("icons")
     }
 
+    pub fn search_index_dir(&self) -> PathBuf {
+        self.cache_dir().join("search_index")
+    }
+
     pub fn plugin_cache(&self, plugin_uuid: &str) -> anyhow::Result<PathBuf> {
         let plugin_cache_dir = self.cache_dir().join("plugins").join(&plugin_uuid);
 
         Ok(plugin_cache_dir)
     }
 
+    pub fn plugin_assets(&self, plugin_uuid: &str) -> anyhow::Result<PathBuf> {
+        let plugin_assets_dir = self.cache_dir().join("plugin_assets").join(&plugin_uuid);
+
+        Ok(plugin_assets_dir)
+    }
+
     pub fn cache_dir(&self) -> PathBuf {
         let cache_dir = if cfg!(feature = "release") || cfg!(feature = "scenario_runner") {
             self.inner.cache_dir().to_path_buf()
@@ -0,0 +1,454 @@
+use std::fs::File;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use anyhow::anyhow;
+use sha2::{Digest, Sha256};
+use crate::dirs::Dirs;
+
+// processed icons are around 5KB, so this fits several thousands of them
+const MAX_ICON_STORE_SIZE_BYTES: u64 = 64 * 1024 * 1024;
+
+// temp file that is not renamed by then was left behind by process which was killed while writing it
+const STALE_TEMP_FILE_AGE: Duration = Duration::from_secs(60 * 60);
+
+static TEMP_FILE_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Content-addressed store of processed icons, persisted in cache dir between restarts.
+/// Used by both server, which saves entrypoint icons there, and plugin runtime,
+/// which caches results of icon processing keyed by source file.
+///
+/// Layout:
+/// - `content/<sha256 of icon>.png` - icon data, modification time is updated on every use and used for eviction
+/// - `sources/<sha256 of source key>` - sha256 of icon produced from source file
+/// - `tmp/` - files that are being written, renamed to their place when complete
+#[derive(Clone)]
+pub struct IconStore {
+    dir: PathBuf,
+    max_size_bytes: u64,
+}
+
+impl IconStore {
+    pub fn new(dirs: &Dirs) -> Self {
+        Self::with_dir(dirs.icon_cache_dir(), MAX_ICON_STORE_SIZE_BYTES)
+    }
+
+    pub fn with_dir(dir: PathBuf, max_size_bytes: u64) -> Self {
+        Self {
+            dir,
+            max_size_bytes,
+        }
+    }
+
+    /// Saves icon data and returns path to it, the same data is always saved to the same path
+    pub fn insert(&self, data: impl AsRef<[u8]>) -> anyhow::Result<PathBuf> {
+        let data = data.as_ref();
+        let hash = hex::encode(Sha256::digest(data));
+
+        let path = self.content_path(&hash);
+
+        if path.exists() {
+            touch(&path)?;
+        } else {
+            self.write_atomically(&path, data)?;
+        }
+
+        Ok(path)
+    }
+
+    /// Marks icon as recently used, so it is not evicted while it is still referenced
+    pub fn touch(&self, path: &Path) -> anyhow::Result<()> {
+        ignore_not_found(touch(path))
+    }
+
+    /// Returns processed icon for source file if it was already processed with the same size
+    /// and file didn't change since then, otherwise processes it using `process` and saves the result
+    pub fn get_or_insert_with(
+        &self,
+        source_path: &Path,
+        size: u32,
+        process: impl FnOnce() -> anyhow::Result<Vec<u8>>
+    ) -> anyhow::Result<Vec<u8>> {
+        let Ok(source_key) = source_key(source_path, size) else {
+            return process()
+        };
+
+        let source_entry_path = self.dir.join("sources").join(&source_key);
+
+        if let Some(data) = self.read_source_entry(&source_entry_path) {
+            return Ok(data)
+        }
+
+        let data = process()?;
+
+        // failing to save to the store shouldn't prevent icon from being used
+        let _ = self.write_source_entry(&source_entry_path, &data);
+
+        Ok(data)
+    }
+
+    /// Removes least recently used icons until store fits into size limit,
+    /// together with source entries pointing to them, stale temp files and files of previous cache layout.
+    /// Other processes may use the store at the same time, so files that disappear during eviction are skipped
+    pub fn evict(&self) -> anyhow::Result<()> {
+        if !self.dir.exists() {
+            return Ok(())
+        }
+
+        for entry in std::fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+
+            if name != "content" && name != "sources" && name != "tmp" {
+                let path = entry.path();
+
+                if path.is_dir() {
+                    ignore_not_found(std::fs::remove_dir_all(&path))?;
+                } else {
+                    ignore_not_found(std::fs::remove_file(&path))?;
+                }
+            }
+        }
+
+        let tmp_dir = self.dir.join("tmp");
+
+        if tmp_dir.exists() {
+            for entry in std::fs::read_dir(&tmp_dir)? {
+                let entry = entry?;
+
+                let Some(metadata) = ignore_not_found(entry.metadata().map(Some))? else {
+                    continue
+                };
+
+                let stale = metadata.modified()?
+                    .elapsed()
+                    .is_ok_and(|age| age > STALE_TEMP_FILE_AGE);
+
+                if stale {
+                    ignore_not_found(std::fs::remove_file(entry.path()))?;
+                }
+            }
+        }
+
+        let content_dir = self.dir.join("content");
+
+        if content_dir.exists() {
+            let mut icons = vec![];
+
+            for entry in std::fs::read_dir(&content_dir)? {
+                let entry = entry?;
+
+                let Some(metadata) = ignore_not_found(entry.metadata().map(Some))? else {
+                    continue
+                };
+
+                icons.push((entry.path(), metadata.len(), metadata.modified()?));
+            }
+
+            icons.sort_by(|(_, _, modified_a), (_, _, modified_b)| modified_b.cmp(modified_a));
+
+            let mut total_size = 0;
+
+            for (path, len, _) in icons {
+                total_size += len;
+
+                if total_size > self.max_size_bytes {
+                    ignore_not_found(std::fs::remove_file(&path))?;
+                }
+            }
+        }
+
+        let sources_dir = self.dir.join("sources");
+
+        if sources_dir.exists() {
+            for entry in std::fs::read_dir(&sources_dir)? {
+                let path = entry?.path();
+
+                let content_exists = std::fs::read_to_string(&path)
+                    .map(|hash| self.content_path(hash.trim()).exists())
+                    .unwrap_or(false);
+
+                if !content_exists {
+                    ignore_not_found(std::fs::remove_file(&path))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read_source_entry(&self, source_entry_path: &Path) -> Option<Vec<u8>> {
+        let hash = std::fs::read_to_string(source_entry_path).ok()?;
+        let path = self.content_path(hash.trim());
+
+        let data = std::fs::read(&path).ok()?;
+
+        // content could have been replaced by partially written file if it was modified manually
+        if hex::encode(Sha256::digest(&data)) != hash.trim() {
+            return None
+        }
+
+        let _ = touch(&path);
+
+        Some(data)
+    }
+
+    fn write_source_entry(&self, source_entry_path: &Path, data: &[u8]) -> anyhow::Result<()> {
+        let path = self.insert(data)?;
+
+        let hash = path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| anyhow!("unexpected icon path {:?}", path))?;
+
+        self.write_atomically(source_entry_path, hash.as_bytes())
+    }
+
+    /// Store is used by several processes at the same time, so files are never visible partially written.
+    /// Temp files are written to separate directory, so they are not mistaken for icons during eviction
+    fn write_atomically(&self, path: &Path, data: &[u8]) -> anyhow::Result<()> {
+        let parent = path.parent()
+            .ok_or_else(|| anyhow!("path doesn't have parent directory: {:?}", path))?;
+
+        let tmp_dir = self.dir.join("tmp");
+
+        std::fs::create_dir_all(parent)?;
+        std::fs::create_dir_all(&tmp_dir)?;
+
+        let file_name = path.file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| anyhow!("unexpected path {:?}", path))?;
+
+        let sequence = TEMP_FILE_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        let temp_path = tmp_dir.join(format!("{}.{}-{}.tmp", file_name, std::process::id(), sequence));
+
+        std::fs::write(&temp_path, data)?;
+        std::fs::rename(&temp_path, path)?;
+
+        Ok(())
+    }
+
+    fn content_path(&self, hash: &str) -> PathBuf {
+        self.dir.join("content").join(format!("{}.png", hash))
+    }
+}
+
+/// Key consists of source path, its modification time and size, and requested icon size
+fn source_key(source_path: &Path, size: u32) -> anyhow::Result<String> {
+    let metadata = std::fs::metadata(source_path)?;
+
+    let modified = metadata.modified()?
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0);
+
+    let source_path = source_path.to_str()
+        .ok_or_else(|| anyhow!("non-utf8 paths are not supported: {:?}", source_path))?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(source_path.as_bytes());
+    hasher.update([0]);
+    hasher.update(modified.to_le_bytes());
+    hasher.update(metadata.len().to_le_bytes());
+    hasher.update(size.to_le_bytes());
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn touch(path: &Path) -> std::io::Result<()> {
+    File::options()
+        .write(true)
+        .open(path)?
+        .set_modified(SystemTime::now())?;
+
+    Ok(())
+}
+
+fn ignore_not_found<T: Default>(result: std::io::Result<T>) -> anyhow::Result<T> {
+    match result {
+        Ok(value) => Ok(value),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(T::default()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use tempfile::TempDir;
+
+    fn test_store(max_size_bytes: u64) -> (TempDir, IconStore) {
+        let dir = tempfile::tempdir().unwrap();
+
+        let store = IconStore::with_dir(dir.path().join("icons"), max_size_bytes);
+
+        (dir, store)
+    }
+
+    fn write_source(dir: &TempDir, name: &str, data: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        std::fs::write(&path, data).unwrap();
+
+        path
+    }
+
+    fn set_modified(path: &Path, modified: SystemTime) {
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    #[test]
+    fn insert_is_content_addressed() {
+        let (_dir, store) = test_store(MAX_ICON_STORE_SIZE_BYTES);
+
+        let first = store.insert(b"icon").unwrap();
+        let second = store.insert(b"icon").unwrap();
+        let other = store.insert(b"other icon").unwrap();
+
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+        assert_eq!(std::fs::read(&first).unwrap(), b"icon");
+    }
+
+    #[test]
+    fn processed_icon_is_reused() {
+        let (dir, store) = test_store(MAX_ICON_STORE_SIZE_BYTES);
+        let source = write_source(&dir, "icon.svg", "<svg/>");
+        let processed = Cell::new(0);
+
+        let process = || {
+            processed.set(processed.get() + 1);
+            Ok(b"processed".to_vec())
+        };
+
+        assert_eq!(store.get_or_insert_with(&source, 48, process).unwrap(), b"processed");
+        assert_eq!(store.get_or_insert_with(&source, 48, process).unwrap(), b"processed");
+        assert_eq!(processed.get(), 1);
+
+        // reused by store created after restart
+        let store = IconStore::with_dir(store.dir.clone(), MAX_ICON_STORE_SIZE_BYTES);
+        assert_eq!(store.get_or_insert_with(&source, 48, process).unwrap(), b"processed");
+        assert_eq!(processed.get(), 1);
+
+        // different size is processed separately
+        assert_eq!(store.get_or_insert_with(&source, 32, process).unwrap(), b"processed");
+        assert_eq!(processed.get(), 2);
+    }
+
+    #[test]
+    fn changed_source_is_processed_again() {
+        let (dir, store) = test_store(MAX_ICON_STORE_SIZE_BYTES);
+        let source = write_source(&dir, "icon.png", "first");
+
+        let first = store.get_or_insert_with(&source, 48, || Ok(b"first".to_vec())).unwrap();
+        assert_eq!(first, b"first");
+
+        std::fs::write(&source, "second version").unwrap();
+
+        let second = store.get_or_insert_with(&source, 48, || Ok(b"second".to_vec())).unwrap();
+        assert_eq!(second, b"second");
+    }
+
+    #[test]
+    fn failed_processing_is_not_cached() {
+        let (dir, store) = test_store(MAX_ICON_STORE_SIZE_BYTES);
+        let source = write_source(&dir, "icon.xpm", "xpm");
+
+        assert!(store.get_or_insert_with(&source, 48, || Err(anyhow!("xpm format"))).is_err());
+        assert_eq!(store.get_or_insert_with(&source, 48, || Ok(b"icon".to_vec())).unwrap(), b"icon");
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let (dir, store) = test_store(10);
+
+        let old = store.insert(b"old icon").unwrap();
+        let recent = store.insert(b"recent").unwrap();
+
+        set_modified(&old, SystemTime::now() - Duration::from_secs(60));
+
+        let source = write_source(&dir, "old.png", "old");
+        store.get_or_insert_with(&source, 48, || Ok(b"old icon".to_vec())).unwrap();
+        set_modified(&old, SystemTime::now() - Duration::from_secs(60));
+
+        store.evict().unwrap();
+
+        assert!(!old.exists());
+        assert!(recent.exists());
+        assert_eq!(std::fs::read_dir(store.dir.join("sources")).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn evict_removes_previous_layout() {
+        let (_dir, store) = test_store(MAX_ICON_STORE_SIZE_BYTES);
+
+        let plugin_dir = store.dir.join("some-plugin-uuid");
+        std::fs::create_dir_all(&plugin_dir).unwrap();
+        std::fs::write(plugin_dir.join("some-entrypoint-uuid.png"), "icon").unwrap();
+
+        let icon = store.insert(b"icon").unwrap();
+
+        store.evict().unwrap();
+
+        assert!(!plugin_dir.exists());
+        assert!(icon.exists());
+    }
+
+    #[test]
+    fn temp_files_are_not_in_content() {
+        let (_dir, store) = test_store(MAX_ICON_STORE_SIZE_BYTES);
+
+        let icon = store.insert(b"icon").unwrap();
+
+        let content: Vec<_> = std::fs::read_dir(store.dir.join("content"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+
+        assert_eq!(content, vec![icon]);
+    }
+
+    #[test]
+    fn evict_keeps_fresh_temp_files() {
+        let (_dir, store) = test_store(0);
+
+        let tmp_dir = store.dir.join("tmp");
+        std::fs::create_dir_all(&tmp_dir).unwrap();
+
+        let fresh = tmp_dir.join("fresh.png.1-0.tmp");
+        std::fs::write(&fresh, "icon").unwrap();
+
+        let stale = tmp_dir.join("stale.png.1-1.tmp");
+        std::fs::write(&stale, "icon").unwrap();
+        set_modified(&stale, SystemTime::now() - STALE_TEMP_FILE_AGE * 2);
+
+        store.evict().unwrap();
+
+        assert!(fresh.exists());
+        assert!(!stale.exists());
+    }
+
+    #[test]
+    fn touched_icon_is_not_evicted() {
+        let (_dir, store) = test_store(10);
+
+        let referenced = store.insert(b"referenced").unwrap();
+        let other = store.insert(b"other").unwrap();
+
+        set_modified(&referenced, SystemTime::now() - Duration::from_secs(120));
+        set_modified(&other, SystemTime::now() - Duration::from_secs(60));
+
+        store.touch(&referenced).unwrap();
+        store.touch(&store.dir.join("content").join("missing.png")).unwrap();
+
+        store.evict().unwrap();
+
+        assert!(referenced.exists());
+        assert!(!other.exists());
+    }
+}
@@ -5,6 +5,7 @@ pub mod rpc;
 pub mod scenario_convert;
 pub mod scenario_model;
 pub mod dirs;
+pub mod icon_store;
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(tag = "type")]
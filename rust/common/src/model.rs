@@ -78,7 +78,10 @@ impl ToString for EntrypointId {
 #[derive(Debug, Clone)]
 pub enum DownloadStatus {
     InProgress,
-    Done,
+    Done {
+        // permissions requested by plugin, empty if all of them are already approved
+        permissions: Vec<SettingsPluginPermission>,
+    },
     Failed {
         message: String
     },
@@ -103,6 +106,8 @@ pub struct PhysicalShortcut {
 pub struct LocalSaveData {
     pub stdout_file_path: String,
     pub stderr_file_path: String,
+    // localhost port of v8 inspector, if enabled in config
+    pub inspector_port: Option<u16>,
 }
 
 #[derive(Debug, Clone)]
@@ -111,6 +116,7 @@ pub struct SearchResult {
     pub plugin_name: String,
     pub entrypoint_id: EntrypointId,
     pub entrypoint_name: String,
+    pub entrypoint_name_match_indices: Vec<usize>,
     pub entrypoint_generator_name: Option<String>,
     pub entrypoint_icon: Option<String>,
     pub entrypoint_type: SearchResultEntrypointType,
@@ -118,7 +124,7 @@ pub struct SearchResult {
     pub entrypoint_accessories: Vec<SearchResultAccessory>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SearchResultAccessory {
     TextAccessory {
         text: String,
@@ -144,7 +150,7 @@ pub enum SearchResultEntrypointActionType {
     View,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SearchResultEntrypointType {
     Command,
     View,
@@ -212,9 +218,19 @@ pub struct UiSetupResponse {
 #[derive(Debug)]
 pub enum UiResponseData {
     Nothing,
+    PermissionPrompt {
+        result: PermissionPromptResult
+    },
     Err(anyhow::Error),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermissionPromptResult {
+    AllowOnce,
+    AllowAlways,
+    Deny,
+}
+
 #[derive(Debug)]
 pub enum UiRequestData {
     ShowWindow,
@@ -241,6 +257,8 @@ pub enum UiRequestData {
         plugin_id: PluginId,
         entrypoint_id: EntrypointId,
         render_location: UiRenderLocation,
+        // set when plugin was terminated by backend, e.g. for exceeding resource limits
+        reason: Option<String>,
     },
     RequestSearchResultUpdate,
     ShowHud {
@@ -257,6 +275,12 @@ pub enum UiRequestData {
     SetTheme {
         theme: UiTheme
     },
+    RequestPermission {
+        plugin_id: PluginId,
+        plugin_name: String,
+        permission: String,
+        value: String,
+    },
 }
 
 #[derive(Debug)]
@@ -290,6 +314,11 @@ pub enum BackendRequestData {
     RequestViewClose {
         plugin_id: PluginId,
     },
+    SearchResultSelected {
+        query: String,
+        plugin_id: PluginId,
+        entrypoint_id: EntrypointId
+    },
     RequestRunCommand {
         plugin_id: PluginId,
         entrypoint_id: EntrypointId
@@ -638,6 +667,7 @@ pub struct SettingsEntrypoint {
     pub enabled: bool,
     pub preferences: HashMap<String, PluginPreference>,
     pub preferences_user_data: HashMap<String, PluginPreferenceUserData>,
+    pub aliases: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -649,6 +679,49 @@ pub struct SettingsPlugin {
     pub entrypoints: HashMap<EntrypointId, SettingsEntrypoint>,
     pub preferences: HashMap<String, PluginPreference>,
     pub preferences_user_data: HashMap<String, PluginPreferenceUserData>,
+    pub revision: Option<String>,
+    pub pinned_revision: Option<String>,
+    pub run_status: SettingsPluginRunStatus,
+    // permissions granted by user in permission prompt, in addition to ones in manifest
+    pub permission_grants: Vec<SettingsPluginPermissionGrant>,
+    // permissions requested in plugin manifest
+    pub permissions: Vec<SettingsPluginPermission>,
+    // permissions that need to be approved by user before plugin can run
+    pub unapproved_permissions: Vec<SettingsPluginPermission>,
+    // plugin was approved before, but an update requested additional permissions
+    pub permissions_changed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingsPluginPermission {
+    // name as in plugin manifest, e.g. "network" or "filesystem.read"
+    pub permission: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingsPluginPermissionGrant {
+    pub permission: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingsPluginRunStatus {
+    Stopped,
+    Running,
+    Restarting {
+        exit_code: Option<i32>,
+    },
+    Crashed {
+        exit_code: Option<i32>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct PluginUpdate {
+    pub plugin_id: PluginId,
+    pub current_revision: Option<String>,
+    pub latest_revision: String,
 }
 
 #[derive(Debug, Clone)]
@@ -659,6 +732,47 @@ pub enum SettingsEntrypointType {
     EntrypointGenerator,
 }
 
+#[derive(Debug, Clone)]
+pub struct FrecencyStats {
+    pub plugin_id: PluginId,
+    pub entrypoint_id: EntrypointId,
+    pub frecency: f64,
+    pub num_accesses: i32,
+    pub last_accessed_secs: f64, // since unix epoch
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditLogEntry {
+    pub plugin_id: PluginId,
+    pub entrypoint_id: Option<EntrypointId>,
+    pub capability: AuditLogCapability,
+    pub value: String,
+    pub timestamp_secs: i64, // since unix epoch
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum AuditLogCapability {
+    ClipboardRead,
+    ClipboardWrite,
+    Command,
+    Network,
+    FileWrite,
+}
+
+impl Display for AuditLogCapability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            AuditLogCapability::ClipboardRead => "Clipboard Read",
+            AuditLogCapability::ClipboardWrite => "Clipboard Write",
+            AuditLogCapability::Command => "Command",
+            AuditLogCapability::Network => "Network",
+            AuditLogCapability::FileWrite => "File Write",
+        };
+
+        write!(f, "{}", label)
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum SettingsTheme {
     AutoDetect,
@@ -949,193 +1063,196 @@ pub enum PhysicalKey {
 
 impl PhysicalKey {
     pub fn from_value(key: String) -> PhysicalKey {
-        match key.as_str() {
-            "Backquote" => PhysicalKey::Backquote,
-            "Backslash" => PhysicalKey::Backslash,
-            "BracketLeft" => PhysicalKey::BracketLeft,
-            "BracketRight" => PhysicalKey::BracketRight,
-            "Comma" => PhysicalKey::Comma,
-            "Digit0" => PhysicalKey::Digit0,
-            "Digit1" => PhysicalKey::Digit1,
-            "Digit2" => PhysicalKey::Digit2,
-            "Digit3" => PhysicalKey::Digit3,
-            "Digit4" => PhysicalKey::Digit4,
-            "Digit5" => PhysicalKey::Digit5,
-            "Digit6" => PhysicalKey::Digit6,
-            "Digit7" => PhysicalKey::Digit7,
-            "Digit8" => PhysicalKey::Digit8,
-            "Digit9" => PhysicalKey::Digit9,
-            "Equal" => PhysicalKey::Equal,
-            "IntlBackslash" => PhysicalKey::IntlBackslash,
-            "IntlRo" => PhysicalKey::IntlRo,
-            "IntlYen" => PhysicalKey::IntlYen,
-            "KeyA" => PhysicalKey::KeyA,
-            "KeyB" => PhysicalKey::KeyB,
-            "KeyC" => PhysicalKey::KeyC,
-            "KeyD" => PhysicalKey::KeyD,
-            "KeyE" => PhysicalKey::KeyE,
-            "KeyF" => PhysicalKey::KeyF,
-            "KeyG" => PhysicalKey::KeyG,
-            "KeyH" => PhysicalKey::KeyH,
-            "KeyI" => PhysicalKey::KeyI,
-            "KeyJ" => PhysicalKey::KeyJ,
-            "KeyK" => PhysicalKey::KeyK,
-            "KeyL" => PhysicalKey::KeyL,
-            "KeyM" => PhysicalKey::KeyM,
-            "KeyN" => PhysicalKey::KeyN,
-            "KeyO" => PhysicalKey::KeyO,
-            "KeyP" => PhysicalKey::KeyP,
-            "KeyQ" => PhysicalKey::KeyQ,
-            "KeyR" => PhysicalKey::KeyR,
-            "KeyS" => PhysicalKey::KeyS,
-            "KeyT" => PhysicalKey::KeyT,
-            "KeyU" => PhysicalKey::KeyU,
-            "KeyV" => PhysicalKey::KeyV,
-            "KeyW" => PhysicalKey::KeyW,
-            "KeyX" => PhysicalKey::KeyX,
-            "KeyY" => PhysicalKey::KeyY,
-            "KeyZ" => PhysicalKey::KeyZ,
-            "Minus" => PhysicalKey::Minus,
-            "Period" => PhysicalKey::Period,
-            "Quote" => PhysicalKey::Quote,
-            "Semicolon" => PhysicalKey::Semicolon,
-            "Slash" => PhysicalKey::Slash,
-            "Backspace" => PhysicalKey::Backspace,
-            "CapsLock" => PhysicalKey::CapsLock,
-            "ContextMenu" => PhysicalKey::ContextMenu,
-            "Enter" => PhysicalKey::Enter,
-            "Space" => PhysicalKey::Space,
-            "Tab" => PhysicalKey::Tab,
-            "Convert" => PhysicalKey::Convert,
-            "KanaMode" => PhysicalKey::KanaMode,
-            "Lang1" => PhysicalKey::Lang1,
-            "Lang2" => PhysicalKey::Lang2,
-            "Lang3" => PhysicalKey::Lang3,
-            "Lang4" => PhysicalKey::Lang4,
-            "Lang5" => PhysicalKey::Lang5,
-            "NonConvert" => PhysicalKey::NonConvert,
-            "Delete" => PhysicalKey::Delete,
-            "End" => PhysicalKey::End,
-            "Help" => PhysicalKey::Help,
-            "Home" => PhysicalKey::Home,
-            "Insert" => PhysicalKey::Insert,
-            "PageDown" => PhysicalKey::PageDown,
-            "PageUp" => PhysicalKey::PageUp,
-            "ArrowDown" => PhysicalKey::ArrowDown,
-            "ArrowLeft" => PhysicalKey::ArrowLeft,
-            "ArrowRight" => PhysicalKey::ArrowRight,
-            "ArrowUp" => PhysicalKey::ArrowUp,
-            "NumLock" => PhysicalKey::NumLock,
-            "Numpad0" => PhysicalKey::Numpad0,
-            "Numpad1" => PhysicalKey::Numpad1,
-            "Numpad2" => PhysicalKey::Numpad2,
-            "Numpad3" => PhysicalKey::Numpad3,
-            "Numpad4" => PhysicalKey::Numpad4,
-            "Numpad5" => PhysicalKey::Numpad5,
-            "Numpad6" => PhysicalKey::Numpad6,
-            "Numpad7" => PhysicalKey::Numpad7,
-            "Numpad8" => PhysicalKey::Numpad8,
-            "Numpad9" => PhysicalKey::Numpad9,
-            "NumpadAdd" => PhysicalKey::NumpadAdd,
-            "NumpadBackspace" => PhysicalKey::NumpadBackspace,
-            "NumpadClear" => PhysicalKey::NumpadClear,
-            "NumpadClearEntry" => PhysicalKey::NumpadClearEntry,
-            "NumpadComma" => PhysicalKey::NumpadComma,
-            "NumpadDecimal" => PhysicalKey::NumpadDecimal,
-            "NumpadDivide" => PhysicalKey::NumpadDivide,
-            "NumpadEnter" => PhysicalKey::NumpadEnter,
-            "NumpadEqual" => PhysicalKey::NumpadEqual,
-            "NumpadHash" => PhysicalKey::NumpadHash,
-            "NumpadMemoryAdd" => PhysicalKey::NumpadMemoryAdd,
-            "NumpadMemoryClear" => PhysicalKey::NumpadMemoryClear,
-            "NumpadMemoryRecall" => PhysicalKey::NumpadMemoryRecall,
-            "NumpadMemoryStore" => PhysicalKey::NumpadMemoryStore,
-            "NumpadMemorySubtract" => PhysicalKey::NumpadMemorySubtract,
-            "NumpadMultiply" => PhysicalKey::NumpadMultiply,
-            "NumpadParenLeft" => PhysicalKey::NumpadParenLeft,
-            "NumpadParenRight" => PhysicalKey::NumpadParenRight,
-            "NumpadStar" => PhysicalKey::NumpadStar,
-            "NumpadSubtract" => PhysicalKey::NumpadSubtract,
-            "Escape" => PhysicalKey::Escape,
-            "Fn" => PhysicalKey::Fn,
-            "FnLock" => PhysicalKey::FnLock,
-            "PrintScreen" => PhysicalKey::PrintScreen,
-            "ScrollLock" => PhysicalKey::ScrollLock,
-            "Pause" => PhysicalKey::Pause,
-            "BrowserBack" => PhysicalKey::BrowserBack,
-            "BrowserFavorites" => PhysicalKey::BrowserFavorites,
-            "BrowserForward" => PhysicalKey::BrowserForward,
-            "BrowserHome" => PhysicalKey::BrowserHome,
-            "BrowserRefresh" => PhysicalKey::BrowserRefresh,
-            "BrowserSearch" => PhysicalKey::BrowserSearch,
-            "BrowserStop" => PhysicalKey::BrowserStop,
-            "Eject" => PhysicalKey::Eject,
-            "LaunchApp1" => PhysicalKey::LaunchApp1,
-            "LaunchApp2" => PhysicalKey::LaunchApp2,
-            "LaunchMail" => PhysicalKey::LaunchMail,
-            "MediaPlayPause" => PhysicalKey::MediaPlayPause,
-            "MediaSelect" => PhysicalKey::MediaSelect,
-            "MediaStop" => PhysicalKey::MediaStop,
-            "MediaTrackNext" => PhysicalKey::MediaTrackNext,
-            "MediaTrackPrevious" => PhysicalKey::MediaTrackPrevious,
-            "Power" => PhysicalKey::Power,
-            "Sleep" => PhysicalKey::Sleep,
-            "AudioVolumeDown" => PhysicalKey::AudioVolumeDown,
-            "AudioVolumeMute" => PhysicalKey::AudioVolumeMute,
-            "AudioVolumeUp" => PhysicalKey::AudioVolumeUp,
-            "WakeUp" => PhysicalKey::WakeUp,
-            "Abort" => PhysicalKey::Abort,
-            "Resume" => PhysicalKey::Resume,
-            "Suspend" => PhysicalKey::Suspend,
-            "Again" => PhysicalKey::Again,
-            "Copy" => PhysicalKey::Copy,
-            "Cut" => PhysicalKey::Cut,
-            "Find" => PhysicalKey::Find,
-            "Open" => PhysicalKey::Open,
-            "Paste" => PhysicalKey::Paste,
-            "Props" => PhysicalKey::Props,
-            "Select" => PhysicalKey::Select,
-            "Undo" => PhysicalKey::Undo,
-            "Hiragana" => PhysicalKey::Hiragana,
-            "Katakana" => PhysicalKey::Katakana,
-            "F1" => PhysicalKey::F1,
-            "F2" => PhysicalKey::F2,
-            "F3" => PhysicalKey::F3,
-            "F4" => PhysicalKey::F4,
-            "F5" => PhysicalKey::F5,
-            "F6" => PhysicalKey::F6,
-            "F7" => PhysicalKey::F7,
-            "F8" => PhysicalKey::F8,
-            "F9" => PhysicalKey::F9,
-            "F10" => PhysicalKey::F10,
-            "F11" => PhysicalKey::F11,
-            "F12" => PhysicalKey::F12,
-            "F13" => PhysicalKey::F13,
-            "F14" => PhysicalKey::F14,
-            "F15" => PhysicalKey::F15,
-            "F16" => PhysicalKey::F16,
-            "F17" => PhysicalKey::F17,
-            "F18" => PhysicalKey::F18,
-            "F19" => PhysicalKey::F19,
-            "F20" => PhysicalKey::F20,
-            "F21" => PhysicalKey::F21,
-            "F22" => PhysicalKey::F22,
-            "F23" => PhysicalKey::F23,
-            "F24" => PhysicalKey::F24,
-            "F25" => PhysicalKey::F25,
-            "F26" => PhysicalKey::F26,
-            "F27" => PhysicalKey::F27,
-            "F28" => PhysicalKey::F28,
-            "F29" => PhysicalKey::F29,
-            "F30" => PhysicalKey::F30,
-            "F31" => PhysicalKey::F31,
-            "F32" => PhysicalKey::F32,
-            "F33" => PhysicalKey::F33,
-            "F34" => PhysicalKey::F34,
-            "F35" => PhysicalKey::F35,
-            _ => {
-                panic!("unknown key: {}", key)
-            }
+        PhysicalKey::try_from_value(&key)
+            .unwrap_or_else(|| panic!("unknown key: {}", key))
+    }
+
+    pub fn try_from_value(key: &str) -> Option<PhysicalKey> {
+        match key {
+            "Backquote" => Some(PhysicalKey::Backquote),
+            "Backslash" => Some(PhysicalKey::Backslash),
+            "BracketLeft" => Some(PhysicalKey::BracketLeft),
+            "BracketRight" => Some(PhysicalKey::BracketRight),
+            "Comma" => Some(PhysicalKey::Comma),
+            "Digit0" => Some(PhysicalKey::Digit0),
+            "Digit1" => Some(PhysicalKey::Digit1),
+            "Digit2" => Some(PhysicalKey::Digit2),
+            "Digit3" => Some(PhysicalKey::Digit3),
+            "Digit4" => Some(PhysicalKey::Digit4),
+            "Digit5" => Some(PhysicalKey::Digit5),
+            "Digit6" => Some(PhysicalKey::Digit6),
+            "Digit7" => Some(PhysicalKey::Digit7),
+            "Digit8" => Some(PhysicalKey::Digit8),
+            "Digit9" => Some(PhysicalKey::Digit9),
+            "Equal" => Some(PhysicalKey::Equal),
+            "IntlBackslash" => Some(PhysicalKey::IntlBackslash),
+            "IntlRo" => Some(PhysicalKey::IntlRo),
+            "IntlYen" => Some(PhysicalKey::IntlYen),
+            "KeyA" => Some(PhysicalKey::KeyA),
+            "KeyB" => Some(PhysicalKey::KeyB),
+            "KeyC" => Some(PhysicalKey::KeyC),
+            "KeyD" => Some(PhysicalKey::KeyD),
+            "KeyE" => Some(PhysicalKey::KeyE),
+            "KeyF" => Some(PhysicalKey::KeyF),
+            "KeyG" => Some(PhysicalKey::KeyG),
+            "KeyH" => Some(PhysicalKey::KeyH),
+            "KeyI" => Some(PhysicalKey::KeyI),
+            "KeyJ" => Some(PhysicalKey::KeyJ),
+            "KeyK" => Some(PhysicalKey::KeyK),
+            "KeyL" => Some(PhysicalKey::KeyL),
+            "KeyM" => Some(PhysicalKey::KeyM),
+            "KeyN" => Some(PhysicalKey::KeyN),
+            "KeyO" => Some(PhysicalKey::KeyO),
+            "KeyP" => Some(PhysicalKey::KeyP),
+            "KeyQ" => Some(PhysicalKey::KeyQ),
+            "KeyR" => Some(PhysicalKey::KeyR),
+            "KeyS" => Some(PhysicalKey::KeyS),
+            "KeyT" => Some(PhysicalKey::KeyT),
+            "KeyU" => Some(PhysicalKey::KeyU),
+            "KeyV" => Some(PhysicalKey::KeyV),
+            "KeyW" => Some(PhysicalKey::KeyW),
+            "KeyX" => Some(PhysicalKey::KeyX),
+            "KeyY" => Some(PhysicalKey::KeyY),
+            "KeyZ" => Some(PhysicalKey::KeyZ),
+            "Minus" => Some(PhysicalKey::Minus),
+            "Period" => Some(PhysicalKey::Period),
+            "Quote" => Some(PhysicalKey::Quote),
+            "Semicolon" => Some(PhysicalKey::Semicolon),
+            "Slash" => Some(PhysicalKey::Slash),
+            "Backspace" => Some(PhysicalKey::Backspace),
+            "CapsLock" => Some(PhysicalKey::CapsLock),
+            "ContextMenu" => Some(PhysicalKey::ContextMenu),
+            "Enter" => Some(PhysicalKey::Enter),
+            "Space" => Some(PhysicalKey::Space),
+            "Tab" => Some(PhysicalKey::Tab),
+            "Convert" => Some(PhysicalKey::Convert),
+            "KanaMode" => Some(PhysicalKey::KanaMode),
+            "Lang1" => Some(PhysicalKey::Lang1),
+            "Lang2" => Some(PhysicalKey::Lang2),
+            "Lang3" => Some(PhysicalKey::Lang3),
+            "Lang4" => Some(PhysicalKey::Lang4),
+            "Lang5" => Some(PhysicalKey::Lang5),
+            "NonConvert" => Some(PhysicalKey::NonConvert),
+            "Delete" => Some(PhysicalKey::Delete),
+            "End" => Some(PhysicalKey::End),
+            "Help" => Some(PhysicalKey::Help),
+            "Home" => Some(PhysicalKey::Home),
+            "Insert" => Some(PhysicalKey::Insert),
+            "PageDown" => Some(PhysicalKey::PageDown),
+            "PageUp" => Some(PhysicalKey::PageUp),
+            "ArrowDown" => Some(PhysicalKey::ArrowDown),
+            "ArrowLeft" => Some(PhysicalKey::ArrowLeft),
+            "ArrowRight" => Some(PhysicalKey::ArrowRight),
+            "ArrowUp" => Some(PhysicalKey::ArrowUp),
+            "NumLock" => Some(PhysicalKey::NumLock),
+            "Numpad0" => Some(PhysicalKey::Numpad0),
+            "Numpad1" => Some(PhysicalKey::Numpad1),
+            "Numpad2" => Some(PhysicalKey::Numpad2),
+            "Numpad3" => Some(PhysicalKey::Numpad3),
+            "Numpad4" => Some(PhysicalKey::Numpad4),
+            "Numpad5" => Some(PhysicalKey::Numpad5),
+            "Numpad6" => Some(PhysicalKey::Numpad6),
+            "Numpad7" => Some(PhysicalKey::Numpad7),
+            "Numpad8" => Some(PhysicalKey::Numpad8),
+            "Numpad9" => Some(PhysicalKey::Numpad9),
+            "NumpadAdd" => Some(PhysicalKey::NumpadAdd),
+            "NumpadBackspace" => Some(PhysicalKey::NumpadBackspace),
+            "NumpadClear" => Some(PhysicalKey::NumpadClear),
+            "NumpadClearEntry" => Some(PhysicalKey::NumpadClearEntry),
+            "NumpadComma" => Some(PhysicalKey::NumpadComma),
+            "NumpadDecimal" => Some(PhysicalKey::NumpadDecimal),
+            "NumpadDivide" => Some(PhysicalKey::NumpadDivide),
+            "NumpadEnter" => Some(PhysicalKey::NumpadEnter),
+            "NumpadEqual" => Some(PhysicalKey::NumpadEqual),
+            "NumpadHash" => Some(PhysicalKey::NumpadHash),
+            "NumpadMemoryAdd" => Some(PhysicalKey::NumpadMemoryAdd),
+            "NumpadMemoryClear" => Some(PhysicalKey::NumpadMemoryClear),
+            "NumpadMemoryRecall" => Some(PhysicalKey::NumpadMemoryRecall),
+            "NumpadMemoryStore" => Some(PhysicalKey::NumpadMemoryStore),
+            "NumpadMemorySubtract" => Some(PhysicalKey::NumpadMemorySubtract),
+            "NumpadMultiply" => Some(PhysicalKey::NumpadMultiply),
+            "NumpadParenLeft" => Some(PhysicalKey::NumpadParenLeft),
+            "NumpadParenRight" => Some(PhysicalKey::NumpadParenRight),
+            "NumpadStar" => Some(PhysicalKey::NumpadStar),
+            "NumpadSubtract" => Some(PhysicalKey::NumpadSubtract),
+            "Escape" => Some(PhysicalKey::Escape),
+            "Fn" => Some(PhysicalKey::Fn),
+            "FnLock" => Some(PhysicalKey::FnLock),
+            "PrintScreen" => Some(PhysicalKey::PrintScreen),
+            "ScrollLock" => Some(PhysicalKey::ScrollLock),
+            "Pause" => Some(PhysicalKey::Pause),
+            "BrowserBack" => Some(PhysicalKey::BrowserBack),
+            "BrowserFavorites" => Some(PhysicalKey::BrowserFavorites),
+            "BrowserForward" => Some(PhysicalKey::BrowserForward),
+            "BrowserHome" => Some(PhysicalKey::BrowserHome),
+            "BrowserRefresh" => Some(PhysicalKey::BrowserRefresh),
+            "BrowserSearch" => Some(PhysicalKey::BrowserSearch),
+            "BrowserStop" => Some(PhysicalKey::BrowserStop),
+            "Eject" => Some(PhysicalKey::Eject),
+            "LaunchApp1" => Some(PhysicalKey::LaunchApp1),
+            "LaunchApp2" => Some(PhysicalKey::LaunchApp2),
+            "LaunchMail" => Some(PhysicalKey::LaunchMail),
+            "MediaPlayPause" => Some(PhysicalKey::MediaPlayPause),
+            "MediaSelect" => Some(PhysicalKey::MediaSelect),
+            "MediaStop" => Some(PhysicalKey::MediaStop),
+            "MediaTrackNext" => Some(PhysicalKey::MediaTrackNext),
+            "MediaTrackPrevious" => Some(PhysicalKey::MediaTrackPrevious),
+            "Power" => Some(PhysicalKey::Power),
+            "Sleep" => Some(PhysicalKey::Sleep),
+            "AudioVolumeDown" => Some(PhysicalKey::AudioVolumeDown),
+            "AudioVolumeMute" => Some(PhysicalKey::AudioVolumeMute),
+            "AudioVolumeUp" => Some(PhysicalKey::AudioVolumeUp),
+            "WakeUp" => Some(PhysicalKey::WakeUp),
+            "Abort" => Some(PhysicalKey::Abort),
+            "Resume" => Some(PhysicalKey::Resume),
+            "Suspend" => Some(PhysicalKey::Suspend),
+            "Again" => Some(PhysicalKey::Again),
+            "Copy" => Some(PhysicalKey::Copy),
+            "Cut" => Some(PhysicalKey::Cut),
+            "Find" => Some(PhysicalKey::Find),
+            "Open" => Some(PhysicalKey::Open),
+            "Paste" => Some(PhysicalKey::Paste),
+            "Props" => Some(PhysicalKey::Props),
+            "Select" => Some(PhysicalKey::Select),
+            "Undo" => Some(PhysicalKey::Undo),
+            "Hiragana" => Some(PhysicalKey::Hiragana),
+            "Katakana" => Some(PhysicalKey::Katakana),
+            "F1" => Some(PhysicalKey::F1),
+            "F2" => Some(PhysicalKey::F2),
+            "F3" => Some(PhysicalKey::F3),
+            "F4" => Some(PhysicalKey::F4),
+            "F5" => Some(PhysicalKey::F5),
+            "F6" => Some(PhysicalKey::F6),
+            "F7" => Some(PhysicalKey::F7),
+            "F8" => Some(PhysicalKey::F8),
+            "F9" => Some(PhysicalKey::F9),
+            "F10" => Some(PhysicalKey::F10),
+            "F11" => Some(PhysicalKey::F11),
+            "F12" => Some(PhysicalKey::F12),
+            "F13" => Some(PhysicalKey::F13),
+            "F14" => Some(PhysicalKey::F14),
+            "F15" => Some(PhysicalKey::F15),
+            "F16" => Some(PhysicalKey::F16),
+            "F17" => Some(PhysicalKey::F17),
+            "F18" => Some(PhysicalKey::F18),
+            "F19" => Some(PhysicalKey::F19),
+            "F20" => Some(PhysicalKey::F20),
+            "F21" => Some(PhysicalKey::F21),
+            "F22" => Some(PhysicalKey::F22),
+            "F23" => Some(PhysicalKey::F23),
+            "F24" => Some(PhysicalKey::F24),
+            "F25" => Some(PhysicalKey::F25),
+            "F26" => Some(PhysicalKey::F26),
+            "F27" => Some(PhysicalKey::F27),
+            "F28" => Some(PhysicalKey::F28),
+            "F29" => Some(PhysicalKey::F29),
+            "F30" => Some(PhysicalKey::F30),
+            "F31" => Some(PhysicalKey::F31),
+            "F32" => Some(PhysicalKey::F32),
+            "F33" => Some(PhysicalKey::F33),
+            "F34" => Some(PhysicalKey::F34),
+            "F35" => Some(PhysicalKey::F35),
+            _ => None
         }
     }
 
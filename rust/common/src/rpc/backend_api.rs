@@ -5,10 +5,10 @@ use tonic::transport::Channel;
 
 use gauntlet_utils::channel::{RequestError, RequestSender};
 
-use crate::model::{BackendRequestData, BackendResponseData, DownloadStatus, EntrypointId, KeyboardEventOrigin, LocalSaveData, PhysicalKey, PhysicalShortcut, PluginId, PluginPreferenceUserData, SearchResult, SettingsEntrypoint, SettingsEntrypointType, SettingsPlugin, SettingsTheme, UiPropertyValue, UiSetupData, UiWidgetId};
-use crate::rpc::grpc::{RpcDownloadPluginRequest, RpcDownloadStatus, RpcDownloadStatusRequest, RpcEntrypointTypeSettings, RpcGetGlobalShortcutRequest, RpcGetThemeRequest, RpcPingRequest, RpcPluginsRequest, RpcRemovePluginRequest, RpcSaveLocalPluginRequest, RpcSetEntrypointStateRequest, RpcSetGlobalShortcutRequest, RpcSetPluginStateRequest, RpcSetPreferenceValueRequest, RpcSetThemeRequest, RpcShortcut, RpcShowSettingsWindowRequest, RpcShowWindowRequest};
+use crate::model::{AuditLogCapability, AuditLogEntry, BackendRequestData, BackendResponseData, DownloadStatus, EntrypointId, FrecencyStats, KeyboardEventOrigin, LocalSaveData, PhysicalKey, PhysicalShortcut, PluginId, PluginPreferenceUserData, PluginUpdate, SearchResult, SettingsEntrypoint, SettingsEntrypointType, SettingsPlugin, SettingsPluginPermission, SettingsPluginPermissionGrant, SettingsPluginRunStatus, SettingsTheme, UiPropertyValue, UiSetupData, UiWidgetId};
+use crate::rpc::grpc::{RpcApprovePluginPermissionsRequest, RpcAuditLogCapability, RpcAuditLogRequest, RpcCheckUpdatesRequest, RpcDownloadPluginRequest, RpcDownloadStatus, RpcDownloadStatusRequest, RpcEntrypointTypeSettings, RpcFrecencyStatsRequest, RpcGetFrecencyHalfLifeRequest, RpcGetGlobalShortcutRequest, RpcGetThemeRequest, RpcInstallPluginRequest, RpcPingRequest, RpcPluginPermission, RpcPluginRunStatus, RpcPluginsRequest, RpcRemovePluginRequest, RpcResetAllFrecencyRequest, RpcResetEntrypointFrecencyRequest, RpcRevokePermissionGrantRequest, RpcSaveLocalPluginRequest, RpcSetEntrypointAliasesRequest, RpcSetEntrypointStateRequest, RpcSetFrecencyHalfLifeRequest, RpcSettingsReadOnlyRequest, RpcSetGlobalShortcutRequest, RpcSetPluginStateRequest, RpcSetPreferenceValueRequest, RpcSetThemeRequest, RpcShortcut, RpcShowSettingsWindowRequest, RpcShowWindowRequest, RpcUpdatePluginRequest};
 use crate::rpc::grpc::rpc_backend_client::RpcBackendClient;
-use crate::rpc::grpc_convert::{plugin_preference_from_rpc, plugin_preference_user_data_from_rpc, plugin_preference_user_data_to_rpc};
+use crate::rpc::grpc_convert::{audit_log_capability_from_rpc, audit_log_capability_to_rpc, plugin_preference_from_rpc, plugin_preference_user_data_from_rpc, plugin_preference_user_data_to_rpc};
 
 #[derive(Error, Debug, Clone)]
 pub enum BackendForFrontendApiError {
@@ -101,6 +101,20 @@ impl BackendForFrontendApi {
         Ok(())
     }
 
+    pub async fn search_result_selected(&mut self, query: String, plugin_id: PluginId, entrypoint_id: EntrypointId) -> Result<(), BackendForFrontendApiError> {
+        let request = BackendRequestData::SearchResultSelected {
+            query,
+            plugin_id,
+            entrypoint_id,
+        };
+
+        let BackendResponseData::Nothing = self.backend_sender.send_receive(request).await? else {
+            unreachable!()
+        };
+
+        Ok(())
+    }
+
     pub async fn request_run_command(&mut self, plugin_id: PluginId, entrypoint_id: EntrypointId) -> Result<(), BackendForFrontendApiError> {
         let request = BackendRequestData::RequestRunCommand {
             plugin_id,
@@ -322,11 +336,19 @@ impl BackendApi {
                             preferences_user_data: entrypoint.preferences_user_data.into_iter()
                                 .map(|(key, value)| (key, plugin_preference_user_data_from_rpc(value)))
                                 .collect(),
+                            aliases: entrypoint.aliases,
                         };
                         (id, entrypoint)
                     })
                     .collect();
 
+                let run_status = match plugin.run_status() {
+                    RpcPluginRunStatus::Stopped => SettingsPluginRunStatus::Stopped,
+                    RpcPluginRunStatus::Running => SettingsPluginRunStatus::Running,
+                    RpcPluginRunStatus::Restarting => SettingsPluginRunStatus::Restarting { exit_code: plugin.exit_code },
+                    RpcPluginRunStatus::Crashed => SettingsPluginRunStatus::Crashed { exit_code: plugin.exit_code },
+                };
+
                 let id = PluginId::from_string(plugin.plugin_id);
                 let plugin = SettingsPlugin {
                     plugin_id: id.clone(),
@@ -340,6 +362,19 @@ impl BackendApi {
                     preferences_user_data: plugin.preferences_user_data.into_iter()
                         .map(|(key, value)| (key, plugin_preference_user_data_from_rpc(value)))
                         .collect(),
+                    revision: plugin.revision,
+                    pinned_revision: plugin.pinned_revision,
+                    run_status,
+                    permission_grants: plugin.permission_grants.into_iter()
+                        .map(|grant| SettingsPluginPermissionGrant { permission: grant.permission, value: grant.value })
+                        .collect(),
+                    permissions: plugin.permissions.into_iter()
+                        .map(|permission| SettingsPluginPermission { permission: permission.permission, value: permission.value })
+                        .collect(),
+                    unapproved_permissions: plugin.unapproved_permissions.into_iter()
+                        .map(|permission| SettingsPluginPermission { permission: permission.permission, value: permission.value })
+                        .collect(),
+                    permissions_changed: plugin.permissions_changed,
                 };
 
                 (id, plugin)
@@ -374,6 +409,19 @@ impl BackendApi {
         Ok(())
     }
 
+    pub async fn set_entrypoint_aliases(&mut self, plugin_id: PluginId, entrypoint_id: EntrypointId, aliases: Vec<String>) -> Result<(), BackendApiError> {
+        let request = RpcSetEntrypointAliasesRequest {
+            plugin_id: plugin_id.to_string(),
+            entrypoint_id: entrypoint_id.to_string(),
+            aliases,
+        };
+
+        self.client.set_entrypoint_aliases(Request::new(request))
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn set_global_shortcut(&mut self, shortcut: Option<PhysicalShortcut>) -> Result<(), BackendApiError> {
         let request = RpcSetGlobalShortcutRequest {
             shortcut: shortcut.map(|shortcut| {
@@ -453,6 +501,74 @@ impl BackendApi {
         Ok(theme)
     }
 
+    pub async fn settings_read_only(&mut self) -> Result<bool, BackendApiError> {
+        let response = self.client.settings_read_only(Request::new(RpcSettingsReadOnlyRequest::default()))
+            .await?;
+
+        Ok(response.into_inner().read_only)
+    }
+
+    pub async fn set_frecency_half_life(&mut self, half_life_secs: f64) -> Result<(), BackendApiError> {
+        let request = RpcSetFrecencyHalfLifeRequest {
+            half_life_secs
+        };
+
+        self.client.set_frecency_half_life(Request::new(request))
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_frecency_half_life(&mut self) -> Result<f64, BackendApiError> {
+        let response = self.client.get_frecency_half_life(Request::new(RpcGetFrecencyHalfLifeRequest::default()))
+            .await?;
+
+        Ok(response.into_inner().half_life_secs)
+    }
+
+    pub async fn frecency_stats(&mut self, plugin_id: PluginId, entrypoint_id: Option<EntrypointId>) -> Result<Vec<FrecencyStats>, BackendApiError> {
+        let request = RpcFrecencyStatsRequest {
+            plugin_id: plugin_id.to_string(),
+            entrypoint_id: entrypoint_id.map(|id| id.to_string()),
+        };
+
+        let response = self.client.frecency_stats(Request::new(request))
+            .await?;
+
+        let stats = response.into_inner()
+            .stats
+            .into_iter()
+            .map(|stats| FrecencyStats {
+                plugin_id: PluginId::from_string(stats.plugin_id),
+                entrypoint_id: EntrypointId::from_string(stats.entrypoint_id),
+                frecency: stats.frecency,
+                num_accesses: stats.num_accesses,
+                last_accessed_secs: stats.last_accessed_secs,
+            })
+            .collect();
+
+        Ok(stats)
+    }
+
+    pub async fn reset_entrypoint_frecency(&mut self, plugin_id: PluginId, entrypoint_id: EntrypointId) -> Result<(), BackendApiError> {
+        let request = RpcResetEntrypointFrecencyRequest {
+            plugin_id: plugin_id.to_string(),
+            entrypoint_id: entrypoint_id.to_string(),
+        };
+
+        self.client.reset_entrypoint_frecency(Request::new(request))
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn reset_all_frecency(&mut self) -> Result<(), BackendApiError> {
+        self.client.reset_all_frecency(Request::new(RpcResetAllFrecencyRequest::default()))
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn set_preference_value(&mut self, plugin_id: PluginId, entrypoint_id: Option<EntrypointId>, id: String, user_data: PluginPreferenceUserData) -> Result<(), BackendApiError> {
         let request = RpcSetPreferenceValueRequest {
             plugin_id: plugin_id.to_string(),
@@ -467,9 +583,10 @@ impl BackendApi {
         Ok(())
     }
 
-    pub async fn download_plugin(&mut self, plugin_id: PluginId) -> Result<(), BackendApiError> {
+    pub async fn download_plugin(&mut self, plugin_id: PluginId, revision: Option<String>) -> Result<(), BackendApiError> {
         let request = RpcDownloadPluginRequest {
-            plugin_id: plugin_id.to_string()
+            plugin_id: plugin_id.to_string(),
+            revision,
         };
 
         self.client.download_plugin(Request::new(request))
@@ -478,6 +595,46 @@ impl BackendApi {
         Ok(())
     }
 
+    pub async fn install_plugin(&mut self, path: String) -> Result<PluginId, BackendApiError> {
+        let request = RpcInstallPluginRequest {
+            path,
+        };
+
+        let plugin_id = self.client.install_plugin(Request::new(request))
+            .await?
+            .into_inner()
+            .plugin_id;
+
+        Ok(PluginId::from_string(plugin_id))
+    }
+
+    pub async fn check_updates(&mut self) -> Result<Vec<PluginUpdate>, BackendApiError> {
+        let updates = self.client.check_updates(Request::new(RpcCheckUpdatesRequest::default()))
+            .await?
+            .into_inner()
+            .updates
+            .into_iter()
+            .map(|update| PluginUpdate {
+                plugin_id: PluginId::from_string(update.plugin_id),
+                current_revision: update.current_revision,
+                latest_revision: update.latest_revision,
+            })
+            .collect();
+
+        Ok(updates)
+    }
+
+    pub async fn update_plugin(&mut self, plugin_id: PluginId) -> Result<(), BackendApiError> {
+        let request = RpcUpdatePluginRequest {
+            plugin_id: plugin_id.to_string(),
+        };
+
+        self.client.update_plugin(Request::new(request))
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn download_status(&mut self) -> Result<HashMap<PluginId, DownloadStatus>, BackendApiError> {
         let plugins = self.client.download_status(Request::new(RpcDownloadStatusRequest::default()))
             .await?
@@ -489,7 +646,11 @@ impl BackendApi {
 
                 let status = match status.status.try_into()? {
                     RpcDownloadStatus::InProgress => DownloadStatus::InProgress,
-                    RpcDownloadStatus::Done => DownloadStatus::Done,
+                    RpcDownloadStatus::Done => DownloadStatus::Done {
+                        permissions: status.permissions.into_iter()
+                            .map(|permission| SettingsPluginPermission { permission: permission.permission, value: permission.value })
+                            .collect(),
+                    },
                     RpcDownloadStatus::Failed => DownloadStatus::Failed { message: status.message },
                 };
 
@@ -509,6 +670,61 @@ impl BackendApi {
         Ok(())
     }
 
+    pub async fn revoke_permission_grant(&mut self, plugin_id: PluginId, permission: String, value: String) -> Result<(), BackendApiError> {
+        let request = RpcRevokePermissionGrantRequest {
+            plugin_id: plugin_id.to_string(),
+            permission,
+            value,
+        };
+
+        self.client.revoke_permission_grant(Request::new(request))
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn approve_plugin_permissions(&mut self, plugin_id: PluginId, permissions: Vec<SettingsPluginPermission>) -> Result<(), BackendApiError> {
+        let request = RpcApprovePluginPermissionsRequest {
+            plugin_id: plugin_id.to_string(),
+            permissions: permissions.into_iter()
+                .map(|permission| RpcPluginPermission { permission: permission.permission, value: permission.value })
+                .collect(),
+        };
+
+        self.client.approve_plugin_permissions(Request::new(request))
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn audit_log(&mut self, plugin_id: Option<PluginId>, capability: Option<AuditLogCapability>) -> Result<Vec<AuditLogEntry>, BackendApiError> {
+        let request = RpcAuditLogRequest {
+            plugin_id: plugin_id.map(|id| id.to_string()),
+            capability: capability.map(|capability| audit_log_capability_to_rpc(capability).into()),
+        };
+
+        let response = self.client.audit_log(Request::new(request))
+            .await?;
+
+        let entries = response.into_inner()
+            .entries
+            .into_iter()
+            .map(|entry| {
+                let capability = audit_log_capability_from_rpc(entry.capability());
+
+                AuditLogEntry {
+                    plugin_id: PluginId::from_string(entry.plugin_id),
+                    entrypoint_id: entry.entrypoint_id.map(|id| EntrypointId::from_string(id)),
+                    capability,
+                    value: entry.value,
+                    timestamp_secs: entry.timestamp_secs,
+                }
+            })
+            .collect();
+
+        Ok(entries)
+    }
+
     pub async fn save_local_plugin(&mut self, path: String) -> Result<LocalSaveData, BackendApiError> {
         let request = RpcSaveLocalPluginRequest { path };
 
@@ -519,6 +735,7 @@ impl BackendApi {
         Ok(LocalSaveData {
             stdout_file_path: response.stdout_file_path,
             stderr_file_path: response.stderr_file_path,
+            inspector_port: response.inspector_port.map(|port| port as u16),
         })
     }
 }
@@ -6,10 +6,10 @@ use tokio::net::TcpStream;
 use tonic::{Request, Response, Status};
 use tonic::transport::Server;
 
-use crate::model::{DownloadStatus, EntrypointId, LocalSaveData, PhysicalKey, PhysicalShortcut, PluginId, PluginPreferenceUserData, SettingsEntrypointType, SettingsPlugin, SettingsTheme};
-use crate::rpc::grpc::{RpcDownloadPluginRequest, RpcDownloadPluginResponse, RpcDownloadStatus, RpcDownloadStatusRequest, RpcDownloadStatusResponse, RpcDownloadStatusValue, RpcEntrypoint, RpcEntrypointTypeSettings, RpcGetGlobalShortcutRequest, RpcGetGlobalShortcutResponse, RpcGetThemeRequest, RpcGetThemeResponse, RpcPingRequest, RpcPingResponse, RpcPlugin, RpcPluginsRequest, RpcPluginsResponse, RpcRemovePluginRequest, RpcRemovePluginResponse, RpcSaveLocalPluginRequest, RpcSaveLocalPluginResponse, RpcSetEntrypointStateRequest, RpcSetEntrypointStateResponse, RpcSetGlobalShortcutRequest, RpcSetGlobalShortcutResponse, RpcSetPluginStateRequest, RpcSetPluginStateResponse, RpcSetPreferenceValueRequest, RpcSetPreferenceValueResponse, RpcSetThemeRequest, RpcSetThemeResponse, RpcShortcut, RpcShowSettingsWindowRequest, RpcShowSettingsWindowResponse, RpcShowWindowRequest, RpcShowWindowResponse};
+use crate::model::{AuditLogCapability, AuditLogEntry, DownloadStatus, EntrypointId, FrecencyStats, LocalSaveData, PhysicalKey, PhysicalShortcut, PluginId, PluginPreferenceUserData, PluginUpdate, SettingsEntrypointType, SettingsPlugin, SettingsPluginPermission, SettingsPluginRunStatus, SettingsTheme};
+use crate::rpc::grpc::{RpcApprovePluginPermissionsRequest, RpcApprovePluginPermissionsResponse, RpcAuditLogCapability, RpcAuditLogEntry, RpcAuditLogRequest, RpcAuditLogResponse, RpcCheckUpdatesRequest, RpcCheckUpdatesResponse, RpcDownloadPluginRequest, RpcDownloadPluginResponse, RpcDownloadStatus, RpcDownloadStatusRequest, RpcDownloadStatusResponse, RpcDownloadStatusValue, RpcEntrypoint, RpcEntrypointTypeSettings, RpcFrecencyStats, RpcFrecencyStatsRequest, RpcFrecencyStatsResponse, RpcGetFrecencyHalfLifeRequest, RpcGetFrecencyHalfLifeResponse, RpcGetGlobalShortcutRequest, RpcGetGlobalShortcutResponse, RpcGetThemeRequest, RpcGetThemeResponse, RpcInstallPluginRequest, RpcInstallPluginResponse, RpcPingRequest, RpcPingResponse, RpcPermissionGrant, RpcPlugin, RpcPluginPermission, RpcPluginRunStatus, RpcPluginUpdate, RpcPluginsRequest, RpcPluginsResponse, RpcRemovePluginRequest, RpcRemovePluginResponse, RpcResetAllFrecencyRequest, RpcResetAllFrecencyResponse, RpcRevokePermissionGrantRequest, RpcRevokePermissionGrantResponse, RpcResetEntrypointFrecencyRequest, RpcResetEntrypointFrecencyResponse, RpcSaveLocalPluginRequest, RpcSaveLocalPluginResponse, RpcSetEntrypointAliasesRequest, RpcSetEntrypointAliasesResponse, RpcSetEntrypointStateRequest, RpcSetEntrypointStateResponse, RpcSetFrecencyHalfLifeRequest, RpcSetFrecencyHalfLifeResponse, RpcSettingsReadOnlyRequest, RpcSettingsReadOnlyResponse, RpcSetGlobalShortcutRequest, RpcSetGlobalShortcutResponse, RpcSetPluginStateRequest, RpcSetPluginStateResponse, RpcSetPreferenceValueRequest, RpcSetPreferenceValueResponse, RpcSetThemeRequest, RpcSetThemeResponse, RpcShortcut, RpcShowSettingsWindowRequest, RpcShowSettingsWindowResponse, RpcShowWindowRequest, RpcShowWindowResponse, RpcUpdatePluginRequest, RpcUpdatePluginResponse};
 use crate::rpc::grpc::rpc_backend_server::{RpcBackend, RpcBackendServer};
-use crate::rpc::grpc_convert::{plugin_preference_to_rpc, plugin_preference_user_data_from_rpc, plugin_preference_user_data_to_rpc};
+use crate::rpc::grpc_convert::{audit_log_capability_from_rpc, audit_log_capability_to_rpc, plugin_preference_to_rpc, plugin_preference_user_data_from_rpc, plugin_preference_user_data_to_rpc};
 
 pub async fn wait_for_backend_server() {
     loop {
@@ -53,6 +53,8 @@ pub trait BackendServer {
 
     async fn plugins(&self) -> anyhow::Result<Vec<SettingsPlugin>>;
 
+    async fn settings_read_only(&self) -> anyhow::Result<bool>;
+
     async fn set_plugin_state(
         &self,
         plugin_id: PluginId,
@@ -66,6 +68,13 @@ pub trait BackendServer {
         enabled: bool
     ) -> anyhow::Result<()>;
 
+    async fn set_entrypoint_aliases(
+        &self,
+        plugin_id: PluginId,
+        entrypoint_id: EntrypointId,
+        aliases: Vec<String>
+    ) -> anyhow::Result<()>;
+
     async fn set_global_shortcut(
         &self,
         shortcut: Option<PhysicalShortcut>
@@ -84,6 +93,29 @@ pub trait BackendServer {
         &self,
     ) -> anyhow::Result<SettingsTheme>;
 
+    async fn set_frecency_half_life(
+        &self,
+        half_life_secs: f64
+    ) -> anyhow::Result<()>;
+
+    async fn get_frecency_half_life(
+        &self,
+    ) -> anyhow::Result<f64>;
+
+    async fn frecency_stats(
+        &self,
+        plugin_id: PluginId,
+        entrypoint_id: Option<EntrypointId>
+    ) -> anyhow::Result<Vec<FrecencyStats>>;
+
+    async fn reset_entrypoint_frecency(
+        &self,
+        plugin_id: PluginId,
+        entrypoint_id: EntrypointId
+    ) -> anyhow::Result<()>;
+
+    async fn reset_all_frecency(&self) -> anyhow::Result<()>;
+
     async fn set_preference_value(
         &self,
         plugin_id: PluginId,
@@ -92,12 +124,24 @@ pub trait BackendServer {
         preference_value: PluginPreferenceUserData
     ) -> anyhow::Result<()>;
 
-    async fn download_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()>;
+    async fn download_plugin(&self, plugin_id: PluginId, revision: Option<String>) -> anyhow::Result<()>;
+
+    async fn install_plugin(&self, path: String) -> anyhow::Result<PluginId>;
+
+    async fn check_updates(&self) -> anyhow::Result<Vec<PluginUpdate>>;
+
+    async fn update_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()>;
 
     async fn download_status(&self) -> anyhow::Result<HashMap<PluginId, DownloadStatus>>;
 
     async fn remove_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()>;
 
+    async fn revoke_permission_grant(&self, plugin_id: PluginId, permission: String, value: String) -> anyhow::Result<()>;
+
+    async fn approve_plugin_permissions(&self, plugin_id: PluginId, permissions: Vec<SettingsPluginPermission>) -> anyhow::Result<()>;
+
+    async fn audit_log(&self, plugin_id: Option<PluginId>, capability: Option<AuditLogCapability>) -> anyhow::Result<Vec<AuditLogEntry>>;
+
     async fn save_local_plugin(&self, path: String) -> anyhow::Result<LocalSaveData>;
 }
 
@@ -130,6 +174,13 @@ impl RpcBackend for RpcBackendServerImpl {
             .map_err(|err| Status::internal(format!("{:#}", err)))?
             .into_iter()
             .map(|plugin| {
+                let (run_status, exit_code) = match plugin.run_status {
+                    SettingsPluginRunStatus::Stopped => (RpcPluginRunStatus::Stopped, None),
+                    SettingsPluginRunStatus::Running => (RpcPluginRunStatus::Running, None),
+                    SettingsPluginRunStatus::Restarting { exit_code } => (RpcPluginRunStatus::Restarting, exit_code),
+                    SettingsPluginRunStatus::Crashed { exit_code } => (RpcPluginRunStatus::Crashed, exit_code),
+                };
+
                 let entrypoints = plugin.entrypoints
                     .into_iter()
                     .map(|(_, entrypoint)| RpcEntrypoint {
@@ -149,6 +200,7 @@ impl RpcBackend for RpcBackendServerImpl {
                         preferences_user_data: entrypoint.preferences_user_data.into_iter()
                             .map(|(key, value)| (key, plugin_preference_user_data_to_rpc(value)))
                             .collect(),
+                        aliases: entrypoint.aliases,
                     })
                     .collect();
 
@@ -164,6 +216,20 @@ impl RpcBackend for RpcBackendServerImpl {
                     preferences_user_data: plugin.preferences_user_data.into_iter()
                         .map(|(key, value)| (key, plugin_preference_user_data_to_rpc(value)))
                         .collect(),
+                    revision: plugin.revision,
+                    pinned_revision: plugin.pinned_revision,
+                    run_status: run_status.into(),
+                    exit_code,
+                    permission_grants: plugin.permission_grants.into_iter()
+                        .map(|grant| RpcPermissionGrant { permission: grant.permission, value: grant.value })
+                        .collect(),
+                    permissions: plugin.permissions.into_iter()
+                        .map(|permission| RpcPluginPermission { permission: permission.permission, value: permission.value })
+                        .collect(),
+                    unapproved_permissions: plugin.unapproved_permissions.into_iter()
+                        .map(|permission| RpcPluginPermission { permission: permission.permission, value: permission.value })
+                        .collect(),
+                    permissions_changed: plugin.permissions_changed,
                 }
             })
             .collect();
@@ -201,6 +267,22 @@ impl RpcBackend for RpcBackendServerImpl {
         Ok(Response::new(RpcSetEntrypointStateResponse::default()))
     }
 
+    async fn set_entrypoint_aliases(&self, request: Request<RpcSetEntrypointAliasesRequest>) -> Result<Response<RpcSetEntrypointAliasesResponse>, Status> {
+        let request = request.into_inner();
+        let plugin_id = request.plugin_id;
+        let entrypoint_id = request.entrypoint_id;
+        let aliases = request.aliases;
+
+        let plugin_id = PluginId::from_string(plugin_id);
+        let entrypoint_id = EntrypointId::from_string(entrypoint_id);
+
+        self.server.set_entrypoint_aliases(plugin_id, entrypoint_id, aliases)
+            .await
+            .map_err(|err| Status::internal(format!("{:#}", err)))?;
+
+        Ok(Response::new(RpcSetEntrypointAliasesResponse::default()))
+    }
+
     async fn set_preference_value(&self, request: Request<RpcSetPreferenceValueRequest>) -> Result<Response<RpcSetPreferenceValueResponse>, Status> {
         let request = request.into_inner();
         let plugin_id = request.plugin_id;
@@ -305,32 +387,147 @@ impl RpcBackend for RpcBackendServerImpl {
         }))
     }
 
+    async fn settings_read_only(&self, _request: Request<RpcSettingsReadOnlyRequest>) -> Result<Response<RpcSettingsReadOnlyResponse>, Status> {
+        let read_only = self.server.settings_read_only()
+            .await
+            .map_err(|err| Status::internal(format!("{:#}", err)))?;
+
+        Ok(Response::new(RpcSettingsReadOnlyResponse {
+            read_only,
+        }))
+    }
+
+    async fn set_frecency_half_life(&self, request: Request<RpcSetFrecencyHalfLifeRequest>) -> Result<Response<RpcSetFrecencyHalfLifeResponse>, Status> {
+        let half_life_secs = request.into_inner().half_life_secs;
+
+        self.server.set_frecency_half_life(half_life_secs)
+            .await
+            .map_err(|err| Status::internal(format!("{:#}", err)))?;
+
+        Ok(Response::new(RpcSetFrecencyHalfLifeResponse::default()))
+    }
+
+    async fn get_frecency_half_life(&self, _request: Request<RpcGetFrecencyHalfLifeRequest>) -> Result<Response<RpcGetFrecencyHalfLifeResponse>, Status> {
+        let half_life_secs = self.server.get_frecency_half_life()
+            .await
+            .map_err(|err| Status::internal(format!("{:#}", err)))?;
+
+        Ok(Response::new(RpcGetFrecencyHalfLifeResponse {
+            half_life_secs,
+        }))
+    }
+
+    async fn frecency_stats(&self, request: Request<RpcFrecencyStatsRequest>) -> Result<Response<RpcFrecencyStatsResponse>, Status> {
+        let request = request.into_inner();
+        let plugin_id = PluginId::from_string(request.plugin_id);
+        let entrypoint_id = request.entrypoint_id.map(|id| EntrypointId::from_string(id));
+
+        let stats = self.server.frecency_stats(plugin_id, entrypoint_id)
+            .await
+            .map_err(|err| Status::internal(format!("{:#}", err)))?
+            .into_iter()
+            .map(|stats| RpcFrecencyStats {
+                plugin_id: stats.plugin_id.to_string(),
+                entrypoint_id: stats.entrypoint_id.to_string(),
+                frecency: stats.frecency,
+                num_accesses: stats.num_accesses,
+                last_accessed_secs: stats.last_accessed_secs,
+            })
+            .collect();
+
+        Ok(Response::new(RpcFrecencyStatsResponse { stats }))
+    }
+
+    async fn reset_entrypoint_frecency(&self, request: Request<RpcResetEntrypointFrecencyRequest>) -> Result<Response<RpcResetEntrypointFrecencyResponse>, Status> {
+        let request = request.into_inner();
+        let plugin_id = PluginId::from_string(request.plugin_id);
+        let entrypoint_id = EntrypointId::from_string(request.entrypoint_id);
+
+        self.server.reset_entrypoint_frecency(plugin_id, entrypoint_id)
+            .await
+            .map_err(|err| Status::internal(format!("{:#}", err)))?;
+
+        Ok(Response::new(RpcResetEntrypointFrecencyResponse::default()))
+    }
+
+    async fn reset_all_frecency(&self, _request: Request<RpcResetAllFrecencyRequest>) -> Result<Response<RpcResetAllFrecencyResponse>, Status> {
+        self.server.reset_all_frecency()
+            .await
+            .map_err(|err| Status::internal(format!("{:#}", err)))?;
+
+        Ok(Response::new(RpcResetAllFrecencyResponse::default()))
+    }
+
     async fn download_plugin(&self, request: Request<RpcDownloadPluginRequest>) -> Result<Response<RpcDownloadPluginResponse>, Status> {
         let request = request.into_inner();
         let plugin_id = request.plugin_id;
+        let revision = request.revision;
 
         let plugin_id = PluginId::from_string(plugin_id);
 
-        self.server.download_plugin(plugin_id)
+        self.server.download_plugin(plugin_id, revision)
             .await
             .map_err(|err| Status::internal(format!("{:#}", err)))?;
 
         Ok(Response::new(RpcDownloadPluginResponse::default()))
     }
 
+    async fn install_plugin(&self, request: Request<RpcInstallPluginRequest>) -> Result<Response<RpcInstallPluginResponse>, Status> {
+        let request = request.into_inner();
+        let path = request.path;
+
+        let plugin_id = self.server.install_plugin(path)
+            .await
+            .map_err(|err| Status::internal(format!("{:#}", err)))?;
+
+        Ok(Response::new(RpcInstallPluginResponse { plugin_id: plugin_id.to_string() }))
+    }
+
+    async fn check_updates(&self, _: Request<RpcCheckUpdatesRequest>) -> Result<Response<RpcCheckUpdatesResponse>, Status> {
+        let updates = self.server.check_updates()
+            .await
+            .map_err(|err| Status::internal(format!("{:#}", err)))?
+            .into_iter()
+            .map(|update| RpcPluginUpdate {
+                plugin_id: update.plugin_id.to_string(),
+                current_revision: update.current_revision,
+                latest_revision: update.latest_revision,
+            })
+            .collect();
+
+        Ok(Response::new(RpcCheckUpdatesResponse { updates }))
+    }
+
+    async fn update_plugin(&self, request: Request<RpcUpdatePluginRequest>) -> Result<Response<RpcUpdatePluginResponse>, Status> {
+        let request = request.into_inner();
+        let plugin_id = request.plugin_id;
+
+        let plugin_id = PluginId::from_string(plugin_id);
+
+        self.server.update_plugin(plugin_id)
+            .await
+            .map_err(|err| Status::internal(format!("{:#}", err)))?;
+
+        Ok(Response::new(RpcUpdatePluginResponse::default()))
+    }
+
     async fn download_status(&self, _: Request<RpcDownloadStatusRequest>) -> Result<Response<RpcDownloadStatusResponse>, Status> {
         let status_per_plugin = self.server.download_status()
             .await
             .map_err(|err| Status::internal(format!("{:#}", err)))?
             .into_iter()
             .map(|(plugin_id, status)| {
-                let (status, message) = match status {
-                    DownloadStatus::InProgress => (RpcDownloadStatus::InProgress, "".to_owned()),
-                    DownloadStatus::Done => (RpcDownloadStatus::Done, "".to_owned()),
-                    DownloadStatus::Failed { message } => (RpcDownloadStatus::Failed, message),
+                let (status, message, permissions) = match status {
+                    DownloadStatus::InProgress => (RpcDownloadStatus::InProgress, "".to_owned(), vec![]),
+                    DownloadStatus::Done { permissions } => (RpcDownloadStatus::Done, "".to_owned(), permissions),
+                    DownloadStatus::Failed { message } => (RpcDownloadStatus::Failed, message, vec![]),
                 };
 
-                (plugin_id.to_string(), RpcDownloadStatusValue { status: status.into(), message })
+                let permissions = permissions.into_iter()
+                    .map(|permission| RpcPluginPermission { permission: permission.permission, value: permission.value })
+                    .collect();
+
+                (plugin_id.to_string(), RpcDownloadStatusValue { status: status.into(), message, permissions })
             })
             .collect();
 
@@ -354,6 +551,63 @@ impl RpcBackend for RpcBackendServerImpl {
         Ok(Response::new(RpcRemovePluginResponse::default()))
     }
 
+    async fn revoke_permission_grant(&self, request: Request<RpcRevokePermissionGrantRequest>) -> Result<Response<RpcRevokePermissionGrantResponse>, Status> {
+        let request = request.into_inner();
+        let plugin_id = request.plugin_id;
+        let permission = request.permission;
+        let value = request.value;
+
+        let plugin_id = PluginId::from_string(plugin_id);
+
+        self.server.revoke_permission_grant(plugin_id, permission, value)
+            .await
+            .map_err(|err| Status::internal(format!("{:#}", err)))?;
+
+        Ok(Response::new(RpcRevokePermissionGrantResponse::default()))
+    }
+
+    async fn approve_plugin_permissions(&self, request: Request<RpcApprovePluginPermissionsRequest>) -> Result<Response<RpcApprovePluginPermissionsResponse>, Status> {
+        let request = request.into_inner();
+        let plugin_id = request.plugin_id;
+
+        let plugin_id = PluginId::from_string(plugin_id);
+
+        let permissions = request.permissions.into_iter()
+            .map(|permission| SettingsPluginPermission { permission: permission.permission, value: permission.value })
+            .collect();
+
+        self.server.approve_plugin_permissions(plugin_id, permissions)
+            .await
+            .map_err(|err| Status::internal(format!("{:#}", err)))?;
+
+        Ok(Response::new(RpcApprovePluginPermissionsResponse::default()))
+    }
+
+    async fn audit_log(&self, request: Request<RpcAuditLogRequest>) -> Result<Response<RpcAuditLogResponse>, Status> {
+        let request = request.into_inner();
+        let plugin_id = request.plugin_id.map(|id| PluginId::from_string(id));
+        let capability = request.capability
+            .map(|capability| RpcAuditLogCapability::try_from(capability))
+            .transpose()
+            .map_err(|err| Status::invalid_argument(format!("{:#}", err)))?
+            .map(|capability| audit_log_capability_from_rpc(capability));
+
+        let entries = self.server.audit_log(plugin_id, capability)
+            .await
+            .map_err(|err| Status::internal(format!("{:#}", err)))?
+            .into_iter()
+            .map(|entry| RpcAuditLogEntry {
+                plugin_id: entry.plugin_id.to_string(),
+                entrypoint_id: entry.entrypoint_id.map(|id| id.to_string()),
+                capability: audit_log_capability_to_rpc(entry.capability).into(),
+                value: entry.value,
+                timestamp_secs: entry.timestamp_secs,
+            })
+            .collect();
+
+        Ok(Response::new(RpcAuditLogResponse { entries }))
+    }
+
     async fn save_local_plugin(&self, request: Request<RpcSaveLocalPluginRequest>) -> Result<Response<RpcSaveLocalPluginResponse>, Status> {
         let request = request.into_inner();
         let path = request.path;
@@ -365,6 +619,7 @@ impl RpcBackend for RpcBackendServerImpl {
         Ok(Response::new(RpcSaveLocalPluginResponse {
             stdout_file_path: local_save_data.stdout_file_path,
             stderr_file_path: local_save_data.stderr_file_path,
+            inspector_port: local_save_data.inspector_port.map(|port| port as u32),
         }))
     }
 }
@@ -3,7 +3,7 @@ use anyhow::anyhow;
 use thiserror::Error;
 use gauntlet_utils::channel::{RequestError, RequestSender};
 
-use crate::model::{EntrypointId, UiTheme, PhysicalShortcut, PluginId, RootWidget, UiRenderLocation, UiRequestData, UiResponseData, UiWidgetId};
+use crate::model::{EntrypointId, UiTheme, PermissionPromptResult, PhysicalShortcut, PluginId, RootWidget, UiRenderLocation, UiRequestData, UiResponseData, UiWidgetId};
 
 #[derive(Error, Debug)]
 pub enum FrontendApiError {
@@ -115,11 +115,13 @@ impl FrontendApi {
         plugin_id: PluginId,
         entrypoint_id: EntrypointId,
         render_location: UiRenderLocation,
+        reason: Option<String>,
     ) -> Result<(), FrontendApiError> {
         let request = UiRequestData::ShowPluginErrorView {
             plugin_id,
             entrypoint_id,
             render_location,
+            reason,
         };
 
         let UiResponseData::Nothing = self.frontend_sender.send_receive(request).await? else {
@@ -177,7 +179,8 @@ impl FrontendApi {
 
         match data {
             UiResponseData::Nothing => Ok(()),
-            UiResponseData::Err(err) => Err(err)
+            UiResponseData::Err(err) => Err(err),
+            value @ _ => panic!("Unexpected UiResponseData type: {:?}", value)
         }
     }
 
@@ -195,7 +198,33 @@ impl FrontendApi {
 
         match data {
             UiResponseData::Nothing => Ok(()),
-            UiResponseData::Err(err) => Err(err)
+            UiResponseData::Err(err) => Err(err),
+            value @ _ => panic!("Unexpected UiResponseData type: {:?}", value)
         }
     }
-}
\ No newline at end of file
+
+    pub async fn request_permission(
+        &self,
+        plugin_id: PluginId,
+        plugin_name: String,
+        permission: String,
+        value: String,
+    ) -> anyhow::Result<PermissionPromptResult> {
+        let request = UiRequestData::RequestPermission {
+            plugin_id,
+            plugin_name,
+            permission,
+            value,
+        };
+
+        let data = self.frontend_sender.send_receive(request)
+            .await
+            .map_err(|err| anyhow!("error: {:?}", err))?;
+
+        match data {
+            UiResponseData::PermissionPrompt { result } => Ok(result),
+            UiResponseData::Err(err) => Err(err),
+            value @ _ => panic!("Unexpected UiResponseData type: {:?}", value)
+        }
+    }
+}
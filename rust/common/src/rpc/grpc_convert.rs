@@ -1,6 +1,6 @@
-use crate::model::{PluginPreference, PluginPreferenceUserData, PreferenceEnumValue};
+use crate::model::{AuditLogCapability, PluginPreference, PluginPreferenceUserData, PreferenceEnumValue};
 use crate::rpc::grpc::rpc_ui_property_value::Value;
-use crate::rpc::grpc::{RpcEnumValue, RpcPluginPreference, RpcPluginPreferenceUserData, RpcPluginPreferenceValueType, RpcUiPropertyValue};
+use crate::rpc::grpc::{RpcAuditLogCapability, RpcEnumValue, RpcPluginPreference, RpcPluginPreferenceUserData, RpcPluginPreferenceValueType, RpcUiPropertyValue};
 
 pub fn plugin_preference_user_data_from_rpc(value: RpcPluginPreferenceUserData) -> PluginPreferenceUserData {
     let value_type: RpcPluginPreferenceValueType = value.r#type.try_into().unwrap();
@@ -404,3 +404,22 @@ pub fn plugin_preference_from_rpc(value: RpcPluginPreference) -> PluginPreferenc
     }
 }
 
+pub fn audit_log_capability_to_rpc(value: AuditLogCapability) -> RpcAuditLogCapability {
+    match value {
+        AuditLogCapability::ClipboardRead => RpcAuditLogCapability::ClipboardRead,
+        AuditLogCapability::ClipboardWrite => RpcAuditLogCapability::ClipboardWrite,
+        AuditLogCapability::Command => RpcAuditLogCapability::Command,
+        AuditLogCapability::Network => RpcAuditLogCapability::Network,
+        AuditLogCapability::FileWrite => RpcAuditLogCapability::FileWrite,
+    }
+}
+
+pub fn audit_log_capability_from_rpc(value: RpcAuditLogCapability) -> AuditLogCapability {
+    match value {
+        RpcAuditLogCapability::ClipboardRead => AuditLogCapability::ClipboardRead,
+        RpcAuditLogCapability::ClipboardWrite => AuditLogCapability::ClipboardWrite,
+        RpcAuditLogCapability::Command => AuditLogCapability::Command,
+        RpcAuditLogCapability::Network => AuditLogCapability::Network,
+        RpcAuditLogCapability::FileWrite => AuditLogCapability::FileWrite,
+    }
+}
@@ -15,8 +15,11 @@ use crate::theme::{Element, GauntletSettingsTheme};
 use crate::theme::button::ButtonStyle;
 use crate::theme::container::ContainerStyle;
 use crate::theme::text::TextStyle;
+use crate::views::audit_log::{ManagementAppAuditLogMsgIn, ManagementAppAuditLogMsgOut, ManagementAppAuditLogState};
 use crate::views::general::{ManagementAppGeneralMsgIn, ManagementAppGeneralMsgOut, ManagementAppGeneralState};
 use crate::views::plugins::{ManagementAppPluginMsgIn, ManagementAppPluginMsgOut, ManagementAppPluginsState};
+use crate::views::plugins::aliases::EntrypointAliasesMsg;
+use crate::views::plugins::table::PluginTableMsgIn;
 
 pub fn run() {
     iced::application::<ManagementAppModel, ManagementAppMsg, GauntletSettingsTheme, Renderer>("Gauntlet Settings", update, view)
@@ -35,9 +38,11 @@ struct ManagementAppModel {
     error_view: Option<ErrorView>,
     downloads_info: HashMap<PluginId, DownloadInfo>,
     download_info_shown: bool,
+    settings_read_only: bool,
     current_settings_view: SettingsView,
     general_state: ManagementAppGeneralState,
-    plugins_state: ManagementAppPluginsState
+    plugins_state: ManagementAppPluginsState,
+    audit_log_state: ManagementAppAuditLogState,
 }
 
 
@@ -46,19 +51,23 @@ enum ManagementAppMsg {
     FontLoaded(Result<(), font::Error>),
     General(ManagementAppGeneralMsgIn),
     Plugin(ManagementAppPluginMsgIn),
+    AuditLog(ManagementAppAuditLogMsgIn),
     SwitchView(SettingsView),
     DownloadStatus { plugins: HashMap<PluginId, DownloadStatus> },
     HandleBackendError(BackendApiError),
     CheckDownloadStatus,
-    DownloadPlugin { plugin_id: PluginId },
+    DownloadPlugin { plugin_id: PluginId, revision: Option<String> },
+    UpdatePlugin { plugin_id: PluginId },
     Noop,
     ToggleDownloadInfo,
+    SettingsReadOnlyFetched(bool),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum SettingsView {
     General,
-    Plugins
+    Plugins,
+    AuditLog,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -91,13 +100,34 @@ fn new() -> (ManagementAppModel, Task<ManagementAppMsg>) {
             error_view: None,
             downloads_info: HashMap::new(),
             download_info_shown: false,
+            settings_read_only: false,
             current_settings_view: SettingsView::Plugins,
             general_state: ManagementAppGeneralState::new(backend_api.clone()),
             plugins_state: ManagementAppPluginsState::new(backend_api.clone()),
+            audit_log_state: ManagementAppAuditLogState::new(backend_api.clone()),
         },
         Task::batch([
             font::load(BOOTSTRAP_FONT_BYTES).map(ManagementAppMsg::FontLoaded),
             Task::done(ManagementAppMsg::Plugin(ManagementAppPluginMsgIn::FetchPlugins)),
+            Task::perform(
+                {
+                    let backend_api = backend_api.clone();
+
+                    async {
+                        match backend_api {
+                            Some(mut backend_api) => Some(backend_api.settings_read_only().await),
+                            None => None
+                        }
+                    }
+                },
+                |read_only| {
+                    match read_only {
+                        None => ManagementAppMsg::Noop,
+                        Some(Ok(read_only)) => ManagementAppMsg::SettingsReadOnlyFetched(read_only),
+                        Some(Err(err)) => ManagementAppMsg::HandleBackendError(err),
+                    }
+                }
+            ),
             Task::perform(
                 async {
                     match backend_api {
@@ -114,7 +144,8 @@ fn new() -> (ManagementAppModel, Task<ManagementAppMsg>) {
                                     ManagementAppMsg::General(ManagementAppGeneralMsgIn::InitSetting {
                                         theme: init.theme,
                                         shortcut: init.global_shortcut,
-                                        shortcut_error: init.global_shortcut_error
+                                        shortcut_error: init.global_shortcut_error,
+                                        frecency_half_life_secs: init.frecency_half_life_secs,
                                     })
                                 },
                                 Err(err) => ManagementAppMsg::HandleBackendError(err)
@@ -130,7 +161,8 @@ fn new() -> (ManagementAppModel, Task<ManagementAppMsg>) {
 struct InitSettingsData {
     global_shortcut: Option<PhysicalShortcut>,
     global_shortcut_error: Option<String>,
-    theme: SettingsTheme
+    theme: SettingsTheme,
+    frecency_half_life_secs: f64,
 }
 
 async fn init_data(mut backend_api: BackendApi) -> Result<InitSettingsData, BackendApiError> {
@@ -140,10 +172,14 @@ async fn init_data(mut backend_api: BackendApi) -> Result<InitSettingsData, Back
     let theme = backend_api.get_theme()
         .await?;
 
+    let frecency_half_life_secs = backend_api.get_frecency_half_life()
+        .await?;
+
     Ok(InitSettingsData {
         global_shortcut,
         global_shortcut_error,
-        theme
+        theme,
+        frecency_half_life_secs,
     })
 }
 
@@ -155,6 +191,10 @@ fn update(state: &mut ManagementAppModel, message: ManagementAppMsg) -> Task<Man
         }
     };
 
+    if state.settings_read_only && changes_settings(&message) {
+        return Task::none()
+    }
+
     match message {
         ManagementAppMsg::Plugin(message) => {
             state.plugins_state.update(message)
@@ -166,8 +206,17 @@ fn update(state: &mut ManagementAppModel, message: ManagementAppMsg) -> Task<Man
                         ManagementAppPluginMsgOut::Noop => {
                             ManagementAppMsg::Plugin(ManagementAppPluginMsgIn::Noop)
                         }
-                        ManagementAppPluginMsgOut::DownloadPlugin { plugin_id } => {
-                            ManagementAppMsg::DownloadPlugin { plugin_id }
+                        ManagementAppPluginMsgOut::DownloadPlugin { plugin_id, revision } => {
+                            ManagementAppMsg::DownloadPlugin { plugin_id, revision }
+                        }
+                        ManagementAppPluginMsgOut::PluginInstalled { plugin_id, plugins } => {
+                            ManagementAppMsg::Plugin(ManagementAppPluginMsgIn::PluginInstalled { plugin_id, plugins })
+                        }
+                        ManagementAppPluginMsgOut::UpdatesChecked(updates) => {
+                            ManagementAppMsg::Plugin(ManagementAppPluginMsgIn::UpdatesChecked(updates))
+                        }
+                        ManagementAppPluginMsgOut::UpdatePlugin { plugin_id } => {
+                            ManagementAppMsg::UpdatePlugin { plugin_id }
                         }
                         ManagementAppPluginMsgOut::SelectedItem(selected_item) => {
                             ManagementAppMsg::Plugin(ManagementAppPluginMsgIn::SelectItem(selected_item))
@@ -191,14 +240,30 @@ fn update(state: &mut ManagementAppModel, message: ManagementAppMsg) -> Task<Man
                     }
                 })
         }
+        ManagementAppMsg::AuditLog(message) => {
+            state.audit_log_state.update(message)
+                .map(|msg| {
+                    match msg {
+                        ManagementAppAuditLogMsgOut::Fetched { plugins, entries } => {
+                            ManagementAppMsg::AuditLog(ManagementAppAuditLogMsgIn::Fetched { plugins, entries })
+                        }
+                        ManagementAppAuditLogMsgOut::HandleBackendError(err) => {
+                            ManagementAppMsg::HandleBackendError(err)
+                        }
+                    }
+                })
+        }
         ManagementAppMsg::FontLoaded(result) => {
             result.expect("unable to load font");
             Task::none()
         }
         ManagementAppMsg::SwitchView(view) => {
-            state.current_settings_view = view;
+            state.current_settings_view = view.clone();
 
-            Task::none()
+            match view {
+                SettingsView::AuditLog => Task::done(ManagementAppMsg::AuditLog(ManagementAppAuditLogMsgIn::Refresh)),
+                _ => Task::none()
+            }
         }
         ManagementAppMsg::HandleBackendError(err) => {
             state.error_view = Some(match err {
@@ -209,13 +274,26 @@ fn update(state: &mut ManagementAppModel, message: ManagementAppMsg) -> Task<Man
             Task::none()
         }
         ManagementAppMsg::DownloadStatus { plugins } => {
+            let mut permission_reviews = vec![];
+
             for (plugin, status) in plugins {
                 match status {
                     DownloadStatus::InProgress => {
                         state.downloads_info.insert(plugin.clone(), DownloadInfo::InProgress);
                     }
-                    DownloadStatus::Done => {
-                        state.downloads_info.insert(plugin.clone(), DownloadInfo::Successful);
+                    DownloadStatus::Done { permissions } => {
+                        let previous = state.downloads_info.insert(plugin.clone(), DownloadInfo::Successful);
+
+                        // finished download is reported for a while, review is opened only once
+                        let just_finished = !matches!(previous, Some(DownloadInfo::Successful));
+
+                        if just_finished && !permissions.is_empty() {
+                            permission_reviews.push(Task::done(ManagementAppMsg::Plugin(ManagementAppPluginMsgIn::ReviewPermissions {
+                                plugin_id: plugin.clone(),
+                                enable: true,
+                                permissions,
+                            })));
+                        }
                     }
                     DownloadStatus::Failed { message } => {
                         state.downloads_info.insert(plugin.clone(), DownloadInfo::Error { message });
@@ -225,7 +303,7 @@ fn update(state: &mut ManagementAppModel, message: ManagementAppMsg) -> Task<Man
 
             let mut backend_api = backend_api.clone();
 
-            Task::perform(
+            let fetch_plugins = Task::perform(
                 async move {
                     let plugins = backend_api.plugins()
                         .await?;
@@ -233,7 +311,9 @@ fn update(state: &mut ManagementAppModel, message: ManagementAppMsg) -> Task<Man
                     Ok(plugins)
                 },
                 |result| handle_backend_error(result, |plugins| ManagementAppMsg::Plugin(ManagementAppPluginMsgIn::PluginsFetched(plugins)))
-            )
+            );
+
+            Task::batch([fetch_plugins].into_iter().chain(permission_reviews))
         }
         ManagementAppMsg::CheckDownloadStatus => {
             if state.downloads_info.is_empty() {
@@ -252,7 +332,7 @@ fn update(state: &mut ManagementAppModel, message: ManagementAppMsg) -> Task<Man
                 )
             }
         }
-        ManagementAppMsg::DownloadPlugin { plugin_id } => {
+        ManagementAppMsg::DownloadPlugin { plugin_id, revision } => {
             let mut backend_client = backend_api.clone();
 
             let already_downloading = state.downloads_info.insert(plugin_id.clone(), DownloadInfo::InProgress)
@@ -263,7 +343,7 @@ fn update(state: &mut ManagementAppModel, message: ManagementAppMsg) -> Task<Man
             } else {
                 Task::perform(
                     async move {
-                        backend_client.download_plugin(plugin_id)
+                        backend_client.download_plugin(plugin_id, revision)
                             .await?;
 
                         Ok(())
@@ -272,11 +352,54 @@ fn update(state: &mut ManagementAppModel, message: ManagementAppMsg) -> Task<Man
                 )
             }
         }
+        ManagementAppMsg::UpdatePlugin { plugin_id } => {
+            let mut backend_client = backend_api.clone();
+
+            let already_downloading = state.downloads_info.insert(plugin_id.clone(), DownloadInfo::InProgress)
+                .is_some();
+
+            if already_downloading {
+                Task::none()
+            } else {
+                Task::perform(
+                    async move {
+                        backend_client.update_plugin(plugin_id)
+                            .await?;
+
+                        Ok(())
+                    },
+                    |result| handle_backend_error(result, |()| ManagementAppMsg::CheckDownloadStatus)
+                )
+            }
+        }
         ManagementAppMsg::Noop => Task::none(),
         ManagementAppMsg::ToggleDownloadInfo => {
             state.download_info_shown = !state.download_info_shown;
             Task::none()
         }
+        ManagementAppMsg::SettingsReadOnlyFetched(read_only) => {
+            state.settings_read_only = read_only;
+            Task::none()
+        }
+    }
+}
+
+// in read only mode settings are managed by config file and server refuses any changes
+fn changes_settings(message: &ManagementAppMsg) -> bool {
+    match message {
+        ManagementAppMsg::General(ManagementAppGeneralMsgIn::ShortcutCaptured(_)) => true,
+        ManagementAppMsg::General(ManagementAppGeneralMsgIn::ThemeChanged(_)) => true,
+        ManagementAppMsg::General(ManagementAppGeneralMsgIn::FrecencyHalfLifeChanged(_)) => true,
+        ManagementAppMsg::Plugin(ManagementAppPluginMsgIn::PluginTableMsg(PluginTableMsgIn::EnabledToggleItem(_))) => true,
+        ManagementAppMsg::Plugin(ManagementAppPluginMsgIn::PluginPreferenceMsg(_)) => true,
+        ManagementAppMsg::Plugin(ManagementAppPluginMsgIn::EntrypointAliasesMsg(EntrypointAliasesMsg::SetAliases { .. })) => true,
+        ManagementAppMsg::Plugin(ManagementAppPluginMsgIn::RemovePlugin { .. }) => true,
+        ManagementAppMsg::Plugin(ManagementAppPluginMsgIn::DownloadPlugin { .. }) => true,
+        ManagementAppMsg::DownloadPlugin { .. } => true,
+        ManagementAppMsg::Plugin(ManagementAppPluginMsgIn::UpdatePlugin { .. }) => true,
+        ManagementAppMsg::UpdatePlugin { .. } => true,
+        ManagementAppMsg::Plugin(ManagementAppPluginMsgIn::InstallPlugin { .. }) => true,
+        _ => false
     }
 }
 
@@ -387,6 +510,10 @@ fn view(state: &ManagementAppModel) -> Element<'_, ManagementAppMsg> {
             state.plugins_state.view()
                 .map(|msg| ManagementAppMsg::Plugin(msg))
         }
+        SettingsView::AuditLog => {
+            state.audit_log_state.view()
+                .map(|msg| ManagementAppMsg::AuditLog(msg))
+        }
     };
 
     let icon_general: Element<_> = value(Bootstrap::GearFill)
@@ -451,7 +578,38 @@ fn view(state: &ManagementAppModel) -> Element<'_, ManagementAppMsg> {
         .padding(8.0)
         .into();
 
-    let top_bar_buttons: Element<_> = row(vec![general_button, plugins_button])
+    let icon_audit_log: Element<_> = value(Bootstrap::ShieldFill)
+        .font(BOOTSTRAP_FONT)
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .align_y(alignment::Vertical::Center)
+        .align_x(alignment::Horizontal::Center)
+        .into();
+
+    let text_audit_log: Element<_> = text("Audit Log")
+        .height(Length::Fill)
+        .align_y(alignment::Vertical::Center)
+        .align_x(alignment::Horizontal::Center)
+        .into();
+
+    let audit_log_button: Element<_> = column(vec![icon_audit_log, text_audit_log])
+        .align_x(Alignment::Center)
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .into();
+
+    let audit_log_button: Element<_> = button(audit_log_button)
+        .on_press(ManagementAppMsg::SwitchView(SettingsView::AuditLog))
+        .height(Length::Fill)
+        .width(80)
+        .class(if state.current_settings_view == SettingsView::AuditLog { ButtonStyle::ViewSwitcherSelected } else { ButtonStyle::ViewSwitcher })
+        .into();
+
+    let audit_log_button: Element<_> = container(audit_log_button)
+        .padding(8.0)
+        .into();
+
+    let top_bar_buttons: Element<_> = row(vec![general_button, plugins_button, audit_log_button])
         .into();
 
     let top_bar_buttons: Element<_> = container(top_bar_buttons)
@@ -603,8 +761,24 @@ fn view(state: &ManagementAppModel) -> Element<'_, ManagementAppMsg> {
     let separator: Element<_> = horizontal_rule(1)
         .into();
 
-    let content: Element<_> = column(vec![top_bar, separator, content])
-        .into();
+    let content: Element<_> = if state.settings_read_only {
+        let read_only_text: Element<_> = text("Settings are managed by config file and can not be changed here")
+            .shaping(Shaping::Advanced)
+            .class(TextStyle::Subtitle)
+            .into();
+
+        let read_only_banner: Element<_> = container(read_only_text)
+            .width(Length::Fill)
+            .align_x(Alignment::Center)
+            .padding(8)
+            .into();
+
+        column(vec![top_bar, separator, read_only_banner, content])
+            .into()
+    } else {
+        column(vec![top_bar, separator, content])
+            .into()
+    };
 
     let download_info_panel: Element<_> = {
         let downloads: Vec<Element<_>> = state.downloads_info.iter()
@@ -769,9 +943,14 @@ fn view(state: &ManagementAppModel) -> Element<'_, ManagementAppMsg> {
         .into()
 }
 
-fn subscription(_state: &ManagementAppModel) -> Subscription<ManagementAppMsg> {
-    time::every(Duration::from_millis(300))
-        .map(|_| ManagementAppMsg::CheckDownloadStatus)
+fn subscription(state: &ManagementAppModel) -> Subscription<ManagementAppMsg> {
+    let download_status = time::every(Duration::from_millis(300))
+        .map(|_| ManagementAppMsg::CheckDownloadStatus);
+
+    let general = state.general_state.subscription()
+        .map(|msg| ManagementAppMsg::General(msg));
+
+    Subscription::batch([download_status, general])
 }
 
 
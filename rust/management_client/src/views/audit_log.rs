@@ -0,0 +1,309 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::time::SystemTime;
+
+use crate::theme::text::TextStyle;
+use crate::theme::Element;
+use gauntlet_common::model::{AuditLogCapability, AuditLogEntry, PluginId, SettingsPlugin};
+use gauntlet_common::rpc::backend_api::{BackendApi, BackendApiError};
+use iced::widget::text::Shaping;
+use iced::widget::{button, column, container, horizontal_rule, pick_list, row, scrollable, text, Space};
+use iced::{Alignment, Length, Task};
+
+pub struct ManagementAppAuditLogState {
+    backend_api: Option<BackendApi>,
+    plugins: HashMap<PluginId, SettingsPlugin>,
+    entries: Vec<AuditLogEntry>,
+    plugin_filter: PluginFilter,
+    capability_filter: CapabilityFilter,
+}
+
+#[derive(Debug, Clone)]
+pub enum ManagementAppAuditLogMsgIn {
+    Refresh,
+    Fetched {
+        plugins: HashMap<PluginId, SettingsPlugin>,
+        entries: Vec<AuditLogEntry>,
+    },
+    PluginFilterChanged(PluginFilter),
+    CapabilityFilterChanged(CapabilityFilter),
+    Noop,
+}
+
+#[derive(Debug, Clone)]
+pub enum ManagementAppAuditLogMsgOut {
+    Fetched {
+        plugins: HashMap<PluginId, SettingsPlugin>,
+        entries: Vec<AuditLogEntry>,
+    },
+    HandleBackendError(BackendApiError)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PluginFilter {
+    All,
+    Plugin {
+        plugin_id: PluginId,
+        plugin_name: String,
+    },
+}
+
+impl Display for PluginFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PluginFilter::All => write!(f, "All Plugins"),
+            PluginFilter::Plugin { plugin_name, .. } => write!(f, "{}", plugin_name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CapabilityFilter {
+    All,
+    Capability(AuditLogCapability),
+}
+
+impl Display for CapabilityFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CapabilityFilter::All => write!(f, "All Capabilities"),
+            CapabilityFilter::Capability(capability) => write!(f, "{}", capability),
+        }
+    }
+}
+
+impl ManagementAppAuditLogState {
+    pub fn new(backend_api: Option<BackendApi>) -> Self {
+        Self {
+            backend_api,
+            plugins: HashMap::new(),
+            entries: vec![],
+            plugin_filter: PluginFilter::All,
+            capability_filter: CapabilityFilter::All,
+        }
+    }
+
+    pub fn update(&mut self, message: ManagementAppAuditLogMsgIn) -> Task<ManagementAppAuditLogMsgOut> {
+        let backend_api = match &self.backend_api {
+            Some(backend_api) => backend_api.clone(),
+            None => {
+                return Task::none()
+            }
+        };
+
+        match message {
+            ManagementAppAuditLogMsgIn::Refresh => {
+                self.fetch(backend_api)
+            }
+            ManagementAppAuditLogMsgIn::Fetched { plugins, entries } => {
+                self.plugins = plugins;
+                self.entries = entries;
+
+                Task::none()
+            }
+            ManagementAppAuditLogMsgIn::PluginFilterChanged(filter) => {
+                self.plugin_filter = filter;
+
+                self.fetch(backend_api)
+            }
+            ManagementAppAuditLogMsgIn::CapabilityFilterChanged(filter) => {
+                self.capability_filter = filter;
+
+                self.fetch(backend_api)
+            }
+            ManagementAppAuditLogMsgIn::Noop => {
+                Task::none()
+            }
+        }
+    }
+
+    fn fetch(&self, mut backend_api: BackendApi) -> Task<ManagementAppAuditLogMsgOut> {
+        let plugin_id = match &self.plugin_filter {
+            PluginFilter::All => None,
+            PluginFilter::Plugin { plugin_id, .. } => Some(plugin_id.clone()),
+        };
+
+        let capability = match &self.capability_filter {
+            CapabilityFilter::All => None,
+            CapabilityFilter::Capability(capability) => Some(*capability),
+        };
+
+        Task::perform(async move {
+            let plugins = backend_api.plugins()
+                .await?;
+
+            let entries = backend_api.audit_log(plugin_id, capability)
+                .await?;
+
+            Ok((plugins, entries))
+        }, |result| handle_backend_error(result, |(plugins, entries)| ManagementAppAuditLogMsgOut::Fetched { plugins, entries }))
+    }
+
+    pub fn view(&self) -> Element<ManagementAppAuditLogMsgIn> {
+        let plugin_items: Vec<_> = [PluginFilter::All]
+            .into_iter()
+            .chain(
+                self.plugins.values()
+                    .map(|plugin| PluginFilter::Plugin {
+                        plugin_id: plugin.plugin_id.clone(),
+                        plugin_name: plugin.plugin_name.clone(),
+                    })
+            )
+            .collect();
+
+        let plugin_field: Element<_> = pick_list(
+            plugin_items,
+            Some(self.plugin_filter.clone()),
+            move |item| ManagementAppAuditLogMsgIn::PluginFilterChanged(item),
+        ).into();
+
+        let capability_items = [
+            CapabilityFilter::All,
+            CapabilityFilter::Capability(AuditLogCapability::ClipboardRead),
+            CapabilityFilter::Capability(AuditLogCapability::ClipboardWrite),
+            CapabilityFilter::Capability(AuditLogCapability::Command),
+            CapabilityFilter::Capability(AuditLogCapability::Network),
+            CapabilityFilter::Capability(AuditLogCapability::FileWrite),
+        ];
+
+        let capability_field: Element<_> = pick_list(
+            capability_items,
+            Some(self.capability_filter.clone()),
+            move |item| ManagementAppAuditLogMsgIn::CapabilityFilterChanged(item),
+        ).into();
+
+        let refresh_button: Element<_> = button(text("Refresh"))
+            .on_press(ManagementAppAuditLogMsgIn::Refresh)
+            .into();
+
+        let filters: Element<_> = row(vec![plugin_field, capability_field, Space::with_width(Length::Fill).into(), refresh_button])
+            .spacing(8)
+            .align_y(Alignment::Center)
+            .padding(12)
+            .into();
+
+        let content: Element<_> = if self.entries.is_empty() {
+            let description: Element<_> = text("No capability use has been recorded")
+                .class(TextStyle::Subtitle)
+                .into();
+
+            container(description)
+                .align_x(Alignment::Center)
+                .align_y(Alignment::Center)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .into()
+        } else {
+            let now = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|duration| duration.as_secs() as i64)
+                .unwrap_or_default();
+
+            let header: Element<_> = self.view_row(
+                text("Time").class(TextStyle::Subtitle).into(),
+                text("Plugin").class(TextStyle::Subtitle).into(),
+                text("Entrypoint").class(TextStyle::Subtitle).into(),
+                text("Capability").class(TextStyle::Subtitle).into(),
+                text("Value").class(TextStyle::Subtitle).into(),
+            );
+
+            let rows: Vec<Element<_>> = self.entries.iter()
+                .map(|entry| {
+                    let plugin = self.plugins.get(&entry.plugin_id);
+
+                    let plugin_name = plugin
+                        .map(|plugin| plugin.plugin_name.clone())
+                        .unwrap_or_else(|| entry.plugin_id.to_string());
+
+                    let entrypoint_name = match &entry.entrypoint_id {
+                        None => "-".to_string(),
+                        Some(entrypoint_id) => {
+                            plugin
+                                .and_then(|plugin| plugin.entrypoints.get(entrypoint_id))
+                                .map(|entrypoint| entrypoint.entrypoint_name.clone())
+                                .unwrap_or_else(|| entrypoint_id.to_string())
+                        }
+                    };
+
+                    self.view_row(
+                        text(format_time_ago(now - entry.timestamp_secs)).into(),
+                        text(plugin_name).shaping(Shaping::Advanced).into(),
+                        text(entrypoint_name).shaping(Shaping::Advanced).into(),
+                        text(entry.capability.to_string()).into(),
+                        text(entry.value.clone()).shaping(Shaping::Advanced).into(),
+                    )
+                })
+                .collect();
+
+            let rows: Element<_> = column(rows)
+                .into();
+
+            let rows: Element<_> = scrollable(rows)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .into();
+
+            column(vec![header, horizontal_rule(1).into(), rows])
+                .into()
+        };
+
+        // network, command and file write values are recorded when deno asks for permission,
+        // and deno remembers allowed values until plugin is restarted
+        let note: Element<_> = text("Each distinct host, command or path is recorded only on first use after plugin start")
+            .size(14)
+            .class(TextStyle::Subtitle)
+            .into();
+
+        let note: Element<_> = container(note)
+            .padding([0, 12])
+            .into();
+
+        let content: Element<_> = column(vec![filters, note, content])
+            .spacing(8)
+            .into();
+
+        let content: Element<_> = container(content)
+            .width(Length::Fill)
+            .into();
+
+        content
+    }
+
+    fn view_row<'a>(
+        &'a self,
+        time: Element<'a, ManagementAppAuditLogMsgIn>,
+        plugin: Element<'a, ManagementAppAuditLogMsgIn>,
+        entrypoint: Element<'a, ManagementAppAuditLogMsgIn>,
+        capability: Element<'a, ManagementAppAuditLogMsgIn>,
+        value: Element<'a, ManagementAppAuditLogMsgIn>,
+    ) -> Element<'a, ManagementAppAuditLogMsgIn> {
+        let content = vec![
+            container(time).width(Length::FillPortion(2)).into(),
+            container(plugin).width(Length::FillPortion(3)).into(),
+            container(entrypoint).width(Length::FillPortion(3)).into(),
+            container(capability).width(Length::FillPortion(2)).into(),
+            container(value).width(Length::FillPortion(5)).into(),
+        ];
+
+        row(content)
+            .spacing(8)
+            .padding([4, 12])
+            .into()
+    }
+}
+
+fn format_time_ago(secs: i64) -> String {
+    match secs {
+        ..=59 => "just now".to_string(),
+        60..=3599 => format!("{} min ago", secs / 60),
+        3600..=86399 => format!("{} h ago", secs / 3600),
+        _ => format!("{} days ago", secs / 86400),
+    }
+}
+
+pub fn handle_backend_error<T>(result: Result<T, BackendApiError>, convert: impl FnOnce(T) -> ManagementAppAuditLogMsgOut) -> ManagementAppAuditLogMsgOut {
+    match result {
+        Ok(val) => convert(val),
+        Err(err) => ManagementAppAuditLogMsgOut::HandleBackendError(err)
+    }
+}
@@ -7,27 +7,39 @@ use iced::alignment::Horizontal;
 use iced::widget::text::Shaping;
 use iced::widget::tooltip::Position;
 use iced::widget::{column, container, pick_list, row, text, tooltip, value, Space};
-use iced::{alignment, Alignment, Length, Padding, Task};
+use iced::{alignment, time, Alignment, Length, Padding, Subscription, Task};
+use iced_aw::number_input;
 use iced_fonts::{Bootstrap, BOOTSTRAP_FONT};
 use crate::theme::container::ContainerStyle;
+use std::time::{Duration, Instant};
 
 pub struct ManagementAppGeneralState {
     backend_api: Option<BackendApi>,
     theme: SettingsTheme,
     current_shortcut: Option<PhysicalShortcut>,
     current_shortcut_error: Option<String>,
-    currently_capturing: bool
+    currently_capturing: bool,
+    frecency_half_life_days: f64,
+    // changing half-life recalculates frecency of every entrypoint,
+    // so value is sent only when user stops changing it
+    frecency_half_life_changed_at: Option<Instant>,
 }
 
+const SECONDS_IN_DAY: f64 = 24.0 * 60.0 * 60.0;
+const FRECENCY_HALF_LIFE_SAVE_DELAY: Duration = Duration::from_millis(700);
+
 #[derive(Debug, Clone)]
 pub enum ManagementAppGeneralMsgIn {
     ShortcutCaptured(Option<PhysicalShortcut>),
     CapturingChanged(bool),
     ThemeChanged(SettingsTheme),
+    FrecencyHalfLifeChanged(f64),
+    SaveFrecencyHalfLife,
     InitSetting {
         theme: SettingsTheme,
         shortcut: Option<PhysicalShortcut>,
-        shortcut_error: Option<String>
+        shortcut_error: Option<String>,
+        frecency_half_life_secs: f64,
     },
     Noop
 }
@@ -46,6 +58,8 @@ impl ManagementAppGeneralState {
             current_shortcut: None,
             current_shortcut_error: None,
             currently_capturing: false,
+            frecency_half_life_days: 3.0,
+            frecency_half_life_changed_at: None,
         }
     }
 
@@ -73,10 +87,11 @@ impl ManagementAppGeneralState {
             ManagementAppGeneralMsgIn::Noop => {
                 Task::none()
             }
-            ManagementAppGeneralMsgIn::InitSetting { theme, shortcut, shortcut_error } => {
+            ManagementAppGeneralMsgIn::InitSetting { theme, shortcut, shortcut_error, frecency_half_life_secs } => {
                 self.theme = theme;
                 self.current_shortcut = shortcut;
                 self.current_shortcut_error = shortcut_error;
+                self.frecency_half_life_days = frecency_half_life_secs / SECONDS_IN_DAY;
 
                 Task::done(ManagementAppGeneralMsgOut::Noop)
             }
@@ -98,6 +113,41 @@ impl ManagementAppGeneralState {
                 }, |result| handle_backend_error(result, |()| ManagementAppGeneralMsgOut::Noop))
 
             }
+            ManagementAppGeneralMsgIn::FrecencyHalfLifeChanged(days) => {
+                self.frecency_half_life_days = days;
+                self.frecency_half_life_changed_at = Some(Instant::now());
+
+                Task::none()
+            }
+            ManagementAppGeneralMsgIn::SaveFrecencyHalfLife => {
+                match self.frecency_half_life_changed_at {
+                    Some(changed_at) if changed_at.elapsed() >= FRECENCY_HALF_LIFE_SAVE_DELAY => {
+                        self.frecency_half_life_changed_at = None;
+
+                        let days = self.frecency_half_life_days;
+
+                        let mut backend_api = backend_api.clone();
+
+                        Task::perform(async move {
+                            backend_api.set_frecency_half_life(days * SECONDS_IN_DAY)
+                                .await?;
+
+                            Ok(())
+                        }, |result| handle_backend_error(result, |()| ManagementAppGeneralMsgOut::Noop))
+                    }
+                    _ => Task::none()
+                }
+            }
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<ManagementAppGeneralMsgIn> {
+        match self.frecency_half_life_changed_at {
+            Some(_) => {
+                time::every(Duration::from_millis(100))
+                    .map(|_| ManagementAppGeneralMsgIn::SaveFrecencyHalfLife)
+            }
+            None => Subscription::none()
         }
     }
 
@@ -143,7 +193,18 @@ impl ManagementAppGeneralState {
             None
         );
 
-        let content: Element<_> = column(vec![global_shortcut_field, theme_field])
+        let frecency_half_life_field: Element<_> = number_input(self.frecency_half_life_days, 0.1..=365.0, ManagementAppGeneralMsgIn::FrecencyHalfLifeChanged)
+            .step(0.5)
+            .width(Length::Fill)
+            .into();
+
+        let frecency_half_life_field = self.view_field(
+            "Frecency Half-Life (days)",
+            frecency_half_life_field,
+            None
+        );
+
+        let content: Element<_> = column(vec![global_shortcut_field, theme_field, frecency_half_life_field])
             .into();
 
         let content: Element<_> = container(content)
@@ -1,2 +1,3 @@
+pub mod audit_log;
 pub mod general;
 pub mod plugins;
\ No newline at end of file
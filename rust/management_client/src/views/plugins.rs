@@ -7,22 +7,25 @@ use iced::widget::{button, column, container, row, scrollable, text, text_input,
 use iced::widget::text::Shaping;
 use iced_fonts::{Bootstrap, BOOTSTRAP_FONT};
 use gauntlet_common::{settings_env_data_from_string, SettingsEnvData};
-use gauntlet_common::model::{EntrypointId, PluginId, PluginPreferenceUserData, SettingsPlugin};
+use gauntlet_common::model::{EntrypointId, PluginId, PluginPreferenceUserData, PluginUpdate, SettingsEntrypointType, SettingsPlugin, SettingsPluginPermission, SettingsPluginRunStatus};
 use gauntlet_common::rpc::backend_api::{BackendApi, BackendApiError};
 
 use crate::theme::button::ButtonStyle;
 use crate::theme::Element;
 use crate::theme::text::TextStyle;
+use crate::views::plugins::aliases::{aliases_ui, EntrypointAliasesMsg};
 use crate::views::plugins::preferences::{PluginPreferencesMsg, preferences_ui, SelectItem};
 use crate::views::plugins::table::{PluginTableMsgIn, PluginTableMsgOut, PluginTableState, PluginTableUpdateResult};
 
+pub mod aliases;
 mod preferences;
-mod table;
+pub mod table;
 
 #[derive(Debug, Clone)]
 pub enum ManagementAppPluginMsgIn {
     PluginTableMsg(PluginTableMsgIn),
     PluginPreferenceMsg(PluginPreferencesMsg),
+    EntrypointAliasesMsg(EntrypointAliasesMsg),
     FetchPlugins,
     PluginsFetched(HashMap<PluginId, SettingsPlugin>),
     RemovePlugin {
@@ -30,7 +33,36 @@ pub enum ManagementAppPluginMsgIn {
     },
     DownloadPlugin {
         plugin_id: PluginId,
+        revision: Option<String>,
     },
+    InstallPlugin {
+        path: String,
+    },
+    PluginInstalled {
+        plugin_id: PluginId,
+        plugins: HashMap<PluginId, SettingsPlugin>,
+    },
+    CheckForUpdates,
+    UpdatesChecked(Vec<PluginUpdate>),
+    UpdatePlugin {
+        plugin_id: PluginId,
+    },
+    RevokePermissionGrant {
+        plugin_id: PluginId,
+        permission: String,
+        value: String,
+    },
+    ReviewPermissions {
+        plugin_id: PluginId,
+        enable: bool,
+        permissions: Vec<SettingsPluginPermission>,
+    },
+    ApprovePermissions {
+        plugin_id: PluginId,
+        enable: bool,
+        permissions: Vec<SettingsPluginPermission>,
+    },
+    CancelPermissionReview,
     SelectItem(SelectedItem),
     Noop
 }
@@ -40,6 +72,15 @@ pub enum ManagementAppPluginMsgOut {
     SelectedItem(SelectedItem),
     DownloadPlugin {
         plugin_id: PluginId,
+        revision: Option<String>,
+    },
+    PluginInstalled {
+        plugin_id: PluginId,
+        plugins: HashMap<PluginId, SettingsPlugin>,
+    },
+    UpdatesChecked(Vec<PluginUpdate>),
+    UpdatePlugin {
+        plugin_id: PluginId,
     },
     HandleBackendError(BackendApiError),
     Noop
@@ -50,7 +91,12 @@ pub struct ManagementAppPluginsState {
     table_state: PluginTableState,
     plugin_data: Rc<RefCell<PluginDataContainer>>,
     preference_user_data: HashMap<(PluginId, Option<EntrypointId>, String), PluginPreferenceUserDataState>,
+    new_alias: String,
     selected_item: SelectedItem,
+    checking_for_updates: bool,
+    // None if updates were not checked yet
+    available_updates: Option<HashMap<PluginId, PluginUpdate>>,
+    permission_review: Option<PermissionReview>,
 }
 
 const SETTINGS_ENV: &'static str = "GAUNTLET_INTERNAL_SETTINGS";
@@ -79,8 +125,12 @@ impl ManagementAppPluginsState {
             backend_api,
             plugin_data: Rc::new(RefCell::new(PluginDataContainer::new())),
             preference_user_data: HashMap::new(),
+            new_alias: "".to_owned(),
             selected_item: select_item,
             table_state: PluginTableState::new(),
+            checking_for_updates: false,
+            available_updates: None,
+            permission_review: None,
         }
     }
 
@@ -99,6 +149,18 @@ impl ManagementAppPluginsState {
                     PluginTableUpdateResult::Value(msg) => {
                         match msg {
                             PluginTableMsgOut::SetPluginState { enabled, plugin_id } => {
+                                let permissions_to_review = self.plugin_data.borrow()
+                                    .plugins
+                                    .get(&plugin_id)
+                                    .filter(|plugin| !plugin.unapproved_permissions.is_empty())
+                                    .map(|plugin| plugin.permissions.clone());
+
+                                if let (true, Some(permissions)) = (enabled, permissions_to_review) {
+                                    self.permission_review = Some(PermissionReview { plugin_id, enable: true, permissions });
+
+                                    return Task::none()
+                                }
+
                                 let mut backend_client = backend_api.clone();
 
                                 Task::perform(
@@ -170,6 +232,33 @@ impl ManagementAppPluginsState {
                     }
                 }
             }
+            ManagementAppPluginMsgIn::EntrypointAliasesMsg(msg) => {
+                match msg {
+                    EntrypointAliasesMsg::UpdateNewAlias { new_alias } => {
+                        self.new_alias = new_alias;
+
+                        Task::none()
+                    }
+                    EntrypointAliasesMsg::SetAliases { plugin_id, entrypoint_id, aliases } => {
+                        self.new_alias = "".to_owned();
+
+                        let mut backend_client = backend_api.clone();
+
+                        Task::perform(
+                            async move {
+                                backend_client.set_entrypoint_aliases(plugin_id, entrypoint_id, aliases)
+                                    .await?;
+
+                                let plugins = backend_client.plugins()
+                                    .await?;
+
+                                Ok(plugins)
+                            },
+                            |result| handle_backend_error(result, |plugins| ManagementAppPluginMsgOut::PluginsReloaded(plugins))
+                        )
+                    }
+                }
+            }
             ManagementAppPluginMsgIn::FetchPlugins => {
                 let mut backend_api = backend_api.clone();
 
@@ -206,11 +295,116 @@ impl ManagementAppPluginsState {
                     |result| handle_backend_error(result, |plugins| ManagementAppPluginMsgOut::PluginsReloaded(plugins))
                 )
             }
-            ManagementAppPluginMsgIn::DownloadPlugin { plugin_id } => {
-                Task::done(ManagementAppPluginMsgOut::DownloadPlugin { plugin_id })
+            ManagementAppPluginMsgIn::DownloadPlugin { plugin_id, revision } => {
+                Task::done(ManagementAppPluginMsgOut::DownloadPlugin { plugin_id, revision })
+            }
+            ManagementAppPluginMsgIn::InstallPlugin { path } => {
+                let mut backend_client = backend_api.clone();
+
+                Task::perform(
+                    async move {
+                        let plugin_id = backend_client.install_plugin(path)
+                            .await?;
+
+                        let plugins = backend_client.plugins()
+                            .await?;
+
+                        Ok((plugin_id, plugins))
+                    },
+                    |result| handle_backend_error(result, |(plugin_id, plugins)| ManagementAppPluginMsgOut::PluginInstalled { plugin_id, plugins })
+                )
+            }
+            ManagementAppPluginMsgIn::PluginInstalled { plugin_id, plugins } => {
+                self.apply_plugin_fetch(plugins);
+                self.selected_item = SelectedItem::Plugin { plugin_id };
+
+                Task::none()
+            }
+            ManagementAppPluginMsgIn::CheckForUpdates => {
+                self.checking_for_updates = true;
+
+                let mut backend_client = backend_api.clone();
+
+                Task::perform(
+                    async move {
+                        let updates = backend_client.check_updates()
+                            .await?;
+
+                        Ok(updates)
+                    },
+                    |result| handle_backend_error(result, |updates| ManagementAppPluginMsgOut::UpdatesChecked(updates))
+                )
+            }
+            ManagementAppPluginMsgIn::UpdatesChecked(updates) => {
+                self.checking_for_updates = false;
+                self.available_updates = Some(
+                    updates.into_iter()
+                        .map(|update| (update.plugin_id.clone(), update))
+                        .collect()
+                );
+
+                Task::none()
+            }
+            ManagementAppPluginMsgIn::UpdatePlugin { plugin_id } => {
+                if let Some(available_updates) = &mut self.available_updates {
+                    available_updates.remove(&plugin_id);
+                }
+
+                Task::done(ManagementAppPluginMsgOut::UpdatePlugin { plugin_id })
+            }
+            ManagementAppPluginMsgIn::RevokePermissionGrant { plugin_id, permission, value } => {
+                let mut backend_client = backend_api.clone();
+
+                Task::perform(
+                    async move {
+                        backend_client.revoke_permission_grant(plugin_id, permission, value)
+                            .await?;
+
+                        let plugins = backend_client.plugins()
+                            .await?;
+
+                        Ok(plugins)
+                    },
+                    |result| handle_backend_error(result, |plugins| ManagementAppPluginMsgOut::PluginsReloaded(plugins))
+                )
+            }
+            ManagementAppPluginMsgIn::ReviewPermissions { plugin_id, enable, permissions } => {
+                self.permission_review = Some(PermissionReview { plugin_id, enable, permissions });
+
+                Task::none()
+            }
+            ManagementAppPluginMsgIn::ApprovePermissions { plugin_id, enable, permissions } => {
+                self.permission_review = None;
+
+                let mut backend_client = backend_api.clone();
+
+                Task::perform(
+                    async move {
+                        backend_client.approve_plugin_permissions(plugin_id.clone(), permissions)
+                            .await?;
+
+                        if enable {
+                            backend_client.set_plugin_state(plugin_id, true)
+                                .await?;
+                        }
+
+                        let plugins = backend_client.plugins()
+                            .await?;
+
+                        Ok(plugins)
+                    },
+                    |result| handle_backend_error(result, |plugins| ManagementAppPluginMsgOut::PluginsReloaded(plugins))
+                )
+            }
+            ManagementAppPluginMsgIn::CancelPermissionReview => {
+                self.permission_review = None;
+
+                Task::none()
             }
             ManagementAppPluginMsgIn::SelectItem(selected_item) => {
+                self.permission_review = None;
                 self.selected_item = selected_item;
+                self.new_alias = "".to_owned();
 
                 Task::none()
             }
@@ -350,6 +544,170 @@ impl ManagementAppPluginsState {
                             column_content.push(content);
                         }
 
+                        let run_status = match &plugin.run_status {
+                            SettingsPluginRunStatus::Stopped => None,
+                            SettingsPluginRunStatus::Running => Some(("Running".to_owned(), TextStyle::Positive)),
+                            SettingsPluginRunStatus::Restarting { exit_code } => Some((format!("Crashed{}, restarting...", exit_code_text(exit_code)), TextStyle::Destructive)),
+                            SettingsPluginRunStatus::Crashed { exit_code } => Some((format!("Crashed{}", exit_code_text(exit_code)), TextStyle::Destructive)),
+                        };
+
+                        if let Some((run_status, run_status_style)) = run_status {
+                            let run_status_label: Element<_> = text("Status")
+                                .size(14)
+                                .class(TextStyle::Subtitle)
+                                .into();
+
+                            let run_status_label = container(run_status_label)
+                                .padding(padding::bottom(8.0))
+                                .into();
+
+                            let run_status: Element<_> = text(run_status)
+                                .class(run_status_style)
+                                .into();
+
+                            let run_status = container(run_status)
+                                .padding(Padding::new(8.0))
+                                .into();
+
+                            let content: Element<_> = column(vec![run_status_label, run_status])
+                                .into();
+
+                            column_content.push(content);
+                        }
+
+                        if let Some(revision) = &plugin.revision {
+                            let revision_label: Element<_> = text("Revision")
+                                .size(14)
+                                .class(TextStyle::Subtitle)
+                                .into();
+
+                            let revision_label = container(revision_label)
+                                .padding(padding::bottom(8.0))
+                                .into();
+
+                            let revision = match &plugin.pinned_revision {
+                                None => short_revision(revision).to_owned(),
+                                Some(pinned_revision) => format!("{} (pinned to {})", short_revision(revision), pinned_revision),
+                            };
+
+                            let revision = container(text(revision))
+                                .padding(Padding::new(8.0))
+                                .into();
+
+                            let content: Element<_> = column(vec![revision_label, revision])
+                                .into();
+
+                            column_content.push(content);
+                        }
+
+                        if !plugin.permissions.is_empty() {
+                            let permissions_label: Element<_> = text("Permissions")
+                                .size(14)
+                                .class(TextStyle::Subtitle)
+                                .into();
+
+                            let permissions_label = container(permissions_label)
+                                .padding(padding::bottom(8.0))
+                                .into();
+
+                            let mut permissions = vec![permissions_label];
+
+                            for permission in &plugin.permissions {
+                                let style = if plugin.unapproved_permissions.contains(permission) {
+                                    TextStyle::Destructive
+                                } else {
+                                    TextStyle::Default
+                                };
+
+                                let permission: Element<_> = text(format!("{}: {}", permission.permission, permission.value))
+                                    .shaping(Shaping::Advanced)
+                                    .class(style)
+                                    .into();
+
+                                let permission = container(permission)
+                                    .padding(Padding::from([4.0, 8.0]))
+                                    .into();
+
+                                permissions.push(permission);
+                            }
+
+                            if !plugin.unapproved_permissions.is_empty() {
+                                let review_text: Element<_> = text("Review permissions")
+                                    .into();
+
+                                let review_text_container: Element<_> = container(review_text)
+                                    .width(Length::Fill)
+                                    .align_y(Alignment::Center)
+                                    .align_x(Alignment::Center)
+                                    .into();
+
+                                let review_button: Element<_> = button(review_text_container)
+                                    .width(Length::Fill)
+                                    .class(ButtonStyle::Primary)
+                                    .on_press(ManagementAppPluginMsgIn::ReviewPermissions { plugin_id: plugin.plugin_id.clone(), enable: false, permissions: plugin.permissions.clone() })
+                                    .into();
+
+                                let review_button = container(review_button)
+                                    .padding(Padding::from([4.0, 8.0]))
+                                    .into();
+
+                                permissions.push(review_button);
+                            }
+
+                            let content: Element<_> = column(permissions)
+                                .into();
+
+                            column_content.push(content);
+                        }
+
+                        if !plugin.permission_grants.is_empty() {
+                            let grants_label: Element<_> = text("Granted permissions")
+                                .size(14)
+                                .class(TextStyle::Subtitle)
+                                .into();
+
+                            let grants_label = container(grants_label)
+                                .padding(padding::bottom(8.0))
+                                .into();
+
+                            let mut grants = vec![grants_label];
+
+                            for grant in &plugin.permission_grants {
+                                let grant_text: Element<_> = text(format!("{}: {}", grant.permission, grant.value))
+                                    .shaping(Shaping::Advanced)
+                                    .width(Length::Fill)
+                                    .into();
+
+                                let revoke_icon = value(Bootstrap::Dash)
+                                    .font(BOOTSTRAP_FONT);
+
+                                let revoke_button: Element<_> = button(revoke_icon)
+                                    .class(ButtonStyle::Primary)
+                                    .on_press(ManagementAppPluginMsgIn::RevokePermissionGrant {
+                                        plugin_id: plugin.plugin_id.clone(),
+                                        permission: grant.permission.clone(),
+                                        value: grant.value.clone(),
+                                    })
+                                    .padding(Padding::from([5.0, 7.0]))
+                                    .into();
+
+                                let grant: Element<_> = row([grant_text, revoke_button])
+                                    .align_y(Alignment::Center)
+                                    .into();
+
+                                let grant = container(grant)
+                                    .padding(Padding::from([4.0, 8.0]))
+                                    .into();
+
+                                grants.push(grant);
+                            }
+
+                            let content: Element<_> = column(grants)
+                                .into();
+
+                            column_content.push(content);
+                        }
+
                         column_content.push(
                             preferences_ui(plugin_id.clone(), None, &plugin.preferences, &self.preference_user_data)
                                 .map(|msg| ManagementAppPluginMsgIn::PluginPreferenceMsg(msg))
@@ -367,7 +725,21 @@ impl ManagementAppPluginsState {
                         let mut column_content = vec![content];
 
                         if !plugin.plugin_id.to_string().starts_with("bundled://") {
-                             let check_for_updates_text: Element<_> = text("Check for updates")
+                            let available_update = self.available_updates
+                                .as_ref()
+                                .map(|updates| updates.get(&plugin.plugin_id));
+
+                            let (update_text, update_msg) = match available_update {
+                                _ if self.checking_for_updates => ("Checking for updates...".to_owned(), None),
+                                Some(Some(update)) => {
+                                    let update_text = format!("Update to {}", short_revision(&update.latest_revision));
+                                    (update_text, Some(ManagementAppPluginMsgIn::UpdatePlugin { plugin_id: plugin.plugin_id.clone() }))
+                                }
+                                Some(None) => ("Up to date. Check for updates again".to_owned(), Some(ManagementAppPluginMsgIn::CheckForUpdates)),
+                                None => ("Check for updates".to_owned(), Some(ManagementAppPluginMsgIn::CheckForUpdates)),
+                            };
+
+                            let check_for_updates_text: Element<_> = text(update_text)
                                 .into();
 
                             let check_for_updates_text_container: Element<_> = container(check_for_updates_text)
@@ -379,7 +751,7 @@ impl ManagementAppPluginsState {
                             let check_for_updates_button: Element<_> = button(check_for_updates_text_container)
                                 .width(Length::Fill)
                                 .class(ButtonStyle::Primary)
-                                .on_press(ManagementAppPluginMsgIn::DownloadPlugin { plugin_id: plugin.plugin_id.clone() })
+                                .on_press_maybe(update_msg)
                                 .into();
 
                             column_content.push(check_for_updates_button);
@@ -464,6 +836,16 @@ impl ManagementAppPluginsState {
                             column_content.push(content);
                         }
 
+                        match entrypoint.entrypoint_type {
+                            SettingsEntrypointType::Command | SettingsEntrypointType::View => {
+                                column_content.push(
+                                    aliases_ui(plugin_id.clone(), entrypoint_id.clone(), &entrypoint.aliases, &self.new_alias)
+                                        .map(|msg| ManagementAppPluginMsgIn::EntrypointAliasesMsg(msg))
+                                );
+                            }
+                            SettingsEntrypointType::InlineView | SettingsEntrypointType::EntrypointGenerator => {}
+                        }
+
                         column_content.push(
                             preferences_ui(plugin_id.clone(), Some(entrypoint_id.clone()), &entrypoint.preferences, &self.preference_user_data)
                                 .map(|msg| ManagementAppPluginMsgIn::PluginPreferenceMsg(msg))
@@ -485,17 +867,38 @@ impl ManagementAppPluginsState {
                     }
                 }
             }
-            SelectedItem::NewPlugin { repository_url } => {
+            SelectedItem::NewPlugin { repository_url, revision, local_path } => {
+                let download_msg = ManagementAppPluginMsgIn::DownloadPlugin {
+                    plugin_id: PluginId::from_string(repository_url),
+                    revision: non_empty_revision(revision),
+                };
+
                 let url_input: Element<_> = text_input("Enter Git Repository URL", &repository_url)
-                    .on_input(|value| ManagementAppPluginMsgIn::SelectItem(SelectedItem::NewPlugin { repository_url: value }))
-                    .on_submit(ManagementAppPluginMsgIn::DownloadPlugin { plugin_id: PluginId::from_string(repository_url) })
+                    .on_input(|value| ManagementAppPluginMsgIn::SelectItem(SelectedItem::NewPlugin { repository_url: value, revision: revision.clone(), local_path: local_path.clone() }))
+                    .on_submit(download_msg.clone())
+                    .into();
+
+                let revision_input: Element<_> = text_input("Tag, branch or commit (optional)", &revision)
+                    .on_input(|value| ManagementAppPluginMsgIn::SelectItem(SelectedItem::NewPlugin { repository_url: repository_url.clone(), revision: value, local_path: local_path.clone() }))
+                    .on_submit(download_msg)
+                    .into();
+
+                let local_path_input: Element<_> = text_input("Enter path to plugin archive or directory", &local_path)
+                    .on_input(|value| ManagementAppPluginMsgIn::SelectItem(SelectedItem::NewPlugin { repository_url: repository_url.clone(), revision: revision.clone(), local_path: value }))
+                    .on_submit(ManagementAppPluginMsgIn::InstallPlugin { path: local_path.clone() })
                     .into();
 
                 let content: Element<_> = column(vec![
                     url_input,
+                    revision_input,
                     text("Supported protocols:").into(),
                     text("http(s), ssh, git").into(),
-                ]).into();
+                    text("If revision is not specified, latest release is downloaded").into(),
+                    text("or").into(),
+                    local_path_input,
+                    text("Supported formats:").into(),
+                    text(".tar.gz, .zip or directory with built plugin").into(),
+                ]).spacing(8.0).into();
 
                 container(content)
                     .padding(Padding::new(8.0))
@@ -506,22 +909,24 @@ impl ManagementAppPluginsState {
             }
         };
 
+        let sidebar_content = match &self.permission_review {
+            Some(review) => self.view_permission_review(review),
+            None => sidebar_content,
+        };
 
-        let plugin_url = if let SelectedItem::NewPlugin { repository_url } = &self.selected_item {
-            if !repository_url.is_empty() {
-                Some(repository_url)
-            } else {
-                None
+        let new_plugin_action = match &self.selected_item {
+            SelectedItem::NewPlugin { repository_url, revision, .. } if !repository_url.is_empty() => {
+                Some(("Download plugin", ManagementAppPluginMsgIn::DownloadPlugin { plugin_id: PluginId::from_string(repository_url), revision: non_empty_revision(revision) }))
             }
-        } else {
-            None
+            SelectedItem::NewPlugin { local_path, .. } if !local_path.is_empty() => {
+                Some(("Install plugin", ManagementAppPluginMsgIn::InstallPlugin { path: local_path.clone() }))
+            }
+            _ => None
         };
 
-        let top_button_text = if plugin_url.is_some() {
-            text("Download plugin")
-        } else {
-            value(Bootstrap::Plus)
-                .font(BOOTSTRAP_FONT)
+        let top_button_text = match &new_plugin_action {
+            Some((label, _)) => text(*label),
+            None => value(Bootstrap::Plus).font(BOOTSTRAP_FONT)
         };
 
         let top_button_text_container: Element<_> = container(top_button_text)
@@ -530,9 +935,9 @@ impl ManagementAppPluginsState {
             .align_x(Alignment::Center)
             .into();
 
-        let top_button_action = match plugin_url {
-            Some(plugin_url) => ManagementAppPluginMsgIn::DownloadPlugin { plugin_id: PluginId::from_string(plugin_url) },
-            None => ManagementAppPluginMsgIn::SelectItem(SelectedItem::NewPlugin { repository_url: Default::default() })
+        let top_button_action = match new_plugin_action {
+            Some((_, action)) => action,
+            None => ManagementAppPluginMsgIn::SelectItem(SelectedItem::NewPlugin { repository_url: Default::default(), revision: Default::default(), local_path: Default::default() })
         };
 
         let top_button = button(top_button_text_container)
@@ -558,6 +963,128 @@ impl ManagementAppPluginsState {
         
         content
     }
+
+    fn view_permission_review(&self, review: &PermissionReview) -> Element<ManagementAppPluginMsgIn> {
+        let plugin_data = self.plugin_data.borrow();
+
+        let Some(plugin) = plugin_data.plugins.get(&review.plugin_id) else {
+            let loading_text: Element<_> = text("Loading...").into();
+
+            return container(loading_text)
+                .align_y(Alignment::Center)
+                .align_x(Alignment::Center)
+                .height(Length::Fill)
+                .width(Length::Fill)
+                .into()
+        };
+
+        let title = if plugin.permissions_changed {
+            format!("Update of \"{}\" requests additional permissions", plugin.plugin_name)
+        } else {
+            format!("\"{}\" requests following permissions", plugin.plugin_name)
+        };
+
+        let title: Element<_> = text(title)
+            .shaping(Shaping::Advanced)
+            .into();
+
+        let title = container(title)
+            .padding(Padding::new(8.0))
+            .into();
+
+        let mut permissions = vec![];
+
+        // exactly the permissions which are sent for approval are shown
+        for permission in &review.permissions {
+            let unapproved = plugin.unapproved_permissions.contains(permission);
+
+            // only newly added permissions are highlighted when plugin was updated
+            let (prefix, style) = match (plugin.permissions_changed, unapproved) {
+                (true, true) => ("+ ", TextStyle::Positive),
+                (true, false) => ("  ", TextStyle::Subtitle),
+                (false, _) => ("", TextStyle::Default),
+            };
+
+            let permission: Element<_> = text(format!("{}{}: {}", prefix, permission.permission, permission.value))
+                .shaping(Shaping::Advanced)
+                .class(style)
+                .into();
+
+            let permission = container(permission)
+                .padding(Padding::from([4.0, 8.0]))
+                .into();
+
+            permissions.push(permission);
+        }
+
+        let permissions: Element<_> = column(permissions)
+            .into();
+
+        let permissions: Element<_> = scrollable(permissions)
+            .height(Length::Fill)
+            .width(Length::Fill)
+            .into();
+
+        let approve_text: Element<_> = text("Approve")
+            .into();
+
+        let approve_text_container: Element<_> = container(approve_text)
+            .width(Length::Fill)
+            .align_y(Alignment::Center)
+            .align_x(Alignment::Center)
+            .into();
+
+        let approve_button: Element<_> = button(approve_text_container)
+            .width(Length::Fill)
+            .class(ButtonStyle::Positive)
+            .on_press(ManagementAppPluginMsgIn::ApprovePermissions { plugin_id: plugin.plugin_id.clone(), enable: review.enable, permissions: review.permissions.clone() })
+            .into();
+
+        let cancel_text: Element<_> = text("Cancel")
+            .into();
+
+        let cancel_text_container: Element<_> = container(cancel_text)
+            .width(Length::Fill)
+            .align_y(Alignment::Center)
+            .align_x(Alignment::Center)
+            .into();
+
+        let cancel_button: Element<_> = button(cancel_text_container)
+            .width(Length::Fill)
+            .class(ButtonStyle::Primary)
+            .on_press(ManagementAppPluginMsgIn::CancelPermissionReview)
+            .into();
+
+        let content: Element<_> = column(vec![title, permissions, approve_button, cancel_button])
+            .spacing(8.0)
+            .into();
+
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
+
+fn exit_code_text(exit_code: &Option<i32>) -> String {
+    match exit_code {
+        None => "".to_owned(),
+        Some(exit_code) => format!(" with exit code {}", exit_code),
+    }
+}
+
+fn short_revision(revision: &str) -> &str {
+    revision.get(..7).unwrap_or(revision)
+}
+
+fn non_empty_revision(revision: &str) -> Option<String> {
+    let revision = revision.trim();
+
+    if revision.is_empty() {
+        None
+    } else {
+        Some(revision.to_owned())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -579,7 +1106,9 @@ impl PluginDataContainer {
 pub enum SelectedItem {
     None,
     NewPlugin {
-        repository_url: String
+        repository_url: String,
+        revision: String,
+        local_path: String,
     },
     Plugin {
         plugin_id: PluginId
@@ -590,6 +1119,14 @@ pub enum SelectedItem {
     },
 }
 
+#[derive(Debug, Clone)]
+struct PermissionReview {
+    plugin_id: PluginId,
+    // enable plugin after permissions are approved
+    enable: bool,
+    permissions: Vec<SettingsPluginPermission>,
+}
+
 #[derive(Debug, Clone)]
 struct SettingsPluginData {
     show_entrypoints: bool,
@@ -0,0 +1,124 @@
+use crate::theme::button::ButtonStyle;
+use crate::theme::text::TextStyle;
+use crate::theme::Element;
+use gauntlet_common::model::{EntrypointId, PluginId};
+use iced::widget::{button, column, container, row, text, text_input};
+use iced::widget::text::Shaping;
+use iced::{padding, widget, Length, Padding};
+use iced_fonts::{Bootstrap, BOOTSTRAP_FONT};
+
+#[derive(Debug, Clone)]
+pub enum EntrypointAliasesMsg {
+    UpdateNewAlias {
+        new_alias: String
+    },
+    SetAliases {
+        plugin_id: PluginId,
+        entrypoint_id: EntrypointId,
+        aliases: Vec<String>
+    },
+}
+
+pub fn aliases_ui<'a>(
+    plugin_id: PluginId,
+    entrypoint_id: EntrypointId,
+    aliases: &[String],
+    new_alias: &str,
+) -> Element<'a, EntrypointAliasesMsg> {
+    let aliases_label: Element<_> = text("Aliases")
+        .size(14)
+        .class(TextStyle::Subtitle)
+        .into();
+
+    let aliases_label = container(aliases_label)
+        .padding(padding::left(8.0))
+        .into();
+
+    let aliases_description = text("Typing alias exactly in the search bar will show this entrypoint first")
+        .shaping(Shaping::Advanced);
+
+    let aliases_description = container(aliases_description)
+        .padding(Padding::from([4.0, 8.0]))
+        .into();
+
+    let mut items = vec![aliases_label, aliases_description];
+
+    for (index, alias) in aliases.iter().enumerate() {
+        let mut remaining_aliases = aliases.to_vec();
+        remaining_aliases.remove(index);
+
+        let item_text: Element<_> = text_input("", alias)
+            .width(Length::Fill)
+            .padding(Padding::new(4.0))
+            .into();
+
+        let remove_icon = widget::value(Bootstrap::Dash)
+            .font(BOOTSTRAP_FONT);
+
+        let remove_button: Element<_> = button(remove_icon)
+            .class(ButtonStyle::Primary)
+            .on_press(EntrypointAliasesMsg::SetAliases {
+                plugin_id: plugin_id.clone(),
+                entrypoint_id: entrypoint_id.clone(),
+                aliases: remaining_aliases,
+            })
+            .padding(Padding::from([5.0, 7.0]))
+            .into();
+
+        let remove_button = container(remove_button)
+            .padding(padding::bottom(8.0))
+            .into();
+
+        let item: Element<_> = row([item_text, remove_button])
+            .into();
+
+        let item = container(item)
+            .padding(Padding::from([4.0, 8.0]))
+            .into();
+
+        items.push(item);
+    }
+
+    let add_msg = if new_alias.trim().is_empty() {
+        None
+    } else {
+        let mut aliases = aliases.to_vec();
+        aliases.push(new_alias.trim().to_owned());
+
+        Some(EntrypointAliasesMsg::SetAliases {
+            plugin_id: plugin_id.clone(),
+            entrypoint_id: entrypoint_id.clone(),
+            aliases,
+        })
+    };
+
+    let add_icon: Element<_> = widget::value(Bootstrap::Plus)
+        .font(BOOTSTRAP_FONT)
+        .into();
+
+    let add_button: Element<_> = button(add_icon)
+        .class(ButtonStyle::Primary)
+        .on_press_maybe(add_msg)
+        .padding(Padding::from([5.0, 7.0]))
+        .into();
+
+    let add_button: Element<_> = container(add_button)
+        .padding(padding::bottom(8.0))
+        .into();
+
+    let add_text_input: Element<_> = text_input("Enter alias...", new_alias)
+        .on_input(|new_alias| EntrypointAliasesMsg::UpdateNewAlias { new_alias })
+        .into();
+
+    let add_item: Element<_> = row([add_text_input, add_button])
+        .into();
+
+    let add_item: Element<_> = container(add_item)
+        .padding(Padding::new(8.0))
+        .into();
+
+    items.push(add_item);
+
+    column(items)
+        .into()
+}
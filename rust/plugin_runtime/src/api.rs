@@ -1,4 +1,4 @@
-use crate::model::{JsGeneratedSearchItem, JsClipboardData, JsPreferenceUserData};
+use crate::model::{JsAuditCapability, JsGeneratedSearchItem, JsClipboardData, JsPreferenceUserData};
 use crate::{JsRequest, JsResponse, JsUiRenderLocation};
 use gauntlet_common::model::{EntrypointId, RootWidget, UiRenderLocation};
 use std::collections::HashMap;
@@ -8,6 +8,7 @@ use gauntlet_utils::channel::{RequestError, RequestSender};
 #[allow(async_fn_in_trait)]
 pub trait BackendForPluginRuntimeApi {
     async fn reload_search_index(&self, generated_commands: Vec<JsGeneratedSearchItem>, refresh_search_list: bool) -> anyhow::Result<()> ;
+    async fn update_search_index(&self, updated_generated_commands: Vec<JsGeneratedSearchItem>, removed_generated_entrypoint_ids: Vec<String>, refresh_search_list: bool) -> anyhow::Result<()>;
     async fn get_asset_data(&self, path: &str) -> anyhow::Result<Vec<u8>>;
     async fn get_entrypoint_generator_entrypoint_ids(&self) -> anyhow::Result<Vec<String>>;
     async fn get_plugin_preferences(&self) -> anyhow::Result<HashMap<String, JsPreferenceUserData>>;
@@ -19,6 +20,8 @@ pub trait BackendForPluginRuntimeApi {
     async fn clipboard_write(&self, data: JsClipboardData) -> anyhow::Result<()>;
     async fn clipboard_write_text(&self, data: String) -> anyhow::Result<()>;
     async fn clipboard_clear(&self) -> anyhow::Result<()>;
+    async fn request_permission(&self, permission: String, value: String) -> anyhow::Result<bool>;
+    async fn record_capability_use(&self, capability: JsAuditCapability, value: String) -> anyhow::Result<()>;
     async fn ui_update_loading_bar(&self, entrypoint_id: EntrypointId, show: bool) -> anyhow::Result<()>;
     async fn ui_show_hud(&self, display: String) -> anyhow::Result<()>;
     async fn ui_get_action_id_for_shortcut(
@@ -90,6 +93,19 @@ impl BackendForPluginRuntimeApi for BackendForPluginRuntimeApiProxy {
         }
     }
 
+    async fn update_search_index(&self, updated_generated_commands: Vec<JsGeneratedSearchItem>, removed_generated_entrypoint_ids: Vec<String>, refresh_search_list: bool) -> anyhow::Result<()> {
+        let request = JsRequest::UpdateSearchIndex {
+            updated_generated_commands,
+            removed_generated_entrypoint_ids,
+            refresh_search_list,
+        };
+
+        match self.request(request).await? {
+            JsResponse::Nothing => Ok(()),
+            value @ _ => panic!("Unexpected JsResponse type: {:?}", value)
+        }
+    }
+
     async fn get_asset_data(&self, path: &str) -> anyhow::Result<Vec<u8>> {
         let request = JsRequest::GetAssetData {
             path: path.to_string(),
@@ -199,6 +215,30 @@ impl BackendForPluginRuntimeApi for BackendForPluginRuntimeApiProxy {
         }
     }
 
+    async fn request_permission(&self, permission: String, value: String) -> anyhow::Result<bool> {
+        let request = JsRequest::RequestPermission {
+            permission,
+            value,
+        };
+
+        match self.request(request).await? {
+            JsResponse::PermissionRequested { granted } => Ok(granted),
+            value @ _ => panic!("Unexpected JsResponse type: {:?}", value)
+        }
+    }
+
+    async fn record_capability_use(&self, capability: JsAuditCapability, value: String) -> anyhow::Result<()> {
+        let request = JsRequest::RecordCapabilityUse {
+            capability,
+            value,
+        };
+
+        match self.request(request).await? {
+            JsResponse::Nothing => Ok(()),
+            value @ _ => panic!("Unexpected JsResponse type: {:?}", value)
+        }
+    }
+
     async fn ui_update_loading_bar(&self, entrypoint_id: EntrypointId, show: bool) -> anyhow::Result<()> {
         let request = JsRequest::UpdateLoadingBar {
             entrypoint_id,
@@ -1,16 +1,21 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::fs::File;
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::rc::Rc;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use anyhow::{anyhow, Context};
-use deno_core::{FastString, ModuleLoadResponse, ModuleLoader, ModuleSource, ModuleSourceCode, ModuleSpecifier, ModuleType, RequestedModuleType, ResolutionKind, StaticModuleLoader};
+use deno_core::{v8, FastString, ModuleLoadResponse, ModuleLoader, ModuleSource, ModuleSourceCode, ModuleSpecifier, ModuleType, RequestedModuleType, ResolutionKind, StaticModuleLoader};
 use deno_core::futures::Stream;
 use deno_core::url::Url;
 use deno_runtime::BootstrapOptions;
 use deno_runtime::deno_fs::{FileSystem, RealFs};
 use deno_runtime::deno_io::{Stdio, StdioPipe};
+use deno_runtime::inspector_server::InspectorServer;
 use deno_runtime::worker::{MainWorker, WorkerOptions, WorkerServiceOptions};
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -24,16 +29,17 @@ use crate::entrypoint_generators::get_entrypoint_generator_entrypoint_ids;
 use crate::component_model::ComponentModel;
 use crate::environment::{environment_gauntlet_version, environment_is_development, environment_plugin_cache_dir, environment_plugin_data_dir};
 use crate::events::{op_plugin_get_pending_event, EventReceiver, JsEvent};
-use crate::JsPluginCode;
+use crate::{JsPluginCode, EXIT_CODE_EVENT_LOOP_TIMEOUT, EXIT_CODE_HEAP_LIMIT};
 use crate::logs::{op_log_debug, op_log_error, op_log_info, op_log_trace, op_log_warn};
 use crate::model::JsInit;
-use crate::permissions::{permissions_to_deno};
+use crate::permissions::{permissions_to_deno, BackendPermissionPrompter};
 use crate::plugin_data::PluginData;
-use crate::plugins::applications::{current_os, wayland, ApplicationContext};
-use crate::plugins::numbat::{run_numbat, NumbatContext};
+use crate::plugins::applications::{applications_open, current_os, wayland, ApplicationContext};
+use crate::plugins::numbat::{numbat_add_history_entry, numbat_clear_history, numbat_history, numbat_remove_history_entry, numbat_save_variables, run_numbat, NumbatContext};
+use crate::window_management::{window_management_capabilities, window_management_close, window_management_focus, window_management_list, window_management_minimize, window_management_pending_event, WindowManagementContext};
 use crate::plugins::settings::open_settings;
 use crate::preferences::{entrypoint_preferences_required, get_entrypoint_preferences, get_plugin_preferences, plugin_preferences_required};
-use crate::search::reload_search_index;
+use crate::search::{reload_search_index, update_search_index};
 use crate::ui::{clear_inline_view, fetch_action_id_for_shortcut, op_component_model, op_entrypoint_names, op_inline_view_entrypoint_id, op_react_replace_view, show_hud, show_plugin_error_view, show_preferences_required_view, update_loading_bar};
 
 
@@ -194,6 +200,7 @@ deno_core::extension!(
 
         // search
         reload_search_index,
+        update_search_index,
 
         // clipboard
         clipboard_read_text,
@@ -202,18 +209,30 @@ deno_core::extension!(
         clipboard_write_text,
         clipboard_clear,
 
+        // windows
+        window_management_capabilities,
+        window_management_list,
+        window_management_focus,
+        window_management_close,
+        window_management_minimize,
+        window_management_pending_event,
+
         // plugin environment
         environment_gauntlet_version,
         environment_is_development,
         environment_plugin_data_dir,
         environment_plugin_cache_dir,
+
+        // applications
+        applications_open,
     ],
     options = {
         event_receiver: EventReceiver,
         plugin_data: PluginData,
         component_model: ComponentModel,
         backend_api: BackendForPluginRuntimeApiProxy,
-        outer_handle: Handle
+        outer_handle: Handle,
+        window_management_context: WindowManagementContext,
     },
     state = |state, options| {
         state.put(options.event_receiver);
@@ -221,6 +240,7 @@ deno_core::extension!(
         state.put(options.component_model);
         state.put(options.backend_api);
         state.put(options.outer_handle);
+        state.put(options.window_management_context);
     },
 );
 
@@ -264,6 +284,11 @@ deno_core::extension!(
     ops = [
         // plugins numbat
         run_numbat,
+        numbat_save_variables,
+        numbat_history,
+        numbat_add_history_entry,
+        numbat_remove_history_entry,
+        numbat_clear_history,
 
         // plugins applications
         current_os,
@@ -354,14 +379,30 @@ pub async fn start_js_runtime(
 
     let home_dir = PathBuf::from(init.home_dir);
 
-    let permissions_container = permissions_to_deno(
+    let (permissions_container, prompter_permissions) = permissions_to_deno(
         fs.clone(),
         &init.permissions,
         &home_dir,
         Path::new(&init.plugin_data_dir),
         Path::new(&init.plugin_cache_dir),
+        Path::new(&init.plugin_assets_dir),
+        init.permission_prompts,
+        init.audit_log,
     )?;
 
+    let prompt_in_progress = Arc::new(AtomicBool::new(false));
+
+    if init.permission_prompts || init.audit_log || !prompter_permissions.is_empty() {
+        deno_runtime::deno_permissions::prompter::set_prompter(Box::new(BackendPermissionPrompter::new(
+            outer_handle.clone(),
+            api.clone(),
+            prompt_in_progress.clone(),
+            prompter_permissions,
+            init.permission_prompts,
+            init.audit_log,
+        )));
+    }
+
     let gauntlet_esm = if cfg!(feature = "release") && !init.dev_plugin {
         prod::gauntlet_esm::init_ops_and_esm()
     } else {
@@ -382,7 +423,8 @@ pub async fn start_js_runtime(
             ),
             ComponentModel::new(),
             api,
-            outer_handle
+            outer_handle,
+            WindowManagementContext::new(init.permissions.windows.clone()),
         ),
         gauntlet_esm,
     ];
@@ -403,6 +445,30 @@ pub async fn start_js_runtime(
         extensions.push(crate::plugins::applications::gauntlet_internal_windows::init_ops_and_esm());
     }
 
+    let maybe_inspector_server = match &init.inspector {
+        Some(inspector) => {
+            let address = SocketAddr::from(([127, 0, 0, 1], inspector.port));
+
+            tracing::info!("Starting inspector for plugin {:?} at {}", init.plugin_id, address);
+
+            let inspector_server = InspectorServer::new(address, "gauntlet")
+                .context("Unable to start inspector server")?;
+
+            Some(Arc::new(inspector_server))
+        }
+        None => None
+    };
+
+    let break_on_first_statement = init.inspector
+        .as_ref()
+        .is_some_and(|inspector| inspector.break_on_first_statement);
+
+    let limit_violation = Arc::new(Mutex::new(None));
+
+    let create_params = init.limits.heap_mb
+        .map(|heap_mb| anyhow::Ok(v8::CreateParams::default().heap_limits(0, heap_limit_bytes(heap_mb)?)))
+        .transpose()?;
+
     let mut worker = MainWorker::bootstrap_from_options(
         init_url.clone(),
         WorkerServiceOptions {
@@ -427,9 +493,10 @@ pub async fn start_js_runtime(
                 ..Default::default()
             },
             extensions,
-            maybe_inspector_server: None,
-            should_wait_for_inspector_session: false,
-            should_break_on_first_statement: false,
+            create_params,
+            maybe_inspector_server,
+            should_wait_for_inspector_session: break_on_first_statement,
+            should_break_on_first_statement: break_on_first_statement,
             origin_storage_dir: Some(PathBuf::from(init.local_storage_dir)),
             stdio: Stdio {
                 stdin: StdioPipe::inherit(),
@@ -440,9 +507,167 @@ pub async fn start_js_runtime(
         },
     );
 
-    worker.execute_main_module(&init_url).await?;
-    worker.run_event_loop(false).await?;
+    if init.limits.heap_mb.is_some() {
+        let isolate_handle = worker.js_runtime.v8_isolate().thread_safe_handle();
+        let limit_violation = limit_violation.clone();
+
+        worker.js_runtime.add_near_heap_limit_callback(move |current_limit, _initial_limit| {
+            limit_violation.lock().expect("lock is poisoned").get_or_insert(LimitViolation::HeapLimit);
+
+            isolate_handle.terminate_execution();
+
+            // give isolate some room to unwind after termination instead of crashing with oom
+            current_limit * 2
+        });
+    }
+
+    // event loop is blocked while execution is paused in debugger
+    let _watchdog = init.limits.event_loop_timeout_secs
+        .filter(|_| init.inspector.is_none())
+        .map(|timeout| {
+            EventLoopWatchdog::start(
+                worker.js_runtime.v8_isolate().thread_safe_handle(),
+                Duration::from_secs(timeout),
+                limit_violation.clone(),
+                prompt_in_progress.clone(),
+            )
+        });
+
+    let result = async {
+        worker.execute_main_module(&init_url).await?;
+        worker.run_event_loop(false).await?;
+
+        anyhow::Ok(())
+    }.await;
+
+    if let Some(limit_violation) = *limit_violation.lock().expect("lock is poisoned") {
+        return Err(anyhow!(limit_violation))
+    }
+
+    result
+}
+
+fn heap_limit_bytes(heap_mb: u64) -> anyhow::Result<usize> {
+    usize::try_from(heap_mb)
+        .ok()
+        .and_then(|heap_mb| heap_mb.checked_mul(1024 * 1024))
+        .ok_or(anyhow!("Heap limit of {} MB is too large", heap_mb))
+}
+
+/// Resource limit violated by plugin, runtime process exits with exit code specific to violation
+#[derive(Debug, Clone, Copy)]
+pub enum LimitViolation {
+    HeapLimit,
+    EventLoopTimeout,
+}
+
+impl LimitViolation {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            LimitViolation::HeapLimit => EXIT_CODE_HEAP_LIMIT,
+            LimitViolation::EventLoopTimeout => EXIT_CODE_EVENT_LOOP_TIMEOUT,
+        }
+    }
+}
+
+impl fmt::Display for LimitViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitViolation::HeapLimit => write!(f, "JavaScript heap limit reached"),
+            LimitViolation::EventLoopTimeout => write!(f, "Event loop was blocked for too long"),
+        }
+    }
+}
+
+impl std::error::Error for LimitViolation {}
+
+const WATCHDOG_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Terminates JS execution if event loop doesn't get a chance to run for longer than specified timeout.
+/// Heartbeat task runs on the same thread as event loop, so it is delayed by any long-running synchronous work.
+/// Time spent waiting for user to answer permission prompt is not counted
+struct EventLoopWatchdog {
+    stop: Arc<AtomicBool>,
+    heartbeat: tokio::task::JoinHandle<()>,
+}
+
+impl EventLoopWatchdog {
+    fn start(
+        isolate_handle: v8::IsolateHandle,
+        timeout: Duration,
+        limit_violation: Arc<Mutex<Option<LimitViolation>>>,
+        prompt_in_progress: Arc<AtomicBool>,
+    ) -> Self {
+        let started = Instant::now();
+        let last_heartbeat = Arc::new(AtomicU64::new(0));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let heartbeat = tokio::spawn({
+            let last_heartbeat = last_heartbeat.clone();
+            async move {
+                loop {
+                    last_heartbeat.store(started.elapsed().as_millis() as u64, Ordering::SeqCst);
+
+                    tokio::time::sleep(WATCHDOG_INTERVAL).await;
+                }
+            }
+        });
+
+        std::thread::spawn({
+            let stop = stop.clone();
+            move || {
+                while !stop.load(Ordering::SeqCst) {
+                    std::thread::sleep(WATCHDOG_INTERVAL);
+
+                    let now = started.elapsed().as_millis() as u64;
+
+                    if prompt_in_progress.load(Ordering::SeqCst) {
+                        last_heartbeat.store(now, Ordering::SeqCst);
+                        continue;
+                    }
+
+                    let blocked_for = Duration::from_millis(now.saturating_sub(last_heartbeat.load(Ordering::SeqCst)));
+
+                    if blocked_for > timeout {
+                        tracing::error!("Event loop was blocked for {:?}, terminating execution", blocked_for);
+
+                        limit_violation.lock().expect("lock is poisoned").get_or_insert(LimitViolation::EventLoopTimeout);
+
+                        isolate_handle.terminate_execution();
 
-    Ok(())
+                        break;
+                    }
+                }
+            }
+        });
+
+        Self {
+            stop,
+            heartbeat,
+        }
+    }
+}
+
+impl Drop for EventLoopWatchdog {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        self.heartbeat.abort();
+    }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heap_limit_is_converted_to_bytes() {
+        assert_eq!(heap_limit_bytes(256).unwrap(), 256 * 1024 * 1024);
+    }
+
+    #[test]
+    fn too_large_heap_limit_is_rejected() {
+        assert!(heap_limit_bytes(u64::MAX).is_err());
+        assert!(heap_limit_bytes(u64::MAX / 1024).is_err());
+    }
+}
@@ -54,7 +54,6 @@ pub enum JsEvent {
         text: String,
     },
     ReloadSearchIndex,
-    RefreshSearchIndex,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Encode, Decode)]
@@ -14,6 +14,7 @@ mod plugins;
 mod preferences;
 mod search;
 mod ui;
+mod window_management;
 
 use crate::api::BackendForPluginRuntimeApiProxy;
 use crate::deno::start_js_runtime;
@@ -42,22 +43,36 @@ use tokio_util::sync::CancellationToken;
 use gauntlet_utils::channel::{Payload, RequestReceiver};
 
 pub use api::BackendForPluginRuntimeApi;
+pub use deno::LimitViolation;
 pub use events::JsEvent;
 pub use events::JsKeyboardEventOrigin;
 pub use events::JsUiPropertyValue;
 pub use model::*;
+pub use permissions::NetworkPermission;
 pub use permissions::PERMISSIONS_VARIABLE_PATTERN;
 
-pub fn run_plugin_runtime(socket_name: String) {
-    tokio::runtime::Builder::new_current_thread()
+/// Exit code of runtime process terminated after reaching V8 heap limit
+pub const EXIT_CODE_HEAP_LIMIT: i32 = 120;
+/// Exit code of runtime process terminated by event loop watchdog
+pub const EXIT_CODE_EVENT_LOOP_TIMEOUT: i32 = 121;
+
+/// Runs plugin runtime until it is stopped. Returns error if runtime was stopped because plugin violated one of its limits,
+/// when run in separate process, the process is expected to exit with [`LimitViolation::exit_code`]
+pub fn run_plugin_runtime(socket_name: String) -> Result<(), LimitViolation> {
+    let limit_violation = tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()
         .expect("unable to start tokio runtime for plugin")
         .block_on(run_outer(socket_name))
         .expect("plugin runtime crashed");
+
+    match limit_violation {
+        None => Ok(()),
+        Some(limit_violation) => Err(limit_violation),
+    }
 }
 
-async fn run_outer(socket_name: String) -> anyhow::Result<()> {
+async fn run_outer(socket_name: String) -> anyhow::Result<Option<LimitViolation>> {
     tracing::info!("Starting plugin runtime at socket: {}", &socket_name);
 
     let stop_token = CancellationToken::new();
@@ -85,9 +100,10 @@ async fn run_outer(socket_name: String) -> anyhow::Result<()> {
 
     let handle = Handle::current();
 
-    tokio::select! {
+    let limit_violation = tokio::select! {
         _ = stop_token.cancelled() => {
-            tracing::debug!("Plugin runtime outer loop will be stopped {:?}", plugin_id)
+            tracing::debug!("Plugin runtime outer loop will be stopped {:?}", plugin_id);
+            None
         }
         result @ _ = {
              tokio::task::unconstrained(async {
@@ -99,7 +115,8 @@ async fn run_outer(socket_name: String) -> anyhow::Result<()> {
                 }
              })
         } => {
-            tracing::error!("Message loop has unexpectedly stopped {:?}", plugin_id)
+            tracing::error!("Message loop has unexpectedly stopped {:?}", plugin_id);
+            None
         }
         result @ _ = {
              tokio::task::unconstrained(async {
@@ -111,14 +128,34 @@ async fn run_outer(socket_name: String) -> anyhow::Result<()> {
                 }
              })
         } => {
-            tracing::error!("Request loop has unexpectedly stopped {:?}", plugin_id)
+            tracing::error!("Request loop has unexpectedly stopped {:?}", plugin_id);
+            None
         }
         result @ _ = {
             run_new_tokio(handle, stop_token.clone(), init, event_receiver, api)
         } => {
-            tracing::error!("Request loop has unexpectedly stopped {:?}", plugin_id)
+            match result {
+                Ok(()) => {
+                    tracing::error!("Request loop has unexpectedly stopped {:?}", plugin_id);
+                    None
+                }
+                Err(err) => {
+                    match err.downcast::<LimitViolation>() {
+                        Ok(limit_violation) => {
+                            tracing::error!("Plugin runtime has violated resource limit {:?}: {}", plugin_id, limit_violation);
+
+                            Some(limit_violation)
+                        }
+                        Err(err) => {
+                            tracing::error!("Plugin runtime inner loop has failed {:?} - {:?}", plugin_id, err);
+
+                            None
+                        }
+                    }
+                }
+            }
         }
-    }
+    };
 
     send_message(JsMessageSide::PluginRuntime, &mut sender, JsPluginRuntimeMessage::Stopped).await?;
 
@@ -126,7 +163,7 @@ async fn run_outer(socket_name: String) -> anyhow::Result<()> {
 
     drop((recver, sender));
 
-    Ok(())
+    Ok(limit_violation)
 }
 
 async fn run_new_tokio(outer_handle: Handle, stop_token: CancellationToken, init: JsInit, event_receiver: Receiver<JsEvent>, api: BackendForPluginRuntimeApiProxy) -> anyhow::Result<()> {
@@ -154,6 +191,10 @@ async fn run(outer_handle: Handle, stop_token: CancellationToken, init: JsInit,
             })
         } => {
             if let Err(err) = result {
+                if err.is::<LimitViolation>() {
+                    return Err(err)
+                }
+
                 tracing::error!("Plugin runtime inner loop has failed {:?} - {:?}", plugin_id, err)
             }
         }
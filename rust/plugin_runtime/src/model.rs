@@ -36,8 +36,29 @@ pub struct JsInit {
     pub local_storage_dir: String,
     pub plugin_cache_dir: String,
     pub plugin_data_dir: String,
+    pub plugin_assets_dir: String,
     pub stdout_file: Option<String>,
     pub stderr_file: Option<String>,
+    pub limits: JsPluginLimits,
+    pub inspector: Option<JsPluginInspector>,
+    pub permission_prompts: bool,
+    pub audit_log: bool,
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct JsPluginInspector {
+    pub port: u16,
+    pub break_on_first_statement: bool,
+}
+
+#[derive(Debug, Clone, Default, Encode, Decode)]
+pub struct JsPluginLimits {
+    pub heap_mb: Option<u64>,
+    // enforced by backend when spawning runtime process
+    pub address_space_mb: Option<u64>,
+    // enforced by backend when spawning runtime process
+    pub cpu_seconds: Option<u64>,
+    pub event_loop_timeout_secs: Option<u64>,
 }
 
 #[derive(Debug, Encode, Decode)]
@@ -48,6 +69,7 @@ pub struct JsPluginPermissions {
     pub exec: JsPluginPermissionsExec,
     pub system: Vec<String>,
     pub main_search_bar: Vec<JsPluginPermissionsMainSearchBar>,
+    pub windows: Vec<JsPluginPermissionsWindows>,
 }
 
 #[derive(Debug, Encode, Decode)]
@@ -67,6 +89,14 @@ pub enum JsPluginPermissionsMainSearchBar {
     Read,
 }
 
+#[derive(Clone, Debug, Encode, Decode, PartialEq)]
+pub enum JsPluginPermissionsWindows {
+    Read,
+    Focus,
+    Close,
+    Minimize,
+}
+
 #[derive(Debug, Encode, Decode)]
 pub enum JsPluginRuntimeMessage {
     Stopped,
@@ -103,6 +133,9 @@ pub enum JsResponse {
     ActionIdForShortcut {
         data: Option<String>
     },
+    PermissionRequested {
+        granted: bool
+    },
 }
 
 #[derive(Debug, Encode, Decode)]
@@ -135,6 +168,11 @@ pub enum JsRequest {
         generated_commands: Vec<JsGeneratedSearchItem>,
         refresh_search_list: bool
     },
+    UpdateSearchIndex {
+        updated_generated_commands: Vec<JsGeneratedSearchItem>,
+        removed_generated_entrypoint_ids: Vec<String>,
+        refresh_search_list: bool
+    },
     GetAssetData {
         path: String,
     },
@@ -164,6 +202,22 @@ pub enum JsRequest {
         modifier_alt: bool,
         modifier_meta: bool
     },
+    RequestPermission {
+        permission: String,
+        value: String,
+    },
+    RecordCapabilityUse {
+        capability: JsAuditCapability,
+        value: String,
+    },
+}
+
+// capabilities enforced by deno permissions, clipboard use is recorded by backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum JsAuditCapability {
+    Network,
+    Command,
+    FileWrite,
 }
 
 #[derive(Deserialize, Serialize, Encode, Decode)]
@@ -175,6 +229,7 @@ pub struct JsGeneratedSearchItem {
     pub entrypoint_icon: Option<Vec<u8>>,
     pub entrypoint_actions: Vec<JsGeneratedSearchItemAction>,
     pub entrypoint_accessories: Vec<JsGeneratedSearchItemAccessory>,
+    pub entrypoint_keywords: Vec<String>,
 }
 
 impl fmt::Debug for JsGeneratedSearchItem {
@@ -187,6 +242,7 @@ impl fmt::Debug for JsGeneratedSearchItem {
             .field("entrypoint_uuid", &self.entrypoint_uuid)
             .field("entrypoint_actions", &self.entrypoint_actions)
             .field("entrypoint_accessories", &self.entrypoint_accessories)
+            .field("entrypoint_keywords", &self.entrypoint_keywords)
             .finish()
     }
 }
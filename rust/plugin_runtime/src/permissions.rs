@@ -1,59 +1,105 @@
 use std::collections::HashSet;
 use std::hash::Hash;
-use std::path::{Path, PathBuf};
+use std::net::{IpAddr, Ipv4Addr, ToSocketAddrs};
+use std::path::{Component, Path, PathBuf};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use anyhow::anyhow;
+use deno_core::url::{Host, Url};
+use directories::{BaseDirs, UserDirs};
 use deno_runtime::deno_fs::{FileSystemRc, RealFs};
+use deno_runtime::deno_permissions::prompter::{GetFormattedStackFn, PermissionPrompter, PromptResponse};
 use deno_runtime::deno_permissions::{AllowRunDescriptor, EnvDescriptor, EnvQueryDescriptor, NetDescriptor, Permissions, PermissionsContainer, QueryDescriptor, ReadDescriptor, RunQueryDescriptor, SysDescriptor, SysDescriptorParseError, UnaryPermission, WriteDescriptor};
 use deno_runtime::permissions::RuntimePermissionDescriptorParser;
 use once_cell::sync::Lazy;
-use regex::Regex;
+use regex::{NoExpand, Regex};
 use typed_path::Utf8TypedPath;
+use tokio::runtime::Handle;
 use gauntlet_common::dirs::Dirs;
+use crate::api::{BackendForPluginRuntimeApi, BackendForPluginRuntimeApiProxy};
+use crate::model::JsAuditCapability;
 use crate::{JsPluginPermissions, JsPluginPermissionsExec};
 
 pub static PERMISSIONS_VARIABLE_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{(?<namespace>.+?):(?<name>.+?)}").expect("invalid regex"));
 
+static PROMPT_MESSAGE_VALUE_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r#"access to "(?<value>.*)""#).expect("invalid regex"));
+
+/// Returns deno permissions and permissions which are checked by [`BackendPermissionPrompter`] instead of deno.
+/// Deno doesn't report access it allowed by itself, so if audit log is enabled, network access, file writes
+/// and command execution are not put into deno allow lists and every new value goes through prompter, which records it
 pub fn permissions_to_deno(
     fs: FileSystemRc,
     permissions: &JsPluginPermissions,
     home_dir: &Path,
     plugin_data_dir: &Path,
     plugin_cache_dir: &Path,
-) -> anyhow::Result<PermissionsContainer> {
-    Ok(PermissionsContainer::new(
+    plugin_assets_dir: &Path,
+    prompt: bool,
+    audit: bool,
+) -> anyhow::Result<(PermissionsContainer, PrompterPermissions)> {
+    let (net, network) = net_permission(&permissions.network, prompt, audit)?;
+
+    let read_paths = augment_paths(&permissions.filesystem.read, home_dir, plugin_data_dir, plugin_cache_dir, plugin_assets_dir)?;
+    let write_paths = augment_paths(&permissions.filesystem.write, home_dir, plugin_data_dir, plugin_cache_dir, plugin_assets_dir)?;
+    let executables = run_allow_list(&permissions.exec, home_dir, plugin_data_dir, plugin_cache_dir, plugin_assets_dir)?;
+
+    let (write, write_paths) = if audit {
+        (Permissions::new_unary(None, None, true), write_paths)
+    } else {
+        (path_permission(write_paths, WriteDescriptor, prompt), vec![])
+    };
+
+    let (run, executables) = if audit {
+        (Permissions::new_unary(None, None, true), executables)
+    } else {
+        (run_permission(executables, prompt), vec![])
+    };
+
+    let container = PermissionsContainer::new(
         Arc::new(RuntimePermissionDescriptorParser::new(fs)),
         Permissions {
-            read: path_permission(&permissions.filesystem.read, ReadDescriptor, home_dir, plugin_data_dir, plugin_cache_dir)?,
-            write: path_permission(&permissions.filesystem.write, WriteDescriptor, home_dir, plugin_data_dir, plugin_cache_dir)?,
-            net: net_permission(&permissions.network),
-            env: env_permission(&permissions.environment),
-            sys: sys_permission(&permissions.system)?,
-            run: run_permission(&permissions.exec, home_dir, plugin_data_dir, plugin_cache_dir)?,
+            read: path_permission(read_paths, ReadDescriptor, prompt),
+            write,
+            net,
+            env: env_permission(&permissions.environment, prompt),
+            sys: sys_permission(&permissions.system, prompt)?,
+            run,
             ffi: Permissions::new_unary(None, None, false),
             import: UnaryPermission::default(),
             all: Permissions::new_all(false),
         }
-    ))
+    );
+
+    let prompter_permissions = PrompterPermissions {
+        network,
+        write: write_paths,
+        run: executables,
+    };
+
+    Ok((container, prompter_permissions))
+}
+
+fn augment_paths(paths: &[String], home_dir: &Path, plugin_data_dir: &Path, plugin_cache_dir: &Path, plugin_assets_dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let paths = paths
+        .iter()
+        .map(|path| augment_path(path, home_dir, plugin_data_dir, plugin_cache_dir, plugin_assets_dir))
+        .collect::<anyhow::Result<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    Ok(paths)
 }
 
 fn path_permission<P: Eq + Hash, T: QueryDescriptor<AllowDesc = P, DenyDesc = P> + Hash>(
-    paths: &[String],
+    paths: Vec<PathBuf>,
     to_permission: fn(PathBuf) -> P,
-    home_dir: &Path,
-    plugin_data_dir: &Path,
-    plugin_cache_dir: &Path,
-) -> anyhow::Result<UnaryPermission<T>> {
+    prompt: bool,
+) -> UnaryPermission<T> {
     let allow_list = paths
         .into_iter()
-        .map(|path| {
-            augment_path(path, home_dir, plugin_data_dir, plugin_cache_dir)
-                .map(|path| path.map(|path| to_permission(path)))
-        })
-        .collect::<anyhow::Result<Vec<_>>>()?
-        .into_iter()
-        .filter_map(std::convert::identity)
+        .map(|path| to_permission(path))
         .collect::<HashSet<_>>();
 
     let allow_list = if allow_list.is_empty() {
@@ -62,27 +108,236 @@ fn path_permission<P: Eq + Hash, T: QueryDescriptor<AllowDesc = P, DenyDesc = P>
         Some(allow_list)
     };
 
-    Ok(Permissions::new_unary(allow_list, None, false))
+    Permissions::new_unary(allow_list, None, prompt)
 }
 
-fn net_permission(domain_and_ports: &[String]) -> UnaryPermission<NetDescriptor> {
-    let allow_list = if domain_and_ports.is_empty() {
+fn net_permission(domain_and_ports: &[String], prompt: bool, audit: bool) -> anyhow::Result<(UnaryPermission<NetDescriptor>, Vec<NetworkPermission>)> {
+    let mut allow_list = HashSet::new();
+    let mut patterns = vec![];
+
+    for domain_and_port in domain_and_ports {
+        let permission = NetworkPermission::parse(domain_and_port)?;
+
+        if audit || permission.is_pattern() {
+            patterns.push(permission);
+        } else {
+            allow_list.insert(NetDescriptor::parse(domain_and_port)?);
+        }
+    }
+
+    let allow_list = if allow_list.is_empty() {
         None
     } else {
-        let allow_list = domain_and_ports.into_iter()
-            .map(|domain_and_port| {
-                NetDescriptor::parse(&domain_and_port)
-                    .expect("should be validated when loading")
-            })
-            .collect();
-
         Some(allow_list)
     };
 
-    Permissions::new_unary(allow_list, None, false)
+    // access not present in allow list goes to prompter, which checks it against patterns
+    let prompt = prompt || audit || !patterns.is_empty();
+
+    Ok((Permissions::new_unary(allow_list, None, prompt), patterns))
+}
+
+/// Network permission from plugin manifest, in addition to `domain[:port]` supported by deno,
+/// allows subdomain wildcard (`*.example.com`) and port range (`example.com:8000-8100`).
+/// Wildcards never match ip addresses or localhost, those need to be listed explicitly
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NetworkPermission {
+    host: NetworkPermissionHost,
+    ports: NetworkPermissionPorts,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum NetworkPermissionHost {
+    Domain(String),
+    // subdomains of the domain on any level, domain itself is not included
+    Subdomains(String),
+    Ip(IpAddr),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum NetworkPermissionPorts {
+    Any,
+    Single(u16),
+    Range(u16, u16),
+}
+
+impl NetworkPermission {
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        if value.is_empty() {
+            Err(anyhow!("Empty string value is not allowed in permissions"))?
+        }
+
+        let (host, port) = split_host_and_port(value)
+            .ok_or_else(|| anyhow!("Network permission can only contain domain and optionally port: {}", value))?;
+
+        let ports = match port {
+            None => NetworkPermissionPorts::Any,
+            Some(port) => match port.split_once('-') {
+                None => NetworkPermissionPorts::Single(parse_port(port, value)?),
+                Some((start, end)) => {
+                    let start = parse_port(start, value)?;
+                    let end = parse_port(end, value)?;
+
+                    if start > end {
+                        Err(anyhow!("Start of port range is larger than its end in network permission: {}", value))?
+                    }
+
+                    NetworkPermissionPorts::Range(start, end)
+                }
+            }
+        };
+
+        let host = match host.strip_prefix("*.") {
+            Some(domain) if domain.contains('*') => {
+                Err(anyhow!("Wildcard is only allowed as \"*.\" prefix in network permission: {}", value))?
+            }
+            None if host.contains('*') => {
+                Err(anyhow!("Wildcard is only allowed as \"*.\" prefix in network permission: {}", value))?
+            }
+            Some(domain) => {
+                let domain = match parse_host(domain) {
+                    Some(Host::Domain(domain)) => domain,
+                    Some(Host::Ipv4(_)) | Some(Host::Ipv6(_)) => {
+                        Err(anyhow!("Wildcard cannot be used with ip address in network permission: {}", value))?
+                    }
+                    None => Err(anyhow!("Invalid domain in network permission: {}", value))?,
+                };
+
+                if !domain.contains('.') {
+                    Err(anyhow!("Wildcard can only be used with domain that has at least two labels, e.g. \"*.example.com\", in network permission: {}", value))?
+                }
+
+                if domain == "localhost" || domain.ends_with(".localhost") {
+                    Err(anyhow!("Wildcard cannot be used with localhost in network permission: {}", value))?
+                }
+
+                NetworkPermissionHost::Subdomains(domain)
+            }
+            None => {
+                match parse_host(host) {
+                    Some(Host::Domain(domain)) => NetworkPermissionHost::Domain(domain),
+                    Some(Host::Ipv4(ip)) => NetworkPermissionHost::Ip(IpAddr::V4(ip)),
+                    Some(Host::Ipv6(ip)) => NetworkPermissionHost::Ip(IpAddr::V6(ip)),
+                    None => Err(anyhow!("Invalid host in network permission: {}", value))?,
+                }
+            }
+        };
+
+        Ok(Self { host, ports })
+    }
+
+    /// Whether deno is unable to check this permission by itself
+    pub fn is_pattern(&self) -> bool {
+        matches!(self.host, NetworkPermissionHost::Subdomains(_)) || matches!(self.ports, NetworkPermissionPorts::Range(_, _))
+    }
+
+    /// Checks value in deno format, `host[:port]`
+    pub fn matches(&self, value: &str) -> bool {
+        let Some((host, port)) = split_host_and_port(value) else {
+            return false
+        };
+
+        let port = match port {
+            None => None,
+            Some(port) => match port.parse::<u16>() {
+                Ok(port) => Some(port),
+                Err(_) => return false,
+            },
+        };
+
+        let host_match = match (&self.host, parse_host(host)) {
+            (NetworkPermissionHost::Domain(allowed), Some(Host::Domain(domain))) => allowed == &domain,
+            (NetworkPermissionHost::Subdomains(allowed), Some(Host::Domain(domain))) => is_subdomain(&domain, allowed),
+            (NetworkPermissionHost::Ip(allowed), Some(Host::Ipv4(ip))) => allowed == &IpAddr::V4(ip),
+            (NetworkPermissionHost::Ip(allowed), Some(Host::Ipv6(ip))) => allowed == &IpAddr::V6(ip),
+            _ => false,
+        };
+
+        self.matches_port(port) && host_match
+    }
+
+    /// Whether ip address is listed literally in this permission
+    fn matches_ip(&self, ip: IpAddr, port: Option<u16>) -> bool {
+        matches!(self.host, NetworkPermissionHost::Ip(allowed) if allowed == ip) && self.matches_port(port)
+    }
+
+    fn matches_port(&self, port: Option<u16>) -> bool {
+        match self.ports {
+            NetworkPermissionPorts::Any => true,
+            NetworkPermissionPorts::Single(allowed) => port == Some(allowed),
+            NetworkPermissionPorts::Range(start, end) => port.is_some_and(|port| start <= port && port <= end),
+        }
+    }
+
+    fn is_wildcard(&self) -> bool {
+        matches!(self.host, NetworkPermissionHost::Subdomains(_))
+    }
+
+    /// Whether everything allowed by other permission is also allowed by this one
+    pub fn contains(&self, other: &NetworkPermission) -> bool {
+        let host_contains = match (&self.host, &other.host) {
+            (NetworkPermissionHost::Domain(domain), NetworkPermissionHost::Domain(other)) => domain == other,
+            (NetworkPermissionHost::Subdomains(domain), NetworkPermissionHost::Domain(other)) => is_subdomain(other, domain),
+            (NetworkPermissionHost::Subdomains(domain), NetworkPermissionHost::Subdomains(other)) => domain == other || is_subdomain(other, domain),
+            (NetworkPermissionHost::Ip(ip), NetworkPermissionHost::Ip(other)) => ip == other,
+            _ => false,
+        };
+
+        let ports_contain = match (&self.ports, &other.ports) {
+            (NetworkPermissionPorts::Any, _) => true,
+            (NetworkPermissionPorts::Single(port), NetworkPermissionPorts::Single(other)) => port == other,
+            (NetworkPermissionPorts::Range(start, end), NetworkPermissionPorts::Single(other)) => start <= other && other <= end,
+            (NetworkPermissionPorts::Range(start, end), NetworkPermissionPorts::Range(other_start, other_end)) => start <= other_start && other_end <= end,
+            _ => false,
+        };
+
+        host_contains && ports_contain
+    }
+}
+
+fn split_host_and_port(value: &str) -> Option<(&str, Option<&str>)> {
+    // ipv6 address has to be in brackets, e.g. "[::1]:8080"
+    if value.starts_with('[') {
+        let end = value.find(']')?;
+        let (host, rest) = value.split_at(end + 1);
+
+        return match rest {
+            "" => Some((host, None)),
+            rest => Some((host, Some(rest.strip_prefix(':')?))),
+        }
+    }
+
+    match value.split_once(':') {
+        None => Some((value, None)),
+        Some((_, port)) if port.contains(':') => None,
+        Some((host, port)) => Some((host, Some(port))),
+    }
+}
+
+fn parse_port(port: &str, value: &str) -> anyhow::Result<u16> {
+    port.parse::<u16>()
+        .map_err(|_| anyhow!("Invalid port in network permission: {}", value))
+}
+
+fn parse_host(host: &str) -> Option<Host> {
+    // url crate normalizes domain, e.g. lowercases it
+    let url = Url::parse(&format!("http://{}/", host)).ok()?;
+
+    let only_host = url.username().is_empty() && url.password().is_none() && url.path() == "/" && url.query().is_none() && url.fragment().is_none() && url.port().is_none();
+
+    if !only_host {
+        return None
+    }
+
+    url.host().map(|host| host.to_owned())
 }
 
-fn env_permission(envs: &[String]) -> UnaryPermission<EnvQueryDescriptor> {
+fn is_subdomain(domain: &str, parent: &str) -> bool {
+    domain.strip_suffix(parent)
+        .is_some_and(|rest| rest.len() > 1 && rest.ends_with('.'))
+}
+
+fn env_permission(envs: &[String], prompt: bool) -> UnaryPermission<EnvQueryDescriptor> {
     let allow_list = if envs.is_empty() {
         None
     } else {
@@ -93,10 +348,10 @@ fn env_permission(envs: &[String]) -> UnaryPermission<EnvQueryDescriptor> {
         Some(allow_list)
     };
 
-    Permissions::new_unary(allow_list, None, false)
+    Permissions::new_unary(allow_list, None, prompt)
 }
 
-fn sys_permission(system: &[String]) -> anyhow::Result<UnaryPermission<SysDescriptor>> {
+fn sys_permission(system: &[String], prompt: bool) -> anyhow::Result<UnaryPermission<SysDescriptor>> {
     let allow_list = if system.is_empty() {
         None
     } else {
@@ -109,34 +364,31 @@ fn sys_permission(system: &[String]) -> anyhow::Result<UnaryPermission<SysDescri
         Some(allow_list)
     };
 
-    Ok(Permissions::new_unary(allow_list, None, false))
+    Ok(Permissions::new_unary(allow_list, None, prompt))
 }
 
-fn run_permission(
+fn run_allow_list(
     permissions: &JsPluginPermissionsExec,
     home_dir: &Path,
     plugin_data_dir: &Path,
     plugin_cache_dir: &Path,
-) -> anyhow::Result<UnaryPermission<RunQueryDescriptor>> {
-    let granted_executable = permissions.executable
-        .iter()
-        .map(|path| {
-            augment_path(path, home_dir, plugin_data_dir, plugin_cache_dir)
-                .map(|path| path.map(|path| AllowRunDescriptor(path)))
-        })
-        .collect::<anyhow::Result<Vec<_>>>()?
-        .into_iter()
-        .filter_map(std::convert::identity)
-        .collect::<Vec<_>>();
+    plugin_assets_dir: &Path,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let granted_executable = augment_paths(&permissions.executable, home_dir, plugin_data_dir, plugin_cache_dir, plugin_assets_dir)?;
 
     let granted_command = permissions.command
         .iter()
-        .flat_map(|cmd| anyhow::Ok(AllowRunDescriptor(which::which_global(cmd)?)))
+        .flat_map(which::which_global)
         .collect::<Vec<_>>();
 
-    let mut granted = HashSet::new();
-    granted.extend(granted_executable);
-    granted.extend(granted_command);
+    Ok([granted_executable, granted_command].concat())
+}
+
+fn run_permission(executables: Vec<PathBuf>, prompt: bool) -> UnaryPermission<RunQueryDescriptor> {
+    let granted = executables
+        .into_iter()
+        .map(|path| AllowRunDescriptor(path))
+        .collect::<HashSet<_>>();
 
     let allow_list = if granted.is_empty() {
         None
@@ -144,10 +396,256 @@ fn run_permission(
         Some(granted)
     };
 
-    Ok(Permissions::new_unary(allow_list, None, false))
+    Permissions::new_unary(allow_list, None, prompt)
+}
+
+/// Permissions which are checked by [`BackendPermissionPrompter`] instead of deno
+pub struct PrompterPermissions {
+    // patterns deno is unable to check by itself, or all network permissions if audit log is enabled
+    network: Vec<NetworkPermission>,
+    // only present if audit log is enabled, deno checks them otherwise
+    write: Vec<PathBuf>,
+    run: Vec<PathBuf>,
+}
+
+impl PrompterPermissions {
+    pub fn is_empty(&self) -> bool {
+        self.network.is_empty() && self.write.is_empty() && self.run.is_empty()
+    }
+
+    /// Value is in the format used in deno permission prompt message
+    fn allows(&self, name: &str, value: &str) -> bool {
+        match name {
+            "net" => self.allows_network(value, resolve_host),
+            "write" => {
+                let path = normalize_path(Path::new(value));
+
+                self.write.iter().any(|allowed| path.starts_with(allowed))
+            },
+            "run" => {
+                let executable = if value.contains(['/', '\\']) {
+                    Some(normalize_path(Path::new(value)))
+                } else {
+                    which::which_global(value).ok()
+                };
+
+                executable.is_some_and(|executable| self.run.contains(&executable))
+            },
+            _ => false,
+        }
+    }
+
+    fn allows_network(&self, value: &str, resolve: impl FnOnce(&str, Option<u16>) -> Vec<IpAddr>) -> bool {
+        let matching = self.network.iter()
+            .filter(|permission| permission.matches(value))
+            .collect::<Vec<_>>();
+
+        if matching.is_empty() {
+            return false
+        }
+
+        if matching.iter().any(|permission| !permission.is_wildcard()) {
+            return true
+        }
+
+        // domain matched by wildcard can resolve to address in local network,
+        // which is only allowed if that address is listed in permissions literally
+        let Some((host, port)) = split_host_and_port(value) else {
+            return false
+        };
+
+        let port = port.and_then(|port| port.parse::<u16>().ok());
+
+        let addresses = resolve(host, port);
+
+        !addresses.is_empty() && addresses.into_iter()
+            .all(|ip| !is_local_ip(ip) || self.network.iter().any(|permission| permission.matches_ip(ip, port)))
+    }
+}
+
+fn resolve_host(host: &str, port: Option<u16>) -> Vec<IpAddr> {
+    match (host, port.unwrap_or(0)).to_socket_addrs() {
+        Ok(addresses) => addresses.map(|address| address.ip()).collect(),
+        Err(err) => {
+            tracing::warn!("Unable to resolve host {}, denying network access: {:?}", host, err);
+            vec![]
+        }
+    }
+}
+
+fn is_local_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let octets = ip.octets();
+            let this_network = octets[0] == 0; // 0.0.0.0/8
+            let shared = octets[0] == 100 && (octets[1] & 0xc0) == 64; // 100.64.0.0/10, carrier-grade nat
+
+            ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified() || ip.is_multicast() || ip.is_broadcast() || this_network || shared
+        }
+        IpAddr::V6(ip) => {
+            let segments = ip.segments();
+
+            // addresses which embed ipv4 address are checked using that address
+            let embedded_ipv4 = if let Some(ipv4) = ip.to_ipv4_mapped() {
+                Some(ipv4)
+            } else if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+                // 64:ff9b::/96, nat64
+                let octets = ip.octets();
+                Some(Ipv4Addr::new(octets[12], octets[13], octets[14], octets[15]))
+            } else if segments[0] == 0x2002 {
+                // 2002::/16, 6to4
+                let octets = ip.octets();
+                Some(Ipv4Addr::new(octets[2], octets[3], octets[4], octets[5]))
+            } else {
+                None
+            };
+
+            match embedded_ipv4 {
+                Some(ip) => is_local_ip(IpAddr::V4(ip)),
+                None => {
+                    let unique_local = (segments[0] & 0xfe00) == 0xfc00;
+                    let link_local = (segments[0] & 0xffc0) == 0xfe80;
+
+                    ip.is_loopback() || ip.is_unspecified() || ip.is_multicast() || unique_local || link_local
+                }
+            }
+        }
+    }
+}
+
+// resolves '.' and '..' segments without accessing file system, same way as deno does for permission checks
+fn normalize_path(path: &Path) -> PathBuf {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map(|dir| dir.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    };
+
+    let mut result = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                result.pop();
+            }
+            component => result.push(component),
+        }
+    }
+
+    result
+}
+
+/// Allows access granted by [`PrompterPermissions`], and if prompts are enabled
+/// forwards the rest of deno permission prompts to backend, which asks user in main window.
+/// If audit log is enabled, every allowed access is recorded in it.
+/// Called synchronously by deno, so plugin event loop is blocked until user answers
+pub struct BackendPermissionPrompter {
+    outer_handle: Handle,
+    api: BackendForPluginRuntimeApiProxy,
+    prompt_in_progress: Arc<AtomicBool>,
+    permissions: PrompterPermissions,
+    prompt_user: bool,
+    audit_log: bool,
+}
+
+impl BackendPermissionPrompter {
+    pub fn new(
+        outer_handle: Handle,
+        api: BackendForPluginRuntimeApiProxy,
+        prompt_in_progress: Arc<AtomicBool>,
+        permissions: PrompterPermissions,
+        prompt_user: bool,
+        audit_log: bool,
+    ) -> Self {
+        Self {
+            outer_handle,
+            api,
+            prompt_in_progress,
+            permissions,
+            prompt_user,
+            audit_log,
+        }
+    }
+
+    fn allow(&self, name: &str, value: String) -> PromptResponse {
+        if !self.audit_log {
+            return PromptResponse::Allow
+        }
+
+        let capability = match name {
+            "net" => JsAuditCapability::Network,
+            "run" => JsAuditCapability::Command,
+            "write" => JsAuditCapability::FileWrite,
+            _ => return PromptResponse::Allow,
+        };
+
+        let api = self.api.clone();
+
+        self.outer_handle.spawn(async move {
+            if let Err(err) = api.record_capability_use(capability, value).await {
+                tracing::warn!("Unable to record capability use in audit log: {:?}", err);
+            }
+        });
+
+        PromptResponse::Allow
+    }
+}
+
+impl PermissionPrompter for BackendPermissionPrompter {
+    fn prompt(
+        &mut self,
+        message: &str,
+        name: &str,
+        _api_name: Option<&str>,
+        _is_unary: bool,
+        _get_stack: Option<GetFormattedStackFn>,
+    ) -> PromptResponse {
+        // only prompts for specific resource are supported, e.g. "read" without path is denied
+        let Some(value) = PROMPT_MESSAGE_VALUE_PATTERN.captures(message).map(|matches| matches["value"].to_string()) else {
+            tracing::warn!("Denying permission prompt without specific value: {}", message);
+            return PromptResponse::Deny
+        };
+
+        if self.permissions.allows(name, &value) {
+            return self.allow(name, value)
+        }
+
+        if !self.prompt_user {
+            return PromptResponse::Deny
+        }
+
+        self.prompt_in_progress.store(true, Ordering::SeqCst);
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        let api = self.api.clone();
+        let permission = name.to_string();
+        let requested_value = value.clone();
+
+        self.outer_handle.spawn(async move {
+            let _ = sender.send(api.request_permission(permission, requested_value).await);
+        });
+
+        let response = match receiver.recv() {
+            Ok(Ok(true)) => self.allow(name, value),
+            Ok(Ok(false)) => PromptResponse::Deny,
+            Ok(Err(err)) => {
+                tracing::warn!("Unable to request permission, denying: {:?}", err);
+                PromptResponse::Deny
+            }
+            Err(_) => PromptResponse::Deny,
+        };
+
+        self.prompt_in_progress.store(false, Ordering::SeqCst);
+
+        response
+    }
 }
 
-fn augment_path(path: &String, home_dir: &Path, plugin_data_dir: &Path, plugin_cache_dir: &Path) -> anyhow::Result<Option<PathBuf>> {
+fn augment_path(path: &String, home_dir: &Path, plugin_data_dir: &Path, plugin_cache_dir: &Path, plugin_assets_dir: &Path) -> anyhow::Result<Option<PathBuf>> {
     if let Some(matches) = PERMISSIONS_VARIABLE_PATTERN.captures(path) {
         let namespace = &matches["namespace"];
         let name = &matches["name"];
@@ -155,27 +653,74 @@ fn augment_path(path: &String, home_dir: &Path, plugin_data_dir: &Path, plugin_c
         let replacement = match (namespace, name) {
             ("macos", "user-home") => {
                 if cfg!(target_os = "macos") {
-                    Some(home_dir)
+                    Some(home_dir.to_path_buf())
                 } else {
                     None
                 }
             },
             ("linux", "user-home") => {
                 if cfg!(target_os = "linux") {
-                    Some(home_dir)
+                    Some(home_dir.to_path_buf())
                 } else {
                     None
                 }
             },
             ("windows", "user-home") => {
                 if cfg!(windows) {
-                    Some(home_dir)
+                    Some(home_dir.to_path_buf())
+                } else {
+                    None
+                }
+            },
+            ("common", "plugin-data") => Some(plugin_data_dir.to_path_buf()),
+            ("common", "plugin-cache") => Some(plugin_cache_dir.to_path_buf()),
+            ("common", "plugin-assets") => Some(plugin_assets_dir.to_path_buf()),
+            ("common", "documents") => UserDirs::new().and_then(|dirs| dirs.document_dir().map(|dir| dir.to_path_buf())),
+            ("common", "downloads") => UserDirs::new().and_then(|dirs| dirs.download_dir().map(|dir| dir.to_path_buf())),
+            ("common", "desktop") => UserDirs::new().and_then(|dirs| dirs.desktop_dir().map(|dir| dir.to_path_buf())),
+            ("common", "temp") => Some(std::env::temp_dir()),
+            ("linux", "xdg-config") => {
+                if cfg!(target_os = "linux") {
+                    BaseDirs::new().map(|dirs| dirs.config_dir().to_path_buf())
+                } else {
+                    None
+                }
+            },
+            ("linux", "xdg-data") => {
+                if cfg!(target_os = "linux") {
+                    BaseDirs::new().map(|dirs| dirs.data_dir().to_path_buf())
+                } else {
+                    None
+                }
+            },
+            ("linux", "xdg-runtime") => {
+                if cfg!(target_os = "linux") {
+                    BaseDirs::new().and_then(|dirs| dirs.runtime_dir().map(|dir| dir.to_path_buf()))
+                } else {
+                    None
+                }
+            },
+            ("macos", "application-support") => {
+                if cfg!(target_os = "macos") {
+                    BaseDirs::new().map(|dirs| dirs.data_dir().to_path_buf())
+                } else {
+                    None
+                }
+            },
+            ("macos", "preferences") => {
+                if cfg!(target_os = "macos") {
+                    BaseDirs::new().map(|dirs| dirs.preference_dir().to_path_buf())
+                } else {
+                    None
+                }
+            },
+            ("macos", "caches") => {
+                if cfg!(target_os = "macos") {
+                    BaseDirs::new().map(|dirs| dirs.cache_dir().to_path_buf())
                 } else {
                     None
                 }
             },
-            ("common", "plugin-data") => Some(plugin_data_dir),
-            ("common", "plugin-cache") => Some(plugin_cache_dir),
             (_, _) => {
                 Err(anyhow!("Trying to load plugin with unknown variable in path in manifest permissions: {}", path))?
             }
@@ -187,7 +732,8 @@ fn augment_path(path: &String, home_dir: &Path, plugin_data_dir: &Path, plugin_c
                 let replacement = replacement.to_str()
                     .expect("non-utf8 file paths are not supported");
 
-                Ok(Some(PathBuf::from(PERMISSIONS_VARIABLE_PATTERN.replace(path, replacement).to_string())))
+                // directory path is inserted as is, without expanding '$' references
+                Ok(Some(PathBuf::from(PERMISSIONS_VARIABLE_PATTERN.replace(path, NoExpand(replacement)).to_string())))
             }
         }
     } else {
@@ -208,4 +754,300 @@ fn augment_path(path: &String, home_dir: &Path, plugin_data_dir: &Path, plugin_c
             }
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompter_permissions(network: &[&str], write: &[&str], run: &[&str]) -> PrompterPermissions {
+        PrompterPermissions {
+            network: network.iter().map(|value| NetworkPermission::parse(value).unwrap()).collect(),
+            write: write.iter().map(PathBuf::from).collect(),
+            run: run.iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    fn network(value: &str) -> NetworkPermission {
+        NetworkPermission::parse(value).unwrap()
+    }
+
+    fn public_address(_host: &str, _port: Option<u16>) -> Vec<IpAddr> {
+        vec![IpAddr::from([93, 184, 215, 14])]
+    }
+
+    fn loopback_address(_host: &str, _port: Option<u16>) -> Vec<IpAddr> {
+        vec![IpAddr::from([127, 0, 0, 1])]
+    }
+
+    #[test]
+    fn allows_network_matching_permission() {
+        let permissions = prompter_permissions(&["example.com:443", "*.example.org"], &[], &[]);
+
+        assert!(permissions.allows("net", "example.com:443"));
+        assert!(permissions.allows_network("api.example.org:8080", public_address));
+        assert!(!permissions.allows("net", "example.com:80"));
+        assert!(!permissions.allows("net", "example.org:443"));
+    }
+
+    #[test]
+    fn wildcard_resolving_to_local_address_is_denied() {
+        let permissions = prompter_permissions(&["*.example.org"], &[], &[]);
+
+        assert!(!permissions.allows_network("api.example.org:443", loopback_address));
+        assert!(!permissions.allows_network("api.example.org:443", |_, _| vec![IpAddr::from([192, 168, 1, 1])]));
+        assert!(!permissions.allows_network("api.example.org:443", |_, _| vec![IpAddr::from([169, 254, 169, 254])]));
+        assert!(!permissions.allows_network("api.example.org:443", |_, _| vec![IpAddr::from([93, 184, 215, 14]), IpAddr::from([10, 0, 0, 1])]));
+        assert!(!permissions.allows_network("api.example.org:443", |_, _| vec![]));
+    }
+
+    #[test]
+    fn wildcard_resolving_to_literally_listed_local_address_is_allowed() {
+        let permissions = prompter_permissions(&["*.example.org", "127.0.0.1:8000-9000"], &[], &[]);
+
+        assert!(permissions.allows_network("api.example.org:8080", loopback_address));
+        assert!(!permissions.allows_network("api.example.org:443", loopback_address));
+    }
+
+    #[test]
+    fn exact_domain_is_not_resolved() {
+        let permissions = prompter_permissions(&["localhost:8000-9000"], &[], &[]);
+
+        assert!(permissions.allows_network("localhost:8080", |_, _| panic!("should not be resolved")));
+    }
+
+    #[test]
+    fn local_ip_addresses() {
+        assert!(is_local_ip(IpAddr::from([127, 0, 0, 1])));
+        assert!(is_local_ip(IpAddr::from([10, 1, 2, 3])));
+        assert!(is_local_ip(IpAddr::from([172, 16, 0, 1])));
+        assert!(is_local_ip(IpAddr::from([192, 168, 0, 1])));
+        assert!(is_local_ip(IpAddr::from([169, 254, 0, 1])));
+        assert!(is_local_ip(IpAddr::from([0, 0, 0, 0])));
+        assert!(is_local_ip("::1".parse().unwrap()));
+        assert!(is_local_ip("::".parse().unwrap()));
+        assert!(is_local_ip("fe80::1".parse().unwrap()));
+        assert!(is_local_ip("fd00::1".parse().unwrap()));
+        assert!(is_local_ip("::ffff:192.168.0.1".parse().unwrap()));
+        assert!(is_local_ip(IpAddr::from([100, 64, 0, 1])));
+        assert!(is_local_ip(IpAddr::from([100, 127, 255, 254])));
+        assert!(is_local_ip(IpAddr::from([0, 1, 2, 3])));
+        assert!(is_local_ip(IpAddr::from([224, 0, 0, 1])));
+        assert!(is_local_ip(IpAddr::from([255, 255, 255, 255])));
+        assert!(is_local_ip("ff02::1".parse().unwrap()));
+        assert!(is_local_ip("64:ff9b::7f00:1".parse().unwrap()));
+        assert!(is_local_ip("64:ff9b::c0a8:1".parse().unwrap()));
+        assert!(is_local_ip("2002:7f00:1::".parse().unwrap()));
+        assert!(is_local_ip("2002:c0a8:1::1".parse().unwrap()));
+
+        assert!(!is_local_ip(IpAddr::from([93, 184, 215, 14])));
+        assert!(!is_local_ip(IpAddr::from([172, 32, 0, 1])));
+        assert!(!is_local_ip("2606:2800:21f:cb07:6820:80da:af6b:8b2c".parse().unwrap()));
+        assert!(!is_local_ip("::ffff:93.184.215.14".parse().unwrap()));
+        assert!(!is_local_ip(IpAddr::from([100, 128, 0, 1])));
+        assert!(!is_local_ip("64:ff9b::5db8:d70e".parse().unwrap()));
+        assert!(!is_local_ip("2002:5db8:d70e::1".parse().unwrap()));
+    }
+
+    #[test]
+    fn split_host_and_port_values() {
+        assert_eq!(split_host_and_port("example.com"), Some(("example.com", None)));
+        assert_eq!(split_host_and_port("example.com:443"), Some(("example.com", Some("443"))));
+        assert_eq!(split_host_and_port("example.com:80-90"), Some(("example.com", Some("80-90"))));
+        assert_eq!(split_host_and_port("[::1]"), Some(("[::1]", None)));
+        assert_eq!(split_host_and_port("[::1]:8080"), Some(("[::1]", Some("8080"))));
+        assert_eq!(split_host_and_port("::1"), None);
+        assert_eq!(split_host_and_port("[::1]8080"), None);
+        assert_eq!(split_host_and_port("[::1"), None);
+    }
+
+    #[test]
+    fn parse_network_permission() {
+        assert!(NetworkPermission::parse("example.com").is_ok());
+        assert!(NetworkPermission::parse("example.com:443").is_ok());
+        assert!(NetworkPermission::parse("example.com:8000-9000").is_ok());
+        assert!(NetworkPermission::parse("*.example.com:443").is_ok());
+        assert!(NetworkPermission::parse("127.0.0.1:8080").is_ok());
+        assert!(NetworkPermission::parse("[::1]:8080").is_ok());
+        assert!(NetworkPermission::parse("[2001:db8::1]").is_ok());
+
+        assert!(NetworkPermission::parse("").is_err());
+        assert!(NetworkPermission::parse("example.com:").is_err());
+        assert!(NetworkPermission::parse("example.com:65536").is_err());
+        assert!(NetworkPermission::parse("example.com:9000-8000").is_err());
+        assert!(NetworkPermission::parse("example.com:80-").is_err());
+        assert!(NetworkPermission::parse("*.com").is_err());
+        assert!(NetworkPermission::parse("*example.com").is_err());
+        assert!(NetworkPermission::parse("api.*.example.com").is_err());
+        assert!(NetworkPermission::parse("*.*.example.com").is_err());
+        assert!(NetworkPermission::parse("*.127.0.0.1").is_err());
+        assert!(NetworkPermission::parse("*.localhost").is_err());
+        assert!(NetworkPermission::parse("*.api.localhost").is_err());
+        assert!(NetworkPermission::parse("::1").is_err());
+        assert!(NetworkPermission::parse("https://example.com").is_err());
+        assert!(NetworkPermission::parse("example.com/path").is_err());
+    }
+
+    #[test]
+    fn network_permission_patterns() {
+        assert!(!network("example.com").is_pattern());
+        assert!(!network("example.com:443").is_pattern());
+        assert!(network("example.com:8000-9000").is_pattern());
+        assert!(network("*.example.com").is_pattern());
+    }
+
+    #[test]
+    fn network_permission_matches_ports() {
+        assert!(network("example.com").matches("example.com"));
+        assert!(network("example.com").matches("example.com:443"));
+        assert!(network("example.com:443").matches("example.com:443"));
+        assert!(!network("example.com:443").matches("example.com:80"));
+        assert!(!network("example.com:443").matches("example.com"));
+        assert!(network("example.com:8000-9000").matches("example.com:8000"));
+        assert!(network("example.com:8000-9000").matches("example.com:9000"));
+        assert!(!network("example.com:8000-9000").matches("example.com:7999"));
+        assert!(!network("example.com:8000-9000").matches("example.com:9001"));
+        assert!(!network("example.com:8000-9000").matches("example.com"));
+        assert!(!network("example.com").matches("example.com:invalid"));
+    }
+
+    #[test]
+    fn network_permission_matches_wildcards() {
+        assert!(network("*.example.com").matches("api.example.com"));
+        assert!(network("*.example.com").matches("v1.api.example.com:443"));
+        assert!(network("*.example.com").matches("API.Example.com"));
+        assert!(!network("*.example.com").matches("example.com"));
+        assert!(!network("*.example.com").matches("apiexample.com"));
+        assert!(!network("*.example.com").matches("api.example.com.evil.com"));
+        assert!(network("*.example.com:443").matches("api.example.com:443"));
+        assert!(!network("*.example.com:443").matches("api.example.com:80"));
+        assert!(!network("example.com").matches("api.example.com"));
+    }
+
+    #[test]
+    fn network_permission_matches_ip_addresses() {
+        assert!(network("127.0.0.1:8080").matches("127.0.0.1:8080"));
+        assert!(!network("127.0.0.1:8080").matches("127.0.0.2:8080"));
+        assert!(network("[::1]:8080").matches("[::1]:8080"));
+        assert!(network("[::1]").matches("[0:0:0:0:0:0:0:1]:443"));
+        assert!(!network("[::1]:8080").matches("[::2]:8080"));
+        assert!(!network("[::1]:8080").matches("[::1]:8081"));
+        assert!(!network("[::ffff:127.0.0.1]").matches("127.0.0.1"));
+        assert!(!network("127.0.0.1").matches("localhost"));
+    }
+
+    #[test]
+    fn network_permission_contains() {
+        assert!(network("example.com").contains(&network("example.com:443")));
+        assert!(network("example.com:443").contains(&network("example.com:443")));
+        assert!(!network("example.com:443").contains(&network("example.com")));
+        assert!(network("example.com:8000-9000").contains(&network("example.com:8080")));
+        assert!(network("example.com:8000-9000").contains(&network("example.com:8100-8200")));
+        assert!(!network("example.com:8000-9000").contains(&network("example.com:8100-9100")));
+        assert!(network("*.example.com").contains(&network("api.example.com")));
+        assert!(network("*.example.com").contains(&network("*.api.example.com")));
+        assert!(network("*.example.com").contains(&network("*.example.com:443")));
+        assert!(!network("*.example.com").contains(&network("example.com")));
+        assert!(!network("*.api.example.com").contains(&network("*.example.com")));
+        assert!(!network("api.example.com").contains(&network("*.example.com")));
+        assert!(network("[::1]").contains(&network("[::1]:8080")));
+        assert!(!network("[::1]").contains(&network("127.0.0.1")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn allows_write_within_allowed_directory() {
+        let permissions = prompter_permissions(&[], &["/a/b"], &[]);
+
+        assert!(permissions.allows("write", "/a/b"));
+        assert!(permissions.allows("write", "/a/b/c/file.txt"));
+        assert!(permissions.allows("write", "/a/b/c/../file.txt"));
+        assert!(!permissions.allows("write", "/a/b/../c/file.txt"));
+        assert!(!permissions.allows("write", "/a/bc/file.txt"));
+        assert!(!permissions.allows("write", "/a"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn allows_run_of_allowed_executable() {
+        let permissions = prompter_permissions(&[], &[], &["/usr/bin/tool"]);
+
+        assert!(permissions.allows("run", "/usr/bin/tool"));
+        assert!(permissions.allows("run", "/usr/bin/../bin/tool"));
+        assert!(!permissions.allows("run", "/usr/bin/other"));
+        assert!(!permissions.allows("run", "/usr/bin/tool-other"));
+    }
+
+    #[test]
+    fn denies_not_checked_permission() {
+        let permissions = prompter_permissions(&["example.com"], &[], &[]);
+
+        assert!(!permissions.allows("read", "example.com"));
+        assert!(!permissions.allows("env", "example.com"));
+    }
+
+    fn augment(path: &str) -> anyhow::Result<Option<PathBuf>> {
+        augment_path(
+            &path.to_string(),
+            Path::new("/home/user"),
+            Path::new("/data/plugin"),
+            Path::new("/cache/plugin"),
+            Path::new("/cache/plugin-assets"),
+        )
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn augment_path_plugin_directories() {
+        assert_eq!(augment("{common:plugin-data}").unwrap(), Some(PathBuf::from("/data/plugin")));
+        assert_eq!(augment("{common:plugin-data}/file.txt").unwrap(), Some(PathBuf::from("/data/plugin/file.txt")));
+        assert_eq!(augment("{common:plugin-cache}/dir").unwrap(), Some(PathBuf::from("/cache/plugin/dir")));
+        assert_eq!(augment("{common:plugin-assets}/bin/tool").unwrap(), Some(PathBuf::from("/cache/plugin-assets/bin/tool")));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn augment_path_user_home_of_current_system() {
+        assert_eq!(augment("{linux:user-home}/.config").unwrap(), Some(PathBuf::from("/home/user/.config")));
+        assert_eq!(augment("{macos:user-home}/Library").unwrap(), None);
+        assert_eq!(augment("{windows:user-home}\\AppData").unwrap(), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn augment_path_system_directories() {
+        let base_dirs = BaseDirs::new().unwrap();
+
+        assert_eq!(augment("{linux:xdg-config}/app").unwrap(), Some(base_dirs.config_dir().join("app")));
+        assert_eq!(augment("{linux:xdg-data}/app").unwrap(), Some(base_dirs.data_dir().join("app")));
+        assert_eq!(augment("{common:temp}/app").unwrap(), Some(std::env::temp_dir().join("app")));
+        assert_eq!(augment("{macos:application-support}/App").unwrap(), None);
+        assert_eq!(augment("{macos:caches}/App").unwrap(), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn augment_path_without_variable() {
+        assert_eq!(augment("/etc/hosts").unwrap(), Some(PathBuf::from("/etc/hosts")));
+        assert_eq!(augment("C:\\Windows\\System32").unwrap(), None);
+    }
+
+    #[test]
+    fn augment_path_unknown_variable() {
+        assert!(augment("{common:pictures}/file").is_err());
+        assert!(augment("{linux:xdg-documents}/file").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn augment_path_replacement_is_not_expanded() {
+        let path = augment_path(
+            &"{common:plugin-data}/file".to_string(),
+            Path::new("/home/user"),
+            Path::new("/data/$name/${namespace}"),
+            Path::new("/cache/plugin"),
+            Path::new("/cache/plugin-assets"),
+        );
+
+        assert_eq!(path.unwrap(), Some(PathBuf::from("/data/$name/${namespace}/file")));
+    }
+}
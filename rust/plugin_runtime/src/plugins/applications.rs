@@ -1,10 +1,13 @@
 use std::cell::RefCell;
 use deno_core::{op2, OpState};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use anyhow::anyhow;
+use gauntlet_common::dirs::Dirs;
+use gauntlet_common::icon_store::IconStore;
 use image::ImageFormat;
 use image::imageops::FilterType;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use tokio::runtime::Handle;
 use tokio::sync::mpsc::Receiver;
@@ -48,6 +51,17 @@ pub struct DesktopApplication {
     desktop_file_path: String,
     icon: Option<Vec<u8>>,
     startup_wm_class: Option<String>,
+    generic_name: Option<String>,
+    comment: Option<String>,
+    keywords: Vec<String>,
+    actions: Vec<DesktopApplicationAction>,
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, Serialize)]
+pub struct DesktopApplicationAction {
+    id: String,
+    name: String,
 }
 
 #[cfg(target_os = "macos")]
@@ -102,6 +116,21 @@ pub fn wayland(state: Rc<RefCell<OpState>>) -> bool {
     wayland
 }
 
+#[op2]
+pub fn applications_open(state: &mut OpState, #[string] desktop_file_path: String, #[serde] uris: Vec<String>) -> anyhow::Result<()> {
+    #[cfg(target_os = "linux")]
+    let result = linux::open_application_with_uris(state, desktop_file_path, uris);
+
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = (state, desktop_file_path, uris);
+
+        Err(anyhow!("Opening applications is only supported on Linux"))
+    };
+
+    result
+}
+
 pub enum DesktopEnvironment {
     #[cfg(target_os = "linux")]
     Linux(linux::LinuxDesktopEnvironment),
@@ -233,7 +262,7 @@ pub fn macos_open_setting_pre_13(#[string] setting_path: String) -> anyhow::Resu
     Ok(())
 }
 
-#[cfg(unix)]
+#[cfg(target_os = "macos")]
 pub fn spawn_detached<I, S>(
     path: &str,
     args: I,
@@ -242,14 +271,20 @@ where
     I: IntoIterator<Item = S> + Copy,
     S: AsRef<std::ffi::OsStr>,
 {
+    let mut command = std::process::Command::new(path);
+
+    command.args(args);
+
+    spawn_detached_command(command)
+}
+
+#[cfg(unix)]
+pub fn spawn_detached_command(mut command: std::process::Command) -> std::io::Result<()> {
     // from https://github.com/alacritty/alacritty/blob/5abb4b73937b17fe501b9ca20b602950f1218b96/alacritty/src/daemon.rs#L65
     use std::os::unix::prelude::CommandExt;
-    use std::process::{Command, Stdio};
-
-    let mut command = Command::new(path);
+    use std::process::Stdio;
 
     command
-        .args(args)
         .stdin(Stdio::null())
         .stdout(Stdio::null())
         .stderr(Stdio::null());
@@ -275,9 +310,23 @@ where
     }
 }
 
+pub(in crate::plugins::applications) const ICON_SIZE: u32 = 48;
+
+static ICON_STORE: Lazy<IconStore> = Lazy::new(|| IconStore::new(&Dirs::new()));
+
+/// Reading and resizing icons of hundreds of applications takes a while,
+/// so result is stored in icon store shared with server and reused until icon source changes
+pub(in crate::plugins::applications) fn cached_icon(
+    source_path: &Path,
+    size: u32,
+    process: impl FnOnce() -> anyhow::Result<Vec<u8>>
+) -> anyhow::Result<Vec<u8>> {
+    ICON_STORE.get_or_insert_with(source_path, size, process)
+}
+
 pub(in crate::plugins::applications) fn resize_icon(data: Vec<u8>) -> anyhow::Result<Vec<u8>> {
     let data = image::load_from_memory_with_format(&data, ImageFormat::Png)?;
-    let data = image::imageops::resize(&data, 48, 48, FilterType::Lanczos3);
+    let data = image::imageops::resize(&data, ICON_SIZE, ICON_SIZE, FilterType::Lanczos3);
 
     let mut buffer = std::io::Cursor::new(vec![]);
 
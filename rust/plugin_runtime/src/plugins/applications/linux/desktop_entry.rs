@@ -0,0 +1,559 @@
+use anyhow::anyhow;
+use deno_core::url::Url;
+use freedesktop_entry_parser::Entry;
+
+const DESKTOP_ENTRY_SECTION: &str = "Desktop Entry";
+
+#[derive(Debug, PartialEq)]
+pub struct DesktopEntry {
+    pub name: String,
+    pub generic_name: Option<String>,
+    pub comment: Option<String>,
+    pub keywords: Vec<String>,
+    pub icon: Option<String>,
+    pub startup_wm_class: Option<String>,
+    pub exec: Option<String>,
+    pub path: Option<String>,
+    pub terminal: bool,
+    pub actions: Vec<DesktopEntryAction>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DesktopEntryAction {
+    pub id: String,
+    pub name: String,
+    pub icon: Option<String>,
+    pub exec: String,
+}
+
+/// Returns `None` if entry should not be shown to the user in current desktop environment.
+/// `locales` are in order of preference, see [`locale_variants`]
+pub fn read_desktop_entry(entry: &Entry, locales: &[String], current_desktops: &[String]) -> Option<DesktopEntry> {
+    let name = localized_attr(entry, DESKTOP_ENTRY_SECTION, "Name", locales)?;
+
+    let no_display = attr(entry, DESKTOP_ENTRY_SECTION, "NoDisplay").is_some_and(|val| val == "true");
+    let hidden = attr(entry, DESKTOP_ENTRY_SECTION, "Hidden").is_some_and(|val| val == "true");
+
+    if no_display || hidden {
+        return None
+    }
+
+    if let Some(only_show_in) = attr(entry, DESKTOP_ENTRY_SECTION, "OnlyShowIn") {
+        let shown = split_list(&only_show_in)
+            .any(|desktop| current_desktops.iter().any(|current| current.eq_ignore_ascii_case(&desktop)));
+
+        if !shown {
+            return None
+        }
+    }
+
+    if let Some(not_show_in) = attr(entry, DESKTOP_ENTRY_SECTION, "NotShowIn") {
+        let hidden = split_list(&not_show_in)
+            .any(|desktop| current_desktops.iter().any(|current| current.eq_ignore_ascii_case(&desktop)));
+
+        if hidden {
+            return None
+        }
+    }
+
+    let keywords = localized_attr(entry, DESKTOP_ENTRY_SECTION, "Keywords", locales)
+        .map(|keywords| split_list(&keywords).collect())
+        .unwrap_or_default();
+
+    let actions = attr(entry, DESKTOP_ENTRY_SECTION, "Actions")
+        .map(|actions| {
+            split_list(&actions)
+                .filter_map(|id| {
+                    let section = format!("Desktop Action {}", id);
+
+                    // actions without name or exec are ignored, as spec requires both of them
+                    let name = localized_attr(entry, &section, "Name", locales)?;
+                    let exec = attr(entry, &section, "Exec")?;
+                    let icon = attr(entry, &section, "Icon");
+
+                    Some(DesktopEntryAction {
+                        id,
+                        name,
+                        icon,
+                        exec,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(DesktopEntry {
+        name,
+        generic_name: localized_attr(entry, DESKTOP_ENTRY_SECTION, "GenericName", locales),
+        comment: localized_attr(entry, DESKTOP_ENTRY_SECTION, "Comment", locales),
+        keywords,
+        icon: attr(entry, DESKTOP_ENTRY_SECTION, "Icon"),
+        startup_wm_class: attr(entry, DESKTOP_ENTRY_SECTION, "StartupWMClass"),
+        exec: attr(entry, DESKTOP_ENTRY_SECTION, "Exec"),
+        path: attr(entry, DESKTOP_ENTRY_SECTION, "Path").filter(|path| !path.is_empty()),
+        terminal: attr(entry, DESKTOP_ENTRY_SECTION, "Terminal").is_some_and(|val| val == "true"),
+        actions,
+    })
+}
+
+fn attr(entry: &Entry, section: &str, key: &str) -> Option<String> {
+    let section = entry.section(section);
+
+    section.attr(key).map(|value| value.to_string())
+}
+
+fn localized_attr(entry: &Entry, section: &str, key: &str, locales: &[String]) -> Option<String> {
+    let section = entry.section(section);
+
+    locales.iter()
+        .find_map(|locale| section.attr_with_param(key, locale))
+        .or_else(|| section.attr(key))
+        .map(|value| value.to_string())
+}
+
+// lists in desktop entries are separated by semicolon, which can be escaped
+fn split_list(value: &str) -> impl Iterator<Item = String> + '_ {
+    let mut items = vec![];
+    let mut current = String::new();
+    let mut chars = value.chars();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => {
+                match chars.next() {
+                    Some(';') => current.push(';'),
+                    Some(next) => {
+                        current.push('\\');
+                        current.push(next);
+                    }
+                    None => current.push('\\'),
+                }
+            }
+            ';' => items.push(std::mem::take(&mut current)),
+            char => current.push(char),
+        }
+    }
+
+    items.push(current);
+
+    items.into_iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+}
+
+/// Locale keys to look up localized values with, from most to least specific, e.g. for `de_DE.UTF-8@euro`
+/// it is `de_DE@euro`, `de_DE`, `de@euro`, `de`
+pub fn locale_variants(locale: &str) -> Vec<String> {
+    let (locale, modifier) = match locale.split_once('@') {
+        None => (locale, None),
+        Some((locale, modifier)) => (locale, Some(modifier)),
+    };
+
+    // encoding is ignored when matching
+    let locale = locale.split_once('.')
+        .map(|(locale, _)| locale)
+        .unwrap_or(locale);
+
+    let (lang, country) = match locale.split_once('_') {
+        None => (locale, None),
+        Some((lang, country)) => (lang, Some(country)),
+    };
+
+    if lang.is_empty() || lang == "C" || lang == "POSIX" {
+        return vec![]
+    }
+
+    let mut result = vec![];
+
+    if let (Some(country), Some(modifier)) = (country, modifier) {
+        result.push(format!("{}_{}@{}", lang, country, modifier));
+    }
+
+    if let Some(country) = country {
+        result.push(format!("{}_{}", lang, country));
+    }
+
+    if let Some(modifier) = modifier {
+        result.push(format!("{}@{}", lang, modifier));
+    }
+
+    result.push(lang.to_string());
+
+    result
+}
+
+pub fn current_locales() -> Vec<String> {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty());
+
+    match locale {
+        None => vec![],
+        Some(locale) => locale_variants(&locale),
+    }
+}
+
+pub fn current_desktops() -> Vec<String> {
+    std::env::var("XDG_CURRENT_DESKTOP")
+        .map(|value| {
+            value.split(':')
+                .filter(|desktop| !desktop.is_empty())
+                .map(|desktop| desktop.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Splits `Exec` key value into program and arguments, expanding field codes.
+/// `uris` are urls or local file paths application is asked to open. If `Exec` key accepts only
+/// single file or url (`%f` or `%u`) and multiple are given, one command per item is returned,
+/// as application is expected to be launched once per item
+pub fn exec_to_commands(
+    exec: &str,
+    name: &str,
+    icon: Option<&str>,
+    desktop_file_path: &str,
+    uris: &[String]
+) -> anyhow::Result<Vec<Vec<String>>> {
+    let exec_args = split_exec(exec)?;
+
+    let single_uri = exec_args.iter().any(|arg| arg == "%f" || arg == "%u");
+
+    let commands = if single_uri && uris.len() > 1 {
+        uris.iter()
+            .map(|uri| expand_field_codes(&exec_args, name, icon, desktop_file_path, std::slice::from_ref(uri)))
+            .collect::<Vec<_>>()
+    } else {
+        vec![expand_field_codes(&exec_args, name, icon, desktop_file_path, uris)]
+    };
+
+    if commands.iter().any(|args| args.is_empty()) {
+        Err(anyhow!("Exec key doesn't contain a program: {:?}", exec))?
+    }
+
+    Ok(commands)
+}
+
+fn expand_field_codes(exec_args: &[String], name: &str, icon: Option<&str>, desktop_file_path: &str, uris: &[String]) -> Vec<String> {
+    let mut args = vec![];
+
+    for arg in exec_args {
+        match arg.as_str() {
+            "%f" => args.extend(uris.iter().filter_map(|uri| uri_to_file(uri)).take(1)),
+            "%F" => args.extend(uris.iter().filter_map(|uri| uri_to_file(uri))),
+            "%u" => args.extend(uris.iter().cloned().take(1)),
+            "%U" => args.extend(uris.iter().cloned()),
+            "%i" => {
+                if let Some(icon) = icon {
+                    args.push("--icon".to_string());
+                    args.push(icon.to_string());
+                }
+            }
+            _ => {
+                let mut result = String::new();
+                let mut chars = arg.chars();
+
+                while let Some(char) = chars.next() {
+                    if char != '%' {
+                        result.push(char);
+                        continue;
+                    }
+
+                    match chars.next() {
+                        Some('%') => result.push('%'),
+                        Some('c') => result.push_str(name),
+                        Some('k') => result.push_str(desktop_file_path),
+                        // deprecated field codes and field codes that are not valid inside of argument are removed
+                        Some(_) | None => {}
+                    }
+                }
+
+                if !result.is_empty() {
+                    args.push(result);
+                }
+            }
+        }
+    }
+
+    args
+}
+
+// file field codes only accept local paths, remote urls are skipped instead of being downloaded
+fn uri_to_file(uri: &str) -> Option<String> {
+    match Url::parse(uri) {
+        Ok(url) if url.scheme() == "file" => {
+            url.to_file_path()
+                .ok()
+                .and_then(|path| path.to_str().map(|path| path.to_string()))
+        }
+        Ok(_) => None,
+        // anything that is not a url is considered to be a local path
+        Err(_) => Some(uri.to_string()),
+    }
+}
+
+// quoting rules from https://specifications.freedesktop.org/desktop-entry-spec/latest/exec-variables.html
+pub fn split_exec(exec: &str) -> anyhow::Result<Vec<String>> {
+    let mut args = vec![];
+    let mut current: Option<String> = None;
+    let mut chars = exec.chars();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '"' => {
+                let arg = current.get_or_insert_with(String::new);
+
+                loop {
+                    match chars.next() {
+                        None => Err(anyhow!("Unterminated quote in Exec key: {:?}", exec))?,
+                        Some('"') => break,
+                        Some('\\') => {
+                            match chars.next() {
+                                Some(escaped @ ('"' | '`' | '$' | '\\')) => arg.push(escaped),
+                                Some(other) => {
+                                    arg.push('\\');
+                                    arg.push(other);
+                                }
+                                None => Err(anyhow!("Unterminated quote in Exec key: {:?}", exec))?,
+                            }
+                        }
+                        Some(char) => arg.push(char),
+                    }
+                }
+            }
+            ' ' | '\t' => {
+                if let Some(arg) = current.take() {
+                    args.push(arg);
+                }
+            }
+            char => current.get_or_insert_with(String::new).push(char),
+        }
+    }
+
+    if let Some(arg) = current.take() {
+        args.push(arg);
+    }
+
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIREFOX: &str = include_str!("test_data/firefox.desktop");
+    const GNOME_ONLY: &str = include_str!("test_data/gnome-only.desktop");
+    const NOT_IN_KDE: &str = include_str!("test_data/not-in-kde.desktop");
+    const HIDDEN: &str = include_str!("test_data/hidden.desktop");
+    const TERMINAL: &str = include_str!("test_data/terminal.desktop");
+
+    fn read(content: &str, locales: &[&str], desktops: &[&str]) -> Option<DesktopEntry> {
+        let entry = Entry::parse(content).expect("sample desktop file should be valid");
+
+        let locales = locales.iter().map(|locale| locale.to_string()).collect::<Vec<_>>();
+        let desktops = desktops.iter().map(|desktop| desktop.to_string()).collect::<Vec<_>>();
+
+        read_desktop_entry(&entry, &locales, &desktops)
+    }
+
+    #[test]
+    fn reads_search_data() {
+        let entry = read(FIREFOX, &[], &[]).unwrap();
+
+        assert_eq!(entry.name, "Firefox");
+        assert_eq!(entry.generic_name.as_deref(), Some("Web Browser"));
+        assert_eq!(entry.comment.as_deref(), Some("Browse the World Wide Web"));
+        assert_eq!(entry.keywords, vec!["Internet", "WWW", "Browser", "Web", "Explorer"]);
+        assert_eq!(entry.icon.as_deref(), Some("firefox"));
+        assert_eq!(entry.startup_wm_class.as_deref(), Some("firefox"));
+        assert_eq!(entry.exec.as_deref(), Some("/usr/lib/firefox/firefox %u"));
+    }
+
+    #[test]
+    fn reads_actions() {
+        let entry = read(FIREFOX, &[], &[]).unwrap();
+
+        assert_eq!(entry.actions, vec![
+            DesktopEntryAction {
+                id: "new-window".to_string(),
+                name: "New Window".to_string(),
+                icon: None,
+                exec: "/usr/lib/firefox/firefox --new-window %u".to_string(),
+            },
+            DesktopEntryAction {
+                id: "new-private-window".to_string(),
+                name: "New Private Window".to_string(),
+                icon: Some("firefox-private".to_string()),
+                exec: "/usr/lib/firefox/firefox --private-window %u".to_string(),
+            },
+        ]);
+    }
+
+    #[test]
+    fn ignores_action_without_section() {
+        let entry = read(GNOME_ONLY, &[], &["GNOME"]).unwrap();
+
+        assert_eq!(entry.actions, vec![]);
+    }
+
+    #[test]
+    fn reads_localized_values() {
+        let entry = read(FIREFOX, &locale_variants("de_DE.UTF-8"), &[]).unwrap();
+
+        assert_eq!(entry.name, "Firefox");
+        assert_eq!(entry.generic_name.as_deref(), Some("Webbrowser"));
+        assert_eq!(entry.comment.as_deref(), Some("Im Internet surfen"));
+        assert_eq!(entry.keywords, vec!["Internet", "WWW", "Browser", "Web", "Explorer", "Surfen"]);
+        assert_eq!(entry.actions[1].name, "Neues privates Fenster");
+    }
+
+    #[test]
+    fn prefers_locale_with_country() {
+        let entry = read(FIREFOX, &locale_variants("pt_BR.UTF-8"), &[]).unwrap();
+
+        assert_eq!(entry.generic_name.as_deref(), Some("Navegador da Web"));
+
+        let entry = read(FIREFOX, &locale_variants("pt_PT.UTF-8"), &[]).unwrap();
+
+        assert_eq!(entry.generic_name.as_deref(), Some("Navegador Web"));
+    }
+
+    #[test]
+    fn falls_back_to_default_value() {
+        let entry = read(FIREFOX, &locale_variants("ja_JP.UTF-8"), &[]).unwrap();
+
+        assert_eq!(entry.generic_name.as_deref(), Some("Web Browser"));
+    }
+
+    #[test]
+    fn only_show_in() {
+        assert!(read(GNOME_ONLY, &[], &["GNOME"]).is_some());
+        assert!(read(GNOME_ONLY, &[], &["ubuntu", "GNOME"]).is_some());
+        assert!(read(GNOME_ONLY, &[], &["KDE"]).is_none());
+        assert!(read(GNOME_ONLY, &[], &[]).is_none());
+    }
+
+    #[test]
+    fn not_show_in() {
+        assert!(read(NOT_IN_KDE, &[], &["GNOME"]).is_some());
+        assert!(read(NOT_IN_KDE, &[], &[]).is_some());
+        assert!(read(NOT_IN_KDE, &[], &["KDE"]).is_none());
+    }
+
+    #[test]
+    fn hidden_and_no_display() {
+        assert!(read(HIDDEN, &[], &[]).is_none());
+    }
+
+    #[test]
+    fn locale_variants_order() {
+        assert_eq!(locale_variants("sr_RS.UTF-8@latin"), vec!["sr_RS@latin", "sr_RS", "sr@latin", "sr"]);
+        assert_eq!(locale_variants("de_DE.UTF-8"), vec!["de_DE", "de"]);
+        assert_eq!(locale_variants("fr"), vec!["fr"]);
+        assert_eq!(locale_variants("C.UTF-8"), Vec::<String>::new());
+        assert_eq!(locale_variants("POSIX"), Vec::<String>::new());
+    }
+
+    fn exec(exec: &str, uris: &[&str]) -> anyhow::Result<Vec<Vec<String>>> {
+        let uris = uris.iter().map(|uri| uri.to_string()).collect::<Vec<_>>();
+
+        exec_to_commands(exec, "App", Some("app-icon"), "/app.desktop", &uris)
+    }
+
+    #[test]
+    fn reads_launch_options() {
+        let entry = read(FIREFOX, &[], &[]).unwrap();
+
+        assert_eq!(entry.terminal, false);
+        assert_eq!(entry.path, None);
+
+        let entry = read(TERMINAL, &[], &[]).unwrap();
+
+        assert_eq!(entry.terminal, true);
+        assert_eq!(entry.path.as_deref(), Some("/tmp"));
+    }
+
+    #[test]
+    fn exec_removes_file_field_codes() {
+        let args = exec("/usr/lib/firefox/firefox --private-window %u", &[]).unwrap();
+
+        assert_eq!(args, vec![vec!["/usr/lib/firefox/firefox", "--private-window"]]);
+    }
+
+    #[test]
+    fn exec_expands_field_codes() {
+        let args = exec("app %i --name=%c --file %k --percent 100%%", &[]).unwrap();
+
+        assert_eq!(args, vec![vec!["app", "--icon", "app-icon", "--name=App", "--file", "/app.desktop", "--percent", "100%"]]);
+    }
+
+    #[test]
+    fn exec_removes_deprecated_field_codes() {
+        let args = exec("app %d %D %n %N %v %m --flag=%d", &[]).unwrap();
+
+        assert_eq!(args, vec![vec!["app", "--flag="]]);
+    }
+
+    #[test]
+    fn exec_icon_without_icon() {
+        let args = exec_to_commands("app %i", "App", None, "/app.desktop", &[]).unwrap();
+
+        assert_eq!(args, vec![vec!["app"]]);
+    }
+
+    #[test]
+    fn exec_expands_multiple_urls() {
+        let args = exec("app %U", &["https://example.com", "/home/user/file.txt"]).unwrap();
+
+        assert_eq!(args, vec![vec!["app", "https://example.com", "/home/user/file.txt"]]);
+    }
+
+    #[test]
+    fn exec_expands_multiple_files() {
+        let args = exec("app %F", &["file:///home/user/a%20b.txt", "/home/user/c.txt", "https://example.com"]).unwrap();
+
+        assert_eq!(args, vec![vec!["app", "/home/user/a b.txt", "/home/user/c.txt"]]);
+    }
+
+    #[test]
+    fn exec_single_url_launches_instance_per_url() {
+        let args = exec("app --open %u", &["https://example.com", "https://example.org"]).unwrap();
+
+        assert_eq!(args, vec![
+            vec!["app", "--open", "https://example.com"],
+            vec!["app", "--open", "https://example.org"],
+        ]);
+    }
+
+    #[test]
+    fn exec_single_file() {
+        let args = exec("app %f", &["file:///home/user/a.txt"]).unwrap();
+
+        assert_eq!(args, vec![vec!["app", "/home/user/a.txt"]]);
+
+        let args = exec("app %f", &["https://example.com"]).unwrap();
+
+        assert_eq!(args, vec![vec!["app"]]);
+    }
+
+    #[test]
+    fn exec_ignores_uris_if_not_accepted() {
+        let args = exec("app --flag", &["https://example.com"]).unwrap();
+
+        assert_eq!(args, vec![vec!["app", "--flag"]]);
+    }
+
+    #[test]
+    fn exec_handles_quotes() {
+        let args = exec(r#""/opt/My App/app" --title "say \"hi\"" "a\\b""#, &[]).unwrap();
+
+        assert_eq!(args, vec![vec!["/opt/My App/app", "--title", "say \"hi\"", "a\\b"]]);
+    }
+
+    #[test]
+    fn exec_invalid() {
+        assert!(exec(r#""/opt/app --flag"#, &[]).is_err());
+        assert!(exec("%U", &[]).is_err());
+    }
+}
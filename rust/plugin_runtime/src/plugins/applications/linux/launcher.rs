@@ -0,0 +1,192 @@
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use anyhow::anyhow;
+use freedesktop_entry_parser::parse_entry;
+use crate::plugins::applications::linux::{desktop_entry, wayland};
+use crate::plugins::applications::spawn_detached_command;
+
+// terminal emulators tried in order if user didn't specify one,
+// together with arguments after which command to run is expected
+const KNOWN_TERMINALS: &[(&str, &[&str])] = &[
+    ("xdg-terminal-exec", &[]),
+    ("x-terminal-emulator", &["-e"]),
+    ("kitty", &[]),
+    ("alacritty", &["-e"]),
+    ("foot", &[]),
+    ("wezterm", &["start", "--"]),
+    ("ghostty", &["-e"]),
+    ("gnome-terminal", &["--"]),
+    ("konsole", &["-e"]),
+    ("xfce4-terminal", &["-x"]),
+    ("xterm", &["-e"]),
+];
+
+static STARTUP_ID_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+pub struct LaunchOptions {
+    /// id of desktop action, application itself is launched if `None`
+    pub action_id: Option<String>,
+    pub uris: Vec<String>,
+    /// command to run `Terminal=true` applications with, e.g. `alacritty -e`
+    pub terminal: Option<String>,
+    pub wayland: bool,
+}
+
+pub fn launch(desktop_file_path: &str, options: LaunchOptions) -> anyhow::Result<()> {
+    launch_checked(desktop_file_path, options, |_| Ok(()))
+}
+
+/// Same as [`launch`] but every program is passed to `check_program` before anything is spawned
+pub fn launch_checked(
+    desktop_file_path: &str,
+    options: LaunchOptions,
+    mut check_program: impl FnMut(&str) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let entry = parse_entry(desktop_file_path)?;
+
+    let entry = desktop_entry::read_desktop_entry(&entry, &desktop_entry::current_locales(), &desktop_entry::current_desktops())
+        .ok_or_else(|| anyhow!("Application is not available: {}", desktop_file_path))?;
+
+    let (exec, icon) = match &options.action_id {
+        None => {
+            let exec = entry.exec
+                .as_ref()
+                .ok_or_else(|| anyhow!("Application doesn't have Exec key: {}", desktop_file_path))?;
+
+            (exec, entry.icon.as_ref())
+        }
+        Some(action_id) => {
+            let action = entry.actions
+                .iter()
+                .find(|action| &action.id == action_id)
+                .ok_or_else(|| anyhow!("Application doesn't have action {}: {}", action_id, desktop_file_path))?;
+
+            (&action.exec, action.icon.as_ref().or(entry.icon.as_ref()))
+        }
+    };
+
+    let commands = desktop_entry::exec_to_commands(
+        exec,
+        &entry.name,
+        icon.map(|icon| icon.as_str()),
+        desktop_file_path,
+        &options.uris
+    )?;
+
+    let terminal = if entry.terminal {
+        Some(terminal_command(options.terminal.as_deref())?)
+    } else {
+        None
+    };
+
+    let app_id = Path::new(desktop_file_path)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(|stem| stem.to_string());
+
+    // program run inside the terminal is checked as well, not only the terminal itself
+    for args in &commands {
+        check_program(&args[0])?;
+    }
+
+    if let Some(terminal) = &terminal {
+        check_program(&terminal[0])?;
+    }
+
+    let commands: Vec<Vec<String>> = commands
+        .into_iter()
+        .map(|args| match &terminal {
+            None => args,
+            Some(terminal) => terminal.iter().cloned().chain(args).collect(),
+        })
+        .collect();
+
+    for args in commands {
+        let (program, args) = args.split_first()
+            .expect("exec_to_commands should always return program");
+
+        let mut command = Command::new(program);
+
+        command.args(args);
+
+        if let Some(path) = &entry.path {
+            command.current_dir(path);
+        }
+
+        // variables inherited from gauntlet itself are already used up
+        command.env_remove("XDG_ACTIVATION_TOKEN");
+        command.env_remove("DESKTOP_STARTUP_ID");
+
+        if options.wayland {
+            match wayland::request_activation_token(app_id.clone()) {
+                Ok(token) => {
+                    command.env("XDG_ACTIVATION_TOKEN", &token);
+                    // some toolkits only read x11 variable even when running on wayland
+                    command.env("DESKTOP_STARTUP_ID", &token);
+                }
+                Err(err) => {
+                    tracing::warn!("Unable to get activation token for {:?}: {:?}", desktop_file_path, err);
+                }
+            }
+        } else {
+            command.env("DESKTOP_STARTUP_ID", x11_startup_id(app_id.as_deref()));
+        }
+
+        tracing::debug!("Launching application {:?}: {:?}", desktop_file_path, command);
+
+        spawn_detached_command(command)?;
+    }
+
+    Ok(())
+}
+
+fn terminal_command(terminal: Option<&str>) -> anyhow::Result<Vec<String>> {
+    match terminal.filter(|terminal| !terminal.trim().is_empty()) {
+        Some(terminal) => {
+            let args = desktop_entry::split_exec(terminal)?;
+
+            if args.is_empty() {
+                Err(anyhow!("Terminal command doesn't contain a program: {:?}", terminal))?
+            }
+
+            Ok(args)
+        }
+        None => {
+            let (program, args) = KNOWN_TERMINALS
+                .iter()
+                .find(|(program, _)| which::which(program).is_ok())
+                .ok_or_else(|| anyhow!("Unable to find terminal emulator to run application in, please specify one in Gauntlet plugin preferences"))?;
+
+            Ok(std::iter::once(program).chain(args.iter()).map(|arg| arg.to_string()).collect())
+        }
+    }
+}
+
+// see https://specifications.freedesktop.org/startup-notification-spec/latest/
+fn x11_startup_id(app_id: Option<&str>) -> String {
+    let sequence = STARTUP_ID_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+
+    format!("gauntlet/{}/{}-{}", app_id.unwrap_or("unknown"), std::process::id(), sequence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn configured_terminal() {
+        assert_eq!(terminal_command(Some("alacritty -e")).unwrap(), vec!["alacritty", "-e"]);
+        assert_eq!(terminal_command(Some(r#""/opt/My Terminal/term" --""#)).unwrap(), vec!["/opt/My Terminal/term", "--"]);
+    }
+
+    #[test]
+    fn invalid_terminal() {
+        assert!(terminal_command(Some(r#""alacritty"#)).is_err());
+    }
+
+    #[test]
+    fn x11_startup_id_is_unique() {
+        assert_ne!(x11_startup_id(Some("firefox")), x11_startup_id(Some("firefox")));
+    }
+}
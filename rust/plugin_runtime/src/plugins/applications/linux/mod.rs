@@ -1,6 +1,7 @@
 use crate::plugin_data::PluginData;
-use crate::plugins::applications::{linux, resize_icon, spawn_detached, DesktopApplication, DesktopPathAction};
+use crate::plugins::applications::{linux, cached_icon, resize_icon, ApplicationContext, ICON_SIZE, DesktopApplication, DesktopApplicationAction, DesktopPathAction};
 use deno_core::{op2, OpState};
+use deno_runtime::deno_permissions::{PermissionsContainer, RunQueryDescriptor};
 use freedesktop_entry_parser::parse_entry;
 use freedesktop_icons::lookup;
 use image::imageops::FilterType;
@@ -8,7 +9,7 @@ use image::ImageFormat;
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::fs::Metadata;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::rc::Rc;
 use tokio::sync::mpsc::Sender;
 use tokio::task::spawn_blocking;
@@ -16,6 +17,8 @@ use walkdir::WalkDir;
 
 mod x11;
 mod wayland;
+mod desktop_entry;
+mod launcher;
 
 deno_core::extension!(
     gauntlet_internal_linux,
@@ -24,6 +27,7 @@ deno_core::extension!(
         linux_app_from_path,
         linux_application_dirs,
         linux_open_application,
+        linux_open_application_action,
         x11::linux_x11_focus_window,
         x11::application_x11_pending_event,
         wayland::linux_wayland_focus_window,
@@ -43,11 +47,7 @@ pub enum LinuxDesktopEnvironment {
 
 impl LinuxDesktopEnvironment {
     pub fn new() -> anyhow::Result<Self> {
-        let wayland = std::env::var("WAYLAND_DISPLAY")
-            .or_else(|_| std::env::var("WAYLAND_SOCKET"))
-            .is_ok();
-
-        if wayland {
+        if is_wayland_session() {
             Ok(LinuxDesktopEnvironment::Wayland(wayland::WaylandDesktopEnvironment::new()?))
         } else {
             Ok(LinuxDesktopEnvironment::X11(x11::X11DesktopEnvironment::new()))
@@ -94,14 +94,98 @@ fn linux_application_dirs(state: Rc<RefCell<OpState>>) -> Vec<String> {
         .collect()
 }
 
-#[op2(fast)]
-fn linux_open_application(#[string] desktop_file_id: String) -> anyhow::Result<()> {
+#[op2]
+fn linux_open_application(
+    state: Rc<RefCell<OpState>>,
+    #[string] desktop_file_path: String,
+    #[serde] uris: Vec<String>,
+    #[serde] terminal: Option<String>,
+) -> anyhow::Result<()> {
+    let options = launcher::LaunchOptions {
+        action_id: None,
+        uris,
+        terminal,
+        wayland: is_wayland(&state),
+    };
+
+    launcher::launch(&desktop_file_path, options)
+}
+
+#[op2]
+fn linux_open_application_action(
+    state: Rc<RefCell<OpState>>,
+    #[string] desktop_file_path: String,
+    #[string] action_id: String,
+    #[serde] terminal: Option<String>,
+) -> anyhow::Result<()> {
+    let options = launcher::LaunchOptions {
+        action_id: Some(action_id),
+        uris: vec![],
+        terminal,
+        wayland: is_wayland(&state),
+    };
+
+    launcher::launch(&desktop_file_path, options)
+}
+
+/// Used by public api, where programs that are going to be run are checked against exec permissions of the plugin
+pub fn open_application_with_uris(state: &mut OpState, desktop_file_path: String, uris: Vec<String>) -> anyhow::Result<()> {
+    let home_dir = state
+        .borrow::<PluginData>()
+        .home_dir();
+
+    // reading arbitrary files is not allowed to plugins, so only installed applications can be opened
+    if !is_in_application_dirs(&linux_application_dirs_inner(home_dir), Path::new(&desktop_file_path)) {
+        Err(anyhow::anyhow!("Desktop file is not located in one of application directories: {:?}", desktop_file_path))?
+    }
+
+    let options = launcher::LaunchOptions {
+        action_id: None,
+        uris,
+        terminal: None,
+        wayland: is_wayland_session(),
+    };
+
+    let permissions = state.borrow_mut::<PermissionsContainer>();
+
+    launcher::launch_checked(&desktop_file_path, options, |program| {
+        let resolved = which::which(program)
+            .map_err(|err| anyhow::anyhow!("Unable to find program {:?}: {}", program, err))?;
+
+        let descriptor = RunQueryDescriptor::Path {
+            requested: program.to_string(),
+            resolved,
+        };
+
+        permissions.check_run(&descriptor, "Applications.open()")?;
+
+        Ok(())
+    })
+}
+
+fn is_in_application_dirs(app_dirs: &[PathBuf], desktop_file_path: &Path) -> bool {
+    let is_desktop_file = desktop_file_path.extension().is_some_and(|extension| extension == "desktop");
+
+    // '..' is rejected instead of resolved, so the path cannot point outside of application directory
+    let is_normal = desktop_file_path.is_absolute() && desktop_file_path.components()
+        .all(|component| !matches!(component, Component::ParentDir));
 
-    spawn_detached("gtk-launch", &[desktop_file_id])?;
+    is_desktop_file && is_normal && app_dirs.iter().any(|dir| desktop_file_path.starts_with(dir))
+}
 
-    Ok(())
+fn is_wayland_session() -> bool {
+    std::env::var("WAYLAND_DISPLAY")
+        .or_else(|_| std::env::var("WAYLAND_SOCKET"))
+        .is_ok()
 }
 
+fn is_wayland(state: &Rc<RefCell<OpState>>) -> bool {
+    state
+        .borrow()
+        .borrow::<ApplicationContext>()
+        .desktop
+        .is_wayland()
+}
 
 fn linux_application_dirs_inner(home_dir: PathBuf) -> Vec<PathBuf> {
     let data_home = match std::env::var_os("XDG_DATA_HOME") {
@@ -199,6 +283,39 @@ fn linux_app_from_path_async(home_dir: PathBuf, path: PathBuf) -> Option<Desktop
     }
 }
 
+fn process_icon(path: &Path) -> anyhow::Result<Vec<u8>> {
+    match path.extension() {
+        None => Err(anyhow::anyhow!("unknown format")),
+        Some(extension) => {
+            match extension.to_str() {
+                Some("png") => {
+                    let data = std::fs::read(path)?;
+
+                    resize_icon(data)
+                },
+                Some("svg") => {
+                    let data = std::fs::read(path)?;
+
+                    let tree = resvg::usvg::Tree::from_data(&data, &resvg::usvg::Options::default())?;
+
+                    let pixmap_size = tree.size().to_int_size();
+                    let mut pixmap = resvg::tiny_skia::Pixmap::new(pixmap_size.width(), pixmap_size.height()).unwrap();
+
+                    resvg::render(&tree, resvg::tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+                    let data = pixmap.encode_png()?;
+
+                    let data = resize_icon(data)?;
+
+                    Ok(data)
+                },
+                Some("xpm") => Err(anyhow::anyhow!("xpm format")),
+                _ => Err(anyhow::anyhow!("unsupported by spec format {:?}", extension)),
+            }
+        }
+    }
+}
+
 fn create_app_entry(desktop_file_path: &Path) -> Option<DesktopApplication> {
     let entry = parse_entry(desktop_file_path)
         .inspect_err(|err| tracing::warn!("error parsing .desktop file at path {:?}: {:?}", desktop_file_path, err))
@@ -208,18 +325,9 @@ fn create_app_entry(desktop_file_path: &Path) -> Option<DesktopApplication> {
         .expect("non-utf8 paths are not supported")
         .to_string();
 
-    let entry = entry.section("Desktop Entry");
-
-    let name = entry.attr("Name")?;
-    let icon = entry.attr("Icon").map(|s| s.to_string());
-    let no_display = entry.attr("NoDisplay").map(|val| val == "true").unwrap_or(false);
-    let hidden = entry.attr("Hidden").map(|val| val == "true").unwrap_or(false);
-    let startup_wm_class = entry.attr("StartupWMClass").map(|s| s.to_string());;
-    // TODO NotShowIn, OnlyShowIn https://wiki.archlinux.org/title/desktop_entries
+    let entry = desktop_entry::read_desktop_entry(&entry, &desktop_entry::current_locales(), &desktop_entry::current_desktops())?;
 
-    if no_display || hidden {
-        return None
-    }
+    let icon = entry.icon.clone();
 
     let icon = icon
         .map(|icon| {
@@ -228,44 +336,13 @@ fn create_app_entry(desktop_file_path: &Path) -> Option<DesktopApplication> {
                 Some(icon_path)
             } else {
                 lookup(&icon)
-                    .with_size(48)
+                    .with_size(ICON_SIZE as u16)
                     .find()
             }
         })
         .flatten()
         .inspect(|path| tracing::debug!("icon path: {:?}", path))
-        .map(|path| {
-            match path.extension() {
-                None => Err(anyhow::anyhow!("unknown format")),
-                Some(extension) => {
-                    match extension.to_str() {
-                        Some("png") => {
-                            let data = std::fs::read(path)?;
-
-                            resize_icon(data)
-                        },
-                        Some("svg") => {
-                            let data = std::fs::read(path)?;
-
-                            let tree = resvg::usvg::Tree::from_data(&data, &resvg::usvg::Options::default())?;
-
-                            let pixmap_size = tree.size().to_int_size();
-                            let mut pixmap = resvg::tiny_skia::Pixmap::new(pixmap_size.width(), pixmap_size.height()).unwrap();
-
-                            resvg::render(&tree, resvg::tiny_skia::Transform::default(), &mut pixmap.as_mut());
-
-                            let data = pixmap.encode_png()?;
-
-                            let data = resize_icon(data)?;
-
-                            Ok(data)
-                        },
-                        Some("xpm") => Err(anyhow::anyhow!("xpm format")),
-                        _ => Err(anyhow::anyhow!("unsupported by spec format {:?}", extension)),
-                    }
-                }
-            }
-        })
+        .map(|path| cached_icon(&path, ICON_SIZE, || process_icon(&path)))
         .map(|res| {
             res
                 .inspect_err(|err| tracing::warn!("error processing icon of {:?}: {:?}", desktop_file_path, err))
@@ -273,10 +350,42 @@ fn create_app_entry(desktop_file_path: &Path) -> Option<DesktopApplication> {
         })
         .flatten();
 
+    let actions = entry.actions
+        .into_iter()
+        .map(|action| DesktopApplicationAction {
+            id: action.id,
+            name: action.name,
+        })
+        .collect();
+
     Some(DesktopApplication {
-        name: name.to_string(),
+        name: entry.name,
         desktop_file_path: desktop_file_path_str,
         icon,
-        startup_wm_class,
+        startup_wm_class: entry.startup_wm_class,
+        generic_name: entry.generic_name,
+        comment: entry.comment,
+        keywords: entry.keywords,
+        actions,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn desktop_file_in_application_dirs() {
+        let app_dirs = vec![PathBuf::from("/usr/share/applications"), PathBuf::from("/home/user/.local/share/applications")];
+
+        assert!(is_in_application_dirs(&app_dirs, Path::new("/usr/share/applications/firefox.desktop")));
+        assert!(is_in_application_dirs(&app_dirs, Path::new("/usr/share/applications/kde/org.kde.dolphin.desktop")));
+        assert!(is_in_application_dirs(&app_dirs, Path::new("/home/user/.local/share/applications/app.desktop")));
+
+        assert!(!is_in_application_dirs(&app_dirs, Path::new("/tmp/app.desktop")));
+        assert!(!is_in_application_dirs(&app_dirs, Path::new("/usr/share/applications/../../../tmp/app.desktop")));
+        assert!(!is_in_application_dirs(&app_dirs, Path::new("usr/share/applications/app.desktop")));
+        assert!(!is_in_application_dirs(&app_dirs, Path::new("/usr/share/applications/mimeinfo.cache")));
+        assert!(!is_in_application_dirs(&app_dirs, Path::new("/usr/share/applications-other/app.desktop")));
+    }
+}
@@ -0,0 +1,83 @@
+use std::time::{Duration, Instant};
+use anyhow::anyhow;
+use smithay_client_toolkit::activation::{ActivationHandler, ActivationState, RequestData};
+use smithay_client_toolkit::reexports::calloop::EventLoop;
+use smithay_client_toolkit::reexports::calloop_wayland_source::WaylandSource;
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::wl_registry;
+use wayland_client::{Connection, Dispatch, QueueHandle};
+
+// application is launched without token if compositor doesn't respond in time
+const ACTIVATION_TOKEN_TIMEOUT: Duration = Duration::from_millis(500);
+
+struct ActivationTokenState {
+    token: Option<String>,
+}
+
+/// Requests xdg-activation token for application that is about to be launched,
+/// so compositor can focus its window once it is opened.
+/// Uses separate connection because it is called synchronously from launching op
+pub fn request_activation_token(app_id: Option<String>) -> anyhow::Result<String> {
+    let conn = Connection::connect_to_env()?;
+    let (globals, event_queue) = registry_queue_init::<ActivationTokenState>(&conn)?;
+    let queue_handle = event_queue.handle();
+
+    let activation_state = ActivationState::bind(&globals, &queue_handle)?;
+
+    let mut event_loop = EventLoop::<ActivationTokenState>::try_new()?;
+
+    if let Err(err) = WaylandSource::new(conn, event_queue).insert(event_loop.handle()) {
+        Err(anyhow!("Unable to insert wayland source into event loop: {:?}", err.error))?
+    }
+
+    let mut state = ActivationTokenState {
+        token: None,
+    };
+
+    activation_state.request_token(
+        &queue_handle,
+        RequestData {
+            app_id,
+            seat_and_serial: None,
+            surface: None,
+        }
+    );
+
+    let deadline = Instant::now() + ACTIVATION_TOKEN_TIMEOUT;
+
+    loop {
+        if let Some(token) = state.token.take() {
+            return Ok(token)
+        }
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+
+        if remaining.is_zero() {
+            Err(anyhow!("Compositor didn't provide activation token in {:?}", ACTIVATION_TOKEN_TIMEOUT))?
+        }
+
+        event_loop.dispatch(Some(remaining), &mut state)?;
+    }
+}
+
+impl ActivationHandler for ActivationTokenState {
+    type RequestData = RequestData;
+
+    fn new_token(&mut self, token: String, _data: &Self::RequestData) {
+        self.token = Some(token);
+    }
+}
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for ActivationTokenState {
+    fn event(
+        _state: &mut ActivationTokenState,
+        _proxy: &wl_registry::WlRegistry,
+        _event: wl_registry::Event,
+        _data: &GlobalListContents,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<ActivationTokenState>,
+    ) {
+    }
+}
+
+smithay_client_toolkit::delegate_activation!(ActivationTokenState);
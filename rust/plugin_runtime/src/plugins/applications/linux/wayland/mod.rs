@@ -18,6 +18,9 @@ use crate::plugins::applications::{linux, ApplicationContext, DesktopEnvironment
 
 mod wlr;
 mod cosmic;
+mod activation;
+
+pub use activation::request_activation_token;
 
 pub struct WaylandDesktopEnvironment {
     activate_sender: calloop::channel::Sender<String>,
@@ -12,7 +12,7 @@ use objc2_foundation::{CGFloat, CGPoint, CGRect, NSDictionary, NSInteger, NSPoin
 use plist::Dictionary;
 use regex::Regex;
 use serde::Deserialize;
-use crate::plugins::applications::{DesktopApplication, DesktopPathAction, DesktopSettings13AndPostData, DesktopSettingsPre13Data};
+use crate::plugins::applications::{cached_icon, DesktopApplication, DesktopPathAction, DesktopSettings13AndPostData, DesktopSettingsPre13Data};
 
 
 pub fn macos_major_version() -> u8 {
@@ -380,6 +380,10 @@ unsafe fn resize_ns_image(source_image: &NSImage, width: NSInteger, height: NSIn
 }
 
 fn get_application_icon(app_path: &Path) -> anyhow::Result<Vec<u8>> {
+    cached_icon(app_path, 40, || get_application_icon_uncached(app_path))
+}
+
+fn get_application_icon_uncached(app_path: &Path) -> anyhow::Result<Vec<u8>> {
     unsafe {
         let workspace = NSWorkspace::sharedWorkspace();
 
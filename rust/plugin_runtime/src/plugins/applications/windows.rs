@@ -1,9 +1,9 @@
 use std::{mem, ptr};
 use std::io::Cursor;
 use std::mem::MaybeUninit;
-use crate::plugins::applications::{resize_icon, DesktopApplication, DesktopPathAction};
+use crate::plugins::applications::{cached_icon, resize_icon, DesktopApplication, DesktopPathAction, ICON_SIZE};
 use deno_core::op2;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use anyhow::{anyhow, Context};
 use image::RgbaImage;
 use windows::core::{GUID, HSTRING, PWSTR};
@@ -56,7 +56,7 @@ fn windows_open_application(#[string] file_path: String) -> anyhow::Result<()> {
 async fn windows_app_from_path(#[string] file_path: String) -> anyhow::Result<Option<DesktopPathAction>> {
     if PathBuf::from(&file_path).exists() {
         let name = extract_name(&file_path)?;
-        let icon = extract_icon(&file_path)
+        let icon = cached_icon(Path::new(&file_path), ICON_SIZE, || extract_icon(&file_path))
             .inspect_err(|err| tracing::error!("Unable to extract icon for {}: {:?}", file_path, err))
             .ok();
 
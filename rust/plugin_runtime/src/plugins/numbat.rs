@@ -1,16 +1,40 @@
 use anyhow::anyhow;
-use deno_core::{op2, OpState};
+use deno_core::{op2, serde_json, OpState};
+use numbat::diagnostic::ErrorDiagnostic;
 use numbat::markup::{Formatter, PlainTextFormatter};
 use numbat::module_importer::BuiltinModuleImporter;
 use numbat::pretty_print::PrettyPrint;
 use numbat::resolver::CodeSource;
-use numbat::{Context, InterpreterResult};
-use serde::Serialize;
+use numbat::typechecker::TypeCheckError;
+use numbat::value::Value;
+use numbat::{Context, InterpreterResult, NumbatError};
+use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use crate::plugin_data::PluginData;
 
+const HISTORY_FILE_NAME: &str = "calculator_history.json";
+const MAX_HISTORY_ENTRIES: usize = 100;
+
+// scratch context keeps text of every input it has seen, so it is recreated from time to time
+const MAX_SCRATCH_EVALUATIONS: usize = 1000;
+
+/// Contains variables saved by user, queries are evaluated on a copy of it,
+/// so incomplete input typed into main search bar doesn't define anything
 #[derive(Clone)]
-pub struct NumbatContext(Rc<RefCell<Context>>);
+pub struct NumbatContext {
+    saved: Rc<RefCell<Context>>,
+    scratch: Rc<RefCell<Option<ScratchContext>>>,
+}
+
+/// Copying whole context is expensive, so the copy is reused between queries
+/// until evaluation could have changed it. Most of the queries typed into main search bar
+/// are not calculations and fail before anything is defined
+struct ScratchContext {
+    context: Context,
+    evaluations: usize,
+}
 
 impl NumbatContext {
     pub fn new() -> NumbatContext {
@@ -24,14 +48,85 @@ impl NumbatContext {
 
         let _ = context.interpret("use prelude", CodeSource::Internal);
 
-        NumbatContext(Rc::new(RefCell::new(context)))
+        NumbatContext {
+            saved: Rc::new(RefCell::new(context)),
+            scratch: Rc::new(RefCell::new(None)),
+        }
     }
+
+    fn evaluate(&self, input: &str) -> NumbatResult {
+        let mut scratch = self.scratch.borrow_mut();
+
+        let current = scratch.get_or_insert_with(|| {
+            ScratchContext {
+                context: self.saved.borrow().clone(),
+                evaluations: 0,
+            }
+        });
+
+        current.evaluations += 1;
+
+        let (result, modified) = evaluate(&mut current.context, input);
+
+        if modified || current.evaluations >= MAX_SCRATCH_EVALUATIONS {
+            *scratch = None;
+        }
+
+        result
+    }
+
+    fn save_variables(&self, input: &str) -> anyhow::Result<()> {
+        *self.scratch.borrow_mut() = None;
+
+        self.saved
+            .borrow_mut()
+            .interpret(input, CodeSource::Text)
+            .map_err(|err| anyhow!("{}", err))?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(tag = "type")]
+enum NumbatResult {
+    Value {
+        left: String,
+        right: String,
+        // only present if result is a quantity
+        number: Option<String>,
+        scientific: Option<String>,
+    },
+    Error {
+        left: String,
+        message: String,
+        spans: Vec<NumbatErrorSpan>,
+    },
+    // input failed to parse or refers to unknown names, so it is most likely not meant as a calculation
+    NotCalculation,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct NumbatErrorSpan {
+    // in characters, not bytes
+    start: usize,
+    end: usize,
+    text: String,
+    message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct NumbatHistoryEntry {
+    expression: String,
+    result: String,
+    number: Option<String>,
+    scientific: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
-struct NumbatResult {
-    left: String,
-    right: String,
+fn history_path(state: &OpState) -> PathBuf {
+    let plugin_data = state.borrow::<PluginData>();
+
+    Path::new(plugin_data.plugin_data_dir()).join(HISTORY_FILE_NAME)
 }
 
 #[op2]
@@ -47,9 +142,64 @@ pub fn run_numbat(state: Rc<RefCell<OpState>>, #[string] input: String) -> anyho
         context
     };
 
-    let mut context = context.0.borrow_mut();
+    Ok(context.evaluate(&input))
+}
+
+/// Interprets input in the context shared between queries,
+/// so variables defined by it and `ans` can be used in following queries
+#[op2(fast)]
+pub fn numbat_save_variables(state: &mut OpState, #[string] input: String) -> anyhow::Result<()> {
+    state.borrow::<NumbatContext>().save_variables(&input)
+}
+
+#[op2]
+#[serde]
+pub fn numbat_history(state: &mut OpState) -> anyhow::Result<Vec<NumbatHistoryEntry>> {
+    read_history(&history_path(state))
+}
+
+#[op2]
+pub fn numbat_add_history_entry(state: &mut OpState, #[serde] entry: NumbatHistoryEntry) -> anyhow::Result<()> {
+    let path = history_path(state);
+
+    let history = add_history_entry(read_history(&path)?, entry);
+
+    write_history(&path, &history)
+}
+
+#[op2(fast)]
+pub fn numbat_remove_history_entry(state: &mut OpState, #[string] expression: String) -> anyhow::Result<()> {
+    let path = history_path(state);
 
-    let (statements, result) = context.interpret(&input, CodeSource::Text)?;
+    let history = read_history(&path)?
+        .into_iter()
+        .filter(|entry| entry.expression != expression)
+        .collect::<Vec<_>>();
+
+    write_history(&path, &history)
+}
+
+#[op2(fast)]
+pub fn numbat_clear_history(state: &mut OpState) -> anyhow::Result<()> {
+    write_history(&history_path(state), &[])
+}
+
+/// Returns result together with flag telling whether context could have been changed by evaluation.
+/// Numbat rolls back context if input fails to parse or typecheck, but not if it fails at runtime,
+/// and successful evaluation always assigns `ans`
+fn evaluate(context: &mut Context, input: &str) -> (NumbatResult, bool) {
+    // input is added to resolver first, before modules it imports
+    let input_file_id = next_file_id(context);
+
+    let (statements, result) = match context.interpret(input, CodeSource::Text) {
+        Ok(result) => result,
+        Err(err) => {
+            let err: &NumbatError = &err;
+            let modified = matches!(err, NumbatError::RuntimeError(_));
+
+            return (error_result(input, input_file_id, err), modified)
+        },
+    };
 
     let formatter = PlainTextFormatter;
 
@@ -60,13 +210,314 @@ pub fn run_numbat(state: Rc<RefCell<OpState>>, #[string] input: String) -> anyho
         .join(" ")
         .replace('➞', "to");
 
+    (value_result(context, input, expression, result), true)
+}
+
+fn value_result(context: &mut Context, input: &str, expression: String, result: InterpreterResult) -> NumbatResult {
     let value = match result {
-        InterpreterResult::Value(value) => format!("{}", value.pretty_print()),
-        InterpreterResult::Continue => Err(anyhow!("numbat returned Continue"))?
+        InterpreterResult::Value(value) => value,
+        InterpreterResult::Continue => {
+            // variable definition doesn't return value, so it is shown explicitly
+            let Some(name) = defined_variable(input) else {
+                return NumbatResult::NotCalculation
+            };
+
+            match context.interpret(name, CodeSource::Text) {
+                Ok((_, InterpreterResult::Value(value))) => value,
+                _ => return NumbatResult::NotCalculation,
+            }
+        }
     };
 
-    Ok(NumbatResult {
+    let number = match &value {
+        Value::Quantity(quantity) => Some(quantity.unsafe_value().to_f64()),
+        _ => None,
+    };
+
+    NumbatResult::Value {
         left: expression,
-        right: value
-    })
-}
\ No newline at end of file
+        right: format!("{}", value.pretty_print()),
+        number: number.map(format_number),
+        scientific: number.map(format_scientific),
+    }
+}
+
+fn next_file_id(context: &Context) -> usize {
+    let files = &context.resolver().files;
+
+    (0..)
+        .find(|id| files.get(*id).is_err())
+        .expect("number of files is finite")
+}
+
+fn error_result(input: &str, input_file_id: usize, err: &NumbatError) -> NumbatResult {
+    match err {
+        NumbatError::TypeCheckError(TypeCheckError::UnknownIdentifier(..) | TypeCheckError::UnknownCallable(..)) => {
+            return NumbatResult::NotCalculation
+        }
+        NumbatError::TypeCheckError(_) | NumbatError::RuntimeError(_) => {}
+        _ => return NumbatResult::NotCalculation,
+    }
+
+    let spans = err.diagnostics()
+        .into_iter()
+        .flat_map(|diagnostic| diagnostic.labels)
+        // labels can also point to definitions in numbat modules
+        .filter(|label| label.file_id == input_file_id)
+        .filter_map(|label| {
+            let text = input.get(label.range.clone())?;
+
+            Some(NumbatErrorSpan {
+                start: input[..label.range.start].chars().count(),
+                end: input[..label.range.end].chars().count(),
+                text: text.to_string(),
+                message: label.message,
+            })
+        })
+        .collect();
+
+    NumbatResult::Error {
+        left: input.to_string(),
+        message: err.to_string(),
+        spans,
+    }
+}
+
+fn defined_variable(input: &str) -> Option<&str> {
+    let rest = input.trim_start().strip_prefix("let ")?;
+
+    let name = rest.trim_start()
+        .split(|c: char| c.is_whitespace() || c == ':' || c == '=')
+        .next()?;
+
+    if name.is_empty() {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+fn format_number(value: f64) -> String {
+    if value.is_finite() && value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
+
+fn format_scientific(value: f64) -> String {
+    format!("{:e}", value)
+}
+
+fn read_history(path: &Path) -> anyhow::Result<Vec<NumbatHistoryEntry>> {
+    if !path.exists() {
+        return Ok(vec![])
+    }
+
+    let content = std::fs::read_to_string(path)?;
+
+    match serde_json::from_str(&content) {
+        Ok(history) => Ok(history),
+        Err(err) => {
+            // corrupt history shouldn't make calculator unusable, start from empty one instead
+            tracing::warn!("Unable to read calculator history {:?}, it will be cleared: {:?}", path, err);
+
+            write_history(path, &[])?;
+
+            Ok(vec![])
+        }
+    }
+}
+
+fn write_history(path: &Path, history: &[NumbatHistoryEntry]) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::write(path, serde_json::to_string(history)?)?;
+
+    Ok(())
+}
+
+/// Most recent entry goes first, repeated expression is moved to the top instead of being duplicated
+fn add_history_entry(history: Vec<NumbatHistoryEntry>, entry: NumbatHistoryEntry) -> Vec<NumbatHistoryEntry> {
+    let mut history = history.into_iter()
+        .filter(|existing| existing.expression != entry.expression)
+        .collect::<Vec<_>>();
+
+    history.insert(0, entry);
+    history.truncate(MAX_HISTORY_ENTRIES);
+
+    history
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(result: NumbatResult) -> (String, Option<String>, Option<String>) {
+        match result {
+            NumbatResult::Value { right, number, scientific, .. } => (right, number, scientific),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    fn entry(expression: &str) -> NumbatHistoryEntry {
+        NumbatHistoryEntry {
+            expression: expression.to_string(),
+            result: "2".to_string(),
+            number: Some("2".to_string()),
+            scientific: Some("2e0".to_string()),
+        }
+    }
+
+    #[test]
+    fn evaluates_quantity() {
+        let context = NumbatContext::new();
+
+        let (right, number, scientific) = value(context.evaluate("1500 m"));
+
+        assert!(right.contains('m'));
+        assert_eq!(number, Some("1500".to_string()));
+        assert_eq!(scientific, Some("1.5e3".to_string()));
+    }
+
+    #[test]
+    fn ignores_text_that_is_not_calculation() {
+        let context = NumbatContext::new();
+
+        assert_eq!(context.evaluate("firefox"), NumbatResult::NotCalculation);
+        assert_eq!(context.evaluate("open (settings"), NumbatResult::NotCalculation);
+    }
+
+    #[test]
+    fn reports_errors_with_spans() {
+        let context = NumbatContext::new();
+
+        match context.evaluate("2 m + 3 s") {
+            NumbatResult::Error { left, spans, .. } => {
+                assert_eq!(left, "2 m + 3 s");
+                assert!(!spans.is_empty());
+                assert!(spans.iter().all(|span| span.end <= left.chars().count()));
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn variables_are_kept_only_when_saved() {
+        let context = NumbatContext::new();
+
+        let (right, _, _) = value(context.evaluate("let distance = 3 km"));
+        assert!(right.contains("3"));
+
+        assert_eq!(context.evaluate("distance"), NumbatResult::NotCalculation);
+
+        context.save_variables("let distance = 3 km").unwrap();
+
+        let (_, number, _) = value(context.evaluate("distance -> m"));
+        assert_eq!(number, Some("3000".to_string()));
+    }
+
+    #[test]
+    fn ans_refers_to_saved_result() {
+        let context = NumbatContext::new();
+
+        context.save_variables("6 * 7").unwrap();
+
+        let (_, number, _) = value(context.evaluate("ans + 1"));
+        assert_eq!(number, Some("43".to_string()));
+    }
+
+    #[test]
+    fn error_spans_only_point_into_input() {
+        let context = NumbatContext::new();
+
+        match context.evaluate("atan2(1 m, 2 s)") {
+            NumbatResult::Error { left, spans, .. } => {
+                for span in spans {
+                    let text = left.chars()
+                        .skip(span.start)
+                        .take(span.end - span.start)
+                        .collect::<String>();
+
+                    assert_eq!(text, span.text);
+                }
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn scratch_context_is_reused_until_modified() {
+        let context = NumbatContext::new();
+
+        context.save_variables("1 + 1").unwrap();
+
+        assert_eq!(context.evaluate("firefox"), NumbatResult::NotCalculation);
+        assert!(context.scratch.borrow().is_some());
+
+        value(context.evaluate("6 * 7"));
+        assert!(context.scratch.borrow().is_none());
+
+        // value assigned to ans while typing is not visible in following queries
+        let (_, number, _) = value(context.evaluate("ans"));
+        assert_eq!(number, Some("2".to_string()));
+    }
+
+    #[test]
+    fn defined_variable_name() {
+        assert_eq!(defined_variable("let x = 5"), Some("x"));
+        assert_eq!(defined_variable("  let speed: Velocity = 5 m/s"), Some("speed"));
+        assert_eq!(defined_variable("let"), None);
+        assert_eq!(defined_variable("5 + 5"), None);
+    }
+
+    #[test]
+    fn number_formats() {
+        assert_eq!(format_number(42.0), "42");
+        assert_eq!(format_number(0.5), "0.5");
+        assert_eq!(format_scientific(0.00012), "1.2e-4");
+    }
+
+    #[test]
+    fn history_keeps_most_recent_first() {
+        let history = add_history_entry(vec![], entry("1 + 1"));
+        let history = add_history_entry(history, entry("4 / 2"));
+        let history = add_history_entry(history, entry("1 + 1"));
+
+        assert_eq!(history, vec![entry("1 + 1"), entry("4 / 2")]);
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let history = (0..MAX_HISTORY_ENTRIES + 10)
+            .fold(vec![], |history, index| add_history_entry(history, entry(&format!("{} + 0", index))));
+
+        assert_eq!(history.len(), MAX_HISTORY_ENTRIES);
+        assert_eq!(history[0].expression, format!("{} + 0", MAX_HISTORY_ENTRIES + 9));
+    }
+
+    #[test]
+    fn history_is_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("numbat").join(HISTORY_FILE_NAME);
+
+        assert_eq!(read_history(&path).unwrap(), vec![]);
+
+        write_history(&path, &[entry("1 + 1")]).unwrap();
+
+        assert_eq!(read_history(&path).unwrap(), vec![entry("1 + 1")]);
+    }
+
+    #[test]
+    fn corrupt_history_is_cleared() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(HISTORY_FILE_NAME);
+
+        std::fs::write(&path, "[{\"expression\": ").unwrap();
+
+        assert_eq!(read_history(&path).unwrap(), vec![]);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "[]");
+    }
+}
@@ -20,3 +20,25 @@ pub async fn reload_search_index(state: Rc<RefCell<OpState>>, #[serde] generated
 
     Ok(())
 }
+
+#[op2(async)]
+pub async fn update_search_index(
+    state: Rc<RefCell<OpState>>,
+    #[serde] updated_generated_commands: Vec<JsGeneratedSearchItem>,
+    #[serde] removed_generated_entrypoint_ids: Vec<String>,
+    refresh_search_list: bool
+) -> anyhow::Result<()> {
+    let api = {
+        let state = state.borrow();
+
+        let api = state
+            .borrow::<BackendForPluginRuntimeApiProxy>()
+            .clone();
+
+        api
+    };
+
+    api.update_search_index(updated_generated_commands, removed_generated_entrypoint_ids, refresh_search_list).await?;
+
+    Ok(())
+}
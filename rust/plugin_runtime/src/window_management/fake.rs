@@ -0,0 +1,117 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+use crate::window_management::{lock_registry, window_not_found, JsWindow, JsWindowCapabilities, SharedWindowRegistry};
+
+pub const CAPABILITIES: JsWindowCapabilities = JsWindowCapabilities {
+    backend: "fake",
+    workspace: true,
+    pid: true,
+    focus: true,
+    close: true,
+    minimize: true,
+};
+
+/// In-memory backend that doesn't need display server, used when running with `scenario_runner` feature and in tests
+pub struct FakeWindowBackend {
+    registry: SharedWindowRegistry,
+    state: Mutex<FakeWindowState>,
+}
+
+#[derive(Default)]
+struct FakeWindowState {
+    focused: Option<String>,
+    minimized: HashSet<String>,
+}
+
+impl FakeWindowBackend {
+    pub fn new(registry: SharedWindowRegistry) -> Self {
+        Self {
+            registry,
+            state: Mutex::new(FakeWindowState::default()),
+        }
+    }
+
+    pub fn with_sample_windows(registry: SharedWindowRegistry) -> Self {
+        let backend = Self::new(registry);
+
+        let samples = [
+            ("1", "Mozilla Firefox", "firefox", "1", 1001),
+            ("2", "Terminal", "org.gnome.Terminal", "1", 1002),
+            ("3", "Files", "org.gnome.Nautilus", "2", 1003),
+        ];
+
+        for (id, title, app_id, workspace, pid) in samples {
+            backend.open_window(JsWindow {
+                id: id.to_string(),
+                title: title.to_string(),
+                app_id: Some(app_id.to_string()),
+                workspace: Some(workspace.to_string()),
+                pid: Some(pid),
+            })
+        }
+
+        backend
+    }
+
+    pub fn open_window(&self, window: JsWindow) {
+        lock_registry(&self.registry).window_opened(window);
+    }
+
+    #[cfg(test)]
+    pub fn set_title(&self, window_id: &str, title: &str) {
+        lock_registry(&self.registry).title_changed(window_id, title.to_string());
+    }
+
+    #[cfg(test)]
+    pub fn focused(&self) -> Option<String> {
+        self.state.lock().expect("lock is poisoned").focused.clone()
+    }
+
+    #[cfg(test)]
+    pub fn is_minimized(&self, window_id: &str) -> bool {
+        self.state.lock().expect("lock is poisoned").minimized.contains(window_id)
+    }
+
+    pub fn focus(&self, window_id: &str) -> anyhow::Result<()> {
+        self.ensure_exists(window_id)?;
+
+        let mut state = self.state.lock().expect("lock is poisoned");
+
+        state.minimized.remove(window_id);
+        state.focused = Some(window_id.to_string());
+
+        Ok(())
+    }
+
+    pub fn close(&self, window_id: &str) -> anyhow::Result<()> {
+        self.ensure_exists(window_id)?;
+
+        lock_registry(&self.registry).window_closed(window_id);
+
+        let mut state = self.state.lock().expect("lock is poisoned");
+
+        state.minimized.remove(window_id);
+
+        if state.focused.as_deref() == Some(window_id) {
+            state.focused = None;
+        }
+
+        Ok(())
+    }
+
+    pub fn minimize(&self, window_id: &str) -> anyhow::Result<()> {
+        self.ensure_exists(window_id)?;
+
+        self.state.lock().expect("lock is poisoned").minimized.insert(window_id.to_string());
+
+        Ok(())
+    }
+
+    fn ensure_exists(&self, window_id: &str) -> anyhow::Result<()> {
+        if !lock_registry(&self.registry).contains(window_id) {
+            Err(window_not_found(window_id))?
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,471 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use anyhow::anyhow;
+use deno_core::{op2, OpState};
+use indexmap::IndexMap;
+use serde::Serialize;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use crate::model::JsPluginPermissionsWindows;
+
+mod fake;
+#[cfg(target_os = "linux")]
+mod x11;
+#[cfg(target_os = "linux")]
+mod wayland;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct JsWindow {
+    id: String,
+    title: String,
+    app_id: Option<String>,
+    workspace: Option<String>,
+    pid: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum JsWindowEvent {
+    WindowOpened {
+        window: JsWindow
+    },
+    WindowClosed {
+        window_id: String
+    },
+    WindowTitleChanged {
+        window_id: String,
+        title: String,
+    },
+}
+
+/// What window data and actions are available with the backend, listing windows and events are supported by all of them
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct JsWindowCapabilities {
+    backend: &'static str,
+    workspace: bool,
+    pid: bool,
+    focus: bool,
+    close: bool,
+    minimize: bool,
+}
+
+pub type SharedWindowRegistry = Arc<Mutex<WindowRegistry>>;
+
+/// Windows reported by backend, shared between backend thread and ops
+pub struct WindowRegistry {
+    windows: IndexMap<String, JsWindow>,
+    // created when plugin starts listening to events, so they are not accumulated otherwise
+    event_sender: Option<UnboundedSender<JsWindowEvent>>,
+}
+
+impl WindowRegistry {
+    pub fn new() -> SharedWindowRegistry {
+        Arc::new(Mutex::new(WindowRegistry {
+            windows: IndexMap::new(),
+            event_sender: None,
+        }))
+    }
+
+    fn subscribe(&mut self) -> UnboundedReceiver<JsWindowEvent> {
+        // unbounded channel is used because events are sent from non-async backend threads and have to be delivered in order
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        self.event_sender = Some(sender);
+
+        receiver
+    }
+
+    fn send_event(&self, event: JsWindowEvent) {
+        if let Some(sender) = &self.event_sender {
+            if let Err(err) = sender.send(event) {
+                tracing::error!("Error while sending window event: {:?}", err);
+            }
+        }
+    }
+
+    pub fn window_opened(&mut self, window: JsWindow) {
+        self.windows.insert(window.id.clone(), window.clone());
+
+        self.send_event(JsWindowEvent::WindowOpened { window });
+    }
+
+    pub fn window_closed(&mut self, window_id: &str) {
+        if self.windows.shift_remove(window_id).is_some() {
+            self.send_event(JsWindowEvent::WindowClosed { window_id: window_id.to_string() });
+        }
+    }
+
+    pub fn title_changed(&mut self, window_id: &str, title: String) {
+        let Some(window) = self.windows.get_mut(window_id) else {
+            return;
+        };
+
+        if window.title == title {
+            return;
+        }
+
+        window.title = title.clone();
+
+        self.send_event(JsWindowEvent::WindowTitleChanged { window_id: window_id.to_string(), title });
+    }
+
+    /// Updates window data that doesn't have its own event
+    pub fn update(&mut self, window_id: &str, update: impl FnOnce(&mut JsWindow)) {
+        if let Some(window) = self.windows.get_mut(window_id) {
+            update(window)
+        }
+    }
+
+    pub fn contains(&self, window_id: &str) -> bool {
+        self.windows.contains_key(window_id)
+    }
+
+    pub fn window_ids(&self) -> Vec<String> {
+        self.windows.keys().cloned().collect()
+    }
+
+    fn list(&self) -> Vec<JsWindow> {
+        self.windows.values().cloned().collect()
+    }
+}
+
+pub fn lock_registry(registry: &SharedWindowRegistry) -> std::sync::MutexGuard<'_, WindowRegistry> {
+    registry.lock().expect("lock is poisoned")
+}
+
+fn window_not_found(window_id: &str) -> anyhow::Error {
+    anyhow!("Unable to find window with id: {}", window_id)
+}
+
+enum WindowBackend {
+    #[cfg(target_os = "linux")]
+    X11(x11::X11WindowBackend),
+    #[cfg(target_os = "linux")]
+    Wayland(wayland::WaylandWindowBackend),
+    Fake(fake::FakeWindowBackend),
+}
+
+impl WindowBackend {
+    fn new(registry: SharedWindowRegistry) -> anyhow::Result<Self> {
+        // plugins are tested without display server
+        if cfg!(feature = "scenario_runner") {
+            return Ok(WindowBackend::Fake(fake::FakeWindowBackend::with_sample_windows(registry)))
+        }
+
+        Self::new_native(registry)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn new_native(registry: SharedWindowRegistry) -> anyhow::Result<Self> {
+        let wayland = std::env::var("WAYLAND_DISPLAY")
+            .or_else(|_| std::env::var("WAYLAND_SOCKET"))
+            .is_ok();
+
+        if wayland {
+            Ok(WindowBackend::Wayland(wayland::WaylandWindowBackend::new(registry)?))
+        } else {
+            Ok(WindowBackend::X11(x11::X11WindowBackend::new(registry)?))
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn new_native(_registry: SharedWindowRegistry) -> anyhow::Result<Self> {
+        Err(anyhow!("Window management is not supported on current operating system"))
+    }
+
+    fn capabilities(&self) -> JsWindowCapabilities {
+        match self {
+            #[cfg(target_os = "linux")]
+            WindowBackend::X11(_) => x11::CAPABILITIES,
+            #[cfg(target_os = "linux")]
+            WindowBackend::Wayland(backend) => backend.capabilities(),
+            WindowBackend::Fake(_) => fake::CAPABILITIES,
+        }
+    }
+
+    fn focus(&self, window_id: &str) -> anyhow::Result<()> {
+        match self {
+            #[cfg(target_os = "linux")]
+            WindowBackend::X11(backend) => backend.focus(window_id),
+            #[cfg(target_os = "linux")]
+            WindowBackend::Wayland(backend) => backend.focus(window_id),
+            WindowBackend::Fake(backend) => backend.focus(window_id),
+        }
+    }
+
+    fn close(&self, window_id: &str) -> anyhow::Result<()> {
+        match self {
+            #[cfg(target_os = "linux")]
+            WindowBackend::X11(backend) => backend.close(window_id),
+            #[cfg(target_os = "linux")]
+            WindowBackend::Wayland(backend) => backend.close(window_id),
+            WindowBackend::Fake(backend) => backend.close(window_id),
+        }
+    }
+
+    fn minimize(&self, window_id: &str) -> anyhow::Result<()> {
+        match self {
+            #[cfg(target_os = "linux")]
+            WindowBackend::X11(backend) => backend.minimize(window_id),
+            #[cfg(target_os = "linux")]
+            WindowBackend::Wayland(backend) => backend.minimize(window_id),
+            WindowBackend::Fake(backend) => backend.minimize(window_id),
+        }
+    }
+}
+
+pub struct WindowManagementContext {
+    permissions: Vec<JsPluginPermissionsWindows>,
+    registry: SharedWindowRegistry,
+    // started on first use, so plugins that don't use the api don't connect to display server
+    backend: RefCell<Option<Rc<WindowBackend>>>,
+    event_receiver: RefCell<Option<Rc<RefCell<UnboundedReceiver<JsWindowEvent>>>>>,
+}
+
+impl WindowManagementContext {
+    pub fn new(permissions: Vec<JsPluginPermissionsWindows>) -> Self {
+        Self {
+            permissions,
+            registry: WindowRegistry::new(),
+            backend: RefCell::new(None),
+            event_receiver: RefCell::new(None),
+        }
+    }
+
+    fn require_permission(&self, permission: JsPluginPermissionsWindows) -> anyhow::Result<()> {
+        if !self.permissions.contains(&permission) {
+            let name = match permission {
+                JsPluginPermissionsWindows::Read => "read",
+                JsPluginPermissionsWindows::Focus => "focus",
+                JsPluginPermissionsWindows::Close => "close",
+                JsPluginPermissionsWindows::Minimize => "minimize",
+            };
+
+            Err(anyhow!("Plugin doesn't have '{}' permission for windows", name))?
+        }
+
+        Ok(())
+    }
+
+    fn backend(&self) -> anyhow::Result<Rc<WindowBackend>> {
+        let mut backend = self.backend.borrow_mut();
+
+        match backend.as_ref() {
+            Some(backend) => Ok(backend.clone()),
+            None => {
+                let new_backend = Rc::new(WindowBackend::new(self.registry.clone())?);
+
+                *backend = Some(new_backend.clone());
+
+                Ok(new_backend)
+            }
+        }
+    }
+
+    fn event_receiver(&self) -> Rc<RefCell<UnboundedReceiver<JsWindowEvent>>> {
+        self.event_receiver
+            .borrow_mut()
+            .get_or_insert_with(|| Rc::new(RefCell::new(lock_registry(&self.registry).subscribe())))
+            .clone()
+    }
+}
+
+#[op2]
+#[serde]
+pub fn window_management_capabilities(state: &mut OpState) -> anyhow::Result<JsWindowCapabilities> {
+    let context = state.borrow::<WindowManagementContext>();
+
+    if context.permissions.is_empty() {
+        Err(anyhow!("Plugin doesn't have any permissions for windows"))?
+    }
+
+    Ok(context.backend()?.capabilities())
+}
+
+#[op2]
+#[serde]
+pub fn window_management_list(state: &mut OpState) -> anyhow::Result<Vec<JsWindow>> {
+    let context = state.borrow::<WindowManagementContext>();
+
+    context.require_permission(JsPluginPermissionsWindows::Read)?;
+
+    context.backend()?;
+
+    Ok(lock_registry(&context.registry).list())
+}
+
+#[op2(fast)]
+pub fn window_management_focus(state: &mut OpState, #[string] window_id: String) -> anyhow::Result<()> {
+    let context = state.borrow::<WindowManagementContext>();
+
+    context.require_permission(JsPluginPermissionsWindows::Focus)?;
+
+    context.backend()?.focus(&window_id)
+}
+
+#[op2(fast)]
+pub fn window_management_close(state: &mut OpState, #[string] window_id: String) -> anyhow::Result<()> {
+    let context = state.borrow::<WindowManagementContext>();
+
+    context.require_permission(JsPluginPermissionsWindows::Close)?;
+
+    context.backend()?.close(&window_id)
+}
+
+#[op2(fast)]
+pub fn window_management_minimize(state: &mut OpState, #[string] window_id: String) -> anyhow::Result<()> {
+    let context = state.borrow::<WindowManagementContext>();
+
+    context.require_permission(JsPluginPermissionsWindows::Minimize)?;
+
+    context.backend()?.minimize(&window_id)
+}
+
+#[op2(async)]
+#[serde]
+pub async fn window_management_pending_event(state: Rc<RefCell<OpState>>) -> anyhow::Result<JsWindowEvent> {
+    let receiver = {
+        let state = state.borrow();
+
+        let context = state
+            .borrow::<WindowManagementContext>();
+
+        context.require_permission(JsPluginPermissionsWindows::Read)?;
+
+        // backend is started before subscribing, so events are only received for changes after windows were listed
+        context.backend()?;
+
+        context.event_receiver()
+    };
+
+    let mut receiver = receiver.borrow_mut();
+    let event = receiver.recv()
+        .await
+        .ok_or_else(|| anyhow!("window event stream was suddenly closed"))?;
+
+    tracing::trace!("Received window event {:?}", event);
+
+    Ok(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(id: &str, title: &str) -> JsWindow {
+        JsWindow {
+            id: id.to_string(),
+            title: title.to_string(),
+            app_id: Some("app".to_string()),
+            workspace: None,
+            pid: None,
+        }
+    }
+
+    fn fake_context(permissions: Vec<JsPluginPermissionsWindows>) -> WindowManagementContext {
+        let context = WindowManagementContext::new(permissions);
+
+        let backend = fake::FakeWindowBackend::new(context.registry.clone());
+
+        *context.backend.borrow_mut() = Some(Rc::new(WindowBackend::Fake(backend)));
+
+        context
+    }
+
+    fn fake_backend(context: &WindowManagementContext) -> Rc<WindowBackend> {
+        context.backend().unwrap()
+    }
+
+    fn with_fake<T>(backend: &WindowBackend, f: impl FnOnce(&fake::FakeWindowBackend) -> T) -> T {
+        match backend {
+            WindowBackend::Fake(fake) => f(fake),
+            #[allow(unreachable_patterns)]
+            _ => panic!("expected fake backend"),
+        }
+    }
+
+    #[test]
+    fn lists_open_windows() {
+        let context = fake_context(vec![JsPluginPermissionsWindows::Read]);
+        let backend = fake_backend(&context);
+
+        with_fake(&backend, |fake| {
+            fake.open_window(window("1", "First"));
+            fake.open_window(window("2", "Second"));
+            fake.close("1").unwrap();
+        });
+
+        assert_eq!(lock_registry(&context.registry).list(), vec![window("2", "Second")]);
+    }
+
+    #[test]
+    fn sends_events_in_order() {
+        let context = fake_context(vec![JsPluginPermissionsWindows::Read]);
+        let backend = fake_backend(&context);
+
+        let receiver = context.event_receiver();
+
+        with_fake(&backend, |fake| {
+            fake.open_window(window("1", "First"));
+            fake.set_title("1", "Renamed");
+            fake.set_title("1", "Renamed");
+            fake.close("1").unwrap();
+        });
+
+        let mut receiver = receiver.borrow_mut();
+
+        assert_eq!(receiver.try_recv().unwrap(), JsWindowEvent::WindowOpened { window: window("1", "First") });
+        assert_eq!(receiver.try_recv().unwrap(), JsWindowEvent::WindowTitleChanged { window_id: "1".to_string(), title: "Renamed".to_string() });
+        assert_eq!(receiver.try_recv().unwrap(), JsWindowEvent::WindowClosed { window_id: "1".to_string() });
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn events_are_not_kept_without_listener() {
+        let context = fake_context(vec![JsPluginPermissionsWindows::Read]);
+        let backend = fake_backend(&context);
+
+        with_fake(&backend, |fake| fake.open_window(window("1", "First")));
+
+        let receiver = context.event_receiver();
+
+        assert!(receiver.borrow_mut().try_recv().is_err());
+    }
+
+    #[test]
+    fn actions() {
+        let context = fake_context(vec![]);
+        let backend = fake_backend(&context);
+
+        with_fake(&backend, |fake| fake.open_window(window("1", "First")));
+
+        backend.focus("1").unwrap();
+        backend.minimize("1").unwrap();
+
+        with_fake(&backend, |fake| {
+            assert_eq!(fake.focused().as_deref(), Some("1"));
+            assert!(fake.is_minimized("1"));
+        });
+
+        assert!(backend.focus("2").is_err());
+        assert!(backend.close("2").is_err());
+        assert!(backend.minimize("2").is_err());
+    }
+
+    #[test]
+    fn permissions() {
+        let context = fake_context(vec![JsPluginPermissionsWindows::Read, JsPluginPermissionsWindows::Focus]);
+
+        assert!(context.require_permission(JsPluginPermissionsWindows::Read).is_ok());
+        assert!(context.require_permission(JsPluginPermissionsWindows::Focus).is_ok());
+        assert!(context.require_permission(JsPluginPermissionsWindows::Close).is_err());
+        assert!(context.require_permission(JsPluginPermissionsWindows::Minimize).is_err());
+    }
+
+    #[test]
+    fn fake_capabilities() {
+        let context = fake_context(vec![]);
+
+        assert_eq!(fake_backend(&context).capabilities(), fake::CAPABILITIES);
+    }
+}
@@ -0,0 +1,125 @@
+use smithay_client_toolkit::seat::SeatState;
+use wayland_client::globals::GlobalList;
+use wayland_client::{event_created_child, Connection, Dispatch, Proxy, QueueHandle};
+use cosmic_protocols::toplevel_info::v1::client::zcosmic_toplevel_handle_v1;
+use cosmic_protocols::toplevel_info::v1::client::zcosmic_toplevel_info_v1;
+use cosmic_protocols::toplevel_management::v1::client::zcosmic_toplevel_manager_v1;
+use crate::window_management::wayland::{first_seat, ToplevelWindows, WaylandWindowState, WaylandWindowStateInner};
+
+pub struct CosmicWindowState {
+    windows: ToplevelWindows<zcosmic_toplevel_handle_v1::ZcosmicToplevelHandleV1>,
+    management: zcosmic_toplevel_manager_v1::ZcosmicToplevelManagerV1,
+}
+
+impl CosmicWindowState {
+    pub fn new(globals: &GlobalList, queue_handle: &QueueHandle<WaylandWindowState>) -> anyhow::Result<Self> {
+        let _info = globals
+            .bind::<zcosmic_toplevel_info_v1::ZcosmicToplevelInfoV1, _, _>(
+                &queue_handle,
+                1..=1,
+                (),
+            )?;
+
+        let management = globals
+            .bind::<zcosmic_toplevel_manager_v1::ZcosmicToplevelManagerV1, _, _>(
+                &queue_handle,
+                1..=3,
+                (),
+            )?;
+
+        Ok(Self {
+            windows: ToplevelWindows::new(),
+            management,
+        })
+    }
+
+    pub fn focus(&self, window_id: &str, seat_state: &SeatState) -> anyhow::Result<()> {
+        let toplevel = self.windows.toplevel(window_id)?;
+
+        self.management.activate(toplevel, &first_seat(seat_state)?);
+
+        Ok(())
+    }
+
+    pub fn close(&self, window_id: &str) -> anyhow::Result<()> {
+        self.management.close(self.windows.toplevel(window_id)?);
+
+        Ok(())
+    }
+
+    pub fn minimize(&self, window_id: &str) -> anyhow::Result<()> {
+        self.management.set_minimized(self.windows.toplevel(window_id)?);
+
+        Ok(())
+    }
+}
+
+impl Dispatch<zcosmic_toplevel_manager_v1::ZcosmicToplevelManagerV1, ()> for WaylandWindowState {
+    fn event(
+        _state: &mut Self,
+        _proxy: &zcosmic_toplevel_manager_v1::ZcosmicToplevelManagerV1,
+        _event: <zcosmic_toplevel_manager_v1::ZcosmicToplevelManagerV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>
+    ) {
+    }
+}
+
+impl Dispatch<zcosmic_toplevel_info_v1::ZcosmicToplevelInfoV1, ()> for WaylandWindowState {
+    fn event(
+        state: &mut Self,
+        _proxy: &zcosmic_toplevel_info_v1::ZcosmicToplevelInfoV1,
+        event: <zcosmic_toplevel_info_v1::ZcosmicToplevelInfoV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        match event {
+            zcosmic_toplevel_info_v1::Event::Toplevel { toplevel } => {
+                match &mut state.inner {
+                    WaylandWindowStateInner::Cosmic(inner) => inner.windows.toplevel_opened(toplevel),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    event_created_child!(WaylandWindowState, zcosmic_toplevel_info_v1::ZcosmicToplevelInfoV1, [
+        zcosmic_toplevel_info_v1::EVT_TOPLEVEL_OPCODE => (zcosmic_toplevel_handle_v1::ZcosmicToplevelHandleV1, ()),
+    ]);
+}
+
+impl Dispatch<zcosmic_toplevel_handle_v1::ZcosmicToplevelHandleV1, ()> for WaylandWindowState {
+    fn event(
+        state: &mut Self,
+        proxy: &zcosmic_toplevel_handle_v1::ZcosmicToplevelHandleV1,
+        event: <zcosmic_toplevel_handle_v1::ZcosmicToplevelHandleV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        let WaylandWindowStateInner::Cosmic(inner) = &mut state.inner else {
+            return;
+        };
+
+        match event {
+            zcosmic_toplevel_handle_v1::Event::Title { title } => {
+                inner.windows.title_changed(&state.registry, &proxy.id(), title);
+            }
+            zcosmic_toplevel_handle_v1::Event::AppId { app_id } => {
+                inner.windows.app_id_changed(&state.registry, &proxy.id(), app_id);
+            }
+            zcosmic_toplevel_handle_v1::Event::Done => {
+                inner.windows.done(&state.registry, &proxy.id());
+            }
+            zcosmic_toplevel_handle_v1::Event::Closed => {
+                if let Some(toplevel) = inner.windows.closed(&state.registry, &proxy.id()) {
+                    toplevel.destroy();
+                }
+            }
+            _ => {}
+        }
+    }
+}
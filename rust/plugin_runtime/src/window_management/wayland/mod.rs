@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+use anyhow::anyhow;
+use smithay_client_toolkit::reexports::calloop;
+use smithay_client_toolkit::reexports::calloop::channel::{Channel, Event};
+use smithay_client_toolkit::reexports::calloop::EventLoop;
+use smithay_client_toolkit::reexports::calloop_wayland_source::WaylandSource;
+use smithay_client_toolkit::seat::{Capability, SeatHandler, SeatState};
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::wl_registry;
+use wayland_client::protocol::wl_seat::WlSeat;
+use wayland_client::backend::ObjectId;
+use wayland_client::{Connection, Dispatch, Proxy, QueueHandle};
+use crate::window_management::{lock_registry, window_not_found, JsWindow, JsWindowCapabilities, SharedWindowRegistry};
+
+mod wlr;
+mod cosmic;
+
+/// Runs separate wayland connection from the one used by bundled applications plugin,
+/// because window data is tracked on rust side and only windows that received initial state are reported
+pub struct WaylandWindowBackend {
+    protocol: WaylandProtocol,
+    registry: SharedWindowRegistry,
+    command_sender: calloop::channel::Sender<WindowCommand>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum WaylandProtocol {
+    Wlr,
+    Cosmic,
+}
+
+#[derive(Debug)]
+enum WindowCommand {
+    Focus(String),
+    Close(String),
+    Minimize(String),
+}
+
+impl WaylandWindowBackend {
+    pub fn new(registry: SharedWindowRegistry) -> anyhow::Result<Self> {
+        let (command_sender, command_receiver) = calloop::channel::channel();
+        let (init_sender, init_receiver) = std::sync::mpsc::channel();
+
+        {
+            let registry = registry.clone();
+
+            std::thread::spawn(move || {
+                if let Err(e) = run_wayland_client(registry, command_receiver, init_sender) {
+                    tracing::error!("Error while running wayland window client: {:?}", e);
+                }
+            });
+        }
+
+        // wait until existing windows are received, so they are available right after backend is started
+        let protocol = init_receiver.recv()??;
+
+        Ok(Self {
+            protocol,
+            registry,
+            command_sender,
+        })
+    }
+
+    pub fn capabilities(&self) -> JsWindowCapabilities {
+        // neither of protocols exposes workspace or pid of the window
+        let backend = match self.protocol {
+            WaylandProtocol::Wlr => "wlr",
+            WaylandProtocol::Cosmic => "cosmic",
+        };
+
+        JsWindowCapabilities {
+            backend,
+            workspace: false,
+            pid: false,
+            focus: true,
+            close: true,
+            minimize: true,
+        }
+    }
+
+    pub fn focus(&self, window_id: &str) -> anyhow::Result<()> {
+        self.send_command(window_id, WindowCommand::Focus(window_id.to_string()))
+    }
+
+    pub fn close(&self, window_id: &str) -> anyhow::Result<()> {
+        self.send_command(window_id, WindowCommand::Close(window_id.to_string()))
+    }
+
+    pub fn minimize(&self, window_id: &str) -> anyhow::Result<()> {
+        self.send_command(window_id, WindowCommand::Minimize(window_id.to_string()))
+    }
+
+    fn send_command(&self, window_id: &str, command: WindowCommand) -> anyhow::Result<()> {
+        if !lock_registry(&self.registry).contains(window_id) {
+            Err(window_not_found(window_id))?
+        }
+
+        self.command_sender.send(command)?;
+
+        Ok(())
+    }
+}
+
+pub struct WaylandWindowState {
+    registry: SharedWindowRegistry,
+    seat_state: SeatState,
+    inner: WaylandWindowStateInner,
+}
+
+enum WaylandWindowStateInner {
+    Wlr(wlr::WlrWindowState),
+    Cosmic(cosmic::CosmicWindowState),
+}
+
+/// Both protocols announce toplevel handle first and then send its state followed by `done` event,
+/// window is reported to plugins only after first `done` so it always has title and app id
+struct ToplevelWindows<H> {
+    uuid_to_obj_id: HashMap<String, ObjectId>,
+    obj_id_to_uuid: HashMap<ObjectId, String>,
+    toplevels: HashMap<ObjectId, H>,
+    pending: HashMap<ObjectId, JsWindow>,
+}
+
+impl<H: Proxy> ToplevelWindows<H> {
+    fn new() -> Self {
+        Self {
+            uuid_to_obj_id: HashMap::new(),
+            obj_id_to_uuid: HashMap::new(),
+            toplevels: HashMap::new(),
+            pending: HashMap::new(),
+        }
+    }
+
+    fn toplevel_opened(&mut self, toplevel: H) {
+        let window_id = uuid::Uuid::new_v4().to_string();
+
+        self.uuid_to_obj_id.insert(window_id.clone(), toplevel.id());
+        self.obj_id_to_uuid.insert(toplevel.id(), window_id.clone());
+        self.pending.insert(toplevel.id(), JsWindow {
+            id: window_id,
+            title: "".to_string(),
+            app_id: None,
+            workspace: None,
+            pid: None,
+        });
+        self.toplevels.insert(toplevel.id(), toplevel);
+    }
+
+    fn title_changed(&mut self, registry: &SharedWindowRegistry, obj_id: &ObjectId, title: String) {
+        if let Some(window) = self.pending.get_mut(obj_id) {
+            window.title = title;
+            return;
+        }
+
+        match self.obj_id_to_uuid.get(obj_id) {
+            Some(window_id) => lock_registry(registry).title_changed(window_id, title),
+            None => tracing::warn!("Received event for wayland toplevel that doesn't exist in state"),
+        }
+    }
+
+    fn app_id_changed(&mut self, registry: &SharedWindowRegistry, obj_id: &ObjectId, app_id: String) {
+        if let Some(window) = self.pending.get_mut(obj_id) {
+            window.app_id = Some(app_id);
+            return;
+        }
+
+        match self.obj_id_to_uuid.get(obj_id) {
+            Some(window_id) => lock_registry(registry).update(window_id, |window| window.app_id = Some(app_id)),
+            None => tracing::warn!("Received event for wayland toplevel that doesn't exist in state"),
+        }
+    }
+
+    fn done(&mut self, registry: &SharedWindowRegistry, obj_id: &ObjectId) {
+        if let Some(window) = self.pending.remove(obj_id) {
+            lock_registry(registry).window_opened(window);
+        }
+    }
+
+    fn closed(&mut self, registry: &SharedWindowRegistry, obj_id: &ObjectId) -> Option<H> {
+        let was_pending = self.pending.remove(obj_id).is_some();
+
+        match self.obj_id_to_uuid.remove(obj_id) {
+            Some(window_id) => {
+                self.uuid_to_obj_id.remove(&window_id);
+
+                if !was_pending {
+                    lock_registry(registry).window_closed(&window_id);
+                }
+            }
+            None => tracing::warn!("Received event for wayland toplevel that doesn't exist in state"),
+        }
+
+        self.toplevels.remove(obj_id)
+    }
+
+    fn toplevel(&self, window_id: &str) -> anyhow::Result<&H> {
+        self.uuid_to_obj_id
+            .get(window_id)
+            .and_then(|obj_id| self.toplevels.get(obj_id))
+            .ok_or_else(|| window_not_found(window_id))
+    }
+}
+
+fn run_wayland_client(
+    registry: SharedWindowRegistry,
+    command_receiver: Channel<WindowCommand>,
+    init_sender: std::sync::mpsc::Sender<anyhow::Result<WaylandProtocol>>,
+) -> anyhow::Result<()> {
+    let (mut event_loop, mut state) = match init_wayland_client(registry, command_receiver) {
+        Ok(result) => result,
+        Err(err) => {
+            let _ = init_sender.send(Err(err));
+            return Ok(())
+        }
+    };
+
+    let protocol = match &state.inner {
+        WaylandWindowStateInner::Wlr(_) => WaylandProtocol::Wlr,
+        WaylandWindowStateInner::Cosmic(_) => WaylandProtocol::Cosmic,
+    };
+
+    let _ = init_sender.send(Ok(protocol));
+
+    loop {
+        if let Err(err) = event_loop.dispatch(None, &mut state) {
+            tracing::error!("Wayland window event queue has failed: {:?}", err);
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn init_wayland_client(
+    registry: SharedWindowRegistry,
+    command_receiver: Channel<WindowCommand>,
+) -> anyhow::Result<(EventLoop<'static, WaylandWindowState>, WaylandWindowState)> {
+    let conn = Connection::connect_to_env()?;
+    let (globals, mut event_queue) = registry_queue_init::<WaylandWindowState>(&conn)?;
+    let queue_handle = event_queue.handle();
+    let seat_state = SeatState::new(&globals, &queue_handle);
+
+    let inner = wlr::WlrWindowState::new(&globals, &queue_handle)
+        .map(WaylandWindowStateInner::Wlr)
+        .or_else(|_| cosmic::CosmicWindowState::new(&globals, &queue_handle).map(WaylandWindowStateInner::Cosmic))
+        .map_err(|_| anyhow!("Wayland compositor supports neither wlr foreign toplevel management nor cosmic toplevel info protocol"))?;
+
+    let mut state = WaylandWindowState {
+        registry,
+        seat_state,
+        inner,
+    };
+
+    // first roundtrip receives existing windows and second one their initial state
+    event_queue.roundtrip(&mut state)?;
+    event_queue.roundtrip(&mut state)?;
+
+    let event_loop = EventLoop::<WaylandWindowState>::try_new()?;
+    let loop_handle = event_loop.handle();
+
+    if let Err(err) = loop_handle.insert_source(command_receiver, command_handler) {
+        tracing::error!("Unable to insert window command source into event loop: {:?}", err);
+
+        Err(anyhow!("Unable to insert window command source into event loop"))?
+    };
+
+    if let Err(err) = WaylandSource::new(conn, event_queue).insert(loop_handle) {
+        tracing::error!("Unable to insert wayland source into event loop: {:?}", err);
+
+        Err(anyhow!("Unable to insert wayland source into event loop"))?
+    };
+
+    Ok((event_loop, state))
+}
+
+fn command_handler(event: Event<WindowCommand>, _metadata: &mut (), state: &mut WaylandWindowState) {
+    let command = match event {
+        Event::Msg(command) => command,
+        Event::Closed => return,
+    };
+
+    let result = match (&state.inner, command) {
+        (WaylandWindowStateInner::Wlr(wlr), WindowCommand::Focus(window_id)) => wlr.focus(&window_id, &state.seat_state),
+        (WaylandWindowStateInner::Wlr(wlr), WindowCommand::Close(window_id)) => wlr.close(&window_id),
+        (WaylandWindowStateInner::Wlr(wlr), WindowCommand::Minimize(window_id)) => wlr.minimize(&window_id),
+        (WaylandWindowStateInner::Cosmic(cosmic), WindowCommand::Focus(window_id)) => cosmic.focus(&window_id, &state.seat_state),
+        (WaylandWindowStateInner::Cosmic(cosmic), WindowCommand::Close(window_id)) => cosmic.close(&window_id),
+        (WaylandWindowStateInner::Cosmic(cosmic), WindowCommand::Minimize(window_id)) => cosmic.minimize(&window_id),
+    };
+
+    if let Err(err) = result {
+        tracing::error!("Unable to execute wayland window command: {:?}", err);
+    }
+}
+
+fn first_seat(seat_state: &SeatState) -> anyhow::Result<WlSeat> {
+    seat_state.seats()
+        .next()
+        .ok_or_else(|| anyhow!("no wayland seats found"))
+}
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for WaylandWindowState {
+    fn event(
+        _state: &mut WaylandWindowState,
+        _proxy: &wl_registry::WlRegistry,
+        _event: wl_registry::Event,
+        _data: &GlobalListContents,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<WaylandWindowState>,
+    ) {
+    }
+}
+
+impl SeatHandler for WaylandWindowState {
+    fn seat_state(&mut self) -> &mut SeatState {
+        &mut self.seat_state
+    }
+
+    fn new_seat(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _seat: WlSeat) {
+    }
+
+    fn new_capability(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _seat: WlSeat, _capability: Capability) {
+    }
+
+    fn remove_capability(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _seat: WlSeat, _capability: Capability) {
+    }
+
+    fn remove_seat(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _seat: WlSeat) {
+    }
+}
+
+smithay_client_toolkit::delegate_seat!(WaylandWindowState);
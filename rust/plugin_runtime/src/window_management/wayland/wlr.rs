@@ -0,0 +1,102 @@
+use smithay_client_toolkit::seat::SeatState;
+use wayland_client::globals::GlobalList;
+use wayland_client::{event_created_child, Connection, Dispatch, Proxy, QueueHandle};
+use wayland_protocols_wlr::foreign_toplevel::v1::client::{zwlr_foreign_toplevel_handle_v1, zwlr_foreign_toplevel_manager_v1};
+use crate::window_management::wayland::{first_seat, ToplevelWindows, WaylandWindowState, WaylandWindowStateInner};
+
+pub struct WlrWindowState {
+    windows: ToplevelWindows<zwlr_foreign_toplevel_handle_v1::ZwlrForeignToplevelHandleV1>,
+}
+
+impl WlrWindowState {
+    pub fn new(globals: &GlobalList, queue_handle: &QueueHandle<WaylandWindowState>) -> anyhow::Result<Self> {
+        let _management = globals
+            .bind::<zwlr_foreign_toplevel_manager_v1::ZwlrForeignToplevelManagerV1, _, _>(
+                &queue_handle,
+                3..=3,
+                (),
+            )?;
+
+        Ok(Self {
+            windows: ToplevelWindows::new(),
+        })
+    }
+
+    pub fn focus(&self, window_id: &str, seat_state: &SeatState) -> anyhow::Result<()> {
+        let toplevel = self.windows.toplevel(window_id)?;
+
+        toplevel.activate(&first_seat(seat_state)?);
+
+        Ok(())
+    }
+
+    pub fn close(&self, window_id: &str) -> anyhow::Result<()> {
+        self.windows.toplevel(window_id)?.close();
+
+        Ok(())
+    }
+
+    pub fn minimize(&self, window_id: &str) -> anyhow::Result<()> {
+        self.windows.toplevel(window_id)?.set_minimized();
+
+        Ok(())
+    }
+}
+
+impl Dispatch<zwlr_foreign_toplevel_manager_v1::ZwlrForeignToplevelManagerV1, ()> for WaylandWindowState {
+    fn event(
+        state: &mut Self,
+        _proxy: &zwlr_foreign_toplevel_manager_v1::ZwlrForeignToplevelManagerV1,
+        event: <zwlr_foreign_toplevel_manager_v1::ZwlrForeignToplevelManagerV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_foreign_toplevel_manager_v1::Event::Toplevel { toplevel } => {
+                match &mut state.inner {
+                    WaylandWindowStateInner::Wlr(inner) => inner.windows.toplevel_opened(toplevel),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    event_created_child!(WaylandWindowState, zwlr_foreign_toplevel_manager_v1::ZwlrForeignToplevelManagerV1, [
+        zwlr_foreign_toplevel_manager_v1::EVT_TOPLEVEL_OPCODE => (zwlr_foreign_toplevel_handle_v1::ZwlrForeignToplevelHandleV1, ()),
+    ]);
+}
+
+impl Dispatch<zwlr_foreign_toplevel_handle_v1::ZwlrForeignToplevelHandleV1, ()> for WaylandWindowState {
+    fn event(
+        state: &mut Self,
+        proxy: &zwlr_foreign_toplevel_handle_v1::ZwlrForeignToplevelHandleV1,
+        event: <zwlr_foreign_toplevel_handle_v1::ZwlrForeignToplevelHandleV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        let WaylandWindowStateInner::Wlr(inner) = &mut state.inner else {
+            return;
+        };
+
+        match event {
+            zwlr_foreign_toplevel_handle_v1::Event::Title { title } => {
+                inner.windows.title_changed(&state.registry, &proxy.id(), title);
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::AppId { app_id } => {
+                inner.windows.app_id_changed(&state.registry, &proxy.id(), app_id);
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::Done => {
+                inner.windows.done(&state.registry, &proxy.id());
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::Closed => {
+                if let Some(toplevel) = inner.windows.closed(&state.registry, &proxy.id()) {
+                    toplevel.destroy();
+                }
+            }
+            _ => {}
+        }
+    }
+}
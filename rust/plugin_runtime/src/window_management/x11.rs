@@ -0,0 +1,282 @@
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::sync::Arc;
+use encoding::{DecoderTrap, Encoding};
+use x11rb::connection::Connection;
+use x11rb::properties::WmClass;
+use x11rb::protocol::xproto::{Atom, AtomEnum, ChangeWindowAttributesAux, ClientMessageEvent, ConnectionExt, EventMask, Window};
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+use crate::window_management::{lock_registry, window_not_found, JsWindow, JsWindowCapabilities, SharedWindowRegistry};
+
+pub const CAPABILITIES: JsWindowCapabilities = JsWindowCapabilities {
+    backend: "x11",
+    workspace: true,
+    pid: true,
+    focus: true,
+    close: true,
+    minimize: true,
+};
+
+// value of _NET_WM_DESKTOP for windows that are shown on all desktops
+const ALL_DESKTOPS: u32 = 0xFFFFFFFF;
+
+// https://tronche.com/gui/x/icccm/sec-4.html#s-4.1.4
+const ICONIC_STATE: u32 = 3;
+
+// same as rofi, see `focus_window` in bundled applications plugin
+const SOURCE_INDICATION_OTHER: u32 = 2;
+
+/// Uses window list maintained by EWMH compliant window manager in `_NET_CLIENT_LIST`,
+/// so only windows managed by window manager are reported, unlike raw x11 events used by bundled applications plugin
+pub struct X11WindowBackend {
+    conn: Arc<RustConnection>,
+    root: Window,
+    atoms: atoms::Atoms,
+    registry: SharedWindowRegistry,
+}
+
+impl X11WindowBackend {
+    pub fn new(registry: SharedWindowRegistry) -> anyhow::Result<Self> {
+        let (conn, screen_num) = RustConnection::connect(None)?;
+        let root = conn.setup().roots[screen_num].root;
+        let atoms = atoms::Atoms::new(&conn)?.reply()?;
+
+        let aux = ChangeWindowAttributesAux::new()
+            .event_mask(EventMask::PROPERTY_CHANGE);
+
+        conn.change_window_attributes(root, &aux)?.check()?;
+
+        let conn = Arc::new(conn);
+
+        sync_client_list(&conn, root, atoms, &registry)?;
+
+        {
+            let conn = conn.clone();
+            let registry = registry.clone();
+
+            std::thread::spawn(move || {
+                if let Err(e) = listen_on_x11_events(conn, root, atoms, registry) {
+                    tracing::error!("Error while listening on x11 window events: {:?}", e);
+                }
+            });
+        }
+
+        Ok(Self {
+            conn,
+            root,
+            atoms,
+            registry,
+        })
+    }
+
+    pub fn focus(&self, window_id: &str) -> anyhow::Result<()> {
+        let window = self.known_window(window_id)?;
+
+        self.send_client_message(window, self.atoms._NET_ACTIVE_WINDOW, [SOURCE_INDICATION_OTHER, x11rb::CURRENT_TIME, 0, 0, 0])
+    }
+
+    pub fn close(&self, window_id: &str) -> anyhow::Result<()> {
+        let window = self.known_window(window_id)?;
+
+        self.send_client_message(window, self.atoms._NET_CLOSE_WINDOW, [x11rb::CURRENT_TIME, SOURCE_INDICATION_OTHER, 0, 0, 0])
+    }
+
+    pub fn minimize(&self, window_id: &str) -> anyhow::Result<()> {
+        let window = self.known_window(window_id)?;
+
+        self.send_client_message(window, self.atoms.WM_CHANGE_STATE, [ICONIC_STATE, 0, 0, 0, 0])
+    }
+
+    fn known_window(&self, window_id: &str) -> anyhow::Result<Window> {
+        if !lock_registry(&self.registry).contains(window_id) {
+            Err(window_not_found(window_id))?
+        }
+
+        Ok(window_id.parse()?)
+    }
+
+    fn send_client_message(&self, window: Window, message_type: Atom, data: [u32; 5]) -> anyhow::Result<()> {
+        let event = ClientMessageEvent::new(32, window, message_type, data);
+
+        self.conn.send_event(
+            false,
+            self.root,
+            EventMask::SUBSTRUCTURE_NOTIFY | EventMask::SUBSTRUCTURE_REDIRECT,
+            event
+        )?;
+
+        self.conn.flush()?;
+
+        Ok(())
+    }
+}
+
+fn listen_on_x11_events(
+    conn: Arc<RustConnection>,
+    root: Window,
+    atoms: atoms::Atoms,
+    registry: SharedWindowRegistry
+) -> anyhow::Result<Infallible> {
+    loop {
+        match conn.wait_for_event()? {
+            Event::PropertyNotify(event) => {
+                match event.atom {
+                    atom if atom == atoms._NET_CLIENT_LIST && event.window == root => {
+                        sync_client_list(&conn, root, atoms, &registry)?;
+                    }
+                    atom if atom == atoms._NET_WM_NAME || atom == Atom::from(AtomEnum::WM_NAME) => {
+                        if let Ok(title) = read_title(&conn, event.window, atoms) {
+                            lock_registry(&registry).title_changed(&event.window.to_string(), title);
+                        }
+                    }
+                    atom if atom == atoms._NET_WM_DESKTOP => {
+                        if let Ok(workspace) = read_workspace(&conn, event.window, atoms) {
+                            lock_registry(&registry).update(&event.window.to_string(), |window| window.workspace = workspace);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn sync_client_list(conn: &RustConnection, root: Window, atoms: atoms::Atoms, registry: &SharedWindowRegistry) -> anyhow::Result<()> {
+    let reply = conn.get_property(false, root, atoms._NET_CLIENT_LIST, AtomEnum::WINDOW, 0, u32::MAX)?
+        .reply()?;
+
+    let client_list = reply.value32()
+        .map(|iter| iter.collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    let known_ids = lock_registry(registry)
+        .window_ids()
+        .into_iter()
+        .collect::<HashSet<_>>();
+
+    let current_ids = client_list.iter()
+        .map(|window| window.to_string())
+        .collect::<HashSet<_>>();
+
+    for window_id in known_ids.difference(&current_ids) {
+        lock_registry(registry).window_closed(window_id);
+    }
+
+    for window in client_list {
+        if known_ids.contains(&window.to_string()) {
+            continue;
+        }
+
+        // window can be destroyed at any moment, so errors are expected
+        match read_window(conn, window, atoms) {
+            Ok(data) => lock_registry(registry).window_opened(data),
+            Err(err) => tracing::debug!("Unable to read x11 window {}: {:?}", window, err),
+        }
+    }
+
+    Ok(())
+}
+
+fn read_window(conn: &RustConnection, window: Window, atoms: atoms::Atoms) -> anyhow::Result<JsWindow> {
+    let aux = ChangeWindowAttributesAux::new()
+        .event_mask(EventMask::PROPERTY_CHANGE);
+
+    conn.change_window_attributes(window, &aux)?.check()?;
+
+    Ok(JsWindow {
+        id: window.to_string(),
+        title: read_title(conn, window, atoms)?,
+        app_id: read_app_id(conn, window, atoms)?,
+        workspace: read_workspace(conn, window, atoms)?,
+        pid: read_cardinal(conn, window, atoms._NET_WM_PID)?,
+    })
+}
+
+fn read_title(conn: &RustConnection, window: Window, atoms: atoms::Atoms) -> anyhow::Result<String> {
+    let net_wm_name = read_string(conn, window, atoms, atoms._NET_WM_NAME)?;
+    let wm_name = read_string(conn, window, atoms, AtomEnum::WM_NAME)?;
+
+    Ok(net_wm_name.or(wm_name).unwrap_or_default())
+}
+
+// same sources as used by bundled applications plugin to match windows to desktop files
+fn read_app_id(conn: &RustConnection, window: Window, atoms: atoms::Atoms) -> anyhow::Result<Option<String>> {
+    let kde_net_wm_desktop_file = read_string(conn, window, atoms, atoms._KDE_NET_WM_DESKTOP_FILE)?;
+    let gtk_application_id = read_string(conn, window, atoms, atoms._GTK_APPLICATION_ID)?;
+
+    if let Some(app_id) = kde_net_wm_desktop_file.or(gtk_application_id) {
+        return Ok(Some(app_id))
+    }
+
+    let class = WmClass::get(conn, window)?
+        .reply()?
+        .and_then(|wm_class| {
+            encoding::all::ISO_8859_1
+                .decode(wm_class.class(), DecoderTrap::Replace)
+                .ok()
+        })
+        .filter(|class| !class.is_empty());
+
+    Ok(class)
+}
+
+fn read_workspace(conn: &RustConnection, window: Window, atoms: atoms::Atoms) -> anyhow::Result<Option<String>> {
+    let desktop = read_cardinal(conn, window, atoms._NET_WM_DESKTOP)?
+        .filter(|desktop| *desktop != ALL_DESKTOPS)
+        .map(|desktop| desktop.to_string());
+
+    Ok(desktop)
+}
+
+fn read_cardinal(conn: &RustConnection, window: Window, atom: Atom) -> anyhow::Result<Option<u32>> {
+    let reply = conn.get_property(false, window, atom, AtomEnum::CARDINAL, 0, 1)?
+        .reply()?;
+
+    Ok(reply.value32().and_then(|mut iter| iter.next()))
+}
+
+fn read_string(conn: &RustConnection, window: Window, atoms: atoms::Atoms, atom: impl Into<Atom>) -> anyhow::Result<Option<String>> {
+    let reply = conn
+        .get_property(false, window, atom, AtomEnum::ANY, 0, 2048)?
+        .reply()?;
+
+    let Some(bytes) = reply.value8() else {
+        return Ok(None)
+    };
+
+    let bytes = bytes.collect::<Vec<u8>>();
+
+    match reply.type_ {
+        x if x == Atom::from(AtomEnum::STRING) => {
+            let decoded = encoding::all::ISO_8859_1
+                .decode(&bytes, DecoderTrap::Replace)
+                .ok();
+
+            Ok(decoded)
+        },
+        x if x == atoms.UTF8_STRING => {
+            Ok(String::from_utf8(bytes).ok())
+        },
+        _ => Ok(None),
+    }
+}
+
+mod atoms {
+    x11rb::atom_manager! {
+        pub Atoms:
+        AtomsCookie {
+            UTF8_STRING,
+            WM_CHANGE_STATE,
+            _NET_CLIENT_LIST,
+            _NET_ACTIVE_WINDOW,
+            _NET_CLOSE_WINDOW,
+            _NET_WM_NAME,
+            _NET_WM_PID,
+            _NET_WM_DESKTOP,
+            _KDE_NET_WM_DESKTOP_FILE,
+            _GTK_APPLICATION_ID,
+        }
+    }
+}
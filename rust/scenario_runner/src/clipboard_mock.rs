@@ -0,0 +1,23 @@
+use std::sync::Mutex;
+
+/// In-memory clipboard used by backend instead of system one when running with `scenario_runner` feature,
+/// so plugins can be run without a display
+#[derive(Debug, Clone, Default)]
+pub struct MockClipboardData {
+    pub text_data: Option<String>,
+    pub png_data: Option<Vec<u8>>,
+}
+
+static CLIPBOARD: Mutex<MockClipboardData> = Mutex::new(MockClipboardData { text_data: None, png_data: None });
+
+pub fn read() -> MockClipboardData {
+    CLIPBOARD.lock().expect("lock is poisoned").clone()
+}
+
+pub fn write(data: MockClipboardData) {
+    *CLIPBOARD.lock().expect("lock is poisoned") = data;
+}
+
+pub fn clear() {
+    *CLIPBOARD.lock().expect("lock is poisoned") = MockClipboardData::default();
+}
@@ -1,7 +1,7 @@
 use std::fs;
 use std::path::Path;
 
-use gauntlet_common::model::{BackendRequestData, BackendResponseData, EntrypointId, PluginId, UiRequestData, UiResponseData};
+use gauntlet_common::model::{BackendRequestData, BackendResponseData, EntrypointId, PermissionPromptResult, PluginId, UiRequestData, UiResponseData};
 use gauntlet_common::rpc::backend_api::{BackendApi, BackendForFrontendApi};
 use gauntlet_common::rpc::backend_server::wait_for_backend_server;
 use gauntlet_common::scenario_convert::{ui_render_location_to_scenario};
@@ -154,9 +154,15 @@ async fn request_loop(mut request_receiver: RequestReceiver<UiRequestData, UiRes
             UiRequestData::UpdateLoadingBar { .. } | UiRequestData::ShowHud { .. } | UiRequestData::ShowWindow | UiRequestData::ClearInlineView { .. } => {
                 unreachable!()
             }
-            UiRequestData::SetGlobalShortcut { .. } | UiRequestData::RequestSearchResultUpdate => {
+            UiRequestData::SetGlobalShortcut { .. } | UiRequestData::SetTheme { .. } | UiRequestData::RequestSearchResultUpdate => {
                 // noop
             }
+            UiRequestData::RequestPermission { .. } => {
+                // scenarios are expected to be run with permissions from manifest only
+                responder.respond(UiResponseData::PermissionPrompt { result: PermissionPromptResult::Deny });
+
+                continue;
+            }
             UiRequestData::ReplaceView {
                 plugin_id: _,
                 plugin_name: _,
@@ -179,7 +185,7 @@ async fn request_loop(mut request_receiver: RequestReceiver<UiRequestData, UiRes
                     .await
                     .expect("send failed")
             }
-            UiRequestData::ShowPluginErrorView { plugin_id: _, entrypoint_id, render_location } => {
+            UiRequestData::ShowPluginErrorView { plugin_id: _, entrypoint_id, render_location, reason: _ } => {
                 let event = ScenarioFrontendEvent::ShowPluginErrorView {
                     entrypoint_id: entrypoint_id.to_string(),
                     render_location: ui_render_location_to_scenario(render_location)
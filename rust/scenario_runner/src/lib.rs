@@ -1,8 +1,10 @@
 use gauntlet_common::model::{BackendRequestData, BackendResponseData, UiRequestData, UiResponseData};
 use gauntlet_utils::channel::{RequestReceiver, RequestSender};
 
+pub mod clipboard_mock;
 pub mod frontend_mock;
 mod model;
+mod plugin_test;
 
 pub async fn run_scenario_runner_frontend_mock(
     request_receiver: RequestReceiver<UiRequestData, UiResponseData>,
@@ -12,3 +14,12 @@ pub async fn run_scenario_runner_frontend_mock(
 
     Ok(())
 }
+
+pub async fn run_plugin_test_frontend_mock(
+    request_receiver: RequestReceiver<UiRequestData, UiResponseData>,
+    backend_sender: RequestSender<BackendRequestData, BackendResponseData>
+) -> anyhow::Result<()> {
+    plugin_test::start_plugin_test_frontend(request_receiver, backend_sender).await?;
+
+    Ok(())
+}
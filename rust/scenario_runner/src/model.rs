@@ -8,3 +8,69 @@ pub enum ScenarioBackendEvent {
     },
     RequestViewRender,
 }
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PluginTest {
+    pub steps: Vec<PluginTestStep>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum PluginTestStep {
+    Search {
+        text: String
+    },
+    OpenView {
+        entrypoint_id: String
+    },
+    CloseView,
+    RunCommand {
+        entrypoint_id: String
+    },
+    RunGeneratedCommand {
+        entrypoint_id: String,
+        #[serde(default)]
+        action_index: usize,
+    },
+    TypeInSearchBar {
+        text: String
+    },
+    WidgetEvent {
+        // subset of widget properties, first widget in view that matches it is used
+        widget: serde_json::Value,
+        event_name: String,
+        #[serde(default)]
+        event_arguments: Vec<serde_json::Value>,
+    },
+    KeyboardShortcut {
+        key: String,
+        #[serde(default)]
+        shift: bool,
+        #[serde(default)]
+        control: bool,
+        #[serde(default)]
+        alt: bool,
+        #[serde(default)]
+        meta: bool,
+    },
+    SetClipboard {
+        text: String
+    },
+    AssertSearchResult {
+        entrypoint_id: String
+    },
+    AssertView {
+        // view is expected to contain a widget that matches this subset of widget properties
+        contains: serde_json::Value
+    },
+    AssertInlineView {
+        contains: serde_json::Value
+    },
+    AssertErrorView,
+    AssertHud {
+        text: String
+    },
+    AssertClipboard {
+        text: String
+    },
+}
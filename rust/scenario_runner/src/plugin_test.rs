@@ -0,0 +1,523 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context};
+use gauntlet_common::model::{BackendRequestData, BackendResponseData, EntrypointId, KeyboardEventOrigin, PermissionPromptResult, PhysicalKey, PluginId, SearchResult, UiPropertyValue, UiRenderLocation, UiRequestData, UiResponseData};
+use gauntlet_common::rpc::backend_api::{BackendApi, BackendForFrontendApi};
+use gauntlet_common::rpc::backend_server::wait_for_backend_server;
+use gauntlet_utils::channel::{RequestReceiver, RequestSender};
+use serde_json::Value;
+
+use crate::clipboard_mock;
+use crate::clipboard_mock::MockClipboardData;
+use crate::model::{PluginTest, PluginTestStep};
+
+const ASSERT_TIMEOUT: Duration = Duration::from_secs(5);
+const ASSERT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+pub async fn start_plugin_test_frontend(
+    request_receiver: RequestReceiver<UiRequestData, UiResponseData>,
+    backend_sender: RequestSender<BackendRequestData, BackendResponseData>
+) -> anyhow::Result<()> {
+    let plugin_dir = std::env::var("GAUNTLET_PLUGIN_TEST_PLUGIN_DIR")
+        .expect("Unable to read GAUNTLET_PLUGIN_TEST_PLUGIN_DIR");
+
+    let test_dir = std::env::var("GAUNTLET_PLUGIN_TEST_DIR")
+        .expect("Unable to read GAUNTLET_PLUGIN_TEST_DIR");
+
+    // plugin id of local plugin is its absolute path, so relative paths are resolved against current directory
+    let plugin_dir = fs::canonicalize(&plugin_dir)
+        .with_context(|| format!("Unable to find plugin directory: {}", plugin_dir))?
+        .to_str()
+        .context("non-uft8 paths are not supported")?
+        .to_string();
+
+    let test_dir = fs::canonicalize(&test_dir)
+        .with_context(|| format!("Unable to find plugin test directory: {}", test_dir))?;
+
+    let state = Arc::new(Mutex::new(PluginTestState::default()));
+
+    let state_clone = state.clone();
+    tokio::spawn(async move {
+        request_loop(request_receiver, state_clone).await
+    });
+
+    println!("waiting for backend");
+
+    wait_for_backend_server().await;
+
+    println!("backend started");
+
+    let mut backend_client = BackendApi::new().await?;
+
+    backend_client.save_local_plugin(plugin_dir.clone()).await?;
+
+    println!("plugin loaded");
+
+    let plugin_id = PluginId::from_string(format!("file://{plugin_dir}"));
+
+    let mut harness = PluginTestHarness::new(BackendForFrontendApi::new(backend_sender), plugin_id, state);
+
+    let mut test_files = fs::read_dir(&test_dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<PathBuf>, _>>()?;
+
+    test_files.retain(|path| path.extension().is_some_and(|extension| extension == "json"));
+    test_files.sort();
+
+    let mut failed = 0;
+
+    for test_file in &test_files {
+        match run_test_file(&mut harness, test_file).await {
+            Ok(()) => println!("PASSED {:?}", test_file),
+            Err(err) => {
+                failed += 1;
+                println!("FAILED {:?}: {:#}", test_file, err);
+            }
+        }
+
+        harness.reset().await?;
+    }
+
+    println!("{} passed, {} failed", test_files.len() - failed, failed);
+
+    std::process::exit(if failed == 0 { 0 } else { 1 })
+}
+
+async fn run_test_file(harness: &mut PluginTestHarness, test_file: &Path) -> anyhow::Result<()> {
+    let test_data = fs::read(test_file)
+        .context("Unable to read plugin test file")?;
+
+    let test: PluginTest = serde_json::from_slice(&test_data)
+        .context("Unable to deserialize plugin test")?;
+
+    for (index, step) in test.steps.into_iter().enumerate() {
+        harness.run_step(step)
+            .await
+            .with_context(|| format!("Step {} failed", index + 1))?;
+    }
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct PluginTestState {
+    view: Option<Value>,
+    inline_view: Option<Value>,
+    error_view: bool,
+    hud: Vec<String>,
+}
+
+/// Drives loaded plugin the same way the frontend would and inspects what plugin has rendered
+struct PluginTestHarness {
+    backend: BackendForFrontendApi,
+    plugin_id: PluginId,
+    state: Arc<Mutex<PluginTestState>>,
+    search_results: Vec<SearchResult>,
+    view_entrypoint_id: Option<EntrypointId>,
+}
+
+impl PluginTestHarness {
+    fn new(backend: BackendForFrontendApi, plugin_id: PluginId, state: Arc<Mutex<PluginTestState>>) -> Self {
+        Self {
+            backend,
+            plugin_id,
+            state,
+            search_results: vec![],
+            view_entrypoint_id: None,
+        }
+    }
+
+    async fn run_step(&mut self, step: PluginTestStep) -> anyhow::Result<()> {
+        match step {
+            PluginTestStep::Search { text } => self.search(text).await,
+            PluginTestStep::OpenView { entrypoint_id } => self.open_view(EntrypointId::from_string(entrypoint_id)).await,
+            PluginTestStep::CloseView => self.close_view().await,
+            PluginTestStep::RunCommand { entrypoint_id } => self.run_command(EntrypointId::from_string(entrypoint_id)).await,
+            PluginTestStep::RunGeneratedCommand { entrypoint_id, action_index } => {
+                self.run_generated_command(EntrypointId::from_string(entrypoint_id), action_index).await
+            }
+            PluginTestStep::TypeInSearchBar { text } => self.type_in_search_bar(text).await,
+            PluginTestStep::WidgetEvent { widget, event_name, event_arguments } => {
+                let event_arguments = event_arguments.into_iter()
+                    .map(|value| json_to_property_value(value))
+                    .collect();
+
+                self.send_widget_event(widget, event_name, event_arguments).await
+            }
+            PluginTestStep::KeyboardShortcut { key, shift, control, alt, meta } => {
+                self.send_keyboard_shortcut(&key, shift, control, alt, meta).await
+            }
+            PluginTestStep::SetClipboard { text } => {
+                clipboard_mock::write(MockClipboardData {
+                    text_data: Some(text),
+                    png_data: None,
+                });
+
+                Ok(())
+            }
+            PluginTestStep::AssertSearchResult { entrypoint_id } => self.assert_search_result(&entrypoint_id),
+            PluginTestStep::AssertView { contains } => {
+                self.wait_until(|state| assert_contains("View", state.view.as_ref(), &contains)).await
+            }
+            PluginTestStep::AssertInlineView { contains } => {
+                self.wait_until(|state| assert_contains("Inline view", state.inline_view.as_ref(), &contains)).await
+            }
+            PluginTestStep::AssertErrorView => {
+                self.wait_until(|state| {
+                    if state.error_view {
+                        Ok(())
+                    } else {
+                        Err(anyhow!("Plugin error view is not shown"))
+                    }
+                }).await
+            }
+            PluginTestStep::AssertHud { text } => {
+                self.wait_until(|state| {
+                    if state.hud.contains(&text) {
+                        Ok(())
+                    } else {
+                        Err(anyhow!("Hud {:?} was not shown, shown: {:?}", text, state.hud))
+                    }
+                }).await
+            }
+            PluginTestStep::AssertClipboard { text } => {
+                self.wait_until(|_| {
+                    let clipboard = clipboard_mock::read();
+
+                    if clipboard.text_data.as_ref() == Some(&text) {
+                        Ok(())
+                    } else {
+                        Err(anyhow!("Clipboard doesn't contain {:?}, contains: {:?}", text, clipboard.text_data))
+                    }
+                }).await
+            }
+        }
+    }
+
+    async fn search(&mut self, text: String) -> anyhow::Result<()> {
+        self.search_results = self.backend.search(text, true).await?;
+
+        Ok(())
+    }
+
+    async fn open_view(&mut self, entrypoint_id: EntrypointId) -> anyhow::Result<()> {
+        {
+            let mut state = self.state.lock().expect("lock is poisoned");
+            state.view = None;
+            state.error_view = false;
+        }
+
+        self.backend.request_view_render(self.plugin_id.clone(), entrypoint_id.clone()).await?;
+
+        self.view_entrypoint_id = Some(entrypoint_id);
+
+        Ok(())
+    }
+
+    async fn close_view(&mut self) -> anyhow::Result<()> {
+        self.backend.request_view_close(self.plugin_id.clone()).await?;
+
+        self.view_entrypoint_id = None;
+        self.state.lock().expect("lock is poisoned").view = None;
+
+        Ok(())
+    }
+
+    async fn run_command(&mut self, entrypoint_id: EntrypointId) -> anyhow::Result<()> {
+        self.backend.request_run_command(self.plugin_id.clone(), entrypoint_id).await?;
+
+        Ok(())
+    }
+
+    async fn run_generated_command(&mut self, entrypoint_id: EntrypointId, action_index: usize) -> anyhow::Result<()> {
+        self.backend.request_run_generated_command(self.plugin_id.clone(), entrypoint_id, action_index).await?;
+
+        Ok(())
+    }
+
+    async fn type_in_search_bar(&mut self, text: String) -> anyhow::Result<()> {
+        let search_bar = serde_json::json!({ "__type__": "gauntlet:search_bar" });
+
+        self.send_widget_event(search_bar, "onChange".to_string(), vec![UiPropertyValue::String(text)]).await
+    }
+
+    async fn send_widget_event(&mut self, widget: Value, event_name: String, event_arguments: Vec<UiPropertyValue>) -> anyhow::Result<()> {
+        let mut widget_id = None;
+
+        self.wait_until(|state| {
+            let view = state.view.as_ref()
+                .ok_or_else(|| anyhow!("View is not shown"))?;
+
+            let id = find_widget(&widget, view)
+                .and_then(|widget| widget.get("__id__"))
+                .and_then(|id| id.as_u64())
+                .ok_or_else(|| anyhow!("View doesn't contain widget matching {}", widget))?;
+
+            widget_id = Some(id as usize);
+
+            Ok(())
+        }).await?;
+
+        let widget_id = widget_id.expect("should be set when widget is found");
+
+        self.backend.send_view_event(self.plugin_id.clone(), widget_id, event_name, event_arguments).await?;
+
+        Ok(())
+    }
+
+    async fn send_keyboard_shortcut(&mut self, key: &str, shift: bool, control: bool, alt: bool, meta: bool) -> anyhow::Result<()> {
+        let key = PhysicalKey::try_from_value(key)
+            .ok_or_else(|| anyhow!("Unknown key: {}", key))?;
+
+        let entrypoint_id = self.view_entrypoint_id.clone()
+            .ok_or_else(|| anyhow!("Keyboard shortcut can only be sent when view is open"))?;
+
+        self.backend.send_keyboard_event(
+            self.plugin_id.clone(),
+            entrypoint_id,
+            KeyboardEventOrigin::PluginView,
+            key,
+            shift,
+            control,
+            alt,
+            meta,
+        ).await?;
+
+        Ok(())
+    }
+
+    fn assert_search_result(&self, entrypoint_id: &str) -> anyhow::Result<()> {
+        let found = self.search_results.iter()
+            .any(|result| result.plugin_id == self.plugin_id && result.entrypoint_id.to_string() == entrypoint_id);
+
+        if !found {
+            let found_ids = self.search_results.iter()
+                .map(|result| result.entrypoint_id.to_string())
+                .collect::<Vec<_>>();
+
+            Err(anyhow!("Search results don't contain entrypoint {:?}, found: {:?}", entrypoint_id, found_ids))?
+        }
+
+        Ok(())
+    }
+
+    /// Rendering is asynchronous, so state is polled until assertion passes or timeout is reached
+    async fn wait_until(&self, mut assertion: impl FnMut(&PluginTestState) -> anyhow::Result<()>) -> anyhow::Result<()> {
+        let start = tokio::time::Instant::now();
+
+        loop {
+            let result = assertion(&self.state.lock().expect("lock is poisoned"));
+
+            match result {
+                Ok(()) => return Ok(()),
+                Err(err) => {
+                    if start.elapsed() > ASSERT_TIMEOUT {
+                        return Err(err)
+                    }
+                }
+            }
+
+            tokio::time::sleep(ASSERT_POLL_INTERVAL).await;
+        }
+    }
+
+    async fn reset(&mut self) -> anyhow::Result<()> {
+        if self.view_entrypoint_id.is_some() {
+            self.close_view().await?;
+        }
+
+        self.search_results = vec![];
+
+        *self.state.lock().expect("lock is poisoned") = PluginTestState::default();
+
+        clipboard_mock::clear();
+
+        Ok(())
+    }
+}
+
+fn assert_contains(name: &str, value: Option<&Value>, subset: &Value) -> anyhow::Result<()> {
+    let value = value.ok_or_else(|| anyhow!("{} is not shown", name))?;
+
+    match find_widget(subset, value) {
+        Some(_) => Ok(()),
+        None => Err(anyhow!("{} doesn't contain widget matching {}, rendered: {}", name, subset, value)),
+    }
+}
+
+fn find_widget<'a>(subset: &Value, value: &'a Value) -> Option<&'a Value> {
+    if value.is_object() && is_subset(subset, value) {
+        return Some(value)
+    }
+
+    match value {
+        Value::Object(map) => map.values().find_map(|value| find_widget(subset, value)),
+        Value::Array(items) => items.iter().find_map(|value| find_widget(subset, value)),
+        _ => None,
+    }
+}
+
+fn is_subset(subset: &Value, value: &Value) -> bool {
+    match (subset, value) {
+        (Value::Object(subset), Value::Object(value)) => {
+            subset.iter()
+                .all(|(key, subset)| value.get(key).is_some_and(|value| is_subset(subset, value)))
+        }
+        (Value::Array(subset), Value::Array(value)) => {
+            subset.iter()
+                .all(|subset| value.iter().any(|value| is_subset(subset, value)))
+        }
+        (subset, value) => subset == value,
+    }
+}
+
+fn json_to_property_value(value: Value) -> UiPropertyValue {
+    match value {
+        Value::Null => UiPropertyValue::Undefined,
+        Value::Bool(value) => UiPropertyValue::Bool(value),
+        Value::Number(value) => UiPropertyValue::Number(value.as_f64().expect("should always be representable as f64")),
+        Value::String(value) => UiPropertyValue::String(value),
+        Value::Array(items) => UiPropertyValue::Array(items.into_iter().map(|value| json_to_property_value(value)).collect()),
+        Value::Object(map) => {
+            let map = map.into_iter()
+                .map(|(key, value)| (key, json_to_property_value(value)))
+                .collect::<HashMap<_, _>>();
+
+            UiPropertyValue::Object(map)
+        }
+    }
+}
+
+async fn request_loop(mut request_receiver: RequestReceiver<UiRequestData, UiResponseData>, state: Arc<Mutex<PluginTestState>>) {
+    loop {
+        let (request_data, responder) = request_receiver.recv().await;
+
+        match request_data {
+            UiRequestData::ShowWindow
+            | UiRequestData::UpdateLoadingBar { .. }
+            | UiRequestData::SetGlobalShortcut { .. }
+            | UiRequestData::SetTheme { .. }
+            | UiRequestData::RequestSearchResultUpdate
+            | UiRequestData::ShowPreferenceRequiredView { .. } => {
+                // noop
+            }
+            UiRequestData::RequestPermission { .. } => {
+                // tests are expected to be run with permissions from manifest only
+                responder.respond(UiResponseData::PermissionPrompt { result: PermissionPromptResult::Deny });
+
+                continue;
+            }
+            UiRequestData::ClearInlineView { .. } => {
+                state.lock().expect("lock is poisoned").inline_view = None;
+            }
+            UiRequestData::ShowHud { display } => {
+                state.lock().expect("lock is poisoned").hud.push(display);
+            }
+            UiRequestData::ReplaceView { render_location, container, .. } => {
+                let container = serde_json::to_value(&container)
+                    .expect("unable to serialize view");
+
+                let mut state = state.lock().expect("lock is poisoned");
+
+                match render_location {
+                    UiRenderLocation::InlineView => state.inline_view = Some(container),
+                    UiRenderLocation::View => state.view = Some(container),
+                }
+            }
+            UiRequestData::ShowPluginErrorView { render_location, .. } => {
+                let mut state = state.lock().expect("lock is poisoned");
+
+                match render_location {
+                    UiRenderLocation::InlineView => state.inline_view = None,
+                    UiRenderLocation::View => state.error_view = true,
+                }
+            }
+        }
+
+        responder.respond(UiResponseData::Nothing);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn view() -> Value {
+        json!({
+            "__id__": 0,
+            "__type__": "gauntlet:root",
+            "content": [
+                {
+                    "__id__": 1,
+                    "__type__": "gauntlet:list",
+                    "content": [
+                        { "__id__": 2, "__type__": "gauntlet:list_item", "title": "First", "accessories": [{ "text": "a" }, { "text": "b" }] },
+                        { "__id__": 3, "__type__": "gauntlet:list_item", "title": "Second", "subtitle": null }
+                    ]
+                },
+                { "__id__": 4, "__type__": "gauntlet:action", "title": "Copy" }
+            ]
+        })
+    }
+
+    #[test]
+    fn subset_of_object() {
+        assert!(is_subset(&json!({}), &json!({ "title": "First" })));
+        assert!(is_subset(&json!({ "title": "First" }), &json!({ "title": "First", "subtitle": "Sub" })));
+        assert!(!is_subset(&json!({ "title": "First" }), &json!({ "title": "Second" })));
+        assert!(!is_subset(&json!({ "title": "First", "subtitle": "Sub" }), &json!({ "title": "First" })));
+        assert!(!is_subset(&json!({ "subtitle": null }), &json!({ "title": "First" })));
+    }
+
+    #[test]
+    fn subset_of_array_ignores_order() {
+        assert!(is_subset(&json!([2, 1]), &json!([1, 2, 3])));
+        assert!(is_subset(&json!([{ "text": "b" }]), &json!([{ "text": "a" }, { "text": "b" }])));
+        assert!(!is_subset(&json!([4]), &json!([1, 2, 3])));
+    }
+
+    #[test]
+    fn subset_of_scalar_requires_equality() {
+        assert!(is_subset(&json!(1), &json!(1)));
+        assert!(!is_subset(&json!(1), &json!("1")));
+        assert!(!is_subset(&json!({ "title": "First" }), &json!("First")));
+    }
+
+    #[test]
+    fn find_nested_widget() {
+        let view = view();
+
+        let widget = find_widget(&json!({ "__type__": "gauntlet:list_item", "title": "Second" }), &view);
+        assert_eq!(widget.and_then(|widget| widget.get("__id__")), Some(&json!(3)));
+
+        let widget = find_widget(&json!({ "accessories": [{ "text": "b" }] }), &view);
+        assert_eq!(widget.and_then(|widget| widget.get("__id__")), Some(&json!(2)));
+
+        let widget = find_widget(&json!({ "__type__": "gauntlet:action" }), &view);
+        assert_eq!(widget.and_then(|widget| widget.get("__id__")), Some(&json!(4)));
+    }
+
+    #[test]
+    fn find_first_matching_widget() {
+        let view = view();
+
+        let widget = find_widget(&json!({ "__type__": "gauntlet:list_item" }), &view);
+        assert_eq!(widget.and_then(|widget| widget.get("__id__")), Some(&json!(2)));
+    }
+
+    #[test]
+    fn find_missing_widget() {
+        let view = view();
+
+        assert!(find_widget(&json!({ "__type__": "gauntlet:list_item", "title": "Third" }), &view).is_none());
+        assert!(find_widget(&json!({ "__type__": "gauntlet:grid" }), &view).is_none());
+        assert!(assert_contains("View", Some(&view), &json!({ "title": "Third" })).is_err());
+        assert!(assert_contains("View", None, &json!({ "title": "First" })).is_err());
+        assert!(assert_contains("View", Some(&view), &json!({ "title": "First" })).is_ok());
+    }
+}
@@ -0,0 +1,2 @@
+ALTER TABLE plugin_entrypoint ADD COLUMN keywords JSON NOT NULL DEFAULT ('[]');
+ALTER TABLE plugin_entrypoint ADD COLUMN aliases JSON NOT NULL DEFAULT ('[]');
@@ -0,0 +1,14 @@
+CREATE TABLE plugin_entrypoint_query_frecency_stats
+(
+    query_prefix   TEXT    NOT NULL,
+    entrypoint_id  TEXT    NOT NULL,
+    plugin_id      TEXT    NOT NULL,
+
+    reference_time REAL    NOT NULL,
+    half_life      REAL    NOT NULL,
+    last_accessed  REAL    NOT NULL,
+    frecency       REAL    NOT NULL,
+    num_accesses   INTEGER NOT NULL,
+
+    PRIMARY KEY (query_prefix, entrypoint_id, plugin_id)
+);
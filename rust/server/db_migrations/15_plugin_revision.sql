@@ -0,0 +1,2 @@
+ALTER TABLE plugin ADD COLUMN revision TEXT;
+ALTER TABLE plugin ADD COLUMN pinned_revision TEXT;
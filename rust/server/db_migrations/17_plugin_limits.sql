@@ -0,0 +1 @@
+ALTER TABLE plugin ADD COLUMN limits JSON NOT NULL DEFAULT ('{}');
@@ -0,0 +1,7 @@
+CREATE TABLE plugin_permission_grant
+(
+    plugin_id  TEXT NOT NULL REFERENCES plugin (id) ON DELETE CASCADE,
+    permission TEXT NOT NULL,
+    value      TEXT NOT NULL,
+    PRIMARY KEY (plugin_id, permission, value)
+);
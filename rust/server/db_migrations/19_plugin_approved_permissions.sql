@@ -0,0 +1,3 @@
+ALTER TABLE plugin ADD COLUMN approved_permissions JSON;
+-- plugins installed before permission review existed are considered approved
+UPDATE plugin SET approved_permissions = permissions;
@@ -0,0 +1,10 @@
+CREATE TABLE plugin_audit_log
+(
+    plugin_id     TEXT    NOT NULL,
+    entrypoint_id TEXT,
+    capability    TEXT    NOT NULL,
+    value         TEXT    NOT NULL,
+    timestamp     INTEGER NOT NULL
+);
+
+CREATE INDEX plugin_audit_log_timestamp ON plugin_audit_log (timestamp);
@@ -0,0 +1,2 @@
+-- permissions narrowed down for specific entrypoint, NULL if entrypoint uses permissions of the whole plugin
+ALTER TABLE plugin_entrypoint ADD COLUMN permissions JSON;
@@ -0,0 +1,7 @@
+CREATE TABLE plugin_generated_entrypoint_aliases
+(
+    entrypoint_id TEXT NOT NULL,
+    plugin_id     TEXT NOT NULL REFERENCES plugin (id) ON DELETE CASCADE,
+    aliases       JSON NOT NULL,
+    PRIMARY KEY (entrypoint_id, plugin_id)
+);
@@ -0,0 +1,18 @@
+CREATE TABLE plugin_audit_log_new
+(
+    plugin_id     TEXT    NOT NULL REFERENCES plugin (id) ON DELETE CASCADE,
+    entrypoint_id TEXT,
+    capability    TEXT    NOT NULL,
+    value         TEXT    NOT NULL,
+    timestamp     INTEGER NOT NULL
+);
+
+INSERT INTO plugin_audit_log_new (plugin_id, entrypoint_id, capability, value, timestamp)
+SELECT plugin_id, entrypoint_id, capability, value, timestamp
+FROM plugin_audit_log
+WHERE plugin_id IN (SELECT id FROM plugin);
+
+DROP TABLE plugin_audit_log;
+ALTER TABLE plugin_audit_log_new RENAME TO plugin_audit_log;
+
+CREATE INDEX plugin_audit_log_timestamp ON plugin_audit_log (timestamp);
@@ -1,5 +1,6 @@
 use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Duration;
 use vergen_pretty::vergen_pretty_env;
 use gauntlet_client::{open_window, start_client};
 use gauntlet_common::model::{BackendRequestData, BackendResponseData, UiRequestData, UiResponseData};
@@ -9,6 +10,8 @@ use gauntlet_common::{settings_env_data_from_string, settings_env_data_to_string
 use gauntlet_plugin_runtime::run_plugin_runtime;
 use gauntlet_utils::channel::{channel, RequestReceiver, RequestSender};
 use crate::plugins::ApplicationManager;
+use crate::plugins::config_watcher::watch_config_files;
+use crate::plugins::supervisor::supervise_plugin_runtimes;
 use crate::rpc::BackendServerImpl;
 use crate::search::SearchIndex;
 
@@ -20,9 +23,19 @@ pub(in crate) mod model;
 const SETTINGS_ENV: &'static str = "GAUNTLET_INTERNAL_SETTINGS";
 const PLUGIN_RUNTIME_ENV: &'static str = "GAUNTLET_INTERNAL_PLUGIN_RUNTIME";
 
+/// Hex encoded hash of built plugin which is expected to be signed by plugin author
+pub fn plugin_content_hash(plugin_dir: &std::path::Path) -> anyhow::Result<String> {
+    let content_hash = plugins::signature::plugin_content_hash(plugin_dir)?;
+
+    Ok(hex::encode(content_hash))
+}
+
 pub fn start(minimized: bool) {
     if let Ok(socket_name) = std::env::var(PLUGIN_RUNTIME_ENV) {
-        run_plugin_runtime(socket_name);
+        if let Err(limit_violation) = run_plugin_runtime(socket_name) {
+            // exit code is used by server to report the violated limit
+            std::process::exit(limit_violation.exit_code())
+        }
 
         return;
     }
@@ -79,6 +92,16 @@ fn run_scenario_runner() {
 
             start_frontend_mock(frontend_receiver, backend_sender)
         }
+        "plugin_test" => {
+            let (frontend_sender, frontend_receiver) = channel::<UiRequestData, UiResponseData>();
+            let (backend_sender, backend_receiver) = channel::<BackendRequestData, BackendResponseData>();
+
+            std::thread::spawn(|| {
+                start_server(frontend_sender, backend_receiver)
+            });
+
+            start_plugin_test_frontend_mock(frontend_receiver, backend_sender)
+        }
         _ => panic!("unknown type")
     }
 }
@@ -128,12 +151,30 @@ fn start_frontend_mock(
         .unwrap();
 }
 
+#[cfg(feature = "scenario_runner")]
+fn start_plugin_test_frontend_mock(
+    request_receiver: RequestReceiver<UiRequestData, UiResponseData>,
+    backend_sender: RequestSender<BackendRequestData, BackendResponseData>
+) {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("unable to start plugin test frontend mock tokio runtime")
+        .block_on(async {
+            gauntlet_scenario_runner::run_plugin_test_frontend_mock(request_receiver, backend_sender).await
+        })
+        .unwrap();
+}
+
+const PRUNE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
 async fn run_server(frontend_sender: RequestSender<UiRequestData, UiResponseData>, mut backend_receiver: RequestReceiver<BackendRequestData, BackendResponseData>) -> anyhow::Result<()> {
-    let application_manager = ApplicationManager::create(frontend_sender).await?;
+    let (plugin_crash_sender, plugin_crash_receiver) = tokio::sync::mpsc::unbounded_channel();
+    let (config_download_sender, mut config_download_receiver) = tokio::sync::mpsc::unbounded_channel();
 
-    let mut application_manager = Arc::new(application_manager);
+    let application_manager = ApplicationManager::create(frontend_sender, plugin_crash_sender, config_download_sender).await?;
 
-    application_manager.clear_all_icon_cache_dir()?;
+    let mut application_manager = Arc::new(application_manager);
 
     #[cfg(not(feature = "scenario_runner"))]
     if let Err(err) = application_manager.load_bundled_plugins().await {
@@ -151,8 +192,56 @@ async fn run_server(frontend_sender: RequestSender<UiRequestData, UiResponseData
         }
     }
 
+    tokio::spawn(supervise_plugin_runtimes(application_manager.clone(), plugin_crash_receiver));
+
     application_manager.reload_all_plugins().await?; // TODO do not fail here ?
 
+    tokio::spawn({
+        let application_manager = application_manager.clone();
+
+        async move {
+            while let Some(plugin_id) = config_download_receiver.recv().await {
+                if let Err(err) = application_manager.handle_config_plugin_downloaded(plugin_id).await {
+                    tracing::error!("Unable to apply config to downloaded plugin: {:?}", err);
+                }
+            }
+        }
+    });
+
+    tokio::spawn({
+        let application_manager = application_manager.clone();
+
+        async move {
+            if let Err(err) = watch_config_files(application_manager).await {
+                tracing::error!("Unable to watch config files: {:?}", err);
+            }
+        }
+    });
+
+    tokio::spawn({
+        let application_manager = application_manager.clone();
+
+        async move {
+            let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+
+            loop {
+                interval.tick().await;
+
+                if let Err(err) = application_manager.prune_audit_log().await {
+                    tracing::error!("Unable to prune audit log: {:?}", err);
+                }
+
+                if let Err(err) = application_manager.prune_query_frecency().await {
+                    tracing::error!("Unable to prune query frecency: {:?}", err);
+                }
+
+                if let Err(err) = application_manager.evict_icon_cache() {
+                    tracing::error!("Unable to evict icon cache: {:?}", err);
+                }
+            }
+        }
+    });
+
     tokio::spawn({
         let application_manager = application_manager.clone();
 
@@ -206,6 +295,12 @@ async fn handle_request(application_manager: Arc<ApplicationManager>, request_da
 
             BackendResponseData::Nothing
         }
+        BackendRequestData::SearchResultSelected { query, plugin_id, entrypoint_id } => {
+            application_manager.handle_search_result_selected(query, plugin_id, entrypoint_id)
+                .await;
+
+            BackendResponseData::Nothing
+        }
         BackendRequestData::RequestRunCommand { plugin_id, entrypoint_id } => {
             application_manager.handle_run_command(plugin_id, entrypoint_id)
                 .await;
@@ -32,7 +32,6 @@ pub enum IntermediateUiEvent {
         text: String,
     },
     ReloadSearchIndex,
-    RefreshSearchIndex,
 }
 
 pub enum ActionShortcutKey {
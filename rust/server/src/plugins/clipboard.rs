@@ -1,15 +1,22 @@
+#[cfg(not(feature = "scenario_runner"))]
 use anyhow::{anyhow, Context, Error};
+#[cfg(not(feature = "scenario_runner"))]
 use arboard::ImageData;
+#[cfg(not(feature = "scenario_runner"))]
 use image::RgbaImage;
+#[cfg(not(feature = "scenario_runner"))]
 use std::io::Cursor;
+#[cfg(not(feature = "scenario_runner"))]
 use std::sync::{Arc, RwLock};
 use gauntlet_plugin_runtime::JsClipboardData;
 
+#[cfg(not(feature = "scenario_runner"))]
 #[derive(Clone)]
 pub struct Clipboard {
     clipboard: Arc<RwLock<arboard::Clipboard>>,
 }
 
+#[cfg(not(feature = "scenario_runner"))]
 impl Clipboard {
     pub fn new() -> anyhow::Result<Self> {
         let clipboard = arboard::Clipboard::new()
@@ -134,10 +141,61 @@ impl Clipboard {
     }
 }
 
+#[cfg(not(feature = "scenario_runner"))]
 fn unknown_err_clipboard(err: arboard::Error) -> Error {
     anyhow!("UNKNOWN_ERROR: {}", err)
 }
 
+#[cfg(not(feature = "scenario_runner"))]
 fn unable_to_convert_image_err() -> Error {
     anyhow!("UNABLE_TO_CONVERT_IMAGE")
-}
\ No newline at end of file
+}
+
+/// Clipboard backed by in-memory storage of scenario runner, so plugins can be tested without a display
+#[cfg(feature = "scenario_runner")]
+#[derive(Clone)]
+pub struct Clipboard;
+
+#[cfg(feature = "scenario_runner")]
+impl Clipboard {
+    pub fn new() -> anyhow::Result<Self> {
+        Ok(Self)
+    }
+
+    pub fn read(&self) -> anyhow::Result<JsClipboardData> {
+        let data = gauntlet_scenario_runner::clipboard_mock::read();
+
+        Ok(JsClipboardData {
+            text_data: data.text_data,
+            png_data: data.png_data,
+        })
+    }
+
+    pub fn read_text(&self) -> anyhow::Result<Option<String>> {
+        Ok(gauntlet_scenario_runner::clipboard_mock::read().text_data)
+    }
+
+    pub fn write(&self, data: JsClipboardData) -> anyhow::Result<()> {
+        gauntlet_scenario_runner::clipboard_mock::write(gauntlet_scenario_runner::clipboard_mock::MockClipboardData {
+            text_data: data.text_data,
+            png_data: data.png_data,
+        });
+
+        Ok(())
+    }
+
+    pub fn write_text(&self, data: String) -> anyhow::Result<()> {
+        gauntlet_scenario_runner::clipboard_mock::write(gauntlet_scenario_runner::clipboard_mock::MockClipboardData {
+            text_data: Some(data),
+            png_data: None,
+        });
+
+        Ok(())
+    }
+
+    pub fn clear(&self) -> anyhow::Result<()> {
+        gauntlet_scenario_runner::clipboard_mock::clear();
+
+        Ok(())
+    }
+}
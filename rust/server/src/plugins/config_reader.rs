@@ -1,80 +1,777 @@
+use std::collections::{HashMap, HashSet};
+use std::io::ErrorKind;
+use std::sync::{Arc, Mutex};
+
+use anyhow::anyhow;
 use serde::Deserialize;
 
 use gauntlet_common::dirs::Dirs;
-use crate::plugins::data_db_repository::{DataDbRepository, DbWritePendingPlugin};
+use gauntlet_common::model::{PhysicalKey, PhysicalShortcut, PluginId};
+use crate::plugins::data_db_repository::{db_plugin_type_from_str, DataDbRepository, DbPluginActionUserData, DbPluginPreference, DbPluginPreferenceUserData, DbPluginType, DbWritePendingPlugin};
 
+#[derive(Clone)]
 pub struct ConfigReader {
     dirs: Dirs,
     repository: DataDbRepository,
+    // config file is read once and cached, it is re-read only when config file changes
+    loaded_config: Arc<Mutex<LoadedConfig>>,
+}
+
+#[derive(Clone)]
+struct LoadedConfig {
+    // last valid config, kept when config file becomes invalid after edit
+    config: ApplicationConfig,
+    // config file has never been read successfully, so it is not known whether
+    // it is meant to manage settings, until it is fixed settings can not be changed
+    locked: bool,
+}
+
+impl LoadedConfig {
+    fn new(config: anyhow::Result<ApplicationConfig>) -> Self {
+        match config {
+            Ok(config) => LoadedConfig { config, locked: false },
+            Err(err) => {
+                tracing::error!("{:#}, settings are locked until config file is fixed", err);
+
+                LoadedConfig { config: ApplicationConfig::default(), locked: true }
+            }
+        }
+    }
+
+    fn refresh(&mut self, config: anyhow::Result<ApplicationConfig>) -> anyhow::Result<()> {
+        *self = LoadedConfig { config: config?, locked: false };
+
+        Ok(())
+    }
+}
+
+/// Result of applying config in `config` configuration mode.
+/// Plugin processes are managed by caller, config reader only updates the database
+#[derive(Debug, Default)]
+pub struct ConfigPluginChanges {
+    // listed in config but not installed yet, and revision to download
+    pub missing: Vec<(PluginId, Option<String>)>,
+    // installed, but pinned to revision different from the one in config
+    pub revision_changed: Vec<(PluginId, Option<String>)>,
+    // installed but not listed in config
+    pub unlisted: Vec<PluginId>,
+    // installed, listed in config and with state updated from config, and whether plugin should be enabled
+    pub configured: Vec<(PluginId, bool)>,
 }
 
 impl ConfigReader {
     pub fn new(dirs: Dirs, repository: DataDbRepository) -> Self {
+        let loaded_config = LoadedConfig::new(try_read_config(&dirs));
+
         Self {
             dirs,
-            repository
+            repository,
+            loaded_config: Arc::new(Mutex::new(loaded_config)),
         }
     }
 
-    pub async fn reload_config(&self) -> anyhow::Result<()> {
+    /// Re-reads config file, used when it changes.
+    /// Returns error if config file exists but cannot be read or parsed, last valid config is kept in that case
+    pub fn refresh_config(&self) -> anyhow::Result<()> {
+        let config = try_read_config(&self.dirs);
+
+        self.loaded_config.lock().expect("lock is poisoned").refresh(config)
+    }
+
+    pub async fn reload_config(&self) -> anyhow::Result<ConfigPluginChanges> {
         let config = self.read_config();
 
-        for plugin in config.plugins {
+        match config.configuration_mode {
+            ConfigurationModeConfig::ConfigAndState => {
+                for plugin in config.plugins {
+                    let exists = self.repository.does_plugin_exist(&plugin.id).await?;
+                    if !exists {
+                        let pending = self.repository.is_plugin_pending(&plugin.id).await?;
+                        if !pending {
+                            let pending_plugin = DbWritePendingPlugin {
+                                id: plugin.id
+                            };
+                            self.repository.save_pending_plugin(pending_plugin).await?
+                        }
+                    }
+                }
+
+                Ok(ConfigPluginChanges::default())
+            }
+            ConfigurationModeConfig::Config => {
+                self.apply_plugins_config(config.plugins).await
+            }
+        }
+    }
+
+    /// In `config` configuration mode config file is the only source of truth,
+    /// and any changes to plugins and settings done via settings ui are refused
+    pub fn is_config_mode(&self) -> bool {
+        matches!(self.read_config().configuration_mode, ConfigurationModeConfig::Config)
+    }
+
+    /// Whether changes to plugins and settings done via settings ui are refused,
+    /// also true if config file is invalid and no valid config has been read since start
+    pub fn is_settings_locked(&self) -> bool {
+        let loaded_config = self.loaded_config.lock().expect("lock is poisoned");
+
+        loaded_config.locked || matches!(loaded_config.config.configuration_mode, ConfigurationModeConfig::Config)
+    }
+
+    /// Global shortcut set in config, `None` if config doesn't specify it
+    pub fn global_shortcut(&self) -> Option<anyhow::Result<Option<PhysicalShortcut>>> {
+        let shortcut = self.read_config().settings.global_shortcut?;
+
+        if shortcut.trim().is_empty() {
+            Some(Ok(None))
+        } else {
+            Some(parse_shortcut(&shortcut).map(Some))
+        }
+    }
+
+    /// Theme set in config, `None` if config doesn't specify it
+    pub fn theme(&self) -> Option<ThemeConfig> {
+        self.read_config().settings.theme
+    }
+
+    pub fn persistent_search_index(&self) -> bool {
+        self.read_config().search.persistent_index
+    }
+
+    /// Signature settings apply in both configuration modes
+    pub fn plugin_signatures(&self) -> PluginSignaturesConfig {
+        self.read_config().signatures
+    }
+
+    /// Dev plugin settings apply in both configuration modes
+    pub fn dev_plugins(&self) -> DevPluginsConfig {
+        self.read_config().dev_plugins
+    }
+
+    /// Permission prompts setting applies in both configuration modes
+    pub fn permission_prompts(&self) -> bool {
+        self.read_config().permissions.prompt
+    }
+
+    /// Audit log settings apply in both configuration modes
+    pub fn audit_log(&self) -> AuditLogConfig {
+        self.read_config().audit_log
+    }
+
+    /// Resource limits overrides for plugin, apply in both configuration modes
+    pub fn plugin_limits(&self, plugin_id: &PluginId) -> PluginLimitsConfig {
+        let plugin_id = plugin_id.to_string();
+
+        self.read_config()
+            .plugins
+            .into_iter()
+            .find(|plugin| plugin.id == plugin_id)
+            .map(|plugin| plugin.limits)
+            .unwrap_or_default()
+    }
+
+    async fn apply_plugins_config(&self, plugins: Vec<PluginEntryConfig>) -> anyhow::Result<ConfigPluginChanges> {
+        let mut changes = ConfigPluginChanges::default();
+
+        let listed: HashSet<_> = plugins.iter()
+            .map(|plugin| plugin.id.clone())
+            .collect();
+
+        for plugin in self.repository.list_plugins().await? {
+            let managed_by_config = match db_plugin_type_from_str(&plugin.plugin_type) {
+                DbPluginType::Bundled => false,
+                // dev plugins are managed by dev tools, and plugins installed from archive can not be downloaded
+                DbPluginType::Normal | DbPluginType::Config => !plugin.id.starts_with("file://") && !plugin.id.starts_with("archive://"),
+            };
+
+            if managed_by_config && !listed.contains(&plugin.id) {
+                changes.unlisted.push(PluginId::from_string(plugin.id));
+            }
+        }
+
+        for plugin in plugins {
+            let plugin_id = PluginId::from_string(&plugin.id);
+
+            let enabled = plugin.enabled;
+
             let exists = self.repository.does_plugin_exist(&plugin.id).await?;
             if !exists {
-                let pending = self.repository.is_plugin_pending(&plugin.id).await?;
-                if !pending {
-                    let pending_plugin = DbWritePendingPlugin {
-                        id: plugin.id
-                    };
-                    self.repository.save_pending_plugin(pending_plugin).await?
-                }
+                changes.missing.push((plugin_id, plugin.revision));
+                continue;
+            }
+
+            let installed_plugin = self.repository.get_plugin_by_id(&plugin.id).await?;
+            if installed_plugin.pinned_revision != plugin.revision {
+                changes.revision_changed.push((plugin_id.clone(), plugin.revision.clone()));
+            }
+
+            if let Err(err) = self.apply_plugin_config(plugin).await {
+                tracing::error!("Unable to apply config for plugin {:?}: {:#}", plugin_id, err);
+                continue;
             }
+
+            changes.configured.push((plugin_id, enabled));
+        }
+
+        Ok(changes)
+    }
+
+    async fn apply_plugin_config(&self, plugin_config: PluginEntryConfig) -> anyhow::Result<()> {
+        let plugin = self.repository.get_plugin_by_id(&plugin_config.id)
+            .await?;
+
+        let preferences = preferences_user_data_from_config(&plugin.preferences, plugin_config.preferences)?;
+
+        self.repository.set_preferences_user_data(&plugin.id, None, preferences)
+            .await?;
+
+        let mut entrypoints_config = plugin_config.entrypoints;
+
+        for entrypoint in self.repository.get_entrypoints_by_plugin_id(&plugin.id).await? {
+            let entrypoint_config = entrypoints_config.remove(&entrypoint.id)
+                .unwrap_or_default();
+
+            self.repository.set_plugin_entrypoint_enabled(&plugin.id, &entrypoint.id, entrypoint_config.enabled)
+                .await?;
+
+            let preferences = preferences_user_data_from_config(&entrypoint.preferences, entrypoint_config.preferences)?;
+
+            self.repository.set_preferences_user_data(&plugin.id, Some(&entrypoint.id), preferences)
+                .await?;
+
+            let actions = entrypoint_config.action_shortcuts
+                .into_iter()
+                .map(|(id, shortcut)| {
+                    if !entrypoint.actions.iter().any(|action| action.id == id) {
+                        return Err(anyhow!("Unknown action '{}' in entrypoint '{}'", id, entrypoint.id))
+                    }
+
+                    let shortcut = parse_shortcut(&shortcut)?;
+
+                    Ok(DbPluginActionUserData {
+                        id,
+                        key: shortcut.physical_key.to_value(),
+                        modifier_shift: shortcut.modifier_shift,
+                        modifier_control: shortcut.modifier_control,
+                        modifier_alt: shortcut.modifier_alt,
+                        modifier_meta: shortcut.modifier_meta,
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            self.repository.set_actions_user_data(&plugin.id, &entrypoint.id, actions)
+                .await?;
+        }
+
+        for entrypoint_id in entrypoints_config.keys() {
+            tracing::warn!("Config contains unknown entrypoint '{}' for plugin {}", entrypoint_id, plugin.id);
         }
 
         Ok(())
     }
 
     fn read_config(&self) -> ApplicationConfig {
-        let config_file = self.dirs.config_file();
-        let config_content = std::fs::read_to_string(config_file);
+        self.loaded_config.lock().expect("lock is poisoned").config.clone()
+    }
+}
 
-        match config_content {
-            Ok(config_content) => {
-                toml::from_str(&config_content)
-                    .unwrap_or_else(|err| {
-                        tracing::error!("Unable to parse config, error: {:?}", err);
+fn try_read_config(dirs: &Dirs) -> anyhow::Result<ApplicationConfig> {
+    let config_file = dirs.config_file();
+    let config_content = std::fs::read_to_string(&config_file);
 
-                        ApplicationConfig::default()
-                    })
-            }
-            Err(_) => {
-                tracing::info!("No config found, using default configuration");
+    match config_content {
+        Ok(config_content) => parse_config(&config_content),
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            tracing::debug!("No config found, using default configuration");
 
-                ApplicationConfig::default()
-            }
+            Ok(ApplicationConfig::default())
+        }
+        Err(err) => {
+            Err(anyhow!("Unable to read config file {:?}: {}", config_file, err))
         }
     }
 }
 
-#[derive(Debug, Deserialize, Default)]
+fn parse_config(config_content: &str) -> anyhow::Result<ApplicationConfig> {
+    toml::from_str(config_content)
+        .map_err(|err| anyhow!("Unable to parse config: {}", err))
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
 pub struct ApplicationConfig {
-    // #[serde(default)] // TODO
-    // configuration_mode: ConfigurationModeConfig,
+    #[serde(default)]
+    configuration_mode: ConfigurationModeConfig,
     #[serde(default)]
     plugins: Vec<PluginEntryConfig>,
+    #[serde(default)]
+    settings: SettingsConfig,
+    #[serde(default)]
+    search: SearchConfig,
+    #[serde(default)]
+    signatures: PluginSignaturesConfig,
+    #[serde(default)]
+    dev_plugins: DevPluginsConfig,
+    #[serde(default)]
+    permissions: PermissionsConfig,
+    #[serde(default)]
+    audit_log: AuditLogConfig,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 struct PluginEntryConfig {
     id: String,
+    // git tag, branch or commit, latest commit of release branch is used if not specified
+    #[serde(default)]
+    revision: Option<String>,
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+    #[serde(default)]
+    preferences: HashMap<String, toml::Value>,
+    #[serde(default)]
+    entrypoints: HashMap<String, EntrypointConfig>,
+    // overrides limits specified in plugin manifest
+    #[serde(default)]
+    limits: PluginLimitsConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct EntrypointConfig {
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+    #[serde(default)]
+    preferences: HashMap<String, toml::Value>,
+    #[serde(default)]
+    action_shortcuts: HashMap<String, String>,
+}
+
+impl Default for EntrypointConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            preferences: HashMap::new(),
+            action_shortcuts: HashMap::new(),
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+struct SettingsConfig {
+    // empty string means no global shortcut
+    #[serde(default)]
+    global_shortcut: Option<String>,
+    #[serde(default)]
+    theme: Option<ThemeConfig>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub enum ThemeConfig {
+    #[serde(rename = "auto_detect")]
+    AutoDetect,
+    #[serde(rename = "macos_light")]
+    MacOSLight,
+    #[serde(rename = "macos_dark")]
+    MacOSDark,
+    #[serde(rename = "legacy")]
+    Legacy,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+struct SearchConfig {
+    // keep search index in cache dir between restarts instead of in memory
+    #[serde(default)]
+    persistent_index: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct PluginSignaturesConfig {
+    // base64 encoded ed25519 public keys
+    #[serde(default)]
+    pub trusted_keys: Vec<String>,
+    // ids of plugins which are refused to be installed without valid signature
+    #[serde(default)]
+    pub required: Vec<String>,
 }
 
-// #[derive(Deserialize, Debug, Default)]
-// enum ConfigurationModeConfig {
-//     #[serde(rename = "config")]
-//     Config,
-//     #[default]
-//     #[serde(rename = "config_and_state")]
-//     ConfigAndState
-// }
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct DevPluginsConfig {
+    // start v8 inspector for dev plugins
+    #[serde(default)]
+    pub inspector: bool,
+    // port of the first dev plugin, every next dev plugin gets next free port
+    #[serde(default)]
+    pub inspector_port: Option<u16>,
+    // pause before first statement of plugin code until debugger is attached
+    #[serde(default)]
+    pub inspector_break_on_first_statement: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+struct PermissionsConfig {
+    // ask user instead of denying access to resources not listed in plugin manifest
+    #[serde(default)]
+    prompt: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuditLogConfig {
+    // record clipboard access, commands run, network hosts contacted and files written by plugins
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    // entries older than this are removed
+    #[serde(default = "default_audit_log_retention_days")]
+    pub retention_days: u64,
+}
+
+impl Default for AuditLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            retention_days: default_audit_log_retention_days(),
+        }
+    }
+}
+
+fn default_audit_log_retention_days() -> u64 {
+    30
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct PluginLimitsConfig {
+    #[serde(default)]
+    pub heap_mb: Option<u64>,
+    #[serde(default)]
+    pub address_space_mb: Option<u64>,
+    #[serde(default)]
+    pub cpu_seconds: Option<u64>,
+    #[serde(default)]
+    pub event_loop_timeout_secs: Option<u64>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+enum ConfigurationModeConfig {
+    #[serde(rename = "config")]
+    Config,
+    #[default]
+    #[serde(rename = "config_and_state")]
+    ConfigAndState
+}
+
+fn preferences_user_data_from_config(
+    preferences: &HashMap<String, DbPluginPreference>,
+    config: HashMap<String, toml::Value>
+) -> anyhow::Result<HashMap<String, DbPluginPreferenceUserData>> {
+    config.into_iter()
+        .map(|(id, value)| {
+            let preference = preferences.get(&id)
+                .ok_or_else(|| anyhow!("Unknown preference: {}", id))?;
+
+            let user_data = preference_user_data_from_config(preference, value)
+                .map_err(|err| anyhow!("Invalid value for preference '{}': {:#}", id, err))?;
+
+            Ok((id, user_data))
+        })
+        .collect()
+}
+
+fn preference_user_data_from_config(preference: &DbPluginPreference, value: toml::Value) -> anyhow::Result<DbPluginPreferenceUserData> {
+    let user_data = match preference {
+        DbPluginPreference::Number { .. } => DbPluginPreferenceUserData::Number {
+            value: Some(number_from_config(&value)?),
+        },
+        DbPluginPreference::String { .. } => DbPluginPreferenceUserData::String {
+            value: Some(string_from_config(value)?),
+        },
+        DbPluginPreference::Enum { enum_values, .. } => {
+            let value = string_from_config(value)?;
+
+            if !enum_values.iter().any(|enum_value| enum_value.value == value) {
+                return Err(anyhow!("'{}' is not one of the allowed values", value))
+            }
+
+            DbPluginPreferenceUserData::Enum {
+                value: Some(value),
+            }
+        },
+        DbPluginPreference::Bool { .. } => DbPluginPreferenceUserData::Bool {
+            value: Some(value.as_bool().ok_or_else(|| anyhow!("expected boolean"))?),
+        },
+        DbPluginPreference::ListOfStrings { .. } => DbPluginPreferenceUserData::ListOfStrings {
+            value: Some(array_from_config(value)?.into_iter().map(string_from_config).collect::<anyhow::Result<_>>()?),
+        },
+        DbPluginPreference::ListOfNumbers { .. } => DbPluginPreferenceUserData::ListOfNumbers {
+            value: Some(array_from_config(value)?.iter().map(number_from_config).collect::<anyhow::Result<_>>()?),
+        },
+        DbPluginPreference::ListOfEnums { enum_values, .. } => {
+            let value = array_from_config(value)?
+                .into_iter()
+                .map(string_from_config)
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            if let Some(value) = value.iter().find(|value| !enum_values.iter().any(|enum_value| &enum_value.value == *value)) {
+                return Err(anyhow!("'{}' is not one of the allowed values", value))
+            }
+
+            DbPluginPreferenceUserData::ListOfEnums {
+                value: Some(value),
+            }
+        },
+    };
+
+    Ok(user_data)
+}
+
+fn number_from_config(value: &toml::Value) -> anyhow::Result<f64> {
+    match value {
+        toml::Value::Integer(value) => Ok(*value as f64),
+        toml::Value::Float(value) => Ok(*value),
+        _ => Err(anyhow!("expected number"))
+    }
+}
+
+fn string_from_config(value: toml::Value) -> anyhow::Result<String> {
+    match value {
+        toml::Value::String(value) => Ok(value),
+        _ => Err(anyhow!("expected string"))
+    }
+}
+
+fn array_from_config(value: toml::Value) -> anyhow::Result<Vec<toml::Value>> {
+    match value {
+        toml::Value::Array(value) => Ok(value),
+        _ => Err(anyhow!("expected array"))
+    }
+}
+
+/// Parses shortcuts like `Meta+Space` or `ctrl+shift+k`,
+/// key can be a name of physical key or a single letter or digit
+fn parse_shortcut(value: &str) -> anyhow::Result<PhysicalShortcut> {
+    let mut parts: Vec<_> = value.split('+')
+        .map(|part| part.trim())
+        .collect();
+
+    let key = parts.pop()
+        .filter(|key| !key.is_empty())
+        .ok_or_else(|| anyhow!("Shortcut '{}' doesn't specify key", value))?;
+
+    let mut shortcut = PhysicalShortcut {
+        physical_key: parse_physical_key(key)
+            .ok_or_else(|| anyhow!("Unknown key '{}' in shortcut '{}'", key, value))?,
+        modifier_shift: false,
+        modifier_control: false,
+        modifier_alt: false,
+        modifier_meta: false,
+    };
+
+    for modifier in parts {
+        match modifier.to_lowercase().as_str() {
+            "shift" => shortcut.modifier_shift = true,
+            "ctrl" | "control" => shortcut.modifier_control = true,
+            "alt" | "option" => shortcut.modifier_alt = true,
+            "meta" | "cmd" | "command" | "super" | "win" => shortcut.modifier_meta = true,
+            _ => return Err(anyhow!("Unknown modifier '{}' in shortcut '{}'", modifier, value))
+        }
+    }
+
+    Ok(shortcut)
+}
+
+fn parse_physical_key(key: &str) -> Option<PhysicalKey> {
+    if let Some(physical_key) = PhysicalKey::try_from_value(key) {
+        return Some(physical_key)
+    }
+
+    let mut chars = key.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(char), None) if char.is_ascii_alphabetic() => {
+            PhysicalKey::try_from_value(&format!("Key{}", char.to_ascii_uppercase()))
+        }
+        (Some(char), None) if char.is_ascii_digit() => {
+            PhysicalKey::try_from_value(&format!("Digit{}", char))
+        }
+        (Some(first), Some(_)) => {
+            let capitalized = first.to_uppercase().collect::<String>() + &key[first.len_utf8()..];
+
+            PhysicalKey::try_from_value(&capitalized)
+        }
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::data_db_repository::DbPreferenceEnumValue;
+
+    fn enum_values(values: &[&str]) -> Vec<DbPreferenceEnumValue> {
+        values.iter()
+            .map(|value| DbPreferenceEnumValue { label: value.to_string(), value: value.to_string() })
+            .collect()
+    }
+
+    fn enum_preference() -> DbPluginPreference {
+        DbPluginPreference::Enum {
+            name: None,
+            default: None,
+            description: "".to_string(),
+            enum_values: enum_values(&["light", "dark"]),
+        }
+    }
+
+    fn list_of_enums_preference() -> DbPluginPreference {
+        DbPluginPreference::ListOfEnums {
+            name: None,
+            default: None,
+            enum_values: enum_values(&["light", "dark"]),
+            description: "".to_string(),
+        }
+    }
+
+    fn config_mode(config: &ApplicationConfig) -> bool {
+        matches!(config.configuration_mode, ConfigurationModeConfig::Config)
+    }
+
+    #[test]
+    fn shortcut_with_modifiers() {
+        let shortcut = parse_shortcut("Ctrl+Shift+Alt+Meta+Space").unwrap();
+
+        assert_eq!(shortcut.physical_key.to_value(), "Space");
+        assert!(shortcut.modifier_control);
+        assert!(shortcut.modifier_shift);
+        assert!(shortcut.modifier_alt);
+        assert!(shortcut.modifier_meta);
+    }
+
+    #[test]
+    fn shortcut_modifier_aliases_are_case_insensitive() {
+        let shortcut = parse_shortcut("control + CMD + option + k").unwrap();
+
+        assert_eq!(shortcut.physical_key.to_value(), "KeyK");
+        assert!(shortcut.modifier_control);
+        assert!(shortcut.modifier_meta);
+        assert!(shortcut.modifier_alt);
+        assert!(!shortcut.modifier_shift);
+    }
+
+    #[test]
+    fn shortcut_without_modifiers() {
+        let shortcut = parse_shortcut("F1").unwrap();
+
+        assert_eq!(shortcut.physical_key.to_value(), "F1");
+        assert!(!shortcut.modifier_control);
+        assert!(!shortcut.modifier_shift);
+        assert!(!shortcut.modifier_alt);
+        assert!(!shortcut.modifier_meta);
+    }
+
+    #[test]
+    fn invalid_shortcuts() {
+        assert!(parse_shortcut("").is_err());
+        assert!(parse_shortcut("Ctrl+").is_err());
+        assert!(parse_shortcut("Hyper+K").is_err());
+        assert!(parse_shortcut("Ctrl+NoSuchKey").is_err());
+    }
+
+    #[test]
+    fn physical_key_letters_and_digits() {
+        assert_eq!(parse_physical_key("k").map(|key| key.to_value()), Some("KeyK".to_string()));
+        assert_eq!(parse_physical_key("K").map(|key| key.to_value()), Some("KeyK".to_string()));
+        assert_eq!(parse_physical_key("1").map(|key| key.to_value()), Some("Digit1".to_string()));
+    }
+
+    #[test]
+    fn physical_key_names() {
+        assert_eq!(parse_physical_key("Space").map(|key| key.to_value()), Some("Space".to_string()));
+        assert_eq!(parse_physical_key("space").map(|key| key.to_value()), Some("Space".to_string()));
+        assert_eq!(parse_physical_key("KeyK").map(|key| key.to_value()), Some("KeyK".to_string()));
+    }
+
+    #[test]
+    fn unknown_physical_keys() {
+        assert!(parse_physical_key("").is_none());
+        assert!(parse_physical_key("ä").is_none());
+        assert!(parse_physical_key("?").is_none());
+        assert!(parse_physical_key("NoSuchKey").is_none());
+    }
+
+    #[test]
+    fn enum_preference_accepts_allowed_value() {
+        let user_data = preference_user_data_from_config(&enum_preference(), toml::Value::String("dark".to_string())).unwrap();
+
+        assert!(matches!(user_data, DbPluginPreferenceUserData::Enum { value: Some(value) } if value == "dark"));
+    }
+
+    #[test]
+    fn enum_preference_rejects_unknown_value() {
+        assert!(preference_user_data_from_config(&enum_preference(), toml::Value::String("blue".to_string())).is_err());
+        assert!(preference_user_data_from_config(&enum_preference(), toml::Value::Integer(1)).is_err());
+    }
+
+    #[test]
+    fn list_of_enums_preference_is_validated() {
+        let valid = toml::Value::Array(vec![toml::Value::String("light".to_string()), toml::Value::String("dark".to_string())]);
+        let user_data = preference_user_data_from_config(&list_of_enums_preference(), valid).unwrap();
+
+        assert!(matches!(user_data, DbPluginPreferenceUserData::ListOfEnums { value: Some(value) } if value == ["light", "dark"]));
+
+        let unknown = toml::Value::Array(vec![toml::Value::String("light".to_string()), toml::Value::String("blue".to_string())]);
+        assert!(preference_user_data_from_config(&list_of_enums_preference(), unknown).is_err());
+
+        assert!(preference_user_data_from_config(&list_of_enums_preference(), toml::Value::String("light".to_string())).is_err());
+    }
+
+    #[test]
+    fn list_preferences_require_items_of_correct_type() {
+        let numbers = DbPluginPreference::ListOfNumbers { name: None, default: None, description: "".to_string() };
+        let strings = DbPluginPreference::ListOfStrings { name: None, default: None, description: "".to_string() };
+
+        let user_data = preference_user_data_from_config(&numbers, toml::Value::Array(vec![toml::Value::Integer(1), toml::Value::Float(2.5)])).unwrap();
+        assert!(matches!(user_data, DbPluginPreferenceUserData::ListOfNumbers { value: Some(value) } if value == [1.0, 2.5]));
+
+        assert!(preference_user_data_from_config(&numbers, toml::Value::Array(vec![toml::Value::String("1".to_string())])).is_err());
+        assert!(preference_user_data_from_config(&strings, toml::Value::Array(vec![toml::Value::Integer(1)])).is_err());
+    }
+
+    #[test]
+    fn unknown_preference_is_rejected() {
+        let preferences = HashMap::from([("theme".to_string(), enum_preference())]);
+        let config = HashMap::from([("other".to_string(), toml::Value::String("dark".to_string()))]);
+
+        assert!(preferences_user_data_from_config(&preferences, config).is_err());
+    }
+
+    #[test]
+    fn invalid_config_keeps_last_valid_config() {
+        let mut loaded_config = LoadedConfig::new(parse_config(r#"configuration_mode = "config""#));
+
+        assert!(!loaded_config.locked);
+        assert!(config_mode(&loaded_config.config));
+
+        assert!(loaded_config.refresh(parse_config("configuration_mode = ")).is_err());
+
+        assert!(!loaded_config.locked);
+        assert!(config_mode(&loaded_config.config));
+
+        loaded_config.refresh(parse_config(r#"configuration_mode = "config_and_state""#)).unwrap();
+
+        assert!(!config_mode(&loaded_config.config));
+    }
+
+    #[test]
+    fn settings_are_locked_until_config_is_fixed() {
+        let mut loaded_config = LoadedConfig::new(parse_config("[[plugins]"));
+
+        assert!(loaded_config.locked);
+        assert!(!config_mode(&loaded_config.config));
+
+        assert!(loaded_config.refresh(parse_config("plugins = 1")).is_err());
+        assert!(loaded_config.locked);
+
+        loaded_config.refresh(parse_config("")).unwrap();
+        assert!(!loaded_config.locked);
+    }
+}
@@ -0,0 +1,83 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+use gauntlet_common::dirs::Dirs;
+use crate::plugins::ApplicationManager;
+
+// editors usually produce several events for a single save
+const DEBOUNCE_DURATION: Duration = Duration::from_millis(300);
+
+pub async fn watch_config_files(application_manager: Arc<ApplicationManager>) -> anyhow::Result<()> {
+    let dirs = Dirs::new();
+    let config_dir = dirs.config_dir();
+    let config_file = dirs.config_file();
+    let theme_file = dirs.theme_file();
+
+    // config and theme files may not exist yet, so watch directory instead
+    std::fs::create_dir_all(&config_dir)
+        .context("Unable to create config directory")?;
+
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<notify::Result<Event>>();
+
+    let mut watcher = notify::recommended_watcher(move |event| {
+        let _ = sender.send(event);
+    })?;
+
+    watcher.watch(&config_dir, RecursiveMode::NonRecursive)
+        .context("Unable to watch config directory")?;
+
+    tracing::debug!("Watching config directory for changes: {:?}", config_dir);
+
+    while let Some(event) = receiver.recv().await {
+        let mut config_changed = false;
+        let mut theme_changed = false;
+
+        let mut handle_event = |event: notify::Result<Event>| {
+            match event {
+                Ok(event) => {
+                    if let EventKind::Access(_) = event.kind {
+                        return;
+                    }
+
+                    for path in &event.paths {
+                        config_changed |= is_same_file_name(path, &config_file);
+                        theme_changed |= is_same_file_name(path, &theme_file);
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!("Error occurred when watching config directory: {:?}", err);
+                }
+            }
+        };
+
+        handle_event(event);
+
+        tokio::time::sleep(DEBOUNCE_DURATION).await;
+
+        while let Ok(event) = receiver.try_recv() {
+            handle_event(event);
+        }
+
+        if config_changed {
+            if let Err(err) = application_manager.handle_config_file_changed().await {
+                tracing::error!("Unable to reload config: {:?}", err);
+            }
+        }
+
+        if theme_changed {
+            if let Err(err) = application_manager.handle_theme_file_changed().await {
+                tracing::error!("Unable to reload theme: {:?}", err);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn is_same_file_name(path: &Path, file: &Path) -> bool {
+    path.file_name().is_some() && path.file_name() == file.file_name()
+}
@@ -11,10 +11,10 @@ use sqlx::sqlite::SqliteConnectOptions;
 use sqlx::types::Json;
 use typed_path::TypedPathBuf;
 use uuid::Uuid;
-use gauntlet_common::model::{UiTheme, PhysicalKey, PhysicalShortcut, PluginId};
+use gauntlet_common::model::{AuditLogCapability, UiTheme, PhysicalKey, PhysicalShortcut, PluginId};
 use gauntlet_common::dirs::Dirs;
 use crate::model::ActionShortcutKey;
-use crate::plugins::frecency::{FrecencyItemStats, FrecencyMetaParams};
+use crate::plugins::frecency::{FrecencyItemStats, FrecencyMetaParams, DEFAULT_HALF_LIFE};
 use crate::plugins::loader::PluginManifestActionShortcutKey;
 
 static MIGRATOR: Migrator = sqlx::migrate!("./db_migrations");
@@ -41,6 +41,14 @@ pub struct DbReadPlugin {
     pub preferences: HashMap<String, DbPluginPreference>,
     #[sqlx(json)]
     pub preferences_user_data: HashMap<String, DbPluginPreferenceUserData>,
+    pub revision: Option<String>,
+    pub pinned_revision: Option<String>,
+    pub content_hash: Option<String>,
+    #[sqlx(json)]
+    pub limits: DbPluginLimits,
+    // permissions user agreed to, None if plugin permissions were never reviewed
+    // #[sqlx(json)] // https://github.com/launchbadge/sqlx/issues/2849
+    pub approved_permissions: Option<Json<DbPluginPermissions>>,
 }
 
 #[derive(sqlx::FromRow)]
@@ -62,6 +70,13 @@ pub struct DbReadPluginEntrypoint {
     pub actions: Vec<DbPluginAction>,
     #[sqlx(json)]
     pub actions_user_data: Vec<DbPluginActionUserData>,
+    #[sqlx(json)]
+    pub keywords: Vec<String>,
+    #[sqlx(json)]
+    pub aliases: Vec<String>,
+    // entrypoint is run in separate plugin runtime with these permissions, if specified
+    // #[sqlx(json)] // https://github.com/launchbadge/sqlx/issues/2849
+    pub permissions: Option<Json<DbPluginPermissions>>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -80,6 +95,11 @@ pub struct DbWritePlugin {
     pub permissions: DbPluginPermissions,
     pub plugin_type: String,
     pub preferences: HashMap<String, DbPluginPreference>,
+    pub revision: Option<String>,
+    pub pinned_revision: Option<String>,
+    // sha256 of plugin manifest, js and assets
+    pub content_hash: String,
+    pub limits: DbPluginLimits,
 }
 
 pub struct DbWritePluginEntrypoint {
@@ -90,6 +110,8 @@ pub struct DbWritePluginEntrypoint {
     pub entrypoint_type: String,
     pub preferences: HashMap<String, DbPluginPreference>,
     pub actions: Vec<DbPluginAction>,
+    pub keywords: Vec<String>,
+    pub permissions: Option<DbPluginPermissions>,
 }
 
 pub struct DbWritePluginAssetData {
@@ -128,6 +150,21 @@ pub struct DbPluginPermissions {
     pub clipboard: Vec<DbPluginClipboardPermissions>,
     #[serde(default)]
     pub main_search_bar: Vec<DbPluginMainSearchBarPermissions>,
+    #[serde(default)]
+    pub windows: Vec<DbPluginWindowsPermissions>,
+}
+
+// limits requested by plugin manifest, can be overridden in config
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct DbPluginLimits {
+    #[serde(default)]
+    pub heap_mb: Option<u64>,
+    #[serde(default)]
+    pub address_space_mb: Option<u64>,
+    #[serde(default)]
+    pub cpu_seconds: Option<u64>,
+    #[serde(default)]
+    pub event_loop_timeout_secs: Option<u64>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Default)]
@@ -162,6 +199,18 @@ pub enum DbPluginMainSearchBarPermissions {
     Read,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub enum DbPluginWindowsPermissions {
+    #[serde(rename = "read")]
+    Read,
+    #[serde(rename = "focus")]
+    Focus,
+    #[serde(rename = "close")]
+    Close,
+    #[serde(rename = "minimize")]
+    Minimize,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(tag = "type")]
 pub enum DbPluginPreferenceUserData {
@@ -238,6 +287,9 @@ pub struct DbSettingsGlobalShortcutData {
 pub struct DbSettings {
     // none means auto-detect
     pub theme: Option<DbTheme>,
+    // in seconds, none means default
+    #[serde(default)]
+    pub frecency_half_life: Option<f64>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -335,6 +387,35 @@ pub struct DbPluginEntrypointFrecencyStats {
     pub num_accesses: i32,
 }
 
+#[derive(sqlx::FromRow)]
+pub struct DbPluginEntrypointQueryFrecencyStats {
+    pub query_prefix: String,
+    pub plugin_id: String,
+    pub entrypoint_id: String,
+
+    pub reference_time: f64,
+    pub half_life: f64,
+    pub last_accessed: f64,
+    pub frecency: f64,
+    pub num_accesses: i32,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct DbPluginPermissionGrant {
+    pub plugin_id: String,
+    pub permission: String,
+    pub value: String,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct DbPluginAuditLogEntry {
+    pub plugin_id: String,
+    pub entrypoint_id: Option<String>,
+    pub capability: String,
+    pub value: String,
+    pub timestamp: i64,
+}
+
 const SETTINGS_DATA_ID: &str = "settings_data"; // only one row in the table
 
 impl DataDbRepository {
@@ -716,6 +797,16 @@ impl DataDbRepository {
         Ok(result.data)
     }
 
+    pub async fn get_all_asset_data(&self, plugin_id: &str) -> anyhow::Result<Vec<(String, Vec<u8>)>> {
+        // language=SQLite
+        let result = sqlx::query_as::<_, (String, Vec<u8>)>("SELECT path, data FROM plugin_asset_data WHERE plugin_id = ?1")
+            .bind(plugin_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(result)
+    }
+
     async fn get_all_asset_data_paths<'a, E>(&self, plugin_id: &str, executor: E) -> anyhow::Result<HashSet<String>>
         where
             E: Executor<'a, Database=Sqlite>,
@@ -732,6 +823,40 @@ impl DataDbRepository {
         Ok(result)
     }
 
+    pub async fn list_permission_grants(&self, plugin_id: &str) -> anyhow::Result<Vec<DbPluginPermissionGrant>> {
+        // language=SQLite
+        let result = sqlx::query_as::<_, DbPluginPermissionGrant>("SELECT plugin_id, permission, value FROM plugin_permission_grant WHERE plugin_id = ?1")
+            .bind(plugin_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn list_all_permission_grants(&self) -> anyhow::Result<Vec<DbPluginPermissionGrant>> {
+        // language=SQLite
+        let result = sqlx::query_as::<_, DbPluginPermissionGrant>("SELECT plugin_id, permission, value FROM plugin_permission_grant")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Newest entries first, filters are ignored if `None`
+    pub async fn list_audit_log(&self, plugin_id: Option<&str>, capability: Option<&str>, limit: i64) -> anyhow::Result<Vec<DbPluginAuditLogEntry>> {
+        // language=SQLite
+        let sql = r#"SELECT plugin_id, entrypoint_id, capability, value, timestamp FROM plugin_audit_log WHERE (?1 IS NULL OR plugin_id = ?1) AND (?2 IS NULL OR capability = ?2) ORDER BY timestamp DESC LIMIT ?3"#;
+
+        let result = sqlx::query_as::<_, DbPluginAuditLogEntry>(sql)
+            .bind(plugin_id)
+            .bind(capability)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(result)
+    }
+
     pub async fn inline_view_shortcuts(&self) -> anyhow::Result<HashMap<String, HashMap<String, PhysicalShortcut>>> {
         // language=SQLite
         let shortcuts: Vec<_> = sqlx::query_as::<_, (String, String)>("SELECT id, plugin_id FROM plugin_entrypoint WHERE type = 'inline-view'")
@@ -752,6 +877,8 @@ impl DataDbRepository {
     }
 
     pub async fn mark_entrypoint_frecency(&self, plugin_id: &str, entrypoint_id: &str) -> anyhow::Result<()> {
+        let half_life = self.get_frecency_half_life().await?;
+
         let mut tx = self.pool.begin().await?;
 
         // TODO reset time after 5 half lives
@@ -770,7 +897,7 @@ impl DataDbRepository {
             .await?;
 
         let meta_params = match meta_params {
-            None => FrecencyMetaParams::default(),
+            None => FrecencyMetaParams::with_half_life(half_life),
             Some(meta_params) => FrecencyMetaParams {
                 reference_time: meta_params.reference_time,
                 half_life: meta_params.half_life,
@@ -823,6 +950,285 @@ impl DataDbRepository {
         Ok(())
     }
 
+    pub async fn mark_entrypoint_query_frecency(&self, plugin_id: &str, entrypoint_id: &str, query_prefixes: &[String]) -> anyhow::Result<()> {
+        let half_life = self.get_frecency_half_life().await?;
+
+        let mut tx = self.pool.begin().await?;
+
+        #[derive(sqlx::FromRow)]
+        struct DbFrecencyMetaParams {
+            pub reference_time: f64,
+            pub half_life: f64,
+        }
+
+        // language=SQLite
+        let meta_params = sqlx::query_as::<_, DbFrecencyMetaParams>("SELECT reference_time, half_life FROM plugin_entrypoint_query_frecency_stats")
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let meta_params = match meta_params {
+            None => FrecencyMetaParams::with_half_life(half_life),
+            Some(meta_params) => FrecencyMetaParams {
+                reference_time: meta_params.reference_time,
+                half_life: meta_params.half_life,
+            }
+        };
+
+        for query_prefix in query_prefixes {
+            // language=SQLite
+            let stats = sqlx::query_as::<_, DbPluginEntrypointQueryFrecencyStats>("SELECT query_prefix, plugin_id, entrypoint_id, reference_time, half_life, last_accessed, frecency, num_accesses FROM plugin_entrypoint_query_frecency_stats WHERE query_prefix = ?1 AND plugin_id = ?2 AND entrypoint_id = ?3")
+                .bind(query_prefix)
+                .bind(plugin_id)
+                .bind(entrypoint_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+
+            let mut new_stats = match stats {
+                None => {
+                    FrecencyItemStats::new(meta_params.reference_time, meta_params.half_life)
+                }
+                Some(stats) => {
+                    FrecencyItemStats {
+                        half_life: stats.half_life,
+                        reference_time: stats.reference_time,
+                        last_accessed: stats.last_accessed,
+                        frecency: stats.frecency,
+                        num_accesses: stats.num_accesses,
+                    }
+                }
+            };
+
+            new_stats.mark_used();
+
+            // language=SQLite
+            let sql = r#"
+                INSERT OR REPLACE INTO plugin_entrypoint_query_frecency_stats (query_prefix, plugin_id, entrypoint_id, reference_time, half_life, last_accessed, frecency, num_accesses)
+                    VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#;
+
+            sqlx::query(sql)
+                .bind(query_prefix)
+                .bind(plugin_id)
+                .bind(entrypoint_id)
+                .bind(new_stats.reference_time)
+                .bind(new_stats.half_life)
+                .bind(new_stats.last_accessed)
+                .bind(new_stats.frecency)
+                .bind(new_stats.num_accesses)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Returns current (decayed) frecency per query prefix per (plugin id, entrypoint id)
+    pub async fn get_query_frecency(&self) -> anyhow::Result<HashMap<String, HashMap<(String, String), f64>>> {
+        // language=SQLite
+        let stats = sqlx::query_as::<_, DbPluginEntrypointQueryFrecencyStats>("SELECT query_prefix, plugin_id, entrypoint_id, reference_time, half_life, last_accessed, frecency, num_accesses FROM plugin_entrypoint_query_frecency_stats")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(query_frecency_by_prefix(stats))
+    }
+
+    /// Same as `get_query_frecency` but only for specified query prefixes
+    pub async fn get_query_frecency_for_prefixes(&self, query_prefixes: &[String]) -> anyhow::Result<HashMap<String, HashMap<(String, String), f64>>> {
+        let mut stats = vec![];
+
+        for query_prefix in query_prefixes {
+            // language=SQLite
+            let prefix_stats = sqlx::query_as::<_, DbPluginEntrypointQueryFrecencyStats>("SELECT query_prefix, plugin_id, entrypoint_id, reference_time, half_life, last_accessed, frecency, num_accesses FROM plugin_entrypoint_query_frecency_stats WHERE query_prefix = ?1")
+                .bind(query_prefix)
+                .fetch_all(&self.pool)
+                .await?;
+
+            stats.extend(prefix_stats);
+        }
+
+        Ok(query_frecency_by_prefix(stats))
+    }
+
+    /// Removes query frecency stats which decayed below `min_frecency` or were last accessed before `last_accessed_before`,
+    /// returns number of removed stats
+    pub async fn remove_stale_query_frecency(&self, min_frecency: f64, last_accessed_before: f64) -> anyhow::Result<u64> {
+        let mut tx = self.pool.begin().await?;
+
+        // language=SQLite
+        let stats = sqlx::query_as::<_, DbPluginEntrypointQueryFrecencyStats>("SELECT query_prefix, plugin_id, entrypoint_id, reference_time, half_life, last_accessed, frecency, num_accesses FROM plugin_entrypoint_query_frecency_stats")
+            .fetch_all(&mut *tx)
+            .await?;
+
+        let mut removed = 0;
+
+        for stats in stats {
+            let item_stats = FrecencyItemStats {
+                half_life: stats.half_life,
+                reference_time: stats.reference_time,
+                last_accessed: stats.last_accessed,
+                frecency: stats.frecency,
+                num_accesses: stats.num_accesses,
+            };
+
+            if item_stats.current_frecency() >= min_frecency && item_stats.last_access() >= last_accessed_before {
+                continue;
+            }
+
+            // language=SQLite
+            sqlx::query("DELETE FROM plugin_entrypoint_query_frecency_stats WHERE query_prefix = ?1 AND plugin_id = ?2 AND entrypoint_id = ?3")
+                .bind(&stats.query_prefix)
+                .bind(&stats.plugin_id)
+                .bind(&stats.entrypoint_id)
+                .execute(&mut *tx)
+                .await?;
+
+            removed += 1;
+        }
+
+        tx.commit().await?;
+
+        Ok(removed)
+    }
+
+    pub async fn get_frecency_stats(&self, plugin_id: &str, entrypoint_id: Option<&str>) -> anyhow::Result<Vec<DbPluginEntrypointFrecencyStats>> {
+        let result = match entrypoint_id {
+            None => {
+                // language=SQLite
+                sqlx::query_as::<_, DbPluginEntrypointFrecencyStats>("SELECT plugin_id, entrypoint_id, reference_time, half_life, last_accessed, frecency, num_accesses FROM plugin_entrypoint_frecency_stats WHERE plugin_id = ?1")
+                    .bind(plugin_id)
+                    .fetch_all(&self.pool)
+                    .await?
+            }
+            Some(entrypoint_id) => {
+                // language=SQLite
+                sqlx::query_as::<_, DbPluginEntrypointFrecencyStats>("SELECT plugin_id, entrypoint_id, reference_time, half_life, last_accessed, frecency, num_accesses FROM plugin_entrypoint_frecency_stats WHERE plugin_id = ?1 AND entrypoint_id = ?2")
+                    .bind(plugin_id)
+                    .bind(entrypoint_id)
+                    .fetch_all(&self.pool)
+                    .await?
+            }
+        };
+
+        Ok(result)
+    }
+
+    pub async fn reset_entrypoint_frecency(&self, plugin_id: &str, entrypoint_id: &str) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        // language=SQLite
+        sqlx::query("DELETE FROM plugin_entrypoint_frecency_stats WHERE plugin_id = ?1 AND entrypoint_id = ?2")
+            .bind(plugin_id)
+            .bind(entrypoint_id)
+            .execute(&mut *tx)
+            .await?;
+
+        // language=SQLite
+        sqlx::query("DELETE FROM plugin_entrypoint_query_frecency_stats WHERE plugin_id = ?1 AND entrypoint_id = ?2")
+            .bind(plugin_id)
+            .bind(entrypoint_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn reset_all_frecency(&self) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        // language=SQLite
+        sqlx::query("DELETE FROM plugin_entrypoint_frecency_stats")
+            .execute(&mut *tx)
+            .await?;
+
+        // language=SQLite
+        sqlx::query("DELETE FROM plugin_entrypoint_query_frecency_stats")
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn get_frecency_half_life(&self) -> anyhow::Result<f64> {
+        let half_life = self.get_settings()
+            .await?
+            .frecency_half_life
+            .unwrap_or(DEFAULT_HALF_LIFE);
+
+        Ok(half_life)
+    }
+
+    /// Changes half life of all existing stats while keeping their current frecency
+    pub async fn set_frecency_half_life(&self, half_life: f64) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        // language=SQLite
+        let stats = sqlx::query_as::<_, DbPluginEntrypointFrecencyStats>("SELECT plugin_id, entrypoint_id, reference_time, half_life, last_accessed, frecency, num_accesses FROM plugin_entrypoint_frecency_stats")
+            .fetch_all(&mut *tx)
+            .await?;
+
+        for stats in stats {
+            let mut new_stats = FrecencyItemStats {
+                half_life: stats.half_life,
+                reference_time: stats.reference_time,
+                last_accessed: stats.last_accessed,
+                frecency: stats.frecency,
+                num_accesses: stats.num_accesses,
+            };
+
+            new_stats.set_half_life(half_life);
+
+            // language=SQLite
+            sqlx::query("UPDATE plugin_entrypoint_frecency_stats SET reference_time = ?1, half_life = ?2, last_accessed = ?3, frecency = ?4 WHERE plugin_id = ?5 AND entrypoint_id = ?6")
+                .bind(new_stats.reference_time)
+                .bind(new_stats.half_life)
+                .bind(new_stats.last_accessed)
+                .bind(new_stats.frecency)
+                .bind(&stats.plugin_id)
+                .bind(&stats.entrypoint_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        // language=SQLite
+        let stats = sqlx::query_as::<_, DbPluginEntrypointQueryFrecencyStats>("SELECT query_prefix, plugin_id, entrypoint_id, reference_time, half_life, last_accessed, frecency, num_accesses FROM plugin_entrypoint_query_frecency_stats")
+            .fetch_all(&mut *tx)
+            .await?;
+
+        for stats in stats {
+            let mut new_stats = FrecencyItemStats {
+                half_life: stats.half_life,
+                reference_time: stats.reference_time,
+                last_accessed: stats.last_accessed,
+                frecency: stats.frecency,
+                num_accesses: stats.num_accesses,
+            };
+
+            new_stats.set_half_life(half_life);
+
+            // language=SQLite
+            sqlx::query("UPDATE plugin_entrypoint_query_frecency_stats SET reference_time = ?1, half_life = ?2, last_accessed = ?3, frecency = ?4 WHERE query_prefix = ?5 AND plugin_id = ?6 AND entrypoint_id = ?7")
+                .bind(new_stats.reference_time)
+                .bind(new_stats.half_life)
+                .bind(new_stats.last_accessed)
+                .bind(new_stats.frecency)
+                .bind(&stats.query_prefix)
+                .bind(&stats.plugin_id)
+                .bind(&stats.entrypoint_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
     pub async fn get_frecency_for_plugin(&self, plugin_id: &str) -> anyhow::Result<HashMap<String, f64>> {
         // language=SQLite
         let result = sqlx::query_as::<_, (String, f64)>("SELECT entrypoint_id, frecency FROM plugin_entrypoint_frecency_stats WHERE plugin_id = ?1")
@@ -846,6 +1252,18 @@ impl DataDbRepository {
         Ok(())
     }
 
+    /// Returns false if permissions requested by plugin are no longer the same as the ones being approved
+    pub async fn approve_plugin_permissions(&self, plugin_id: &str, permissions: &DbPluginPermissions) -> anyhow::Result<bool> {
+        // language=SQLite
+        let result = sqlx::query("UPDATE plugin SET approved_permissions = permissions WHERE id = ?1 AND permissions = ?2")
+            .bind(plugin_id)
+            .bind(Json(permissions))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     pub async fn set_plugin_entrypoint_enabled(&self, plugin_id: &str, entrypoint_id: &str, enabled: bool) -> anyhow::Result<()> {
         // language=SQLite
         sqlx::query("UPDATE plugin_entrypoint SET enabled = ?1 WHERE id = ?2 AND plugin_id = ?3")
@@ -858,6 +1276,62 @@ impl DataDbRepository {
         Ok(())
     }
 
+    pub async fn set_plugin_entrypoint_aliases(&self, plugin_id: &str, entrypoint_id: &str, aliases: Vec<String>) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        // language=SQLite
+        let result = sqlx::query("UPDATE plugin_entrypoint SET aliases = ?1 WHERE id = ?2 AND plugin_id = ?3")
+            .bind(Json(&aliases))
+            .bind(entrypoint_id)
+            .bind(plugin_id)
+            .execute(&mut *tx)
+            .await?;
+
+        // generated entrypoints are not stored in db, so their aliases are stored separately
+        if result.rows_affected() == 0 {
+            if aliases.is_empty() {
+                // language=SQLite
+                sqlx::query("DELETE FROM plugin_generated_entrypoint_aliases WHERE entrypoint_id = ?1 AND plugin_id = ?2")
+                    .bind(entrypoint_id)
+                    .bind(plugin_id)
+                    .execute(&mut *tx)
+                    .await?;
+            } else {
+                // language=SQLite
+                let sql = r#"
+                    INSERT INTO plugin_generated_entrypoint_aliases (entrypoint_id, plugin_id, aliases)
+                        VALUES(?1, ?2, ?3)
+                            ON CONFLICT (entrypoint_id, plugin_id)
+                                DO UPDATE SET aliases = ?3
+                "#;
+
+                sqlx::query(sql)
+                    .bind(entrypoint_id)
+                    .bind(plugin_id)
+                    .bind(Json(&aliases))
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn get_generated_entrypoint_aliases(&self, plugin_id: &str) -> anyhow::Result<HashMap<String, Vec<String>>> {
+        // language=SQLite
+        let result = sqlx::query_as::<_, (String, Json<Vec<String>>)>("SELECT entrypoint_id, aliases FROM plugin_generated_entrypoint_aliases WHERE plugin_id = ?1")
+            .bind(plugin_id)
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|(entrypoint_id, Json(aliases))| (entrypoint_id, aliases))
+            .collect();
+
+        Ok(result)
+    }
+
     pub async fn set_global_shortcut(&self, shortcut: Option<PhysicalShortcut>, error: Option<String>) -> anyhow::Result<()> {
         // language=SQLite
         let sql = r#"
@@ -1005,6 +1479,42 @@ impl DataDbRepository {
         Ok(())
     }
 
+    pub async fn set_preferences_user_data(&self, plugin_id: &str, entrypoint_id: Option<&str>, user_data: HashMap<String, DbPluginPreferenceUserData>) -> anyhow::Result<()> {
+        match entrypoint_id {
+            None => {
+                // language=SQLite
+                sqlx::query("UPDATE plugin SET preferences_user_data = ?1 WHERE id = ?2")
+                    .bind(Json(user_data))
+                    .bind(plugin_id)
+                    .execute(&self.pool)
+                    .await?;
+            }
+            Some(entrypoint_id) => {
+                // language=SQLite
+                sqlx::query("UPDATE plugin_entrypoint SET preferences_user_data = ?1 WHERE id = ?2 AND plugin_id = ?3")
+                    .bind(Json(user_data))
+                    .bind(entrypoint_id)
+                    .bind(plugin_id)
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn set_actions_user_data(&self, plugin_id: &str, entrypoint_id: &str, user_data: Vec<DbPluginActionUserData>) -> anyhow::Result<()> {
+        // language=SQLite
+        sqlx::query("UPDATE plugin_entrypoint SET actions_user_data = ?1 WHERE id = ?2 AND plugin_id = ?3")
+            .bind(Json(user_data))
+            .bind(entrypoint_id)
+            .bind(plugin_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn save_pending_plugin(&self, plugin: DbWritePendingPlugin) -> anyhow::Result<()> {
         // language=SQLite
         sqlx::query("INSERT INTO pending_plugin VALUES(?1)")
@@ -1015,6 +1525,54 @@ impl DataDbRepository {
         Ok(())
     }
 
+    pub async fn save_permission_grant(&self, plugin_id: &str, permission: &str, value: &str) -> anyhow::Result<()> {
+        // language=SQLite
+        sqlx::query("INSERT OR IGNORE INTO plugin_permission_grant (plugin_id, permission, value) VALUES(?1, ?2, ?3)")
+            .bind(plugin_id)
+            .bind(permission)
+            .bind(value)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_permission_grant(&self, plugin_id: &str, permission: &str, value: &str) -> anyhow::Result<()> {
+        // language=SQLite
+        sqlx::query("DELETE FROM plugin_permission_grant WHERE plugin_id = ?1 AND permission = ?2 AND value = ?3")
+            .bind(plugin_id)
+            .bind(permission)
+            .bind(value)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn save_audit_log_entry(&self, plugin_id: &str, entrypoint_id: Option<&str>, capability: &str, value: &str, timestamp: i64) -> anyhow::Result<()> {
+        // language=SQLite
+        sqlx::query("INSERT INTO plugin_audit_log (plugin_id, entrypoint_id, capability, value, timestamp) VALUES(?1, ?2, ?3, ?4, ?5)")
+            .bind(plugin_id)
+            .bind(entrypoint_id)
+            .bind(capability)
+            .bind(value)
+            .bind(timestamp)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_audit_log_entries_before(&self, timestamp: i64) -> anyhow::Result<()> {
+        // language=SQLite
+        sqlx::query("DELETE FROM plugin_audit_log WHERE timestamp < ?1")
+            .bind(timestamp)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn remove_plugin(&self, plugin_id: &str) -> anyhow::Result<()> {
         // language=SQLite
         sqlx::query("DELETE FROM plugin WHERE id = ?1")
@@ -1027,16 +1585,23 @@ impl DataDbRepository {
     pub async fn save_plugin(&self, new_plugin: DbWritePlugin) -> anyhow::Result<()> {
         let mut tx = self.pool.begin().await?;
 
-        let (uuid, enabled, preferences_user_data) = self.get_plugin_by_id_option_with_executor(&new_plugin.id, &mut *tx).await?
-            .map(|plugin| (plugin.uuid, plugin.enabled, plugin.preferences_user_data))
-            .unwrap_or((Uuid::new_v4().to_string(), new_plugin.enabled, HashMap::new()));
+        let (uuid, enabled, preferences_user_data, old_content_hash) = self.get_plugin_by_id_option_with_executor(&new_plugin.id, &mut *tx).await?
+            .map(|plugin| (plugin.uuid, plugin.enabled, plugin.preferences_user_data, plugin.content_hash))
+            .unwrap_or((Uuid::new_v4().to_string(), new_plugin.enabled, HashMap::new(), None));
+
+        match old_content_hash {
+            Some(old_content_hash) if old_content_hash != new_plugin.content_hash => {
+                tracing::info!("Code of plugin {:?} has changed, previous content hash: {}, new content hash: {}", new_plugin.id, old_content_hash, new_plugin.content_hash);
+            }
+            _ => {}
+        }
 
         // language=SQLite
         let sql = r#"
-            INSERT INTO plugin (id, name, enabled, code, permissions, preferences, preferences_user_data, description, type, uuid)
-                VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            INSERT INTO plugin (id, name, enabled, code, permissions, preferences, preferences_user_data, description, type, uuid, revision, pinned_revision, content_hash, limits)
+                VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
                     ON CONFLICT (id)
-                        DO UPDATE SET name = ?2, enabled = ?3, code = ?4, permissions = ?5, preferences = ?6, preferences_user_data = ?7, description = ?8, type = ?9, uuid = ?10
+                        DO UPDATE SET name = ?2, enabled = ?3, code = ?4, permissions = ?5, preferences = ?6, preferences_user_data = ?7, description = ?8, type = ?9, uuid = ?10, revision = ?11, pinned_revision = ?12, content_hash = ?13, limits = ?14
         "#;
 
         sqlx::query(sql)
@@ -1050,6 +1615,10 @@ impl DataDbRepository {
             .bind(new_plugin.description)
             .bind(new_plugin.plugin_type)
             .bind(uuid)
+            .bind(new_plugin.revision)
+            .bind(new_plugin.pinned_revision)
+            .bind(new_plugin.content_hash)
+            .bind(Json(new_plugin.limits))
             .execute(&mut *tx)
             .await?;
 
@@ -1061,12 +1630,12 @@ impl DataDbRepository {
         for new_entrypoint in new_plugin.entrypoints {
             old_entrypoint_ids.remove(&new_entrypoint.id);
 
-            let (uuid, preferences_user_data, actions_user_data, enabled) = self.get_entrypoint_by_id_option_with_executor(&new_plugin.id, &new_entrypoint.id, &mut *tx).await?
-                .map(|entrypoint| (entrypoint.uuid, entrypoint.preferences_user_data, entrypoint.actions_user_data, entrypoint.enabled))
-                .unwrap_or((Uuid::new_v4().to_string(), HashMap::new(), vec![], true));
+            let (uuid, preferences_user_data, actions_user_data, aliases, enabled) = self.get_entrypoint_by_id_option_with_executor(&new_plugin.id, &new_entrypoint.id, &mut *tx).await?
+                .map(|entrypoint| (entrypoint.uuid, entrypoint.preferences_user_data, entrypoint.actions_user_data, entrypoint.aliases, entrypoint.enabled))
+                .unwrap_or((Uuid::new_v4().to_string(), HashMap::new(), vec![], vec![], true));
 
             // language=SQLite
-            sqlx::query("INSERT OR REPLACE INTO plugin_entrypoint (id, plugin_id, name, enabled, type, preferences, preferences_user_data, description, actions, actions_user_data, icon_path, uuid) VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)")
+            sqlx::query("INSERT OR REPLACE INTO plugin_entrypoint (id, plugin_id, name, enabled, type, preferences, preferences_user_data, description, actions, actions_user_data, icon_path, uuid, keywords, aliases, permissions) VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)")
                 .bind(&new_entrypoint.id)
                 .bind(&new_plugin.id)
                 .bind(new_entrypoint.name)
@@ -1079,6 +1648,9 @@ impl DataDbRepository {
                 .bind(Json(actions_user_data))
                 .bind(new_entrypoint.icon_path)
                 .bind(uuid)
+                .bind(Json(new_entrypoint.keywords))
+                .bind(Json(aliases))
+                .bind(new_entrypoint.permissions.map(|permissions| Json(permissions)))
                 .execute(&mut *tx)
                 .await?;
         }
@@ -1121,6 +1693,25 @@ impl DataDbRepository {
     }
 }
 
+fn query_frecency_by_prefix(stats: Vec<DbPluginEntrypointQueryFrecencyStats>) -> HashMap<String, HashMap<(String, String), f64>> {
+    let mut result: HashMap<String, HashMap<(String, String), f64>> = HashMap::new();
+
+    for stats in stats {
+        let frecency = FrecencyItemStats {
+            half_life: stats.half_life,
+            reference_time: stats.reference_time,
+            last_accessed: stats.last_accessed,
+            frecency: stats.frecency,
+            num_accesses: stats.num_accesses,
+        }.current_frecency();
+
+        result.entry(stats.query_prefix)
+            .or_default()
+            .insert((stats.plugin_id, stats.entrypoint_id), frecency);
+    }
+
+    result
+}
 
 pub fn db_entrypoint_to_str(value: DbPluginEntrypointType) -> &'static str {
     match value {
@@ -1142,6 +1733,27 @@ pub fn db_entrypoint_from_str(value: &str) -> DbPluginEntrypointType {
 }
 
 
+pub fn db_audit_log_capability_to_str(value: AuditLogCapability) -> &'static str {
+    match value {
+        AuditLogCapability::ClipboardRead => "clipboard_read",
+        AuditLogCapability::ClipboardWrite => "clipboard_write",
+        AuditLogCapability::Command => "command",
+        AuditLogCapability::Network => "network",
+        AuditLogCapability::FileWrite => "file_write",
+    }
+}
+
+pub fn db_audit_log_capability_from_str(value: &str) -> AuditLogCapability {
+    match value {
+        "clipboard_read" => AuditLogCapability::ClipboardRead,
+        "clipboard_write" => AuditLogCapability::ClipboardWrite,
+        "command" => AuditLogCapability::Command,
+        "network" => AuditLogCapability::Network,
+        "file_write" => AuditLogCapability::FileWrite,
+        _ => panic!("illegal audit log capability: {}", value)
+    }
+}
+
 pub fn db_plugin_type_to_str(value: DbPluginType) -> &'static str {
     match value {
         DbPluginType::Normal => "normal",
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-use gauntlet_common::model::{DownloadStatus, PluginId};
+use gauntlet_common::model::{DownloadStatus, PluginId, SettingsPluginPermission};
 
 pub struct DownloadStatusHolder {
     running_downloads: Arc<Mutex<HashMap<PluginId, DownloadStatus>>>
@@ -38,10 +38,10 @@ pub struct DownloadStatusGuard {
 }
 
 impl DownloadStatusGuard {
-    pub fn download_finished(&self) {
+    pub fn download_finished(&self, permissions: Vec<SettingsPluginPermission>) {
         let mut running_downloads = self.running_downloads.lock().expect("lock is poisoned");
 
-        running_downloads.insert(self.id.clone(), DownloadStatus::Done);
+        running_downloads.insert(self.id.clone(), DownloadStatus::Done { permissions });
 
         self.drop_eventually()
     }
@@ -8,15 +8,23 @@ pub struct FrecencyMetaParams {
     pub half_life: f64,
 }
 
-impl Default for FrecencyMetaParams {
-    fn default() -> FrecencyMetaParams {
+pub const DEFAULT_HALF_LIFE: f64 = 60.0 * 60.0 * 24.0 * 3.0; // three day half life
+
+impl FrecencyMetaParams {
+    pub fn with_half_life(half_life: f64) -> FrecencyMetaParams {
         FrecencyMetaParams {
             reference_time: current_time_secs(),
-            half_life: 60.0 * 60.0 * 24.0 * 3.0, // three day half life
+            half_life,
         }
     }
 }
 
+impl Default for FrecencyMetaParams {
+    fn default() -> FrecencyMetaParams {
+        FrecencyMetaParams::with_half_life(DEFAULT_HALF_LIFE)
+    }
+}
+
 
 #[derive(Clone)]
 pub struct FrecencyItemStats {
@@ -75,6 +83,11 @@ impl FrecencyItemStats {
         self.set_frecency(original_frecency + weight);
     }
 
+    /// Calculate the frecency of the item at the current time
+    pub fn current_frecency(&self) -> f64 {
+        self.get_frecency(current_time_secs())
+    }
+
     /// Update the number of accesses of the item by the given weight
     pub fn update_num_accesses(&mut self, weight: i32) {
         self.num_accesses += weight;
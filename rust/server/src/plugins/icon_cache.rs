@@ -1,48 +1,34 @@
+use std::path::Path;
 use anyhow::anyhow;
 use gauntlet_common::dirs::Dirs;
+use gauntlet_common::icon_store::IconStore;
 
+/// Entrypoint icons are saved to icon store shared with plugin runtime,
+/// unused icons are removed by eviction instead of when plugin is stopped
 #[derive(Clone)]
 pub struct IconCache {
-    dirs: Dirs,
+    store: IconStore,
 }
 
 impl IconCache {
     pub fn new(dirs: Dirs) -> Self {
         Self {
-            dirs
+            store: IconStore::new(&dirs)
         }
     }
 
-    pub fn clear_all_icon_cache_dir(&self) -> anyhow::Result<()> {
-        let cache_dir = self.dirs.icon_cache_dir();
-        std::fs::create_dir_all(&cache_dir)?;
-
-        if cache_dir.exists() {
-            std::fs::remove_dir_all(&cache_dir)?;
-        }
-
-        Ok(())
-    }
-
-    pub fn clear_plugin_icon_cache_dir(&self, plugin_uuid: &str) -> anyhow::Result<()> {
-        let cache_dir = self.dirs.icon_cache_dir();
-        let plugin_cache_dir = cache_dir.join(plugin_uuid);
-
-        if plugin_cache_dir.exists() {
-            std::fs::remove_dir_all(&plugin_cache_dir)?;
+    /// Icons that are still referenced are marked as used first,
+    /// because server only marks them when they are saved
+    pub fn evict_icon_cache(&self, referenced_icon_paths: Vec<String>) -> anyhow::Result<()> {
+        for path in referenced_icon_paths {
+            self.store.touch(Path::new(&path))?;
         }
 
-        Ok(())
+        self.store.evict()
     }
 
-    pub fn save_entrypoint_icon_to_cache(&self, plugin_uuid: &str, entrypoint_uuid: &str, data: impl AsRef<[u8]>) -> anyhow::Result<String> {
-        let cache_dir = self.dirs.icon_cache_dir();
-        let plugin_cache_dir = cache_dir.join(plugin_uuid);
-        std::fs::create_dir_all(&plugin_cache_dir)?;
-
-        let path_to_icon = plugin_cache_dir.join(format!("{}.png", &entrypoint_uuid));
-
-        std::fs::write(&path_to_icon, data).expect(&format!("unable to create icon file {:?}", &path_to_icon));
+    pub fn save_entrypoint_icon_to_cache(&self, data: impl AsRef<[u8]>) -> anyhow::Result<String> {
+        let path_to_icon = self.store.insert(data)?;
 
         let path_to_icon = path_to_icon.to_str()
             .ok_or(anyhow!("unable to convert {:?} to utf-8 while saving icon to cache", &path_to_icon))?;
@@ -50,5 +36,3 @@ impl IconCache {
         Ok(path_to_icon.to_string())
     }
 }
-
-
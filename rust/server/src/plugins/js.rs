@@ -1,5 +1,5 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::hash::Hash;
 use std::io;
@@ -9,7 +9,7 @@ use std::pin::Pin;
 use std::rc::Rc;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
 
 use anyhow::{anyhow, Context};
 use bytes::Bytes;
@@ -26,17 +26,18 @@ use tokio::sync::Mutex;
 use tokio::task::spawn_blocking;
 use tokio_util::sync::CancellationToken;
 use gauntlet_common::dirs::Dirs;
-use gauntlet_common::model::{EntrypointId, KeyboardEventOrigin, PhysicalKey, PluginId, RootWidget, SearchResultAccessory, SearchResultEntrypointType, UiPropertyValue, UiRenderLocation, UiWidgetId};
+use gauntlet_common::model::{AuditLogCapability, EntrypointId, KeyboardEventOrigin, PermissionPromptResult, PhysicalKey, PluginId, RootWidget, SearchResultAccessory, SearchResultEntrypointType, UiPropertyValue, UiRenderLocation, UiWidgetId};
 use gauntlet_common::rpc::frontend_api::FrontendApi;
 use gauntlet_common::settings_env_data_to_string;
-use gauntlet_plugin_runtime::{recv_message, send_message, BackendForPluginRuntimeApi, JsGeneratedSearchItem, JsClipboardData, JsInit, JsKeyboardEventOrigin, JsPluginCode, JsPluginPermissions, JsPreferenceUserData, JsEvent, JsUiPropertyValue, JsRequest, JsUiRenderLocation, JsResponse, JsMessage, JsPluginPermissionsFileSystem, JsPluginPermissionsExec, JsPluginPermissionsMainSearchBar, JsMessageSide, JsPluginRuntimeMessage, JsGeneratedSearchItemAccessory, JsGeneratedSearchItemActionType};
+use gauntlet_plugin_runtime::{recv_message, send_message, BackendForPluginRuntimeApi, JsAuditCapability, JsGeneratedSearchItem, JsClipboardData, JsInit, JsKeyboardEventOrigin, JsPluginCode, JsPluginInspector, JsPluginLimits, JsPluginPermissions, JsPreferenceUserData, JsEvent, JsUiPropertyValue, JsRequest, JsUiRenderLocation, JsResponse, JsMessage, JsPluginPermissionsFileSystem, JsPluginPermissionsExec, JsPluginPermissionsMainSearchBar, JsPluginPermissionsWindows, JsMessageSide, JsPluginRuntimeMessage, JsGeneratedSearchItemAccessory, JsGeneratedSearchItemActionType};
 use crate::model::{IntermediateUiEvent};
 use crate::plugins::clipboard::Clipboard;
-use crate::plugins::data_db_repository::{db_entrypoint_from_str, DataDbRepository, DbPluginClipboardPermissions, DbPluginEntrypointType, DbPluginPreference, DbPluginPreferenceUserData, DbReadPlugin, DbReadPluginEntrypoint};
+use crate::plugins::data_db_repository::{db_audit_log_capability_to_str, db_entrypoint_from_str, DataDbRepository, DbPluginClipboardPermissions, DbPluginEntrypointType, DbPluginPreference, DbPluginPreferenceUserData, DbReadPlugin, DbReadPluginEntrypoint};
 use crate::plugins::icon_cache::IconCache;
 use crate::plugins::run_status::RunStatusGuard;
 use crate::search::{SearchIndex, SearchIndexItem, SearchIndexItemAction, SearchIndexItemActionActionType};
 use crate::{PLUGIN_RUNTIME_ENV, SETTINGS_ENV};
+use gauntlet_plugin_runtime::{EXIT_CODE_EVENT_LOOP_TIMEOUT, EXIT_CODE_HEAP_LIMIT};
 use crate::plugins::image_gatherer::ImageGatherer;
 
 pub struct PluginRuntimeData {
@@ -45,8 +46,14 @@ pub struct PluginRuntimeData {
     pub name: String,
     pub entrypoint_names: HashMap<EntrypointId, String>,
     pub code: JsPluginCode,
+    pub code_version: String,
     pub inline_view_entrypoint_id: Option<String>,
+    pub entrypoints: PluginRuntimeEntrypoints,
     pub permissions: PluginPermissions,
+    pub limits: JsPluginLimits,
+    pub inspector: Option<JsPluginInspector>,
+    pub permission_prompts: bool,
+    pub audit_log: bool,
     pub command_receiver: tokio::sync::broadcast::Receiver<PluginCommand>,
     pub db_repository: DataDbRepository,
     pub search_index: SearchIndex,
@@ -56,6 +63,37 @@ pub struct PluginRuntimeData {
     pub clipboard: Clipboard,
 }
 
+/// Entrypoints handled by plugin runtime. Entrypoints that declare their own permissions in manifest
+/// are run in separate runtime processes, the rest of the plugin is run in main runtime
+#[derive(Clone, Debug)]
+pub enum PluginRuntimeEntrypoints {
+    Main {
+        isolated: HashSet<EntrypointId>,
+    },
+    Isolated(EntrypointId),
+}
+
+impl PluginRuntimeEntrypoints {
+    fn handles(&self, entrypoint_id: &EntrypointId) -> bool {
+        match self {
+            PluginRuntimeEntrypoints::Main { isolated } => !isolated.contains(entrypoint_id),
+            PluginRuntimeEntrypoints::Isolated(isolated_entrypoint_id) => isolated_entrypoint_id == entrypoint_id,
+        }
+    }
+
+    fn is_main(&self) -> bool {
+        matches!(self, PluginRuntimeEntrypoints::Main { .. })
+    }
+
+    /// Unique name of the runtime among runtimes of all plugins, used for socket and log file names
+    fn runtime_name(&self, plugin_uuid: &str) -> String {
+        match self {
+            PluginRuntimeEntrypoints::Main { .. } => plugin_uuid.to_string(),
+            PluginRuntimeEntrypoints::Isolated(entrypoint_id) => format!("{}-{}", plugin_uuid, entrypoint_id),
+        }
+    }
+}
+
 pub struct PluginPermissions {
     pub environment: Vec<String>,
     pub network: Vec<String>,
@@ -64,6 +102,7 @@ pub struct PluginPermissions {
     pub system: Vec<String>,
     pub clipboard: Vec<PluginPermissionsClipboard>,
     pub main_search_bar: Vec<JsPluginPermissionsMainSearchBar>,
+    pub windows: Vec<JsPluginPermissionsWindows>,
 }
 
 #[derive(Clone, Debug)]
@@ -117,7 +156,6 @@ pub enum OnePluginCommandData {
         modifier_meta: bool,
     },
     ReloadSearchIndex,
-    RefreshSearchIndex,
 }
 
 #[derive(Clone, Debug)]
@@ -127,34 +165,57 @@ pub enum AllPluginCommandData {
     }
 }
 
-pub async fn start_plugin_runtime(data: PluginRuntimeData, run_status_guard: RunStatusGuard) -> anyhow::Result<()> {
+// repeated use of the same resource, e.g. writing to the same file, is recorded once per interval
+const AUDIT_LOG_DEDUPLICATION_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Runs plugin runtime process until it exits, returns exit code of the process
+pub async fn start_plugin_runtime(data: PluginRuntimeData, run_status_guard: &RunStatusGuard) -> anyhow::Result<Option<i32>> {
+
+    // each runtime process of the plugin gets its own copy, so isolated runtimes starting at the same time don't interfere
+    let plugin_assets_dir = data.dirs.plugin_assets(&data.entrypoints.runtime_name(&data.uuid))?;
+
+    if uses_plugin_assets(&data.permissions) {
+        write_plugin_assets(&data.db_repository, &data.id.to_string(), &plugin_assets_dir, &data.permissions.exec.executable).await
+            .context("Unable to write plugin assets")?;
+    }
 
     let runtime_permissions = PluginRuntimePermissions {
         clipboard: data.permissions.clipboard,
     };
 
+    // entrypoint that handled the most recent event, used to attribute capability use in audit log
+    let active_entrypoint = Arc::new(std::sync::Mutex::new(None));
+
     let api = BackendForPluginRuntimeApiImpl::new(
         data.icon_cache.clone(),
         data.db_repository,
         data.search_index,
         data.clipboard,
-        data.frontend_api,
-        data.uuid.clone(),
+        data.frontend_api.clone(),
         data.id.clone(),
         data.name,
+        data.code_version,
         runtime_permissions,
+        data.audit_log,
+        active_entrypoint.clone(),
+        data.entrypoints.clone(),
     );
 
     let mut command_receiver = data.command_receiver;
-    let cache = data.icon_cache;
+    let frontend_api = data.frontend_api;
+    let limits = data.limits;
     let plugin_uuid = data.uuid.clone();
     let plugin_id = data.id.clone();
+    let inline_view_entrypoint_id = data.inline_view_entrypoint_id.clone()
+        .map(|id| EntrypointId::from_string(id));
+    let entrypoints = data.entrypoints;
+    let runtime_name = entrypoints.runtime_name(&plugin_uuid);
 
     let plugin_id_str = plugin_id.to_string();
     let dev_plugin = plugin_id_str.starts_with("file://");
 
     let (stdout_file, stderr_file) = if dev_plugin {
-        let (stdout_file, stderr_file) = data.dirs.plugin_log_files(&plugin_uuid);
+        let (stdout_file, stderr_file) = data.dirs.plugin_log_files(&runtime_name);
 
         let stdout_file = stdout_file
             .to_str()
@@ -172,12 +233,12 @@ pub async fn start_plugin_runtime(data: PluginRuntimeData, run_status_guard: Run
 
     let home_dir = data.dirs.home_dir();
     let local_storage_dir = data.dirs.plugin_local_storage(&plugin_uuid);
-    let uds_socket_file = data.dirs.plugin_uds_socket(&plugin_uuid);
+    let uds_socket_file = data.dirs.plugin_uds_socket(&runtime_name);
     let plugin_cache_dir = data.dirs.plugin_cache(&plugin_uuid)?;
     let plugin_data_dir = data.dirs.plugin_data(&plugin_uuid)?;
 
     #[cfg(target_os = "windows")]
-    let name_str = format!("project-gauntlet-{}", plugin_uuid);
+    let name_str = format!("project-gauntlet-{}", runtime_name);
 
     #[cfg(unix)]
     let name_str = uds_socket_file.clone();
@@ -230,6 +291,11 @@ pub async fn start_plugin_runtime(data: PluginRuntimeData, run_status_guard: Run
         .context("non-uft8 paths are not supported")?
         .to_string();
 
+    let plugin_assets_dir = plugin_assets_dir
+        .to_str()
+        .context("non-uft8 paths are not supported")?
+        .to_string();
+
     let permissions = JsPluginPermissions {
         environment: data.permissions.environment,
         network: data.permissions.network,
@@ -237,6 +303,7 @@ pub async fn start_plugin_runtime(data: PluginRuntimeData, run_status_guard: Run
         exec: data.permissions.exec,
         system: data.permissions.system,
         main_search_bar: data.permissions.main_search_bar,
+        windows: data.permissions.windows,
     };
 
     let init = JsInit {
@@ -244,29 +311,44 @@ pub async fn start_plugin_runtime(data: PluginRuntimeData, run_status_guard: Run
         plugin_uuid: plugin_uuid.clone(),
         code: data.code,
         permissions,
-        inline_view_entrypoint_id: data.inline_view_entrypoint_id,
+        // inline view is rendered only by runtime that handles its entrypoint
+        inline_view_entrypoint_id: inline_view_entrypoint_id.as_ref()
+            .filter(|entrypoint_id| entrypoints.handles(entrypoint_id))
+            .map(|entrypoint_id| entrypoint_id.to_string()),
         entrypoint_names: data.entrypoint_names,
         dev_plugin,
         home_dir,
         local_storage_dir,
         plugin_cache_dir,
         plugin_data_dir,
+        plugin_assets_dir,
         stdout_file,
         stderr_file,
+        limits: limits.clone(),
+        inspector: data.inspector,
+        permission_prompts: data.permission_prompts,
+        audit_log: data.audit_log,
     };
 
     let current_exe = std::env::current_exe()
         .context("unable to get current_exe")?;
 
     #[cfg(not(feature = "scenario_runner"))]
-    let mut runtime_process = std::process::Command::new(current_exe)
-        .env(PLUGIN_RUNTIME_ENV, name_str)
-        .spawn()
-        .context("start plugin runtime process")?;
+    let mut runtime_process = {
+        let mut command = std::process::Command::new(current_exe);
+
+        command.env(PLUGIN_RUNTIME_ENV, name_str);
+
+        #[cfg(target_os = "linux")]
+        set_process_limits(&mut command, &limits)?;
+
+        command.spawn()
+            .context("start plugin runtime process")?
+    };
 
     // use only for debugging and scenario_runner, only works if only one plugin is enabled
     #[cfg(feature = "scenario_runner")]
-    std::thread::spawn(move || {
+    let runtime_thread = std::thread::spawn(move || {
         gauntlet_plugin_runtime::run_plugin_runtime(name_str.to_str().unwrap().to_string())
     });
 
@@ -281,10 +363,14 @@ pub async fn start_plugin_runtime(data: PluginRuntimeData, run_status_guard: Run
 
     let sender = Arc::new(Mutex::new(sender));
 
-    tokio::task::spawn({
+    // entrypoint of the view that is currently opened, used to show limit violations
+    let open_view = std::sync::Mutex::new(None);
+
+    let stop_handle = tokio::task::spawn({
         let sender = sender.clone();
+        let stopped = run_status_guard.stopped();
         async move {
-            run_status_guard.stopped().await;
+            stopped.await;
 
             tracing::info!("Requesting plugin runtime to stop...");
 
@@ -299,9 +385,14 @@ pub async fn start_plugin_runtime(data: PluginRuntimeData, run_status_guard: Run
         result = {
             let sender = sender.clone();
             let plugin_id = plugin_id.clone();
+            let open_view = &open_view;
+            let active_entrypoint = &active_entrypoint;
+            let inline_view_entrypoint_id = &inline_view_entrypoint_id;
+            let entrypoints = &entrypoints;
             tokio::task::unconstrained(async move {
+                let mut view_entrypoint = None;
                 loop {
-                    if let Err(err) = event_loop(&mut command_receiver, &sender, plugin_id.clone()).await {
+                    if let Err(err) = event_loop(&mut command_receiver, &sender, plugin_id.clone(), entrypoints, &mut view_entrypoint, open_view, active_entrypoint, inline_view_entrypoint_id).await {
                         tracing::error!("Event loop faced an error {:?}", err);
                         break;
                     }
@@ -333,17 +424,32 @@ pub async fn start_plugin_runtime(data: PluginRuntimeData, run_status_guard: Run
         }
     }
 
-    drop((recver, sender));
+    // runtime could have exited on its own, in that case there is nobody to send stop request to
+    stop_handle.abort();
 
-    if let Err(err) = cache.clear_plugin_icon_cache_dir(&plugin_uuid) {
-        tracing::error!(target = "plugin", "plugin {:?} unable to cleanup icon cache {:?}", plugin_id, err)
-    }
+    drop((recver, sender));
 
     #[cfg(not(feature = "scenario_runner"))]
-    {
-        let code = runtime_process.wait()
-            .context("Error while waiting for JS runtime process to finish")?
-            .code();
+    let code = {
+        let status = runtime_process.wait()
+            .context("Error while waiting for JS runtime process to finish")?;
+
+        if let Some(reason) = termination_reason(&status, &limits) {
+            tracing::error!(target = "plugin", "plugin {:?} was terminated: {}", plugin_id, reason);
+
+            let open_view = open_view.lock().expect("lock is poisoned").take();
+
+            let result = match open_view {
+                Some(entrypoint_id) => frontend_api.show_plugin_error_view(plugin_id.clone(), entrypoint_id, UiRenderLocation::View, Some(reason)).await,
+                None => frontend_api.show_hud(reason).await,
+            };
+
+            if let Err(err) = result {
+                tracing::error!("Unable to report termination of plugin {:?} to frontend {:?}", plugin_id, err);
+            }
+        }
+
+        let code = status.code();
 
         match code {
             Some(code) => {
@@ -355,14 +461,174 @@ pub async fn start_plugin_runtime(data: PluginRuntimeData, run_status_guard: Run
             },
             None => tracing::error!("Process terminated by signal")
         }
+
+        code
+    };
+
+    // runtime is run in the same process, so limit violation is reported by returning error instead of exit code
+    #[cfg(feature = "scenario_runner")]
+    let code = {
+        let result = spawn_blocking(move || runtime_thread.join()).await?
+            .map_err(|_| anyhow!("Plugin runtime thread panicked"))?;
+
+        if let Err(limit_violation) = result {
+            let reason = format!("Plugin was terminated: {}", limit_violation);
+
+            let open_view = open_view.lock().expect("lock is poisoned").take();
+
+            if let Some(entrypoint_id) = open_view {
+                frontend_api.show_plugin_error_view(plugin_id.clone(), entrypoint_id, UiRenderLocation::View, Some(reason.clone())).await?;
+            }
+
+            Err(anyhow!("Plugin runtime of {:?} was stopped: {}", plugin_id, limit_violation))?
+        }
+
+        None
+    };
+
+    Ok(code)
+}
+
+/// Returns human-readable reason if runtime process was terminated because of violating one of the resource limits
+/// or because it has crashed
+#[cfg(not(feature = "scenario_runner"))]
+fn termination_reason(status: &std::process::ExitStatus, limits: &JsPluginLimits) -> Option<String> {
+    match status.code() {
+        Some(EXIT_CODE_HEAP_LIMIT) => {
+            let heap_mb = limits.heap_mb?;
+            return Some(format!("Plugin exceeded JavaScript heap limit of {} MB", heap_mb))
+        }
+        Some(EXIT_CODE_EVENT_LOOP_TIMEOUT) => {
+            let timeout = limits.event_loop_timeout_secs?;
+            return Some(format!("Plugin was blocking its event loop for longer than {} seconds", timeout))
+        }
+        _ => {}
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::process::ExitStatusExt;
+
+        match status.signal() {
+            Some(libc::SIGXCPU) => {
+                let cpu_seconds = limits.cpu_seconds?;
+                return Some(format!("Plugin exceeded CPU time limit of {} seconds", cpu_seconds))
+            }
+            // v8 and rust allocator abort the process when they are unable to allocate memory,
+            // but so do failed assertions, so running out of memory is only assumed if memory is limited
+            Some(signal @ (libc::SIGABRT | libc::SIGTRAP)) => {
+                let reason = match (limits.address_space_mb, limits.heap_mb) {
+                    (Some(address_space_mb), _) => format!("Plugin most likely exceeded memory limit of {} MB", address_space_mb),
+                    (None, Some(heap_mb)) => format!("Plugin most likely exceeded JavaScript heap limit of {} MB", heap_mb),
+                    (None, None) => format!("Plugin crashed, runtime process was aborted with signal {}", signal),
+                };
+
+                return Some(reason)
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, PartialEq)]
+struct ProcessLimits {
+    address_space: Option<u64>,
+    // soft and hard limits
+    cpu_seconds: Option<(u64, u64)>,
+}
+
+#[cfg(target_os = "linux")]
+fn process_limits(limits: &JsPluginLimits) -> anyhow::Result<ProcessLimits> {
+    let address_space = limits.address_space_mb
+        .map(|address_space_mb| {
+            address_space_mb.checked_mul(1024 * 1024)
+                .ok_or(anyhow!("Address space limit of {} MB is too large", address_space_mb))
+        })
+        .transpose()?;
+
+    // RLIMIT_CPU counts CPU time used over the whole lifetime of the process, not per event or request,
+    // so long-running plugin will eventually reach it and will be restarted.
+    // SIGXCPU is sent when soft limit is reached, SIGKILL when hard limit is reached
+    let cpu_seconds = limits.cpu_seconds
+        .map(|cpu_seconds| {
+            let hard_limit = cpu_seconds.checked_add(5)
+                .ok_or(anyhow!("CPU time limit of {} seconds is too large", cpu_seconds))?;
+
+            anyhow::Ok((cpu_seconds, hard_limit))
+        })
+        .transpose()?;
+
+    Ok(ProcessLimits {
+        address_space,
+        cpu_seconds,
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn set_process_limits(command: &mut std::process::Command, limits: &JsPluginLimits) -> anyhow::Result<()> {
+    use std::os::unix::process::CommandExt;
+
+    let ProcessLimits { address_space, cpu_seconds } = process_limits(limits)?;
+
+    if address_space.is_none() && cpu_seconds.is_none() {
+        return Ok(());
+    }
+
+    unsafe {
+        command.pre_exec(move || {
+            if let Some(address_space) = address_space {
+                let limit = libc::rlimit {
+                    rlim_cur: address_space as libc::rlim_t,
+                    rlim_max: address_space as libc::rlim_t,
+                };
+
+                if libc::setrlimit(libc::RLIMIT_AS, &limit) == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+
+            if let Some((soft_limit, hard_limit)) = cpu_seconds {
+                let limit = libc::rlimit {
+                    rlim_cur: soft_limit as libc::rlim_t,
+                    rlim_max: hard_limit as libc::rlim_t,
+                };
+
+                if libc::setrlimit(libc::RLIMIT_CPU, &limit) == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+
+            Ok(())
+        });
     }
 
     Ok(())
 }
 
-async fn event_loop(command_receiver: &mut tokio::sync::broadcast::Receiver<PluginCommand>, send: &Mutex<SendHalf>, plugin_id: PluginId) -> anyhow::Result<()>  {
+async fn event_loop(
+    command_receiver: &mut tokio::sync::broadcast::Receiver<PluginCommand>,
+    send: &Mutex<SendHalf>,
+    plugin_id: PluginId,
+    entrypoints: &PluginRuntimeEntrypoints,
+    view_entrypoint: &mut Option<EntrypointId>,
+    open_view: &std::sync::Mutex<Option<EntrypointId>>,
+    active_entrypoint: &std::sync::Mutex<Option<EntrypointId>>,
+    inline_view_entrypoint_id: &Option<EntrypointId>,
+) -> anyhow::Result<()>  {
     let command = command_receiver.recv().await?;
 
+    // every runtime of the plugin receives all commands and tracks which entrypoint has view shown,
+    // view events don't specify entrypoint and are handled by runtime that rendered that view
+    let handles_view = |view_entrypoint: &Option<EntrypointId>| {
+        match view_entrypoint {
+            Some(entrypoint_id) => entrypoints.handles(entrypoint_id),
+            None => entrypoints.is_main(),
+        }
+    };
+
     let event = match command {
         PluginCommand::One { id, data } => {
             if id != plugin_id {
@@ -370,47 +636,103 @@ async fn event_loop(command_receiver: &mut tokio::sync::broadcast::Receiver<Plug
             } else {
                 match data {
                     OnePluginCommandData::RenderView { entrypoint_id } => {
-                        Some(IntermediateUiEvent::OpenView {
-                            entrypoint_id,
-                        })
+                        *view_entrypoint = Some(entrypoint_id.clone());
+
+                        if entrypoints.handles(&entrypoint_id) {
+                            *open_view.lock().expect("lock is poisoned") = Some(entrypoint_id.clone());
+                            *active_entrypoint.lock().expect("lock is poisoned") = Some(entrypoint_id.clone());
+
+                            Some(IntermediateUiEvent::OpenView {
+                                entrypoint_id,
+                            })
+                        } else {
+                            None
+                        }
                     }
                     OnePluginCommandData::CloseView => {
-                        Some(IntermediateUiEvent::CloseView)
+                        let handles = handles_view(view_entrypoint);
+
+                        // after view is closed main search list with inline view is shown again
+                        *view_entrypoint = inline_view_entrypoint_id.clone();
+
+                        if handles {
+                            *open_view.lock().expect("lock is poisoned") = None;
+                            *active_entrypoint.lock().expect("lock is poisoned") = None;
+
+                            Some(IntermediateUiEvent::CloseView)
+                        } else {
+                            None
+                        }
                     }
                     OnePluginCommandData::RunCommand { entrypoint_id } => {
-                        Some(IntermediateUiEvent::RunCommand {
-                            entrypoint_id,
-                        })
+                        if entrypoints.handles(&EntrypointId::from_string(&entrypoint_id)) {
+                            *active_entrypoint.lock().expect("lock is poisoned") = Some(EntrypointId::from_string(&entrypoint_id));
+
+                            Some(IntermediateUiEvent::RunCommand {
+                                entrypoint_id,
+                            })
+                        } else {
+                            None
+                        }
                     }
                     OnePluginCommandData::RunGeneratedCommand { entrypoint_id, action_index } => {
-                        Some(IntermediateUiEvent::RunGeneratedCommand {
-                            entrypoint_id,
-                            action_index
-                        })
+                        // generated command can render a view
+                        *view_entrypoint = Some(EntrypointId::from_string(&entrypoint_id));
+
+                        if entrypoints.handles(&EntrypointId::from_string(&entrypoint_id)) {
+                            *active_entrypoint.lock().expect("lock is poisoned") = Some(EntrypointId::from_string(&entrypoint_id));
+
+                            Some(IntermediateUiEvent::RunGeneratedCommand {
+                                entrypoint_id,
+                                action_index
+                            })
+                        } else {
+                            None
+                        }
                     }
                     OnePluginCommandData::HandleViewEvent { widget_id, event_name, event_arguments } => {
-                        Some(IntermediateUiEvent::HandleViewEvent {
-                            widget_id,
-                            event_name,
-                            event_arguments,
-                        })
+                        if handles_view(view_entrypoint) {
+                            Some(IntermediateUiEvent::HandleViewEvent {
+                                widget_id,
+                                event_name,
+                                event_arguments,
+                            })
+                        } else {
+                            None
+                        }
                     }
                     OnePluginCommandData::HandleKeyboardEvent { entrypoint_id, origin, key, modifier_shift, modifier_control, modifier_alt, modifier_meta } => {
-                        Some(IntermediateUiEvent::HandleKeyboardEvent {
-                            entrypoint_id,
-                            origin,
-                            key,
-                            modifier_shift,
-                            modifier_control,
-                            modifier_alt,
-                            modifier_meta
-                        })
+                        // action of generated command in main view can render a view
+                        if matches!(origin, KeyboardEventOrigin::MainView) {
+                            *view_entrypoint = Some(entrypoint_id.clone());
+                        }
+
+                        if entrypoints.handles(&entrypoint_id) {
+                            *active_entrypoint.lock().expect("lock is poisoned") = Some(entrypoint_id.clone());
+
+                            Some(IntermediateUiEvent::HandleKeyboardEvent {
+                                entrypoint_id,
+                                origin,
+                                key,
+                                modifier_shift,
+                                modifier_control,
+                                modifier_alt,
+                                modifier_meta
+                            })
+                        } else {
+                            None
+                        }
                     }
                     OnePluginCommandData::ReloadSearchIndex => {
-                        Some(IntermediateUiEvent::ReloadSearchIndex)
-                    }
-                    OnePluginCommandData::RefreshSearchIndex => {
-                        Some(IntermediateUiEvent::RefreshSearchIndex)
+                        // entrypoint generators are run only in main runtime
+                        if entrypoints.is_main() {
+                            // entrypoint generators are not attributed to a specific entrypoint
+                            *active_entrypoint.lock().expect("lock is poisoned") = None;
+
+                            Some(IntermediateUiEvent::ReloadSearchIndex)
+                        } else {
+                            None
+                        }
                     }
                 }
             }
@@ -418,7 +740,17 @@ async fn event_loop(command_receiver: &mut tokio::sync::broadcast::Receiver<Plug
         PluginCommand::All { data } => {
             match data {
                 AllPluginCommandData::OpenInlineView { text } => {
-                    Some(IntermediateUiEvent::OpenInlineView { text })
+                    *view_entrypoint = inline_view_entrypoint_id.clone();
+
+                    if handles_view(inline_view_entrypoint_id) {
+                        if let Some(inline_view_entrypoint_id) = inline_view_entrypoint_id {
+                            *active_entrypoint.lock().expect("lock is poisoned") = Some(inline_view_entrypoint_id.clone());
+                        }
+
+                        Some(IntermediateUiEvent::OpenInlineView { text })
+                    } else {
+                        None
+                    }
                 }
             }
         }
@@ -519,6 +851,11 @@ async fn handle_message(message: JsRequest, api: &BackendForPluginRuntimeApiImpl
 
             Ok(JsResponse::Nothing)
         }
+        JsRequest::UpdateSearchIndex { updated_generated_commands, removed_generated_entrypoint_ids, refresh_search_list } => {
+            api.update_search_index(updated_generated_commands, removed_generated_entrypoint_ids, refresh_search_list).await?;
+
+            Ok(JsResponse::Nothing)
+        }
         JsRequest::GetAssetData { path } => {
             let data = api.get_asset_data(&path).await?;
 
@@ -604,6 +941,18 @@ async fn handle_message(message: JsRequest, api: &BackendForPluginRuntimeApiImpl
                 data
             })
         }
+        JsRequest::RequestPermission { permission, value } => {
+            let granted = api.request_permission(permission, value).await?;
+
+            Ok(JsResponse::PermissionRequested {
+                granted
+            })
+        }
+        JsRequest::RecordCapabilityUse { capability, value } => {
+            api.record_capability_use(capability, value).await?;
+
+            Ok(JsResponse::Nothing)
+        }
     }
 }
 
@@ -655,7 +1004,6 @@ fn from_intermediate_to_js_event(event: IntermediateUiEvent) -> JsEvent {
         }
         IntermediateUiEvent::OpenInlineView { text } => JsEvent::OpenInlineView { text },
         IntermediateUiEvent::ReloadSearchIndex => JsEvent::ReloadSearchIndex,
-        IntermediateUiEvent::RefreshSearchIndex => JsEvent::RefreshSearchIndex,
     }
 }
 
@@ -666,10 +1014,14 @@ pub struct BackendForPluginRuntimeApiImpl {
     search_index: SearchIndex,
     clipboard: Clipboard,
     frontend_api: FrontendApi,
-    plugin_uuid: String,
     plugin_id: PluginId,
     plugin_name: String,
-    permissions: PluginRuntimePermissions
+    plugin_code_version: String,
+    permissions: PluginRuntimePermissions,
+    audit_log: bool,
+    active_entrypoint: Arc<std::sync::Mutex<Option<EntrypointId>>>,
+    audit_log_last_recorded: Arc<std::sync::Mutex<HashMap<(Option<EntrypointId>, AuditLogCapability, String), Instant>>>,
+    entrypoints: PluginRuntimeEntrypoints,
 }
 
 impl BackendForPluginRuntimeApiImpl {
@@ -679,10 +1031,13 @@ impl BackendForPluginRuntimeApiImpl {
         search_index: SearchIndex,
         clipboard: Clipboard,
         frontend_api: FrontendApi,
-        plugin_uuid: String,
         plugin_id: PluginId,
         plugin_name: String,
-        permissions: PluginRuntimePermissions
+        plugin_code_version: String,
+        permissions: PluginRuntimePermissions,
+        audit_log: bool,
+        active_entrypoint: Arc<std::sync::Mutex<Option<EntrypointId>>>,
+        entrypoints: PluginRuntimeEntrypoints,
     ) -> Self {
         Self {
             icon_cache,
@@ -690,23 +1045,61 @@ impl BackendForPluginRuntimeApiImpl {
             search_index,
             clipboard,
             frontend_api,
-            plugin_uuid,
             plugin_id,
             plugin_name,
-            permissions
+            plugin_code_version,
+            permissions,
+            audit_log,
+            active_entrypoint,
+            audit_log_last_recorded: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            entrypoints,
         }
     }
-}
 
-impl BackendForPluginRuntimeApi for BackendForPluginRuntimeApiImpl {
-    async fn reload_search_index(&self, generated_commands: Vec<JsGeneratedSearchItem>, refresh_search_list: bool) -> anyhow::Result<()> {
-        self.icon_cache.clear_plugin_icon_cache_dir(&self.plugin_uuid)
-            .context("error when clearing up icon cache before recreating it")?;
+    /// Saves capability use to audit log, attributed to entrypoint that handled the most recent event.
+    /// Same use by the same entrypoint is recorded at most once per deduplication interval
+    async fn record_audit_log_entry(&self, capability: AuditLogCapability, value: String) {
+        if !self.audit_log {
+            return;
+        }
 
-        let DbReadPlugin { name, .. } = self.repository.get_plugin_by_id(&self.plugin_id.to_string())
-            .await
-            .context("error when getting plugin by id")?;
+        let entrypoint_id = self.active_entrypoint.lock().expect("lock is poisoned").clone();
+
+        {
+            let mut last_recorded = self.audit_log_last_recorded.lock().expect("lock is poisoned");
+
+            last_recorded.retain(|_, recorded_at| recorded_at.elapsed() < AUDIT_LOG_DEDUPLICATION_INTERVAL);
+
+            let key = (entrypoint_id.clone(), capability, value.clone());
+
+            if last_recorded.contains_key(&key) {
+                return;
+            }
+
+            last_recorded.insert(key, Instant::now());
+        }
 
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("failed to get system time")
+            .as_secs() as i64;
+
+        let entrypoint_id = entrypoint_id.map(|id| id.to_string());
+
+        let result = self.repository.save_audit_log_entry(
+            &self.plugin_id.to_string(),
+            entrypoint_id.as_deref(),
+            db_audit_log_capability_to_str(capability),
+            &value,
+            timestamp,
+        ).await;
+
+        if let Err(err) = result {
+            tracing::warn!("Unable to save audit log entry for plugin {:?}: {:?}", self.plugin_id, err);
+        }
+    }
+
+    async fn generated_search_items(&self, generated_commands: Vec<JsGeneratedSearchItem>) -> anyhow::Result<Vec<SearchIndexItem>> {
         let entrypoints = self.repository.get_entrypoints_by_plugin_id(&self.plugin_id.to_string())
             .await
             .context("error when getting entrypoints by plugin id")?;
@@ -715,6 +1108,10 @@ impl BackendForPluginRuntimeApi for BackendForPluginRuntimeApiImpl {
             .await
             .context("error when getting frecency for plugin")?;
 
+        let mut aliases_map = self.repository.get_generated_entrypoint_aliases(&self.plugin_id.to_string())
+            .await
+            .context("error when getting generated entrypoint aliases for plugin")?;
+
         let mut shortcuts = HashMap::new();
 
         for DbReadPluginEntrypoint { id, .. } in &entrypoints {
@@ -727,15 +1124,17 @@ impl BackendForPluginRuntimeApi for BackendForPluginRuntimeApiImpl {
             .map(|entrypoint| (entrypoint.id.clone(), entrypoint.name.clone()))
             .collect();
 
-        let mut generated_search_items = generated_commands.into_iter()
+        generated_commands.into_iter()
             .map(|item| {
                 let entrypoint_icon_path = match item.entrypoint_icon {
                     None => None,
-                    Some(data) => Some(self.icon_cache.save_entrypoint_icon_to_cache(&self.plugin_uuid, &item.entrypoint_uuid, &data)?),
+                    Some(data) => Some(self.icon_cache.save_entrypoint_icon_to_cache(&data)?),
                 };
 
                 let entrypoint_frecency = frecency_map.get(&item.entrypoint_id).cloned().unwrap_or(0.0);
 
+                let entrypoint_aliases = aliases_map.remove(&item.entrypoint_id).unwrap_or_default();
+
                 let shortcuts = shortcuts
                     .get(&item.generator_entrypoint_id);
 
@@ -785,9 +1184,23 @@ impl BackendForPluginRuntimeApi for BackendForPluginRuntimeApiImpl {
                     entrypoint_actions,
                     entrypoint_accessories,
                     entrypoint_generator_name,
+                    entrypoint_keywords: item.entrypoint_keywords,
+                    entrypoint_description: None,
+                    entrypoint_aliases,
                 })
             })
-            .collect::<anyhow::Result<Vec<_>>>()?;
+            .collect::<anyhow::Result<Vec<_>>>()
+    }
+
+    /// Returns search items for enabled builtin entrypoints and ids of disabled ones
+    async fn builtin_search_items(&self) -> anyhow::Result<(Vec<SearchIndexItem>, Vec<EntrypointId>)> {
+        let entrypoints = self.repository.get_entrypoints_by_plugin_id(&self.plugin_id.to_string())
+            .await
+            .context("error when getting entrypoints by plugin id")?;
+
+        let frecency_map = self.repository.get_frecency_for_plugin(&self.plugin_id.to_string())
+            .await
+            .context("error when getting frecency for plugin")?;
 
         let mut icon_asset_data = HashMap::new();
 
@@ -802,8 +1215,14 @@ impl BackendForPluginRuntimeApi for BackendForPluginRuntimeApiImpl {
             }
         }
 
-        let mut builtin_search_items = entrypoints.into_iter()
-            .filter(|entrypoint| entrypoint.enabled)
+        let (enabled_entrypoints, disabled_entrypoints): (Vec<_>, Vec<_>) = entrypoints.into_iter()
+            .partition(|entrypoint| entrypoint.enabled);
+
+        let disabled_entrypoint_ids = disabled_entrypoints.into_iter()
+            .map(|entrypoint| EntrypointId::from_string(entrypoint.id))
+            .collect();
+
+        let builtin_search_items = enabled_entrypoints.into_iter()
             .map(|entrypoint| {
                 let entrypoint_type = db_entrypoint_from_str(&entrypoint.entrypoint_type);
                 let entrypoint_id = entrypoint.id.to_string();
@@ -815,13 +1234,16 @@ impl BackendForPluginRuntimeApi for BackendForPluginRuntimeApiImpl {
                     Some(path_to_asset) => {
                         match icon_asset_data.get(&(entrypoint.id, path_to_asset)) {
                             None => None,
-                            Some(data) => Some(self.icon_cache.save_entrypoint_icon_to_cache(&self.plugin_uuid, &entrypoint.uuid, data)?)
+                            Some(data) => Some(self.icon_cache.save_entrypoint_icon_to_cache(data)?)
                         }
                     },
                 };
 
                 let entrypoint_id = EntrypointId::from_string(entrypoint_id);
 
+                let entrypoint_description = Some(entrypoint.description)
+                    .filter(|description| !description.is_empty());
+
                 match &entrypoint_type {
                     DbPluginEntrypointType::Command => {
                         Ok(Some(SearchIndexItem {
@@ -833,6 +1255,9 @@ impl BackendForPluginRuntimeApi for BackendForPluginRuntimeApiImpl {
                             entrypoint_frecency,
                             entrypoint_actions: vec![],
                             entrypoint_accessories: vec![],
+                            entrypoint_keywords: entrypoint.keywords,
+                            entrypoint_description,
+                            entrypoint_aliases: entrypoint.aliases,
                         }))
                     },
                     DbPluginEntrypointType::View => {
@@ -845,6 +1270,9 @@ impl BackendForPluginRuntimeApi for BackendForPluginRuntimeApiImpl {
                             entrypoint_frecency,
                             entrypoint_actions: vec![],
                             entrypoint_accessories: vec![],
+                            entrypoint_keywords: entrypoint.keywords,
+                            entrypoint_description,
+                            entrypoint_aliases: entrypoint.aliases,
                         }))
                     },
                     DbPluginEntrypointType::EntrypointGenerator | DbPluginEntrypointType::InlineView => {
@@ -857,9 +1285,55 @@ impl BackendForPluginRuntimeApi for BackendForPluginRuntimeApiImpl {
             .flat_map(|item| item)
             .collect::<Vec<_>>();
 
+        Ok((builtin_search_items, disabled_entrypoint_ids))
+    }
+}
+
+impl BackendForPluginRuntimeApi for BackendForPluginRuntimeApiImpl {
+    async fn reload_search_index(&self, generated_commands: Vec<JsGeneratedSearchItem>, refresh_search_list: bool) -> anyhow::Result<()> {
+        // search index of the plugin is maintained by main runtime
+        if !self.entrypoints.is_main() {
+            return Ok(())
+        }
+
+        let DbReadPlugin { name, .. } = self.repository.get_plugin_by_id(&self.plugin_id.to_string())
+            .await
+            .context("error when getting plugin by id")?;
+
+        let mut generated_search_items = self.generated_search_items(generated_commands).await?;
+
+        let (mut builtin_search_items, _) = self.builtin_search_items().await?;
+
         generated_search_items.append(&mut builtin_search_items);
 
-        self.search_index.save_for_plugin(self.plugin_id.clone(), name, generated_search_items, refresh_search_list)
+        self.search_index.save_for_plugin(self.plugin_id.clone(), name, self.plugin_code_version.clone(), generated_search_items, refresh_search_list)
+            .context("error when updating search index")?;
+
+        Ok(())
+    }
+
+    async fn update_search_index(&self, updated_generated_commands: Vec<JsGeneratedSearchItem>, removed_generated_entrypoint_ids: Vec<String>, refresh_search_list: bool) -> anyhow::Result<()> {
+        if !self.entrypoints.is_main() {
+            return Ok(())
+        }
+
+        let DbReadPlugin { name, .. } = self.repository.get_plugin_by_id(&self.plugin_id.to_string())
+            .await
+            .context("error when getting plugin by id")?;
+
+        let mut search_items = self.generated_search_items(updated_generated_commands).await?;
+
+        // builtin entrypoints are always updated, there is only a few of them
+        let (mut builtin_search_items, disabled_entrypoint_ids) = self.builtin_search_items().await?;
+
+        search_items.append(&mut builtin_search_items);
+
+        let removed_entrypoint_ids = removed_generated_entrypoint_ids.into_iter()
+            .map(|id| EntrypointId::from_string(id))
+            .chain(disabled_entrypoint_ids)
+            .collect();
+
+        self.search_index.update_for_plugin(self.plugin_id.clone(), name, self.plugin_code_version.clone(), search_items, removed_entrypoint_ids, refresh_search_list)
             .context("error when updating search index")?;
 
         Ok(())
@@ -877,6 +1351,7 @@ impl BackendForPluginRuntimeApi for BackendForPluginRuntimeApiImpl {
             .into_iter()
             .filter(|entrypoint| entrypoint.enabled)
             .filter(|entrypoint| matches!(db_entrypoint_from_str(&entrypoint.entrypoint_type), DbPluginEntrypointType::EntrypointGenerator))
+            .filter(|entrypoint| self.entrypoints.handles(&EntrypointId::from_string(&entrypoint.id)))
             .map(|entrypoint| entrypoint.id)
             .collect::<Vec<_>>();
 
@@ -925,7 +1400,11 @@ impl BackendForPluginRuntimeApi for BackendForPluginRuntimeApiImpl {
 
         tracing::debug!("Reading from clipboard, plugin id: {:?}", self.plugin_id);
 
-        self.clipboard.read()
+        let data = self.clipboard.read()?;
+
+        self.record_audit_log_entry(AuditLogCapability::ClipboardRead, clipboard_data_kind(&data)).await;
+
+        Ok(data)
     }
 
     async fn clipboard_read_text(&self) -> anyhow::Result<Option<String>> {
@@ -940,7 +1419,11 @@ impl BackendForPluginRuntimeApi for BackendForPluginRuntimeApiImpl {
 
         tracing::debug!("Reading text from clipboard, plugin id: {:?}", self.plugin_id);
 
-        self.clipboard.read_text()
+        let data = self.clipboard.read_text()?;
+
+        self.record_audit_log_entry(AuditLogCapability::ClipboardRead, "text".to_string()).await;
+
+        Ok(data)
     }
 
     async fn clipboard_write(&self, data: JsClipboardData) -> anyhow::Result<()> {
@@ -955,7 +1438,13 @@ impl BackendForPluginRuntimeApi for BackendForPluginRuntimeApiImpl {
 
         tracing::debug!("Writing to clipboard, plugin id: {:?}", self.plugin_id);
 
-        self.clipboard.write(data)
+        let kind = clipboard_data_kind(&data);
+
+        self.clipboard.write(data)?;
+
+        self.record_audit_log_entry(AuditLogCapability::ClipboardWrite, kind).await;
+
+        Ok(())
     }
 
     async fn clipboard_write_text(&self, data: String) -> anyhow::Result<()> {
@@ -970,7 +1459,11 @@ impl BackendForPluginRuntimeApi for BackendForPluginRuntimeApiImpl {
 
         tracing::debug!("Writing text to clipboard, plugin id: {:?}", self.plugin_id);
 
-        self.clipboard.write_text(data)
+        self.clipboard.write_text(data)?;
+
+        self.record_audit_log_entry(AuditLogCapability::ClipboardWrite, "text".to_string()).await;
+
+        Ok(())
     }
 
     async fn clipboard_clear(&self) -> anyhow::Result<()> {
@@ -985,7 +1478,55 @@ impl BackendForPluginRuntimeApi for BackendForPluginRuntimeApiImpl {
 
         tracing::debug!("Clearing clipboard, plugin id: {:?}", self.plugin_id);
 
-        self.clipboard.clear()
+        self.clipboard.clear()?;
+
+        self.record_audit_log_entry(AuditLogCapability::ClipboardWrite, "clear".to_string()).await;
+
+        Ok(())
+    }
+
+    async fn request_permission(&self, permission: String, value: String) -> anyhow::Result<bool> {
+        tracing::info!("Plugin {:?} requested '{}' permission for {:?}", self.plugin_id, permission, value);
+
+        let result = self.frontend_api.request_permission(
+            self.plugin_id.clone(),
+            self.plugin_name.clone(),
+            permission.clone(),
+            value.clone(),
+        ).await;
+
+        let result = match result {
+            Ok(result) => result,
+            Err(err) => {
+                tracing::warn!("Permission prompt was not answered, denying: {:?}", err);
+
+                PermissionPromptResult::Deny
+            }
+        };
+
+        match result {
+            PermissionPromptResult::AllowOnce => Ok(true),
+            PermissionPromptResult::AllowAlways => {
+                self.repository.save_permission_grant(&self.plugin_id.to_string(), &permission, &value)
+                    .await
+                    .context("error when saving permission grant")?;
+
+                Ok(true)
+            }
+            PermissionPromptResult::Deny => Ok(false),
+        }
+    }
+
+    async fn record_capability_use(&self, capability: JsAuditCapability, value: String) -> anyhow::Result<()> {
+        let capability = match capability {
+            JsAuditCapability::Network => AuditLogCapability::Network,
+            JsAuditCapability::Command => AuditLogCapability::Command,
+            JsAuditCapability::FileWrite => AuditLogCapability::FileWrite,
+        };
+
+        self.record_audit_log_entry(capability, value).await;
+
+        Ok(())
     }
 
     async fn ui_update_loading_bar(&self, entrypoint_id: EntrypointId, show: bool) -> anyhow::Result<()> {
@@ -1055,7 +1596,8 @@ impl BackendForPluginRuntimeApi for BackendForPluginRuntimeApiImpl {
         self.frontend_api.show_plugin_error_view(
             self.plugin_id.clone(),
             entrypoint_id,
-            render_location
+            render_location,
+            None
         ).await?;
 
         Ok(())
@@ -1086,6 +1628,16 @@ impl BackendForPluginRuntimeApi for BackendForPluginRuntimeApiImpl {
 }
 
 
+// clipboard content itself is not stored in audit log
+fn clipboard_data_kind(data: &JsClipboardData) -> String {
+    match (&data.text_data, &data.png_data) {
+        (Some(_), Some(_)) => "text and image".to_string(),
+        (Some(_), None) => "text".to_string(),
+        (None, Some(_)) => "image".to_string(),
+        (None, None) => "empty".to_string(),
+    }
+}
+
 fn preferences_to_js(
     preferences: HashMap<String, DbPluginPreference>,
     mut preferences_user_data: HashMap<String, DbPluginPreferenceUserData>
@@ -1155,4 +1707,193 @@ fn any_preferences_missing_value(preferences: HashMap<String, DbPluginPreference
     }
 
     false
-}
\ No newline at end of file
+}
+
+fn uses_plugin_assets(permissions: &PluginPermissions) -> bool {
+    permissions.filesystem.read
+        .iter()
+        .chain(&permissions.exec.executable)
+        .any(|path| path.starts_with("{common:plugin-assets}"))
+}
+
+/// Assets are stored in database, but programs started by plugin or plugin itself
+/// can only access them as files, so they are written to `{common:plugin-assets}` directory on every start
+async fn write_plugin_assets(repository: &DataDbRepository, plugin_id: &str, plugin_assets_dir: &Path, executable: &[String]) -> anyhow::Result<()> {
+    let assets = repository.get_all_asset_data(plugin_id).await?;
+
+    match std::fs::remove_dir_all(plugin_assets_dir) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => Err(err)?,
+    }
+
+    std::fs::create_dir_all(plugin_assets_dir)?;
+
+    for (path, data) in assets {
+        let relative_path = Path::new(&path);
+
+        let only_normal = relative_path.components()
+            .all(|component| matches!(component, std::path::Component::Normal(_)));
+
+        if !only_normal {
+            Err(anyhow!("Unexpected plugin asset path: {}", path))?
+        }
+
+        let asset_path = plugin_assets_dir.join(relative_path);
+
+        if let Some(parent) = asset_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(asset_path, data)?;
+    }
+
+    // only assets listed in exec permissions are made executable,
+    // plugin loader doesn't allow '..' segments, so path cannot escape assets directory
+    for path in executable {
+        if let Some(relative_path) = path.strip_prefix("{common:plugin-assets}") {
+            let asset_path = plugin_assets_dir.join(relative_path.trim_start_matches(['/', '\\']));
+
+            if asset_path.is_file() {
+                set_executable(&asset_path)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path) -> anyhow::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path) -> anyhow::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn main_entrypoints(isolated: &[&str]) -> PluginRuntimeEntrypoints {
+        PluginRuntimeEntrypoints::Main {
+            isolated: isolated.iter().map(EntrypointId::from_string).collect(),
+        }
+    }
+
+    #[test]
+    fn main_runtime_handles_entrypoints_that_are_not_isolated() {
+        let entrypoints = main_entrypoints(&["isolated"]);
+
+        assert!(entrypoints.is_main());
+        assert!(entrypoints.handles(&EntrypointId::from_string("main")));
+        assert!(!entrypoints.handles(&EntrypointId::from_string("isolated")));
+    }
+
+    #[test]
+    fn isolated_runtime_handles_only_its_entrypoint() {
+        let entrypoints = PluginRuntimeEntrypoints::Isolated(EntrypointId::from_string("isolated"));
+
+        assert!(!entrypoints.is_main());
+        assert!(entrypoints.handles(&EntrypointId::from_string("isolated")));
+        assert!(!entrypoints.handles(&EntrypointId::from_string("main")));
+        assert!(!entrypoints.handles(&EntrypointId::from_string("isolated-other")));
+    }
+
+    #[test]
+    fn runtime_names_are_unique() {
+        let plugin_uuid = "a4c0c0a6-3f2e-4a55-9a51-7d2a8a0b8f7e";
+
+        let main = main_entrypoints(&["first", "second"]).runtime_name(plugin_uuid);
+        let first = PluginRuntimeEntrypoints::Isolated(EntrypointId::from_string("first")).runtime_name(plugin_uuid);
+        let second = PluginRuntimeEntrypoints::Isolated(EntrypointId::from_string("second")).runtime_name(plugin_uuid);
+
+        assert_eq!(main, plugin_uuid);
+        assert_eq!(first, format!("{}-first", plugin_uuid));
+        assert_ne!(first, second);
+        assert_ne!(main, first);
+    }
+
+    #[cfg(target_os = "linux")]
+    fn limits(address_space_mb: Option<u64>, cpu_seconds: Option<u64>) -> JsPluginLimits {
+        JsPluginLimits {
+            heap_mb: None,
+            address_space_mb,
+            cpu_seconds,
+            event_loop_timeout_secs: None,
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn process_limits_are_converted() {
+        let expected = ProcessLimits {
+            address_space: Some(16 * 1024 * 1024 * 1024),
+            cpu_seconds: Some((60, 65)),
+        };
+
+        assert_eq!(process_limits(&limits(Some(16 * 1024), Some(60))).unwrap(), expected);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn no_process_limits() {
+        let expected = ProcessLimits {
+            address_space: None,
+            cpu_seconds: None,
+        };
+
+        assert_eq!(process_limits(&limits(None, None)).unwrap(), expected);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn overflowing_process_limits_are_rejected() {
+        assert!(process_limits(&limits(Some(u64::MAX), None)).is_err());
+        assert!(process_limits(&limits(None, Some(u64::MAX))).is_err());
+    }
+
+    #[cfg(all(target_os = "linux", not(feature = "scenario_runner")))]
+    fn limits(heap_mb: Option<u64>, address_space_mb: Option<u64>) -> JsPluginLimits {
+        JsPluginLimits {
+            heap_mb,
+            address_space_mb,
+            cpu_seconds: None,
+            event_loop_timeout_secs: None,
+        }
+    }
+
+    #[cfg(all(target_os = "linux", not(feature = "scenario_runner")))]
+    #[test]
+    fn abort_is_reported_as_memory_limit_only_if_memory_is_limited() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let aborted = std::process::ExitStatus::from_raw(libc::SIGABRT);
+
+        let reason = termination_reason(&aborted, &limits(None, Some(512))).unwrap();
+        assert!(reason.contains("memory limit of 512 MB"));
+
+        let reason = termination_reason(&aborted, &limits(Some(256), None)).unwrap();
+        assert!(reason.contains("heap limit of 256 MB"));
+
+        let reason = termination_reason(&aborted, &limits(None, None)).unwrap();
+        assert!(reason.contains("crashed"));
+        assert!(!reason.contains("limit"));
+    }
+
+    #[cfg(all(target_os = "linux", not(feature = "scenario_runner")))]
+    #[test]
+    fn normal_exit_has_no_termination_reason() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let exited = std::process::ExitStatus::from_raw(0);
+
+        assert_eq!(termination_reason(&exited, &limits(Some(256), Some(512))), None);
+    }
+}
@@ -1,34 +1,56 @@
 use std::collections::HashMap;
 use std::ffi::OsStr;
-use std::fs::DirEntry;
-use std::io::{ErrorKind};
+use std::fs::{DirEntry, File};
+use std::io::{BufReader, ErrorKind, Seek};
 use std::path::{Path, PathBuf};
 use std::thread;
 
 use anyhow::{anyhow, Context};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
 use include_dir::Dir;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 use walkdir::WalkDir;
 use itertools::Itertools;
 use once_cell::sync::Lazy;
+use tokio::sync::mpsc::UnboundedSender;
 use typed_path::{TypedPathBuf, Utf8TypedPath, Utf8UnixComponent, Utf8WindowsComponent, Utf8WindowsPrefix, Utf8WindowsPrefixComponent};
-use gauntlet_common::model::{DownloadStatus, PluginId};
-use gauntlet_plugin_runtime::PERMISSIONS_VARIABLE_PATTERN;
+use gauntlet_common::model::{DownloadStatus, PluginId, SettingsPluginPermission};
+use gauntlet_plugin_runtime::{NetworkPermission, PERMISSIONS_VARIABLE_PATTERN};
 use crate::model::ActionShortcutKey;
-use crate::plugins::data_db_repository::{DataDbRepository, db_entrypoint_to_str, db_plugin_type_to_str, DbCode, DbPluginAction, DbPluginActionShortcutKind, DbPluginEntrypointType, DbPluginPermissions, DbPluginPreference, DbPluginPreferenceUserData, DbPluginType, DbPreferenceEnumValue, DbWritePlugin, DbWritePluginAssetData, DbWritePluginEntrypoint, DbPluginClipboardPermissions, DbPluginMainSearchBarPermissions, DbPluginPermissionsFileSystem, DbPluginPermissionsExec};
+use crate::plugins::data_db_repository::{DataDbRepository, db_entrypoint_to_str, db_plugin_type_to_str, DbCode, DbPluginAction, DbPluginActionShortcutKind, DbPluginEntrypointType, DbPluginPermissions, DbPluginPreference, DbPluginPreferenceUserData, DbPluginType, DbPreferenceEnumValue, DbWritePlugin, DbWritePluginAssetData, DbWritePluginEntrypoint, DbPluginClipboardPermissions, DbPluginMainSearchBarPermissions, DbPluginWindowsPermissions, DbPluginPermissionsFileSystem, DbPluginPermissionsExec, DbPluginLimits};
+use crate::plugins::config_reader::{ConfigReader, PluginSignaturesConfig};
 use crate::plugins::download_status::DownloadStatusHolder;
+use crate::plugins::permissions_to_review;
+use crate::plugins::signature;
+
+const RELEASE_BRANCH: &str = "gauntlet/release";
+
+// upper bounds of manifest limits, anything above them is most likely a mistake
+const MAX_HEAP_MB: u64 = 64 * 1024;
+const MAX_ADDRESS_SPACE_MB: u64 = 1024 * 1024;
+const MAX_CPU_SECONDS: u64 = 30 * 24 * 60 * 60;
+const MAX_EVENT_LOOP_TIMEOUT_SECS: u64 = 24 * 60 * 60;
+
+/// Only full object ids are treated as commits, abbreviated ones could be ambiguous or be a name of tag or branch
+fn is_full_commit_id(revision: &str) -> bool {
+    matches!(revision.len(), 40 | 64) && revision.chars().all(|char| char.is_ascii_hexdigit())
+}
 
 pub struct PluginLoader {
     db_repository: DataDbRepository,
+    config_reader: ConfigReader,
     download_status_holder: DownloadStatusHolder
 }
 
 
 impl PluginLoader {
-    pub fn new(db_repository: DataDbRepository) -> Self {
+    pub fn new(db_repository: DataDbRepository, config_reader: ConfigReader) -> Self {
         Self {
             db_repository,
+            config_reader,
             download_status_holder: DownloadStatusHolder::new()
         }
     }
@@ -37,43 +59,29 @@ impl PluginLoader {
         self.download_status_holder.download_status()
     }
 
-    pub async fn download_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()> {
+    /// Downloads plugin in background, `finished_sender` receives plugin id if download succeeds
+    pub async fn download_plugin(&self, plugin_id: PluginId, pinned_revision: Option<String>, finished_sender: Option<UnboundedSender<PluginId>>) -> anyhow::Result<()> {
         let download_status_guard = self.download_status_holder.download_started(plugin_id.clone());
 
         let data_db_repository = self.db_repository.clone();
+        let signatures = self.config_reader.plugin_signatures();
         let handle = tokio::runtime::Handle::current();
 
         let plugin_id_clone = plugin_id.clone();
         thread::spawn(move || {
             let result = handle.block_on(async move {
-                let temp_dir = tempfile::tempdir()?;
-
-                PluginLoader::download(temp_dir.path(), plugin_id_clone.clone())?;
-
-                let plugin_data = PluginLoader::read_plugin_dir(temp_dir.path(), plugin_id_clone.clone())
-                    .await?;
-
-                data_db_repository.save_plugin(DbWritePlugin {
-                    id: plugin_data.id,
-                    name: plugin_data.name,
-                    description: plugin_data.description,
-                    enabled: false,
-                    code: plugin_data.code,
-                    entrypoints: plugin_data.entrypoints,
-                    asset_data: plugin_data.asset_data,
-                    permissions: plugin_data.permissions,
-                    plugin_type: db_plugin_type_to_str(DbPluginType::Normal).to_owned(),
-                    preferences: plugin_data.preferences,
-                }).await?;
-
-                anyhow::Ok(())
+                PluginLoader::download_and_save(data_db_repository, signatures, plugin_id_clone, pinned_revision).await
             });
 
             handle.block_on(async move {
                 match result {
-                    Ok(()) => {
+                    Ok(permissions) => {
                         tracing::info!("Finished download of plugin: {:?}", plugin_id);
-                        download_status_guard.download_finished()
+                        download_status_guard.download_finished(permissions);
+
+                        if let Some(finished_sender) = finished_sender {
+                            let _ = finished_sender.send(plugin_id);
+                        }
                     },
                     Err(err) => {
                         tracing::warn!("Download of plugin {:?} returned an error {:?}", plugin_id, err);
@@ -86,11 +94,83 @@ impl PluginLoader {
         Ok(())
     }
 
+    pub async fn update_plugin(&self, plugin_id: PluginId, pinned_revision: Option<String>) -> anyhow::Result<()> {
+        let download_status_guard = self.download_status_holder.download_started(plugin_id.clone());
+
+        let signatures = self.config_reader.plugin_signatures();
+
+        let result = PluginLoader::download_and_save(self.db_repository.clone(), signatures, plugin_id.clone(), pinned_revision).await;
+
+        match result {
+            Ok(permissions) => {
+                tracing::info!("Finished update of plugin: {:?}", plugin_id);
+                download_status_guard.download_finished(permissions);
+
+                Ok(())
+            }
+            Err(err) => {
+                tracing::warn!("Update of plugin {:?} returned an error {:?}", plugin_id, err);
+                download_status_guard.download_failed(format!("{}", err));
+
+                Err(err)
+            }
+        }
+    }
+
+    /// Returns latest commit available for installed plugin, or None if plugin is pinned to a specific commit
+    pub async fn latest_revision(&self, plugin_id: PluginId, pinned_revision: Option<String>) -> anyhow::Result<Option<String>> {
+        tokio::task::spawn_blocking(move || PluginLoader::remote_revision(plugin_id, pinned_revision.as_deref()))
+            .await?
+    }
+
+    /// Returns permissions requested by plugin if some of them need to be approved by user
+    async fn download_and_save(data_db_repository: DataDbRepository, signatures: PluginSignaturesConfig, plugin_id: PluginId, pinned_revision: Option<String>) -> anyhow::Result<Vec<SettingsPluginPermission>> {
+        let temp_dir = tempfile::tempdir()?;
+
+        let revision = {
+            let target_dir = temp_dir.path().to_owned();
+            let plugin_id = plugin_id.clone();
+            let pinned_revision = pinned_revision.clone();
+
+            tokio::task::spawn_blocking(move || PluginLoader::download(&target_dir, plugin_id, pinned_revision.as_deref()))
+                .await??
+        };
+
+        let content_hash = signature::verify_plugin(temp_dir.path(), &plugin_id, &signatures)?;
+
+        let plugin_data = PluginLoader::read_plugin_dir(temp_dir.path(), plugin_id.clone())
+            .await?;
+
+        data_db_repository.save_plugin(DbWritePlugin {
+            id: plugin_data.id,
+            name: plugin_data.name,
+            description: plugin_data.description,
+            enabled: false,
+            code: plugin_data.code,
+            entrypoints: plugin_data.entrypoints,
+            asset_data: plugin_data.asset_data,
+            permissions: plugin_data.permissions,
+            limits: plugin_data.limits,
+            plugin_type: db_plugin_type_to_str(DbPluginType::Normal).to_owned(),
+            preferences: plugin_data.preferences,
+            revision: Some(revision),
+            pinned_revision,
+            content_hash,
+        }).await?;
+
+        let plugin = data_db_repository.get_plugin_by_id(&plugin_id.to_string())
+            .await?;
+
+        Ok(permissions_to_review(&plugin))
+    }
+
     pub async fn save_local_plugin(&self, path: &str) -> anyhow::Result<PluginId> {
         let plugin_id = PluginId::from_string(format!("file://{}", &path));
 
         let plugin_dir = plugin_id.try_to_path()?.join("dist");
 
+        let content_hash = signature::verify_plugin(&plugin_dir, &plugin_id, &self.config_reader.plugin_signatures())?;
+
         let plugin_data = PluginLoader::read_plugin_dir(&plugin_dir, plugin_id.clone())
             .await
             .context(format!("Unable to read plugin: {}", &plugin_id.to_string()))?;
@@ -104,8 +184,52 @@ impl PluginLoader {
             entrypoints: plugin_data.entrypoints,
             asset_data: plugin_data.asset_data,
             permissions: plugin_data.permissions,
+            limits: plugin_data.limits,
             plugin_type: db_plugin_type_to_str(DbPluginType::Normal).to_owned(),
             preferences: plugin_data.preferences,
+            revision: None,
+            pinned_revision: None,
+            content_hash,
+        }).await?;
+
+        Ok(plugin_id)
+    }
+
+    /// Installs plugin from .tar.gz/.zip archive or directory containing built plugin as a regular (non-dev) plugin
+    pub async fn install_plugin_from_path(&self, path: &str) -> anyhow::Result<PluginId> {
+        let path = PathBuf::from(path);
+        let temp_dir = tempfile::tempdir()?;
+
+        let (hash, plugin_dir) = {
+            let target_dir = temp_dir.path().to_owned();
+
+            tokio::task::spawn_blocking(move || PluginLoader::unpack_plugin(&path, &target_dir))
+                .await??
+        };
+
+        let plugin_id = PluginId::from_string(format!("archive://sha256:{}", hash));
+
+        let content_hash = signature::verify_plugin(&plugin_dir, &plugin_id, &self.config_reader.plugin_signatures())?;
+
+        let plugin_data = PluginLoader::read_plugin_dir(&plugin_dir, plugin_id.clone())
+            .await
+            .context(format!("Unable to read plugin: {}", &plugin_id.to_string()))?;
+
+        self.db_repository.save_plugin(DbWritePlugin {
+            id: plugin_data.id,
+            name: plugin_data.name,
+            description: plugin_data.description,
+            enabled: false,
+            code: plugin_data.code,
+            entrypoints: plugin_data.entrypoints,
+            asset_data: plugin_data.asset_data,
+            permissions: plugin_data.permissions,
+            limits: plugin_data.limits,
+            plugin_type: db_plugin_type_to_str(DbPluginType::Normal).to_owned(),
+            preferences: plugin_data.preferences,
+            revision: None,
+            pinned_revision: None,
+            content_hash,
         }).await?;
 
         Ok(plugin_id)
@@ -117,6 +241,9 @@ impl PluginLoader {
 
         dir.extract(&temp_dir)?;
 
+        // bundled plugins are part of application binary, so there is nothing to verify
+        let content_hash = hex::encode(signature::plugin_content_hash(temp_dir.path())?);
+
         let plugin_data = PluginLoader::read_plugin_dir(temp_dir.path(), plugin_id.clone())
             .await
             .context(format!("Unable to read plugin: {}", &plugin_id.to_string()))?;
@@ -130,30 +257,262 @@ impl PluginLoader {
             entrypoints: plugin_data.entrypoints,
             asset_data: plugin_data.asset_data,
             permissions: plugin_data.permissions,
+            limits: plugin_data.limits,
             plugin_type: db_plugin_type_to_str(DbPluginType::Bundled).to_owned(),
             preferences: plugin_data.preferences,
+            revision: None,
+            pinned_revision: None,
+            content_hash,
         }).await?;
 
         Ok(plugin_id)
     }
 
-    fn download(target_dir: &Path, plugin_id: PluginId) -> anyhow::Result<()> {
+    fn download(target_dir: &Path, plugin_id: PluginId, pinned_revision: Option<&str>) -> anyhow::Result<String> {
         let url = plugin_id.try_to_git_url()?;
 
-        let _ = git2::build::RepoBuilder::new()
-            .branch("gauntlet/release")
-            .clone(&url, target_dir)?;
+        let repo = match pinned_revision {
+            None => {
+                git2::build::RepoBuilder::new()
+                    .branch(RELEASE_BRANCH)
+                    .clone(&url, target_dir)?
+            }
+            Some(pinned_revision) => {
+                let repo = git2::build::RepoBuilder::new()
+                    .clone(&url, target_dir)?;
+
+                let commit_id = {
+                    let commit = PluginLoader::resolve_revision(&repo, pinned_revision)
+                        .ok_or_else(|| anyhow!("Revision '{}' does not exist in plugin repository {}", pinned_revision, url))?;
+
+                    repo.checkout_tree(commit.as_object(), Some(git2::build::CheckoutBuilder::new().force()))?;
+
+                    commit.id()
+                };
+
+                repo.set_head_detached(commit_id)?;
+
+                repo
+            }
+        };
+
+        let revision = repo.head()?
+            .peel_to_commit()?
+            .id()
+            .to_string();
+
+        Ok(revision)
+    }
+
+    /// Returns sha256 of archive or directory content and directory which contains plugin manifest
+    fn unpack_plugin(path: &Path, target_dir: &Path) -> anyhow::Result<(String, PathBuf)> {
+        let metadata = std::fs::metadata(path)
+            .context(format!("Unable to read {:?}", path))?;
+
+        let find_manifest_dir = |dir: &Path| {
+            PluginLoader::find_manifest_dir(dir)
+                .ok_or_else(|| anyhow!("Unable to find gauntlet.toml in {:?}, expected built plugin 'dist' directory", path))
+        };
+
+        if metadata.is_dir() {
+            let plugin_dir = find_manifest_dir(path)?;
+
+            // hash only built plugin, so sources and dependencies next to it do not affect plugin id
+            Ok((PluginLoader::hash_dir(&plugin_dir)?, plugin_dir))
+        } else {
+            let file_name = path.file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default();
+
+            let mut file = File::open(path)
+                .context(format!("Unable to read {:?}", path))?;
+
+            // the same file is read twice instead of being loaded into memory, archives can be large
+            let mut hasher = Sha256::new();
+            std::io::copy(&mut file, &mut hasher)
+                .context(format!("Unable to read {:?}", path))?;
+
+            file.rewind()?;
+
+            if file_name.ends_with(".tar.gz") || file_name.ends_with(".tgz") {
+                PluginLoader::unpack_tar(file, target_dir)
+                    .context("Unable to unpack .tar.gz archive")?;
+            } else if file_name.ends_with(".zip") {
+                PluginLoader::unpack_zip(file, target_dir)
+                    .context("Unable to unpack .zip archive")?;
+            } else {
+                return Err(anyhow!("Unsupported plugin archive {:?}, expected .tar.gz, .tgz or .zip", path));
+            }
+
+            Ok((hex::encode(hasher.finalize()), find_manifest_dir(target_dir)?))
+        }
+    }
+
+    fn unpack_tar(file: File, target_dir: &Path) -> anyhow::Result<()> {
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(BufReader::new(file)));
+
+        std::fs::create_dir_all(target_dir)?;
+
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let entry_type = entry.header().entry_type();
+
+            if entry_type.is_symlink() || entry_type.is_hard_link() {
+                return Err(anyhow!("Archive contains link {:?}, links are not allowed in plugins", entry.path()?));
+            }
+
+            if entry_type == tar::EntryType::XGlobalHeader {
+                continue;
+            }
+
+            // entries with paths outside of target dir are skipped
+            entry.unpack_in(target_dir)?;
+        }
+
+        Ok(())
+    }
+
+    fn unpack_zip(file: File, target_dir: &Path) -> anyhow::Result<()> {
+        let mut archive = zip::ZipArchive::new(BufReader::new(file))?;
+
+        for index in 0..archive.len() {
+            let entry = archive.by_index_raw(index)?;
+
+            if entry.is_symlink() {
+                return Err(anyhow!("Archive contains link {:?}, links are not allowed in plugins", entry.name()));
+            }
+        }
+
+        archive.extract(target_dir)?;
 
         Ok(())
     }
 
+    fn hash_dir(dir: &Path) -> anyhow::Result<String> {
+        let mut hasher = Sha256::new();
+
+        let entries = WalkDir::new(dir)
+            .sort_by_file_name()
+            .into_iter()
+            .collect::<walkdir::Result<Vec<_>>>()
+            .context(format!("Unable to read directory {:?}", dir))?;
+
+        for entry in entries {
+            if entry.path_is_symlink() {
+                return Err(anyhow!("Symlinks are not allowed in plugins: {:?}", entry.path()));
+            }
+
+            if !entry.file_type().is_file() {
+                continue;
+            }
+
+            let relative_path = entry.path()
+                .strip_prefix(dir)
+                .expect("dir is a base of entry path")
+                .to_str()
+                .ok_or(anyhow!("filename is not a valid utf-8"))?;
+
+            // separate path and content so different layouts with same bytes produce different hash
+            hasher.update(relative_path.as_bytes());
+            hasher.update([0]);
+            hasher.update(std::fs::read(entry.path())?);
+            hasher.update([0]);
+        }
+
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    /// Plugin files are copied into database, so symlinks could be used to pull arbitrary local files into plugin
+    fn reject_symlink(path: &Path) -> anyhow::Result<()> {
+        match std::fs::symlink_metadata(path) {
+            Ok(metadata) if metadata.file_type().is_symlink() => Err(anyhow!("Symlinks are not allowed in plugins: {:?}", path)),
+            _ => Ok(()),
+        }
+    }
+
+    // archives are allowed to contain either content of 'dist' directory, 'dist' directory itself or any single top level directory
+    fn find_manifest_dir(dir: &Path) -> Option<PathBuf> {
+        if dir.join("gauntlet.toml").is_file() {
+            return Some(dir.to_owned())
+        }
+
+        if dir.join("dist").join("gauntlet.toml").is_file() {
+            return Some(dir.join("dist"))
+        }
+
+        let subdirs = std::fs::read_dir(dir)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+            // resource forks added by macOS archive utility
+            .filter(|entry| entry.file_name() != "__MACOSX")
+            .collect::<Vec<_>>();
+
+        match subdirs.as_slice() {
+            [subdir] => PluginLoader::find_manifest_dir(&subdir.path()),
+            _ => None
+        }
+    }
+
+    fn resolve_revision<'a>(repo: &'a git2::Repository, revision: &str) -> Option<git2::Commit<'a>> {
+        // tags take priority over branches, full commit ids are tried last.
+        // references are looked up by name, so revision expressions like `HEAD~3` are not accepted
+        let reference = [format!("refs/tags/{}", revision), format!("refs/remotes/origin/{}", revision)]
+            .iter()
+            .find_map(|name| repo.find_reference(name).ok());
+
+        match reference {
+            Some(reference) => reference.peel_to_commit().ok(),
+            None if is_full_commit_id(revision) => {
+                let oid = git2::Oid::from_str(revision).ok()?;
+
+                repo.find_commit(oid).ok()
+            }
+            None => None,
+        }
+    }
+
+    fn remote_revision(plugin_id: PluginId, pinned_revision: Option<&str>) -> anyhow::Result<Option<String>> {
+        let url = plugin_id.try_to_git_url()?;
+
+        let mut remote = git2::Remote::create_detached(url.as_str())?;
+        remote.connect(git2::Direction::Fetch)?;
+
+        let ref_names = match pinned_revision {
+            None => vec![format!("refs/heads/{}", RELEASE_BRANCH)],
+            // "^{}" is a peeled annotated tag which points to a commit instead of tag object
+            Some(pinned_revision) => vec![format!("refs/tags/{}^{{}}", pinned_revision), format!("refs/tags/{}", pinned_revision), format!("refs/heads/{}", pinned_revision)],
+        };
+
+        let remote_heads = remote.list()?;
+
+        let revision = ref_names.iter()
+            .find_map(|ref_name| remote_heads.iter().find(|head| head.name() == ref_name.as_str()))
+            .map(|head| head.oid().to_string());
+
+        match (revision, pinned_revision) {
+            (Some(revision), _) => Ok(Some(revision)),
+            (None, None) => Err(anyhow!("Branch '{}' does not exist in plugin repository {}", RELEASE_BRANCH, url)),
+            (None, Some(pinned_revision)) if is_full_commit_id(pinned_revision) => Ok(None), // pinned to commit, there is never an update
+            (None, Some(pinned_revision)) => Err(anyhow!("Revision '{}' does not exist in plugin repository {}", pinned_revision, url)),
+        }
+    }
+
     async fn read_plugin_dir(plugin_dir: &Path, plugin_id: PluginId) -> anyhow::Result<PluginDownloadData> {
         let js_dir = plugin_dir.join("js");
         let assets = plugin_dir.join("assets");
+        let plugin_manifest_path = plugin_dir.join("gauntlet.toml");
+
+        for path in [&js_dir, &assets, &plugin_manifest_path] {
+            PluginLoader::reject_symlink(path)?;
+        }
 
         let js_dir_context = js_dir.display().to_string();
         let js_files = std::fs::read_dir(js_dir).context(js_dir_context)?;
 
+        // source maps are only useful when debugging, so inline them for dev plugins only
+        let dev_plugin = plugin_id.to_string().starts_with("file://");
+
         let js: HashMap<_, _> = js_files.into_iter()
             .collect::<std::io::Result<Vec<DirEntry>>>()
             .context("Unable to get list of plugin js files")?
@@ -161,7 +520,16 @@ impl PluginLoader {
             .map(|dist_path| dist_path.path())
             .filter(|dist_path| dist_path.extension() == Some(OsStr::new("js")))
             .map(|dist_path| {
+                PluginLoader::reject_symlink(&dist_path)?;
+
                 let js_content = std::fs::read_to_string(&dist_path)?;
+
+                let js_content = if dev_plugin {
+                    PluginLoader::inline_source_map(js_content, &dist_path)?
+                } else {
+                    js_content
+                };
+
                 let id = dist_path.file_stem()
                     .expect("file returned from read_dir doesn't have filename?")
                     .to_str()
@@ -184,6 +552,15 @@ impl PluginLoader {
             })
             .context("Unable to get list of plugin asset data files")?
             .into_iter()
+            .map(|dir_entry| {
+                if dir_entry.path_is_symlink() {
+                    Err(anyhow!("Symlinks are not allowed in plugins: {:?}", dir_entry.path()))
+                } else {
+                    Ok(dir_entry)
+                }
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?
+            .into_iter()
             .filter(|dir_entry| dir_entry.file_type().is_file())
             .map(|path| {
                 let path = path.path();
@@ -208,7 +585,6 @@ impl PluginLoader {
             .into_iter()
             .collect();
 
-        let plugin_manifest_path = plugin_dir.join("gauntlet.toml");
         let plugin_manifest_path_context = plugin_manifest_path.display().to_string();
         let plugin_manifest_content = std::fs::read_to_string(plugin_manifest_path).context(plugin_manifest_path_context)?;
         let plugin_manifest: PluginManifest = toml::from_str(&plugin_manifest_content)
@@ -269,6 +645,8 @@ impl PluginLoader {
                         },
                     })
                     .collect(),
+                keywords: entrypoint.keywords,
+                permissions: entrypoint.permissions.map(|permissions| Self::permissions_to_db(permissions)),
             })
             .collect();
 
@@ -297,7 +675,33 @@ impl PluginLoader {
             })
             .collect();
 
-        let clipboard = plugin_manifest.permissions
+        let permissions = Self::permissions_to_db(plugin_manifest.permissions);
+
+        let limits = DbPluginLimits {
+            heap_mb: plugin_manifest.limits.heap_mb,
+            address_space_mb: plugin_manifest.limits.address_space_mb,
+            cpu_seconds: plugin_manifest.limits.cpu_seconds,
+            event_loop_timeout_secs: plugin_manifest.limits.event_loop_timeout_secs,
+        };
+
+        Ok(PluginDownloadData {
+            id: plugin_id.to_string(),
+            name: plugin_name,
+            description: plugin_description,
+            code: DbCode {
+                js
+            },
+            entrypoints,
+            asset_data,
+            permissions,
+            limits,
+            preferences: plugin_preferences,
+            preferences_user_data: HashMap::new()
+        })
+    }
+
+    fn permissions_to_db(permissions: PluginManifestPermissions) -> DbPluginPermissions {
+        let clipboard = permissions
             .clipboard
             .into_iter()
             .map(|permission| {
@@ -309,7 +713,7 @@ impl PluginLoader {
             })
             .collect();
 
-        let main_search_bar = plugin_manifest.permissions
+        let main_search_bar = permissions
             .main_search_bar
             .into_iter()
             .map(|permission| {
@@ -319,35 +723,53 @@ impl PluginLoader {
             })
             .collect();
 
-        let permissions = DbPluginPermissions {
-            environment: plugin_manifest.permissions.environment,
-            network: plugin_manifest.permissions.network,
+        let windows = permissions
+            .windows
+            .into_iter()
+            .map(|permission| {
+                match permission {
+                    PluginManifestWindowsPermissions::Read => DbPluginWindowsPermissions::Read,
+                    PluginManifestWindowsPermissions::Focus => DbPluginWindowsPermissions::Focus,
+                    PluginManifestWindowsPermissions::Close => DbPluginWindowsPermissions::Close,
+                    PluginManifestWindowsPermissions::Minimize => DbPluginWindowsPermissions::Minimize,
+                }
+            })
+            .collect();
+
+        DbPluginPermissions {
+            environment: permissions.environment,
+            network: permissions.network,
             filesystem: DbPluginPermissionsFileSystem {
-                read: plugin_manifest.permissions.filesystem.read,
-                write: plugin_manifest.permissions.filesystem.write,
+                read: permissions.filesystem.read,
+                write: permissions.filesystem.write,
             },
             exec: DbPluginPermissionsExec {
-                command: plugin_manifest.permissions.exec.command,
-                executable: plugin_manifest.permissions.exec.executable,
+                command: permissions.exec.command,
+                executable: permissions.exec.executable,
             },
-            system: plugin_manifest.permissions.system,
+            system: permissions.system,
             clipboard,
             main_search_bar,
+            windows,
+        }
+    }
+
+    /// Embeds source map file next to js file into the code, because inspector has no way to fetch it otherwise
+    fn inline_source_map(js_content: String, js_path: &Path) -> anyhow::Result<String> {
+        let mut source_map_path = js_path.as_os_str().to_owned();
+        source_map_path.push(".map");
+
+        let source_map = match std::fs::read(&source_map_path) {
+            Ok(source_map) => source_map,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(js_content),
+            Err(err) => Err(err).context(format!("Unable to read source map {:?}", source_map_path))?,
         };
 
-        Ok(PluginDownloadData {
-            id: plugin_id.to_string(),
-            name: plugin_name,
-            description: plugin_description,
-            code: DbCode {
-                js
-            },
-            entrypoints,
-            asset_data,
-            permissions,
-            preferences: plugin_preferences,
-            preferences_user_data: HashMap::new()
-        })
+        let js_content = js_content.lines()
+            .filter(|line| !line.starts_with("//# sourceMappingURL="))
+            .join("\n");
+
+        Ok(format!("{}\n//# sourceMappingURL=data:application/json;base64,{}\n", js_content, STANDARD.encode(source_map)))
     }
 
     fn validate_manifest(plugin_manifest: &PluginManifest) -> anyhow::Result<()> {
@@ -360,18 +782,22 @@ impl PluginLoader {
 
         let permissions = &plugin_manifest.permissions;
 
-        Self::validate_string_permissions(&permissions.environment)?;
-        Self::validate_network_permissions(&permissions.network)?;
-        Self::validate_path_permissions(&permissions.filesystem.read, supports_linux, supports_macos, supports_windows)?;
-        Self::validate_path_permissions(&permissions.filesystem.write, supports_linux, supports_macos, supports_windows)?;
-        Self::validate_command_permissions(&permissions.exec.command)?;
-        Self::validate_path_permissions(&permissions.exec.executable, supports_linux, supports_macos, supports_windows)?;
+        Self::validate_permissions(permissions, supports_linux, supports_macos, supports_windows)?;
 
-        // even though system accepts a list of predefined values
-        // unknown values are ignored to allow for easier
-        // adoption to breaking changes in deno
-        // TODO do a warning
-        Self::validate_string_permissions(&permissions.system)?;
+        for entrypoint in &plugin_manifest.entrypoint {
+            if let Some(entrypoint_permissions) = &entrypoint.permissions {
+                // generated entrypoints are added to search index of the whole plugin, which is maintained by main plugin runtime
+                if matches!(entrypoint.entrypoint_type, PluginManifestEntrypointTypes::EntrypointGenerator) {
+                    Err(anyhow!("Entrypoint of type 'entrypoint-generator' cannot specify its own permissions: {}", entrypoint.id))?
+                }
+
+                Self::validate_permissions(entrypoint_permissions, supports_linux, supports_macos, supports_windows)
+                    .context(format!("Invalid permissions of entrypoint: {}", entrypoint.id))?;
+
+                Self::validate_entrypoint_permissions_subset(entrypoint_permissions, permissions)
+                    .context(format!("Invalid permissions of entrypoint: {}", entrypoint.id))?;
+            }
+        }
 
         let env_exists = !permissions.environment.is_empty();
         let fs_read_exists = !permissions.filesystem.read.is_empty();
@@ -398,6 +824,13 @@ impl PluginLoader {
             }
         }
 
+        let limits = &plugin_manifest.limits;
+
+        Self::validate_limit("heap_mb", limits.heap_mb, MAX_HEAP_MB)?;
+        Self::validate_limit("address_space_mb", limits.address_space_mb, MAX_ADDRESS_SPACE_MB)?;
+        Self::validate_limit("cpu_seconds", limits.cpu_seconds, MAX_CPU_SECONDS)?;
+        Self::validate_limit("event_loop_timeout_secs", limits.event_loop_timeout_secs, MAX_EVENT_LOOP_TIMEOUT_SECS)?;
+
         let has_inline_view = plugin_manifest.entrypoint
             .iter()
             .find(|entrypoint| matches!(entrypoint.entrypoint_type, PluginManifestEntrypointTypes::InlineView))
@@ -413,6 +846,132 @@ impl PluginLoader {
         Ok(())
     }
 
+    fn validate_permissions(permissions: &PluginManifestPermissions, supports_linux: &bool, supports_macos: &bool, supports_windows: &bool) -> anyhow::Result<()> {
+        Self::validate_string_permissions(&permissions.environment)?;
+        Self::validate_network_permissions(&permissions.network)?;
+        Self::validate_path_permissions(&permissions.filesystem.read, supports_linux, supports_macos, supports_windows)?;
+        Self::validate_path_permissions(&permissions.filesystem.write, supports_linux, supports_macos, supports_windows)?;
+        Self::validate_read_only_path_permissions(&permissions.filesystem.write)?;
+        Self::validate_command_permissions(&permissions.exec.command)?;
+        Self::validate_path_permissions(&permissions.exec.executable, supports_linux, supports_macos, supports_windows)?;
+
+        // even though system accepts a list of predefined values
+        // unknown values are ignored to allow for easier
+        // adoption to breaking changes in deno
+        // TODO do a warning
+        Self::validate_string_permissions(&permissions.system)?;
+
+        Ok(())
+    }
+
+    /// Entrypoint permissions can only narrow down permissions of the plugin
+    fn validate_entrypoint_permissions_subset(entrypoint_permissions: &PluginManifestPermissions, plugin_permissions: &PluginManifestPermissions) -> anyhow::Result<()> {
+        fn validate_values<T: PartialEq + std::fmt::Debug>(name: &str, values: &[T], plugin_values: &[T]) -> anyhow::Result<()> {
+            for value in values {
+                if !plugin_values.contains(value) {
+                    Err(anyhow!("Entrypoint {} permission is not present in plugin permissions: {:?}", name, value))?
+                }
+            }
+
+            Ok(())
+        }
+
+        fn validate_paths(name: &str, paths: &[String], plugin_paths: &[String]) -> anyhow::Result<()> {
+            for path in paths {
+                let within_plugin_path = plugin_paths.iter()
+                    .any(|plugin_path| PluginLoader::is_path_within(path, plugin_path));
+
+                if !within_plugin_path {
+                    Err(anyhow!("Entrypoint {} permission is not within any of plugin {} permission paths: {}", name, name, path))?
+                }
+            }
+
+            Ok(())
+        }
+
+        fn validate_network(values: &[String], plugin_values: &[String]) -> anyhow::Result<()> {
+            let plugin_values = plugin_values.iter()
+                .map(|value| NetworkPermission::parse(value))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            for value in values {
+                let permission = NetworkPermission::parse(value)?;
+
+                let within_plugin_value = plugin_values.iter()
+                    .any(|plugin_value| plugin_value.contains(&permission));
+
+                if !within_plugin_value {
+                    Err(anyhow!("Entrypoint network permission is not covered by any of plugin network permissions: {}", value))?
+                }
+            }
+
+            Ok(())
+        }
+
+        validate_values("environment", &entrypoint_permissions.environment, &plugin_permissions.environment)?;
+        validate_network(&entrypoint_permissions.network, &plugin_permissions.network)?;
+        validate_paths("filesystem read", &entrypoint_permissions.filesystem.read, &plugin_permissions.filesystem.read)?;
+        validate_paths("filesystem write", &entrypoint_permissions.filesystem.write, &plugin_permissions.filesystem.write)?;
+        validate_values("exec command", &entrypoint_permissions.exec.command, &plugin_permissions.exec.command)?;
+        validate_paths("exec executable", &entrypoint_permissions.exec.executable, &plugin_permissions.exec.executable)?;
+        validate_values("system", &entrypoint_permissions.system, &plugin_permissions.system)?;
+        validate_values("clipboard", &entrypoint_permissions.clipboard, &plugin_permissions.clipboard)?;
+        validate_values("main search bar", &entrypoint_permissions.main_search_bar, &plugin_permissions.main_search_bar)?;
+        validate_values("windows", &entrypoint_permissions.windows, &plugin_permissions.windows)?;
+
+        Ok(())
+    }
+
+    /// Compares paths segment by segment, variable can only be at the beginning, so it is compared as a normal segment.
+    /// If both paths exist, symlinks are resolved and resolved path is required to be within resolved parent
+    fn is_path_within(path: &str, parent: &str) -> bool {
+        fn segments(path: &str) -> Option<Vec<&str>> {
+            let segments = path.split(['/', '\\'])
+                .filter(|segment| !segment.is_empty())
+                .collect::<Vec<_>>();
+
+            // '.' and '..' are rejected by `validate_path_permissions`, but this check should never depend on it
+            if segments.iter().any(|segment| *segment == "." || *segment == "..") {
+                None
+            } else {
+                Some(segments)
+            }
+        }
+
+        let (Some(path_segments), Some(parent_segments)) = (segments(path), segments(parent)) else {
+            return false
+        };
+
+        if !path_segments.starts_with(&parent_segments) {
+            return false
+        }
+
+        match (std::fs::canonicalize(path), std::fs::canonicalize(parent)) {
+            (Ok(path), Ok(parent)) => path.starts_with(parent),
+            _ => true,
+        }
+    }
+
+    fn validate_limit(name: &str, value: Option<u64>, max: u64) -> anyhow::Result<()> {
+        match value {
+            Some(0) => Err(anyhow!("Zero value is not allowed in limits: {}", name))?,
+            Some(value) if value > max => Err(anyhow!("Value {} of limit {} is too large, maximum is {}", value, name, max))?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn validate_read_only_path_permissions(paths: &[String]) -> anyhow::Result<()> {
+        for path in paths {
+            if path.starts_with("{common:plugin-assets}") {
+                Err(anyhow!("Plugin assets can only be read, not written: {}", path))?
+            }
+        }
+
+        Ok(())
+    }
+
     fn validate_path_permissions(paths: &[String], supports_linux: &bool, supports_macos: &bool, supports_windows: &bool) -> anyhow::Result<()> {
         for path in paths {
             if path.is_empty() {
@@ -453,6 +1012,17 @@ impl PluginLoader {
                         ("windows", "user-home") => windows_like_path,
                         ("common", "plugin-data") => windows_like_path,
                         ("common", "plugin-cache") => windows_like_path,
+                        ("common", "plugin-assets") => windows_like_path,
+                        ("common", "documents") => windows_like_path,
+                        ("common", "downloads") => windows_like_path,
+                        ("common", "desktop") => windows_like_path,
+                        ("common", "temp") => windows_like_path,
+                        ("linux", "xdg-config") => false,
+                        ("linux", "xdg-data") => false,
+                        ("linux", "xdg-runtime") => false,
+                        ("macos", "application-support") => false,
+                        ("macos", "preferences") => false,
+                        ("macos", "caches") => false,
                         (namespace, name) => {
                             Err(anyhow!("Unknown variable namespace and name combination in path in permissions: {}:{}", namespace, name))?
                         }
@@ -560,22 +1130,7 @@ impl PluginLoader {
 
     fn validate_network_permissions(values: &[String]) -> anyhow::Result<()> {
         for value in values {
-            if value.is_empty() {
-                Err(anyhow!("Empty string value is not allowed in permissions"))?
-            }
-
-            let url = url::Url::parse(&format!("http://{value}"))?;
-
-            let contains_username = !url.username().is_empty();
-            let contains_password = matches!(url.password(), Some(_));
-            let contains_path = url.path() != "/";
-            let contains_query = matches!(url.query(), Some(_));
-            let contains_fragment = matches!(url.fragment(), Some(_));
-
-            // allow only domain and optional port
-            if contains_username || contains_password || contains_path || contains_query || contains_fragment {
-                Err(anyhow!("Network permission can only contain domain and optionally port: {}", value))?
-            }
+            NetworkPermission::parse(value)?;
         }
         Ok(())
     }
@@ -589,6 +1144,7 @@ struct PluginDownloadData {
     pub entrypoints: Vec<DbWritePluginEntrypoint>,
     pub asset_data: Vec<DbWritePluginAssetData>,
     pub permissions: DbPluginPermissions,
+    pub limits: DbPluginLimits,
     pub preferences: HashMap<String, DbPluginPreference>,
     pub preferences_user_data: HashMap<String, DbPluginPreferenceUserData>,
 }
@@ -602,6 +1158,8 @@ struct PluginManifest {
     #[serde(default)]
     permissions: PluginManifestPermissions,
     #[serde(default)]
+    limits: PluginManifestLimits,
+    #[serde(default)]
     preferences: Vec<PluginManifestPreference>,
 }
 
@@ -619,6 +1177,10 @@ struct PluginManifestEntrypoint {
     preferences: Vec<PluginManifestPreference>,
     #[serde(default)]
     actions: Vec<PluginManifestAction>,
+    #[serde(default)]
+    keywords: Vec<String>,
+    #[serde(default)]
+    permissions: Option<PluginManifestPermissions>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -1052,6 +1614,20 @@ pub struct PluginManifestPermissions {
     clipboard: Vec<PluginManifestClipboardPermissions>,
     #[serde(default)]
     main_search_bar: Vec<PluginManifestMainSearchBarPermissions>,
+    #[serde(default)]
+    windows: Vec<PluginManifestWindowsPermissions>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct PluginManifestLimits {
+    #[serde(default)]
+    heap_mb: Option<u64>,
+    #[serde(default)]
+    address_space_mb: Option<u64>,
+    #[serde(default)]
+    cpu_seconds: Option<u64>,
+    #[serde(default)]
+    event_loop_timeout_secs: Option<u64>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -1070,7 +1646,7 @@ pub struct PluginManifestPermissionsExec {
     pub executable: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Eq, PartialEq)]
 pub enum PluginManifestClipboardPermissions {
     #[serde(rename = "read")]
     Read,
@@ -1086,3 +1662,422 @@ pub enum PluginManifestMainSearchBarPermissions {
     Read,
 }
 
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub enum PluginManifestWindowsPermissions {
+    #[serde(rename = "read")]
+    Read,
+    #[serde(rename = "focus")]
+    Focus,
+    #[serde(rename = "close")]
+    Close,
+    #[serde(rename = "minimize")]
+    Minimize,
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_zero_is_rejected() {
+        assert!(PluginLoader::validate_limit("heap_mb", Some(0), MAX_HEAP_MB).is_err());
+    }
+
+    #[test]
+    fn limit_within_bounds() {
+        assert!(PluginLoader::validate_limit("heap_mb", None, MAX_HEAP_MB).is_ok());
+        assert!(PluginLoader::validate_limit("heap_mb", Some(1), MAX_HEAP_MB).is_ok());
+        assert!(PluginLoader::validate_limit("heap_mb", Some(MAX_HEAP_MB), MAX_HEAP_MB).is_ok());
+    }
+
+    #[test]
+    fn limit_above_bound_is_rejected() {
+        assert!(PluginLoader::validate_limit("heap_mb", Some(MAX_HEAP_MB + 1), MAX_HEAP_MB).is_err());
+        assert!(PluginLoader::validate_limit("cpu_seconds", Some(u64::MAX), MAX_CPU_SECONDS).is_err());
+    }
+
+    fn validate_unix_path(path: &str) -> anyhow::Result<()> {
+        PluginLoader::validate_path_permissions(&[path.to_string()], &true, &true, &false)
+    }
+
+    fn validate_windows_path(path: &str) -> anyhow::Result<()> {
+        PluginLoader::validate_path_permissions(&[path.to_string()], &false, &false, &true)
+    }
+
+    // variables of linux and macos namespaces are always unix-style paths, even if followed by a backslash
+    fn assert_requires_unix_system(path: &str) {
+        let err = validate_windows_path(path).unwrap_err();
+
+        assert!(err.to_string().contains("unix-style path"), "unexpected error: {}", err);
+        assert!(PluginLoader::validate_path_permissions(&[path.to_string()], &true, &false, &true).is_ok());
+        assert!(PluginLoader::validate_path_permissions(&[path.to_string()], &false, &true, &true).is_ok());
+    }
+
+    #[test]
+    fn path_variable_common_plugin_assets() {
+        assert!(validate_unix_path("{common:plugin-assets}/bin/tool").is_ok());
+        assert!(validate_windows_path("{common:plugin-assets}\\bin\\tool.exe").is_ok());
+    }
+
+    #[test]
+    fn path_variable_common_plugin_assets_is_read_only() {
+        assert!(PluginLoader::validate_read_only_path_permissions(&["{common:plugin-assets}/file".to_string()]).is_err());
+        assert!(PluginLoader::validate_read_only_path_permissions(&["{common:plugin-data}/file".to_string()]).is_ok());
+    }
+
+    #[test]
+    fn path_variable_common_documents() {
+        assert!(validate_unix_path("{common:documents}/notes").is_ok());
+        assert!(validate_windows_path("{common:documents}\\notes").is_ok());
+    }
+
+    #[test]
+    fn path_variable_common_downloads() {
+        assert!(validate_unix_path("{common:downloads}/file.zip").is_ok());
+        assert!(validate_windows_path("{common:downloads}\\file.zip").is_ok());
+    }
+
+    #[test]
+    fn path_variable_common_desktop() {
+        assert!(validate_unix_path("{common:desktop}").is_ok());
+        assert!(validate_windows_path("{common:desktop}\\test").is_ok());
+    }
+
+    #[test]
+    fn path_variable_common_temp() {
+        assert!(validate_unix_path("{common:temp}/my-plugin").is_ok());
+        assert!(validate_windows_path("{common:temp}\\my-plugin").is_ok());
+    }
+
+    #[test]
+    fn path_variable_linux_xdg_config() {
+        assert!(validate_unix_path("{linux:xdg-config}/my-app").is_ok());
+        assert_requires_unix_system("{linux:xdg-config}/my-app");
+        assert_requires_unix_system("{linux:xdg-config}\\my-app");
+    }
+
+    #[test]
+    fn path_variable_linux_xdg_data() {
+        assert!(validate_unix_path("{linux:xdg-data}/my-app").is_ok());
+        assert_requires_unix_system("{linux:xdg-data}/my-app");
+        assert_requires_unix_system("{linux:xdg-data}\\my-app");
+    }
+
+    #[test]
+    fn path_variable_linux_xdg_runtime() {
+        assert!(validate_unix_path("{linux:xdg-runtime}/my-app.sock").is_ok());
+        assert_requires_unix_system("{linux:xdg-runtime}/my-app.sock");
+        assert_requires_unix_system("{linux:xdg-runtime}\\my-app.sock");
+    }
+
+    #[test]
+    fn path_variable_macos_application_support() {
+        assert!(validate_unix_path("{macos:application-support}/MyApp").is_ok());
+        assert_requires_unix_system("{macos:application-support}/MyApp");
+        assert_requires_unix_system("{macos:application-support}\\MyApp");
+    }
+
+    #[test]
+    fn path_variable_macos_preferences() {
+        assert!(validate_unix_path("{macos:preferences}/com.example.plist").is_ok());
+        assert_requires_unix_system("{macos:preferences}/com.example.plist");
+        assert_requires_unix_system("{macos:preferences}\\com.example.plist");
+    }
+
+    #[test]
+    fn path_variable_macos_caches() {
+        assert!(validate_unix_path("{macos:caches}/MyApp").is_ok());
+        assert_requires_unix_system("{macos:caches}/MyApp");
+        assert_requires_unix_system("{macos:caches}\\MyApp");
+    }
+
+    #[test]
+    fn path_variable_not_at_beginning() {
+        assert!(validate_unix_path("/test/{common:documents}").is_err());
+    }
+
+    #[test]
+    fn path_variable_not_followed_by_slash() {
+        assert!(validate_unix_path("{common:documents}notes").is_err());
+    }
+
+    #[test]
+    fn path_variable_unknown() {
+        assert!(validate_unix_path("{common:pictures}/test").is_err());
+        assert!(validate_unix_path("{linux:xdg-documents}/test").is_err());
+    }
+    fn manifest_permissions(value: &str) -> PluginManifestPermissions {
+        toml::from_str(value).unwrap()
+    }
+
+    fn validate_subset(entrypoint_permissions: &str, plugin_permissions: &str) -> anyhow::Result<()> {
+        PluginLoader::validate_entrypoint_permissions_subset(&manifest_permissions(entrypoint_permissions), &manifest_permissions(plugin_permissions))
+    }
+
+    const PLUGIN_PERMISSIONS: &str = r#"
+        environment = ["HOME", "LANG"]
+        network = ["*.example.com:443", "api.example.org"]
+        system = ["hostname"]
+        clipboard = ["read", "write"]
+        main_search_bar = ["read"]
+        windows = ["read"]
+
+        [filesystem]
+        read = ["/a/b", "{common:plugin-data}"]
+        write = ["/a/b/out"]
+
+        [exec]
+        command = ["ls"]
+        executable = ["/usr/bin"]
+    "#;
+
+    #[test]
+    fn entrypoint_permissions_subset_is_accepted() {
+        assert!(validate_subset("", PLUGIN_PERMISSIONS).is_ok());
+        assert!(validate_subset(PLUGIN_PERMISSIONS, PLUGIN_PERMISSIONS).is_ok());
+
+        let entrypoint_permissions = r#"
+            environment = ["HOME"]
+            network = ["api.example.com:443", "api.example.org:8080"]
+            clipboard = ["read"]
+
+            [filesystem]
+            read = ["/a/b/c", "{common:plugin-data}/cache"]
+            write = ["/a/b/out"]
+
+            [exec]
+            command = ["ls"]
+            executable = ["/usr/bin/tool"]
+        "#;
+
+        assert!(validate_subset(entrypoint_permissions, PLUGIN_PERMISSIONS).is_ok());
+    }
+
+    #[test]
+    fn entrypoint_permissions_superset_is_rejected() {
+        assert!(validate_subset(r#"environment = ["PATH"]"#, PLUGIN_PERMISSIONS).is_err());
+        assert!(validate_subset(r#"network = ["example.com:443"]"#, PLUGIN_PERMISSIONS).is_err());
+        assert!(validate_subset(r#"network = ["*.example.com"]"#, PLUGIN_PERMISSIONS).is_err());
+        assert!(validate_subset(r#"system = ["osRelease"]"#, PLUGIN_PERMISSIONS).is_err());
+        assert!(validate_subset(r#"clipboard = ["clear"]"#, PLUGIN_PERMISSIONS).is_err());
+        assert!(validate_subset(r#"windows = ["close"]"#, PLUGIN_PERMISSIONS).is_err());
+        assert!(validate_subset("[filesystem]\nread = [\"/a\"]", PLUGIN_PERMISSIONS).is_err());
+        assert!(validate_subset("[filesystem]\nwrite = [\"/a/b\"]", PLUGIN_PERMISSIONS).is_err());
+        assert!(validate_subset("[filesystem]\nread = [\"{common:plugin-cache}\"]", PLUGIN_PERMISSIONS).is_err());
+        assert!(validate_subset("[exec]\ncommand = [\"rm\"]", PLUGIN_PERMISSIONS).is_err());
+        assert!(validate_subset("[exec]\nexecutable = [\"/usr/local/bin/tool\"]", PLUGIN_PERMISSIONS).is_err());
+    }
+
+    #[test]
+    fn entrypoint_permissions_parent_dir_escape_is_rejected() {
+        assert!(validate_subset("[filesystem]\nread = [\"/a/b/../c\"]", PLUGIN_PERMISSIONS).is_err());
+        assert!(validate_subset("[filesystem]\nread = [\"{common:plugin-data}/../other\"]", PLUGIN_PERMISSIONS).is_err());
+    }
+
+    #[test]
+    fn path_within_parent() {
+        assert!(PluginLoader::is_path_within("/a/b", "/a/b"));
+        assert!(PluginLoader::is_path_within("/a/b/c", "/a/b"));
+        assert!(PluginLoader::is_path_within("/a/b/c", "/a/b/"));
+        assert!(PluginLoader::is_path_within("{common:plugin-data}/c", "{common:plugin-data}"));
+        assert!(PluginLoader::is_path_within("C:\\a\\b\\c", "C:\\a\\b"));
+    }
+
+    #[test]
+    fn path_with_common_prefix_is_not_within_parent() {
+        assert!(!PluginLoader::is_path_within("/a/bc", "/a/b"));
+        assert!(!PluginLoader::is_path_within("/a/bc/d", "/a/b"));
+        assert!(!PluginLoader::is_path_within("/a", "/a/b"));
+        assert!(!PluginLoader::is_path_within("{common:plugin-data-other}", "{common:plugin-data}"));
+        assert!(!PluginLoader::is_path_within("C:\\a\\bc", "C:\\a\\b"));
+    }
+
+    #[test]
+    fn path_with_parent_dir_segment_is_not_within_parent() {
+        assert!(!PluginLoader::is_path_within("/a/b/../c", "/a/b"));
+        assert!(!PluginLoader::is_path_within("/a/b/./c", "/a/b"));
+        assert!(!PluginLoader::is_path_within("/a/b/c", "/a/b/.."));
+        assert!(!PluginLoader::is_path_within("C:\\a\\b\\..\\c", "C:\\a\\b"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn path_through_symlink_outside_of_parent_is_not_within_parent() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let parent = dir.path().join("parent");
+        let outside = dir.path().join("outside");
+
+        std::fs::create_dir(&parent).unwrap();
+        std::fs::create_dir(&outside).unwrap();
+        std::fs::create_dir(parent.join("inside")).unwrap();
+        std::os::unix::fs::symlink(&outside, parent.join("link")).unwrap();
+
+        let parent_str = parent.to_str().unwrap();
+
+        assert!(PluginLoader::is_path_within(parent.join("inside").to_str().unwrap(), parent_str));
+        assert!(!PluginLoader::is_path_within(parent.join("link").to_str().unwrap(), parent_str));
+    }
+
+    fn commit(repo: &git2::Repository, message: &str, parents: &[git2::Oid]) -> git2::Oid {
+        let signature = git2::Signature::now("test", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+
+        let parents = parents.iter()
+            .map(|parent| repo.find_commit(*parent).unwrap())
+            .collect::<Vec<_>>();
+
+        let parents = parents.iter().collect::<Vec<_>>();
+
+        repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents).unwrap()
+    }
+
+    #[test]
+    fn full_commit_id() {
+        assert!(is_full_commit_id("0123456789abcdef0123456789abcdef01234567"));
+        assert!(is_full_commit_id(&"a".repeat(64)));
+        assert!(!is_full_commit_id("0123456"));
+        assert!(!is_full_commit_id("v1.0.0"));
+        assert!(!is_full_commit_id("0123456789abcdef0123456789abcdef0123456g"));
+    }
+
+    #[test]
+    fn revision_is_resolved_from_tags_branches_and_full_commit_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+
+        let first = commit(&repo, "first", &[]);
+        let second = commit(&repo, "second", &[first]);
+
+        repo.reference("refs/tags/v1", first, false, "tag").unwrap();
+        repo.reference("refs/remotes/origin/stable", second, false, "branch").unwrap();
+
+        let resolve = |revision: &str| PluginLoader::resolve_revision(&repo, revision).map(|commit| commit.id());
+
+        assert_eq!(resolve("v1"), Some(first));
+        assert_eq!(resolve("stable"), Some(second));
+        assert_eq!(resolve(&first.to_string()), Some(first));
+    }
+
+    #[test]
+    fn revision_expressions_are_not_resolved() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+
+        let first = commit(&repo, "first", &[]);
+        commit(&repo, "second", &[first]);
+
+        let resolve = |revision: &str| PluginLoader::resolve_revision(&repo, revision).map(|commit| commit.id());
+
+        assert_eq!(resolve("HEAD"), None);
+        assert_eq!(resolve("HEAD~1"), None);
+        assert_eq!(resolve("@{1}"), None);
+        assert_eq!(resolve(&first.to_string()[..7]), None);
+        assert_eq!(resolve("missing"), None);
+    }
+
+    fn write_tar_gz(path: &Path, build: impl FnOnce(&mut tar::Builder<flate2::write::GzEncoder<File>>)) {
+        let encoder = flate2::write::GzEncoder::new(File::create(path).unwrap(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+
+        build(&mut builder);
+
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    fn append_file(builder: &mut tar::Builder<flate2::write::GzEncoder<File>>, path: &str, data: &[u8]) {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+
+        builder.append_data(&mut header, path, data).unwrap();
+    }
+
+    #[test]
+    fn tar_archive_is_unpacked() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("plugin.tar.gz");
+        let target_dir = dir.path().join("target");
+
+        write_tar_gz(&archive, |builder| {
+            append_file(builder, "dist/gauntlet.toml", b"");
+            append_file(builder, "dist/assets/icon.png", b"icon");
+        });
+
+        let (hash, manifest_dir) = PluginLoader::unpack_plugin(&archive, &target_dir).unwrap();
+
+        assert_eq!(hash, hex::encode(Sha256::digest(std::fs::read(&archive).unwrap())));
+        assert_eq!(manifest_dir, target_dir.join("dist"));
+        assert_eq!(std::fs::read(manifest_dir.join("assets").join("icon.png")).unwrap(), b"icon");
+    }
+
+    #[test]
+    fn tar_archive_with_symlink_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("plugin.tar.gz");
+        let target_dir = dir.path().join("target");
+
+        write_tar_gz(&archive, |builder| {
+            append_file(builder, "gauntlet.toml", b"");
+
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(tar::EntryType::Symlink);
+            header.set_size(0);
+            builder.append_link(&mut header, "assets/x", "/etc/passwd").unwrap();
+        });
+
+        assert!(PluginLoader::unpack_plugin(&archive, &target_dir).is_err());
+        assert!(std::fs::symlink_metadata(target_dir.join("assets").join("x")).is_err());
+    }
+
+    #[test]
+    fn zip_archive_with_symlink_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("plugin.zip");
+        let target_dir = dir.path().join("target");
+
+        let mut writer = zip::ZipWriter::new(File::create(&archive).unwrap());
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+
+        writer.start_file("gauntlet.toml", options).unwrap();
+        writer.add_symlink("assets/x", "/etc/passwd", options).unwrap();
+        writer.finish().unwrap();
+
+        assert!(PluginLoader::unpack_plugin(&archive, &target_dir).is_err());
+        assert!(std::fs::symlink_metadata(target_dir.join("assets").join("x")).is_err());
+    }
+
+    #[test]
+    fn macos_resource_forks_are_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+
+        std::fs::create_dir_all(dir.path().join("__MACOSX").join("plugin")).unwrap();
+        std::fs::create_dir_all(dir.path().join("plugin")).unwrap();
+        std::fs::write(dir.path().join("plugin").join("gauntlet.toml"), "").unwrap();
+
+        assert_eq!(PluginLoader::find_manifest_dir(dir.path()), Some(dir.path().join("plugin")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn plugin_directory_with_symlink_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugin_dir = dir.path().join("plugin");
+        let secret = dir.path().join("secret");
+
+        std::fs::create_dir_all(plugin_dir.join("assets")).unwrap();
+        std::fs::write(plugin_dir.join("gauntlet.toml"), "").unwrap();
+        std::fs::write(&secret, "secret").unwrap();
+
+        assert!(PluginLoader::hash_dir(&plugin_dir).is_ok());
+
+        std::os::unix::fs::symlink(&secret, plugin_dir.join("assets").join("x")).unwrap();
+
+        assert!(PluginLoader::hash_dir(&plugin_dir).is_err());
+        assert!(PluginLoader::unpack_plugin(&plugin_dir, &dir.path().join("target")).is_err());
+    }
+}
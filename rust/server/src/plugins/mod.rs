@@ -1,31 +1,40 @@
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::Path;
 use std::sync::Mutex;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 use anyhow::anyhow;
 use include_dir::{include_dir, Dir};
+use itertools::Itertools;
 use tokio::runtime::Handle;
+use sqlx::types::Json;
+use tokio::sync::mpsc::UnboundedSender;
 
-use gauntlet_common::model::{DownloadStatus, EntrypointId, KeyboardEventOrigin, LocalSaveData, PhysicalKey, PhysicalShortcut, PluginId, PluginPreference, PluginPreferenceUserData, PreferenceEnumValue, SearchResult, SettingsEntrypoint, SettingsEntrypointType, SettingsPlugin, SettingsTheme, UiPropertyValue, UiRequestData, UiResponseData, UiSetupData, UiWidgetId};
+use gauntlet_common::model::{AuditLogCapability, AuditLogEntry, DownloadStatus, EntrypointId, FrecencyStats, KeyboardEventOrigin, LocalSaveData, PhysicalKey, PhysicalShortcut, PluginId, PluginPreference, PluginPreferenceUserData, PluginUpdate, PreferenceEnumValue, SearchResult, SettingsEntrypoint, SettingsEntrypointType, SettingsPlugin, SettingsPluginPermission, SettingsPluginPermissionGrant, SettingsTheme, UiPropertyValue, UiRequestData, UiResponseData, UiSetupData, UiWidgetId};
 use gauntlet_common::rpc::frontend_api::FrontendApi;
 use gauntlet_common::{settings_env_data_to_string, SettingsEnvData};
 use gauntlet_utils::channel::RequestSender;
 use gauntlet_common::dirs::Dirs;
-use gauntlet_plugin_runtime::{JsPluginCode, JsPluginPermissions, JsPluginPermissionsExec, JsPluginPermissionsFileSystem, JsPluginPermissionsMainSearchBar};
+use gauntlet_plugin_runtime::{JsPluginCode, JsPluginInspector, JsPluginLimits, JsPluginPermissions, JsPluginPermissionsExec, JsPluginPermissionsFileSystem, JsPluginPermissionsMainSearchBar, JsPluginPermissionsWindows};
 use crate::model::{ActionShortcutKey};
 use crate::plugins::clipboard::Clipboard;
 use crate::plugins::config_reader::ConfigReader;
-use crate::plugins::data_db_repository::{db_entrypoint_from_str, DataDbRepository, DbPluginActionShortcutKind, DbPluginClipboardPermissions, DbPluginEntrypointType, DbPluginMainSearchBarPermissions, DbPluginPreference, DbPluginPreferenceUserData, DbReadPluginEntrypoint};
+use crate::plugins::data_db_repository::{db_audit_log_capability_from_str, db_audit_log_capability_to_str, db_entrypoint_from_str, db_plugin_type_from_str, DataDbRepository, DbCode, DbPluginActionShortcutKind, DbPluginClipboardPermissions, DbPluginEntrypointType, DbPluginMainSearchBarPermissions, DbPluginPermissions, DbPluginWindowsPermissions, DbPluginPreference, DbPluginPreferenceUserData, DbPluginType, DbReadPlugin, DbReadPluginEntrypoint};
+use crate::plugins::frecency::FrecencyItemStats;
 use crate::plugins::icon_cache::IconCache;
-use crate::plugins::js::{start_plugin_runtime, AllPluginCommandData, OnePluginCommandData, PluginCommand, PluginPermissions, PluginPermissionsClipboard, PluginRuntimeData};
+use crate::plugins::supervisor::{crash_action, CrashAction, PluginCrash};
+use crate::plugins::js::{start_plugin_runtime, AllPluginCommandData, OnePluginCommandData, PluginCommand, PluginPermissions, PluginPermissionsClipboard, PluginRuntimeData, PluginRuntimeEntrypoints};
 use crate::plugins::loader::PluginLoader;
 use crate::plugins::run_status::RunStatusHolder;
 use crate::plugins::settings::Settings;
-use crate::search::SearchIndex;
+use crate::search::{query_prefixes, SearchIndex};
 use crate::SETTINGS_ENV;
 
 pub mod js;
+pub mod config_watcher;
+pub mod supervisor;
 mod data_db_repository;
 mod config_reader;
 mod loader;
@@ -38,11 +47,24 @@ mod runtime;
 mod image_gatherer;
 mod settings;
 mod theme;
+pub(in crate) mod signature;
 
 static BUNDLED_PLUGINS: [(&str, Dir); 1] = [
     ("gauntlet", include_dir!("$CARGO_MANIFEST_DIR/../../bundled_plugins/gauntlet/dist")),
 ];
 
+// viewer shows only most recent entries
+const AUDIT_LOG_MAX_ENTRIES: i64 = 1000;
+
+// single selection decays below this in about a month with default half life,
+// at that point it doesn't change the order of search results anymore
+const MIN_QUERY_FRECENCY: f64 = 0.001;
+// removed even if it was selected a lot, prefixes typed that long ago are unlikely to be typed again
+const MAX_QUERY_FRECENCY_AGE: Duration = Duration::from_secs(90 * 24 * 60 * 60);
+
+// same as default port of deno and node inspector
+const DEFAULT_INSPECTOR_PORT: u16 = 9229;
+
 pub struct ApplicationManager {
     config_reader: ConfigReader,
     search_index: SearchIndex,
@@ -55,20 +77,30 @@ pub struct ApplicationManager {
     dirs: Dirs,
     clipboard: Clipboard,
     settings: Settings,
+    plugin_crash_sender: UnboundedSender<PluginCrash>,
+    config_download_sender: UnboundedSender<PluginId>,
+    // ports are assigned once and kept while application is running, so debugger can reconnect after plugin reload
+    inspector_ports: Mutex<HashMap<PluginId, u16>>,
 }
 
 impl ApplicationManager {
-    pub async fn create(frontend_sender: RequestSender<UiRequestData, UiResponseData>) -> anyhow::Result<Self> {
+    pub async fn create(
+        frontend_sender: RequestSender<UiRequestData, UiResponseData>,
+        plugin_crash_sender: UnboundedSender<PluginCrash>,
+        config_download_sender: UnboundedSender<PluginId>,
+    ) -> anyhow::Result<Self> {
         let frontend_api = FrontendApi::new(frontend_sender);
         let dirs = Dirs::new();
         let db_repository = DataDbRepository::new(dirs.clone()).await?;
-        let plugin_downloader = PluginLoader::new(db_repository.clone());
         let config_reader = ConfigReader::new(dirs.clone(), db_repository.clone());
+        let plugin_downloader = PluginLoader::new(db_repository.clone(), config_reader.clone());
         let icon_cache = IconCache::new(dirs.clone());
         let run_status_holder = RunStatusHolder::new();
-        let search_index = SearchIndex::create_index(frontend_api.clone())?;
+        let search_index_dir = config_reader.persistent_search_index().then(|| dirs.search_index_dir());
+        let search_index = SearchIndex::create_index(frontend_api.clone(), search_index_dir)?;
+        search_index.set_query_frecency(query_frecency_from_db(db_repository.get_query_frecency().await?));
         let clipboard = Clipboard::new()?;
-        let settings = Settings::new(dirs.clone(), db_repository.clone(), frontend_api.clone())?;
+        let settings = Settings::new(dirs.clone(), db_repository.clone(), frontend_api.clone(), config_reader.clone())?;
 
         let (command_broadcaster, _) = tokio::sync::broadcast::channel::<PluginCommand>(100);
 
@@ -83,7 +115,10 @@ impl ApplicationManager {
             frontend_api,
             clipboard,
             settings,
-            dirs
+            dirs,
+            plugin_crash_sender,
+            config_download_sender,
+            inspector_ports: Mutex::new(HashMap::new()),
         })
     }
 
@@ -103,14 +138,67 @@ impl ApplicationManager {
         Ok(())
     }
 
-    pub fn clear_all_icon_cache_dir(&self) -> anyhow::Result<()> {
-        tracing::debug!("clearing all icon cache");
+    pub fn evict_icon_cache(&self) -> anyhow::Result<()> {
+        tracing::debug!("evicting least recently used icons from icon cache");
 
-        self.icon_cache.clear_all_icon_cache_dir()
+        self.icon_cache.evict_icon_cache(self.search_index.entrypoint_icon_paths())
     }
 
-    pub async fn download_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()> {
-        self.plugin_downloader.download_plugin(plugin_id).await
+    pub async fn download_plugin(&self, plugin_id: PluginId, revision: Option<String>) -> anyhow::Result<()> {
+        self.ensure_not_managed_by_config()?;
+
+        self.plugin_downloader.download_plugin(plugin_id, revision, None).await
+    }
+
+    pub async fn install_plugin(&self, path: &str) -> anyhow::Result<PluginId> {
+        self.ensure_not_managed_by_config()?;
+
+        tracing::info!(target = "plugin", "Installing plugin from path: {:?}", path);
+
+        self.plugin_downloader.install_plugin_from_path(path).await
+    }
+
+    pub async fn check_updates(&self) -> anyhow::Result<Vec<PluginUpdate>> {
+        let mut updates = vec![];
+
+        for plugin in self.db_repository.list_plugins().await? {
+            let plugin_id = PluginId::from_string(&plugin.id);
+
+            if plugin_id.try_to_git_url().is_err() {
+                continue;
+            }
+
+            let latest_revision = match self.plugin_downloader.latest_revision(plugin_id.clone(), plugin.pinned_revision.clone()).await {
+                Ok(Some(latest_revision)) => latest_revision,
+                Ok(None) => continue,
+                Err(err) => {
+                    tracing::warn!(target = "plugin", "Unable to check updates for plugin {:?}: {:?}", plugin_id, err);
+                    continue;
+                }
+            };
+
+            if plugin.revision.as_ref() != Some(&latest_revision) {
+                updates.push(PluginUpdate {
+                    plugin_id,
+                    current_revision: plugin.revision,
+                    latest_revision,
+                })
+            }
+        }
+
+        Ok(updates)
+    }
+
+    pub async fn update_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()> {
+        self.ensure_not_managed_by_config()?;
+
+        let plugin = self.db_repository.get_plugin_by_id(&plugin_id.to_string())
+            .await?;
+
+        self.plugin_downloader.update_plugin(plugin_id.clone(), plugin.pinned_revision)
+            .await?;
+
+        self.reload_plugin(plugin_id).await
     }
 
     pub fn download_status(&self) -> HashMap<PluginId, DownloadStatus> {
@@ -148,9 +236,16 @@ impl ApplicationManager {
 
         let (stdout_file_path, stderr_file_path) = self.dirs.plugin_log_files(&plugin.uuid);
 
+        let inspector_port = if self.config_reader.dev_plugins().inspector {
+            self.inspector_ports.lock().expect("lock is poisoned").get(&plugin_id).cloned()
+        } else {
+            None
+        };
+
         Ok(LocalSaveData {
             stdout_file_path: stdout_file_path.into_os_string().into_string().map_err(|_| anyhow!("non uft8 paths are not supported"))?,
             stderr_file_path: stderr_file_path.into_os_string().into_string().map_err(|_| anyhow!("non uft8 paths are not supported"))?,
+            inspector_port,
         })
     }
 
@@ -167,6 +262,13 @@ impl ApplicationManager {
     }
 
     pub async fn plugins(&self) -> anyhow::Result<Vec<SettingsPlugin>> {
+        let mut permission_grants = self.db_repository
+            .list_all_permission_grants()
+            .await?
+            .into_iter()
+            .map(|grant| (grant.plugin_id, SettingsPluginPermissionGrant { permission: grant.permission, value: grant.value }))
+            .into_group_map();
+
         let result = self.db_repository
             .list_plugins_and_entrypoints()
             .await?
@@ -197,14 +299,25 @@ impl ApplicationManager {
                             preferences_user_data: entrypoint.preferences_user_data.into_iter()
                                 .map(|(key, value)| (key, plugin_preference_user_data_from_db(value)))
                                 .collect(),
+                            aliases: entrypoint.aliases,
                         };
 
                         (entrypoint_id, entrypoint)
                     })
                     .collect();
 
+                let permission_grants = permission_grants.remove(&plugin.id)
+                    .unwrap_or_default();
+
+                let permissions = plugin_permissions_to_settings(&plugin.permissions);
+                let unapproved_permissions = unapproved_permissions(&plugin);
+                let permissions_changed = plugin.approved_permissions.is_some() && !unapproved_permissions.is_empty();
+
+                let plugin_id = PluginId::from_string(plugin.id);
+                let run_status = self.run_status_holder.plugin_status(&plugin_id);
+
                 SettingsPlugin {
-                    plugin_id: PluginId::from_string(plugin.id),
+                    plugin_id,
                     plugin_name: plugin.name,
                     plugin_description: plugin.description,
                     enabled: plugin.enabled,
@@ -218,6 +331,13 @@ impl ApplicationManager {
                     preferences_user_data: plugin.preferences_user_data.into_iter()
                         .map(|(key, value)| (key, plugin_preference_user_data_from_db(value)))
                         .collect(),
+                    revision: plugin.revision,
+                    pinned_revision: plugin.pinned_revision,
+                    run_status,
+                    permission_grants,
+                    permissions,
+                    unapproved_permissions,
+                    permissions_changed,
                 }
             })
             .collect();
@@ -226,6 +346,21 @@ impl ApplicationManager {
     }
 
     pub async fn set_plugin_state(&self, plugin_id: PluginId, set_enabled: bool) -> anyhow::Result<()> {
+        self.ensure_not_managed_by_config()?;
+
+        if set_enabled {
+            let plugin = self.db_repository.get_plugin_by_id(&plugin_id.to_string())
+                .await?;
+
+            if !unapproved_permissions(&plugin).is_empty() {
+                return Err(anyhow!("Permissions requested by plugin need to be approved before it can be enabled"))
+            }
+        }
+
+        self.change_plugin_state(plugin_id, set_enabled).await
+    }
+
+    async fn change_plugin_state(&self, plugin_id: PluginId, set_enabled: bool) -> anyhow::Result<()> {
         let currently_running = self.run_status_holder.is_plugin_running(&plugin_id);
         let currently_enabled = self.is_plugin_enabled(&plugin_id).await?;
 
@@ -248,6 +383,13 @@ impl ApplicationManager {
                 self.stop_plugin(plugin_id.clone()).await;
                 self.search_index.remove_for_plugin(plugin_id)?;
             }
+            (false, true, false) => {
+                self.db_repository.set_plugin_enabled(&plugin_id.to_string(), false)
+                    .await?;
+
+                // plugin could be not running because it has crashed
+                self.run_status_holder.clear_status(&plugin_id);
+            }
             (true, false, _) => {
                 tracing::error!("Plugin is running but is disabled, please report this: {}", plugin_id.to_string())
             }
@@ -257,7 +399,42 @@ impl ApplicationManager {
         Ok(())
     }
 
+    /// Approves permissions only if they are the same as ones that plugin currently requests,
+    /// so that permissions added by update that happened during review are not approved without user seeing them
+    pub async fn approve_plugin_permissions(&self, plugin_id: PluginId, reviewed_permissions: Vec<SettingsPluginPermission>) -> anyhow::Result<()> {
+        tracing::info!(target = "plugin", "Approving permissions of plugin with id: {:?}", plugin_id);
+
+        let plugin = self.db_repository.get_plugin_by_id(&plugin_id.to_string())
+            .await?;
+
+        if plugin_permissions_to_settings(&plugin.permissions) != reviewed_permissions {
+            return Err(anyhow!("Permissions requested by plugin have changed, please review them again"))
+        }
+
+        let approved = self.db_repository.approve_plugin_permissions(&plugin_id.to_string(), &plugin.permissions)
+            .await?;
+
+        if !approved {
+            return Err(anyhow!("Permissions requested by plugin have changed, please review them again"))
+        }
+
+        // plugin could have been enabled while waiting for approval, e.g. after update or in config mode
+        self.reload_plugin(plugin_id).await
+    }
+
+    pub async fn revoke_permission_grant(&self, plugin_id: PluginId, permission: String, value: String) -> anyhow::Result<()> {
+        tracing::info!(target = "plugin", "Revoking '{}' permission for {:?} from plugin with id: {:?}", permission, value, plugin_id);
+
+        self.db_repository.remove_permission_grant(&plugin_id.to_string(), &permission, &value)
+            .await?;
+
+        // deno keeps granted permissions for the lifetime of runtime, restart is required for revoke to take effect
+        self.reload_plugin(plugin_id).await
+    }
+
     pub async fn set_entrypoint_state(&self, plugin_id: PluginId, entrypoint_id: EntrypointId, enabled: bool) -> anyhow::Result<()> {
+        self.ensure_not_managed_by_config()?;
+
         tracing::debug!(target = "plugin", "Setting entrypoint state for plugin id: {:?}, entrypoint_id: {:?}, enabled: {}", plugin_id, entrypoint_id, enabled);
 
         self.db_repository.set_plugin_entrypoint_enabled(&plugin_id.to_string(), &entrypoint_id.to_string(), enabled)
@@ -268,6 +445,25 @@ impl ApplicationManager {
         Ok(())
     }
 
+    pub async fn set_entrypoint_aliases(&self, plugin_id: PluginId, entrypoint_id: EntrypointId, aliases: Vec<String>) -> anyhow::Result<()> {
+        self.ensure_not_managed_by_config()?;
+
+        tracing::debug!(target = "plugin", "Setting entrypoint aliases for plugin id: {:?}, entrypoint_id: {:?}, aliases: {:?}", plugin_id, entrypoint_id, aliases);
+
+        let aliases = aliases.into_iter()
+            .map(|alias| alias.trim().to_string())
+            .filter(|alias| !alias.is_empty())
+            .unique()
+            .collect();
+
+        self.db_repository.set_plugin_entrypoint_aliases(&plugin_id.to_string(), &entrypoint_id.to_string(), aliases)
+            .await?;
+
+        self.request_search_index_reload(plugin_id);
+
+        Ok(())
+    }
+
     pub async fn set_global_shortcut(&self, shortcut: Option<PhysicalShortcut>) -> anyhow::Result<()> {
         self.settings.set_global_shortcut(shortcut).await
     }
@@ -284,7 +480,102 @@ impl ApplicationManager {
         self.settings.theme_setting().await
     }
 
+    pub async fn set_frecency_half_life(&self, half_life_secs: f64) -> anyhow::Result<()> {
+        self.ensure_not_managed_by_config()?;
+
+        self.settings.set_frecency_half_life(half_life_secs).await?;
+
+        self.refresh_all_frecency().await
+    }
+
+    pub async fn get_frecency_half_life(&self) -> anyhow::Result<f64> {
+        self.settings.frecency_half_life().await
+    }
+
+    pub async fn frecency_stats(&self, plugin_id: PluginId, entrypoint_id: Option<EntrypointId>) -> anyhow::Result<Vec<FrecencyStats>> {
+        let entrypoint_id = entrypoint_id.map(|id| id.to_string());
+
+        let stats = self.db_repository.get_frecency_stats(&plugin_id.to_string(), entrypoint_id.as_deref())
+            .await?
+            .into_iter()
+            .map(|stats| {
+                let item_stats = FrecencyItemStats {
+                    half_life: stats.half_life,
+                    reference_time: stats.reference_time,
+                    last_accessed: stats.last_accessed,
+                    frecency: stats.frecency,
+                    num_accesses: stats.num_accesses,
+                };
+
+                FrecencyStats {
+                    plugin_id: PluginId::from_string(stats.plugin_id),
+                    entrypoint_id: EntrypointId::from_string(stats.entrypoint_id),
+                    frecency: item_stats.current_frecency(),
+                    num_accesses: stats.num_accesses,
+                    last_accessed_secs: item_stats.last_access(),
+                }
+            })
+            .sorted_by(|a, b| b.frecency.total_cmp(&a.frecency))
+            .collect();
+
+        Ok(stats)
+    }
+
+    pub async fn reset_entrypoint_frecency(&self, plugin_id: PluginId, entrypoint_id: EntrypointId) -> anyhow::Result<()> {
+        tracing::debug!(target = "plugin", "Resetting frecency for plugin id: {:?}, entrypoint_id: {:?}", plugin_id, entrypoint_id);
+
+        self.db_repository.reset_entrypoint_frecency(&plugin_id.to_string(), &entrypoint_id.to_string())
+            .await?;
+
+        self.refresh_all_frecency().await
+    }
+
+    pub async fn reset_all_frecency(&self) -> anyhow::Result<()> {
+        tracing::debug!(target = "plugin", "Resetting frecency for all entrypoints");
+
+        self.db_repository.reset_all_frecency()
+            .await?;
+
+        self.refresh_all_frecency().await
+    }
+
+    pub async fn audit_log(&self, plugin_id: Option<PluginId>, capability: Option<AuditLogCapability>) -> anyhow::Result<Vec<AuditLogEntry>> {
+        let plugin_id = plugin_id.map(|id| id.to_string());
+        let capability = capability.map(|capability| db_audit_log_capability_to_str(capability));
+
+        let entries = self.db_repository.list_audit_log(plugin_id.as_deref(), capability, AUDIT_LOG_MAX_ENTRIES)
+            .await?
+            .into_iter()
+            .map(|entry| AuditLogEntry {
+                plugin_id: PluginId::from_string(entry.plugin_id),
+                entrypoint_id: entry.entrypoint_id.map(|id| EntrypointId::from_string(id)),
+                capability: db_audit_log_capability_from_str(&entry.capability),
+                value: entry.value,
+                timestamp_secs: entry.timestamp,
+            })
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// Removes audit log entries older than retention period set in config
+    pub async fn prune_audit_log(&self) -> anyhow::Result<()> {
+        let retention_days = self.config_reader.audit_log().retention_days;
+
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("failed to get system time");
+
+        let cutoff = now.saturating_sub(Duration::from_secs(retention_days.saturating_mul(24 * 60 * 60)));
+
+        tracing::debug!(target = "plugin", "Removing audit log entries older than {} days", retention_days);
+
+        self.db_repository.remove_audit_log_entries_before(cutoff.as_secs() as i64).await
+    }
+
     pub async fn set_preference_value(&self, plugin_id: PluginId, entrypoint_id: Option<EntrypointId>, preference_id: String, preference_value: PluginPreferenceUserData) -> anyhow::Result<()> {
+        self.ensure_not_managed_by_config()?;
+
         tracing::debug!(target = "plugin", "Setting preference value for plugin id: {:?}, entrypoint_id: {:?}, preference_id: {}", plugin_id, entrypoint_id, preference_id);
 
         let user_data = plugin_preference_user_data_to_db(preference_value);
@@ -295,18 +586,129 @@ impl ApplicationManager {
         Ok(())
     }
 
+    pub fn settings_read_only(&self) -> bool {
+        self.config_reader.is_settings_locked()
+    }
+
     pub async fn reload_config(&self) -> anyhow::Result<()> {
-        self.config_reader.reload_config().await?;
+        if let Err(err) = self.config_reader.refresh_config() {
+            tracing::error!("{:#}, using last valid configuration", err);
+
+            self.show_error_hud(format!("{:#}", err)).await;
+        }
+
+        let changes = self.config_reader.reload_config().await?;
+
+        for plugin_id in changes.unlisted {
+            tracing::info!(target = "plugin", "Removing plugin not listed in config: {:?}", plugin_id);
+
+            self.uninstall_plugin(plugin_id).await?;
+        }
+
+        let download_status = self.plugin_downloader.download_status();
+
+        for (plugin_id, revision) in changes.missing {
+            if let Some(DownloadStatus::InProgress) = download_status.get(&plugin_id) {
+                continue;
+            }
+
+            tracing::info!(target = "plugin", "Downloading plugin listed in config: {:?}", plugin_id);
+
+            // plugin is saved disabled, config is applied to it again when download finishes
+            self.plugin_downloader.download_plugin(plugin_id, revision, Some(self.config_download_sender.clone())).await?;
+        }
+
+        for (plugin_id, revision) in changes.revision_changed {
+            tracing::info!(target = "plugin", "Downloading plugin {:?} at revision set in config: {:?}", plugin_id, revision);
+
+            if let Err(err) = self.plugin_downloader.update_plugin(plugin_id.clone(), revision).await {
+                tracing::error!("Unable to download plugin {:?}: {:#}", plugin_id, err);
+
+                self.show_error_hud(format!("Unable to download plugin {}: {:#}", plugin_id.to_string(), err)).await;
+
+                continue;
+            }
+
+            self.reload_plugin(plugin_id).await?;
+        }
+
+        for (plugin_id, enabled) in changes.configured {
+            let running = self.run_status_holder.is_plugin_running(&plugin_id);
+
+            self.change_plugin_state(plugin_id.clone(), enabled).await?;
+
+            // entrypoint state and preferences could have changed as well
+            if running && enabled {
+                self.request_search_index_reload(plugin_id);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn handle_config_plugin_downloaded(&self, plugin_id: PluginId) -> anyhow::Result<()> {
+        tracing::info!(target = "plugin", "Plugin listed in config finished downloading, applying config: {:?}", plugin_id);
+
+        self.reload_config().await
+    }
+
+    pub async fn handle_config_file_changed(&self) -> anyhow::Result<()> {
+        tracing::info!("Config file changed, reloading");
+
+        let was_config_mode = self.config_reader.is_config_mode();
+
+        self.reload_config().await?;
+
+        let is_config_mode = self.config_reader.is_config_mode();
+
+        // when switching back to `config_and_state` mode, values from settings ui replace ones from config
+        if is_config_mode || was_config_mode {
+            if let Err(err) = self.settings.reload_global_shortcut().await {
+                tracing::warn!("Unable to set global shortcut from config: {:?}", err);
+            }
+
+            self.settings.reload_theme().await?;
+        }
 
         Ok(())
     }
 
+    pub async fn handle_theme_file_changed(&self) -> anyhow::Result<()> {
+        tracing::info!("Theme file changed, reloading");
+
+        if let Err(err) = self.settings.validate_theme_file() {
+            tracing::error!("{:#}", err);
+
+            self.show_error_hud(format!("{:#}", err)).await;
+        }
+
+        self.settings.reload_theme().await
+    }
+
+    async fn show_error_hud(&self, display: String) {
+        if let Err(err) = self.frontend_api.show_hud(display).await {
+            tracing::warn!("Unable to show hud: {:?}", err);
+        }
+    }
+
     pub async fn reload_all_plugins(&self) -> anyhow::Result<()> {
         tracing::info!("Reloading all plugins");
 
         self.reload_config().await?;
 
-        for plugin in self.db_repository.list_plugins().await? {
+        let plugins = self.db_repository.list_plugins().await?;
+
+        // search index may be persisted from previous run
+        for plugin_id in self.search_index.plugin_ids() {
+            let enabled = plugins.iter()
+                .any(|plugin| plugin.id == plugin_id.to_string() && plugin.enabled);
+
+            if !enabled {
+                self.search_index.remove_for_plugin(plugin_id)?;
+            }
+        }
+
+        for plugin in plugins {
             let plugin_id = PluginId::from_string(plugin.id);
             let running = self.run_status_holder.is_plugin_running(&plugin_id);
             match (running, plugin.enabled) {
@@ -325,6 +727,12 @@ impl ApplicationManager {
     }
 
     pub async fn remove_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()> {
+        self.ensure_not_managed_by_config()?;
+
+        self.uninstall_plugin(plugin_id).await
+    }
+
+    async fn uninstall_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()> {
         tracing::info!(target = "plugin", "Removing plugin with id: {:?}", plugin_id);
 
         let running = self.run_status_holder.is_plugin_running(&plugin_id);
@@ -367,6 +775,53 @@ impl ApplicationManager {
         self.mark_entrypoint_frecency(plugin_id, entrypoint_id).await
     }
 
+    pub async fn handle_search_result_selected(&self, query: String, plugin_id: PluginId, entrypoint_id: EntrypointId) {
+        let query_prefixes = query_prefixes(&query);
+
+        if query_prefixes.is_empty() {
+            return;
+        }
+
+        let result = self.db_repository.mark_entrypoint_query_frecency(&plugin_id.to_string(), &entrypoint_id.to_string(), &query_prefixes)
+            .await;
+
+        if let Err(err) = &result {
+            tracing::warn!(target = "rpc", "error occurred when marking entrypoint query frecency {:?}", err)
+        }
+
+        let result = self.db_repository.get_query_frecency_for_prefixes(&query_prefixes)
+            .await;
+
+        match result {
+            Ok(query_frecency) => self.search_index.update_query_frecency(&query_prefixes, query_frecency_from_db(query_frecency)),
+            Err(err) => tracing::warn!(target = "rpc", "error occurred when refreshing query frecency in search index {:?}", err)
+        }
+    }
+
+    /// Removes frecency of query prefixes that were not used for a long time,
+    /// every typed prefix of every selection is stored so without that the table only grows
+    pub async fn prune_query_frecency(&self) -> anyhow::Result<()> {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("failed to get system time");
+
+        let last_accessed_before = now.saturating_sub(MAX_QUERY_FRECENCY_AGE).as_secs_f64();
+
+        let removed = self.db_repository.remove_stale_query_frecency(MIN_QUERY_FRECENCY, last_accessed_before)
+            .await?;
+
+        tracing::debug!(target = "plugin", "Removed {} stale query frecency entries", removed);
+
+        if removed > 0 {
+            let query_frecency = self.db_repository.get_query_frecency()
+                .await?;
+
+            self.search_index.set_query_frecency(query_frecency_from_db(query_frecency));
+        }
+
+        Ok(())
+    }
+
     pub async fn handle_render_view(&self, plugin_id: PluginId, entrypoint_id: EntrypointId) -> anyhow::Result<HashMap<String, PhysicalShortcut>> {
         self.send_command(PluginCommand::One {
             id: plugin_id.clone(),
@@ -422,11 +877,34 @@ impl ApplicationManager {
         })
     }
 
-    pub fn request_search_index_refresh(&self, plugin_id: PluginId) {
-        self.send_command(PluginCommand::One {
-            id: plugin_id,
-            data: OnePluginCommandData::RefreshSearchIndex
-        })
+    fn ensure_not_managed_by_config(&self) -> anyhow::Result<()> {
+        if self.config_reader.is_settings_locked() {
+            return Err(anyhow!("Plugins are managed by config file and can not be changed from settings"))
+        }
+
+        Ok(())
+    }
+
+    async fn refresh_all_frecency(&self) -> anyhow::Result<()> {
+        for plugin_id in self.search_index.plugin_ids() {
+            self.refresh_search_index_frecency(plugin_id).await?;
+        }
+
+        let query_frecency = self.db_repository.get_query_frecency()
+            .await?;
+
+        self.search_index.set_query_frecency(query_frecency_from_db(query_frecency));
+
+        Ok(())
+    }
+
+    async fn refresh_search_index_frecency(&self, plugin_id: PluginId) -> anyhow::Result<()> {
+        let frecency = self.db_repository.get_frecency_for_plugin(&plugin_id.to_string())
+            .await?;
+
+        self.search_index.update_frecency(&plugin_id, frecency);
+
+        Ok(())
     }
 
     pub fn handle_open(&self, href: String) {
@@ -468,6 +946,56 @@ impl ApplicationManager {
             .expect("failed to execute settings process"); // this can fail in dev if binary was replaced by more recent compilation
     }
 
+    pub async fn handle_plugin_crash(&self, plugin_id: PluginId, exit_code: Option<i32>) -> anyhow::Result<()> {
+        tracing::error!(target = "plugin", "Plugin {:?} has crashed with exit code {:?}", plugin_id, exit_code);
+
+        // search items of crashed plugin can not be executed
+        self.search_index.remove_for_plugin(plugin_id.clone())?;
+
+        let crash_count = self.run_status_holder.record_crash(&plugin_id);
+
+        let backoff = match crash_action(crash_count) {
+            CrashAction::Restart { backoff } => backoff,
+            CrashAction::Disable => {
+                tracing::error!(target = "plugin", "Plugin {:?} has crashed {} times in short period of time, disabling it", plugin_id, crash_count);
+
+                self.db_repository.set_plugin_enabled(&plugin_id.to_string(), false)
+                    .await?;
+
+                self.run_status_holder.clear_crashes(&plugin_id);
+
+                let plugin = self.db_repository.get_plugin_by_id(&plugin_id.to_string())
+                    .await?;
+
+                self.show_error_hud(format!("Plugin \"{}\" keeps crashing and was disabled", plugin.name)).await;
+
+                return Ok(())
+            }
+        };
+
+        self.run_status_holder.restarting(&plugin_id, exit_code);
+
+        tracing::info!(target = "plugin", "Restarting plugin {:?} in {:?}", plugin_id, backoff);
+
+        tokio::time::sleep(backoff).await;
+
+        // plugin could have been disabled, removed or started manually while waiting
+        if self.run_status_holder.is_plugin_running(&plugin_id) {
+            return Ok(())
+        }
+
+        if !self.db_repository.does_plugin_exist(&plugin_id.to_string()).await? {
+            return Ok(())
+        }
+
+        if !self.is_plugin_enabled(&plugin_id).await? {
+            self.run_status_holder.clear_status(&plugin_id);
+            return Ok(())
+        }
+
+        self.start_plugin(plugin_id).await
+    }
+
     async fn reload_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()> {
         tracing::info!(target = "plugin", "Reloading plugin with id: {:?}", plugin_id);
 
@@ -500,84 +1028,195 @@ impl ApplicationManager {
         let plugin = self.db_repository.get_plugin_by_id(&plugin_id_str)
             .await?;
 
-        let entrypoint_names = self.db_repository.get_entrypoints_by_plugin_id(&plugin_id_str)
-            .await?
-            .into_iter()
-            .map(|entrypoint| (EntrypointId::from_string(entrypoint.id), entrypoint.name))
+        let unapproved_permissions = unapproved_permissions(&plugin);
+        if !unapproved_permissions.is_empty() {
+            tracing::info!(target = "plugin", "Plugin {:?} is not started because following permissions are not approved: {:?}", plugin_id, unapproved_permissions);
+
+            return Ok(())
+        }
+
+        let entrypoints = self.db_repository.get_entrypoints_by_plugin_id(&plugin_id_str)
+            .await?;
+
+        let entrypoint_names = entrypoints.iter()
+            .map(|entrypoint| (EntrypointId::from_string(&entrypoint.id), entrypoint.name.clone()))
             .collect::<HashMap<EntrypointId, String>>();
 
+        // entrypoints with their own permissions are run in separate runtimes, so that they don't get permissions of the whole plugin
+        let isolated_entrypoints = entrypoints.into_iter()
+            .filter_map(|entrypoint| {
+                entrypoint.permissions
+                    .map(|Json(permissions)| (EntrypointId::from_string(entrypoint.id), permissions))
+            })
+            .collect::<Vec<_>>();
+
         let inline_view_entrypoint_id = self.db_repository.get_inline_view_entrypoint_id_for_plugin(&plugin_id_str)
             .await?;
 
-        let receiver = self.command_broadcaster.subscribe();
+        let code_version = plugin_code_version(&plugin.code);
 
-        let clipboard_permissions = plugin.permissions
-            .clipboard
-            .into_iter()
-            .map(|permission| match permission {
-                DbPluginClipboardPermissions::Read => PluginPermissionsClipboard::Read,
-                DbPluginClipboardPermissions::Write => PluginPermissionsClipboard::Write,
-                DbPluginClipboardPermissions::Clear => PluginPermissionsClipboard::Clear,
+        let frecency = self.db_repository.get_frecency_for_plugin(&plugin_id_str)
+            .await?;
+
+        self.search_index.retain_for_plugin(plugin_id.clone(), &code_version, frecency)?;
+
+        let mut permissions = plugin_permissions(plugin.permissions);
+
+        // permissions granted by user via "allow always" in permission prompt
+        for grant in self.db_repository.list_permission_grants(&plugin_id_str).await? {
+            match grant.permission.as_str() {
+                "env" => permissions.environment.push(grant.value),
+                "net" => permissions.network.push(grant.value),
+                "read" => permissions.filesystem.read.push(grant.value),
+                "write" => permissions.filesystem.write.push(grant.value),
+                "run" if Path::new(&grant.value).is_absolute() => permissions.exec.executable.push(grant.value),
+                "run" => permissions.exec.command.push(grant.value),
+                "sys" => permissions.system.push(grant.value),
+                permission @ _ => tracing::warn!("Unknown permission grant {:?} for plugin {:?}, ignoring", permission, plugin_id),
+            }
+        }
+
+        let limits_config = self.config_reader.plugin_limits(&plugin_id);
+
+        let limits = JsPluginLimits {
+            heap_mb: limits_config.heap_mb.or(plugin.limits.heap_mb),
+            address_space_mb: limits_config.address_space_mb.or(plugin.limits.address_space_mb),
+            cpu_seconds: limits_config.cpu_seconds.or(plugin.limits.cpu_seconds),
+            event_loop_timeout_secs: limits_config.event_loop_timeout_secs.or(plugin.limits.event_loop_timeout_secs),
+        };
+
+        let dev_plugins = self.config_reader.dev_plugins();
+
+        let inspector = if dev_plugins.inspector && plugin_id_str.starts_with("file://") {
+            Some(JsPluginInspector {
+                port: self.inspector_port(&plugin_id, dev_plugins.inspector_port.unwrap_or(DEFAULT_INSPECTOR_PORT))?,
+                break_on_first_statement: dev_plugins.inspector_break_on_first_statement,
             })
+        } else {
+            None
+        };
+
+        let audit_log = self.config_reader.audit_log().enabled;
+
+        let isolated_entrypoint_ids = isolated_entrypoints.iter()
+            .map(|(entrypoint_id, _)| entrypoint_id.clone())
             .collect();
 
-        let main_search_bar_permissions = plugin.permissions
-            .main_search_bar
-            .into_iter()
-            .map(|permission| match permission {
-                DbPluginMainSearchBarPermissions::Read => JsPluginPermissionsMainSearchBar::Read,
+        let mut runtimes = isolated_entrypoints.into_iter()
+            .map(|(entrypoint_id, entrypoint_permissions)| PluginRuntimeData {
+                id: plugin_id.clone(),
+                uuid: plugin.uuid.clone(),
+                name: plugin.name.clone(),
+                entrypoint_names: entrypoint_names.clone(),
+                code: JsPluginCode { js: plugin.code.js.clone() },
+                code_version: code_version.clone(),
+                inline_view_entrypoint_id: inline_view_entrypoint_id.clone(),
+                entrypoints: PluginRuntimeEntrypoints::Isolated(entrypoint_id),
+                permissions: plugin_permissions(entrypoint_permissions),
+                limits: limits.clone(),
+                // inspector and prompts are only available in main runtime,
+                // isolated entrypoints are limited to permissions declared in manifest
+                inspector: None,
+                permission_prompts: false,
+                audit_log,
+                command_receiver: self.command_broadcaster.subscribe(),
+                db_repository: self.db_repository.clone(),
+                search_index: self.search_index.clone(),
+                icon_cache: self.icon_cache.clone(),
+                frontend_api: self.frontend_api.clone(),
+                dirs: self.dirs.clone(),
+                clipboard: self.clipboard.clone(),
             })
-            .collect();
+            .collect::<Vec<_>>();
 
-        let data = PluginRuntimeData {
+        runtimes.push(PluginRuntimeData {
             id: plugin_id,
             uuid: plugin.uuid,
             name: plugin.name,
             entrypoint_names,
             code: JsPluginCode { js: plugin.code.js },
+            code_version,
             inline_view_entrypoint_id,
-            permissions: PluginPermissions {
-                environment: plugin.permissions.environment,
-                network: plugin.permissions.network,
-                filesystem: JsPluginPermissionsFileSystem {
-                    read: plugin.permissions.filesystem.read,
-                    write: plugin.permissions.filesystem.write,
-                },
-                exec: JsPluginPermissionsExec {
-                    command: plugin.permissions.exec.command,
-                    executable: plugin.permissions.exec.executable,
-                },
-                system: plugin.permissions.system,
-                clipboard: clipboard_permissions,
-                main_search_bar: main_search_bar_permissions
+            entrypoints: PluginRuntimeEntrypoints::Main {
+                isolated: isolated_entrypoint_ids,
             },
-            command_receiver: receiver,
+            permissions,
+            limits,
+            inspector,
+            permission_prompts: self.config_reader.permission_prompts(),
+            audit_log,
+            command_receiver: self.command_broadcaster.subscribe(),
             db_repository: self.db_repository.clone(),
             search_index: self.search_index.clone(),
             icon_cache: self.icon_cache.clone(),
             frontend_api: self.frontend_api.clone(),
             dirs: self.dirs.clone(),
             clipboard: self.clipboard.clone(),
-        };
+        });
 
-        self.start_plugin_runtime(data);
+        self.start_plugin_runtimes(runtimes);
 
         Ok(())
     }
 
+    fn inspector_port(&self, plugin_id: &PluginId, first_port: u16) -> anyhow::Result<u16> {
+        let mut inspector_ports = self.inspector_ports.lock().expect("lock is poisoned");
+
+        if let Some(port) = inspector_ports.get(plugin_id) {
+            return Ok(*port)
+        }
+
+        // port is kept for the plugin across restarts, so debugger can reconnect to the same address.
+        // it is probed with a bind because other applications can use ports in the range as well
+        let port = (first_port..=u16::MAX)
+            .filter(|port| !inspector_ports.values().contains(port))
+            .find(|port| std::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, *port)).is_ok())
+            .ok_or(anyhow!("No free port left for inspector"))?;
+
+        inspector_ports.insert(plugin_id.clone(), port);
+
+        Ok(port)
+    }
+
     async fn stop_plugin(&self, plugin_id: PluginId) {
         tracing::info!(target = "plugin", "Stopping plugin with id: {:?}", plugin_id);
 
         self.run_status_holder.stop_plugin(&plugin_id)
     }
 
-    fn start_plugin_runtime(&self, data: PluginRuntimeData) {
-        let run_status_guard = self.run_status_holder.start_block(data.id.clone());
+    /// Runs all runtimes of the plugin, if one of them exits without being requested to stop,
+    /// the rest are stopped and whole plugin is considered crashed
+    fn start_plugin_runtimes(&self, runtimes: Vec<PluginRuntimeData>) {
+        let Some(plugin_id) = runtimes.first().map(|data| data.id.clone()) else {
+            return;
+        };
+
+        let run_status_guard = self.run_status_holder.start_block(plugin_id.clone());
+        let plugin_crash_sender = self.plugin_crash_sender.clone();
+
+        tokio::spawn(async move {
+            let runtimes = runtimes.into_iter()
+                .map(|data| Box::pin(start_plugin_runtime(data, &run_status_guard)))
+                .collect::<Vec<_>>();
 
-        tokio::spawn(async {
-            start_plugin_runtime(data, run_status_guard)
-                .await
-                .expect("failed to start plugin runtime")
+            let (result, _, remaining) = futures::future::select_all(runtimes).await;
+
+            let exit_code = match result {
+                Ok(exit_code) => exit_code,
+                Err(err) => {
+                    tracing::error!(target = "plugin", "Plugin runtime {:?} failed: {:?}", plugin_id, err);
+                    None
+                }
+            };
+
+            if !run_status_guard.stop_requested() {
+                run_status_guard.crashed(exit_code);
+
+                // wait for the rest of the runtimes to stop, so that restarted plugin doesn't overlap with them
+                futures::future::join_all(remaining).await;
+
+                let _ = plugin_crash_sender.send(PluginCrash { plugin_id, exit_code });
+            }
         });
     }
 
@@ -594,7 +1233,9 @@ impl ApplicationManager {
             tracing::warn!(target = "rpc", "error occurred when marking entrypoint frecency {:?}", err)
         }
 
-        self.request_search_index_refresh(plugin_id);
+        if let Err(err) = self.refresh_search_index_frecency(plugin_id).await {
+            tracing::warn!(target = "rpc", "error occurred when refreshing frecency in search index {:?}", err)
+        }
     }
 
     pub async fn inline_view_shortcuts(&self) -> anyhow::Result<HashMap<PluginId, HashMap<String, PhysicalShortcut>>> {
@@ -608,6 +1249,154 @@ impl ApplicationManager {
     }
 }
 
+fn query_frecency_from_db(query_frecency: HashMap<String, HashMap<(String, String), f64>>) -> HashMap<String, HashMap<(PluginId, EntrypointId), f64>> {
+    query_frecency.into_iter()
+        .map(|(query_prefix, frecency)| {
+            let frecency = frecency.into_iter()
+                .map(|((plugin_id, entrypoint_id), frecency)| ((PluginId::from_string(plugin_id), EntrypointId::from_string(entrypoint_id)), frecency))
+                .collect();
+
+            (query_prefix, frecency)
+        })
+        .collect()
+}
+
+fn plugin_permissions(permissions: DbPluginPermissions) -> PluginPermissions {
+    let clipboard = permissions.clipboard
+        .into_iter()
+        .map(|permission| match permission {
+            DbPluginClipboardPermissions::Read => PluginPermissionsClipboard::Read,
+            DbPluginClipboardPermissions::Write => PluginPermissionsClipboard::Write,
+            DbPluginClipboardPermissions::Clear => PluginPermissionsClipboard::Clear,
+        })
+        .collect();
+
+    let main_search_bar = permissions.main_search_bar
+        .into_iter()
+        .map(|permission| match permission {
+            DbPluginMainSearchBarPermissions::Read => JsPluginPermissionsMainSearchBar::Read,
+        })
+        .collect();
+
+    let windows = permissions.windows
+        .into_iter()
+        .map(|permission| match permission {
+            DbPluginWindowsPermissions::Read => JsPluginPermissionsWindows::Read,
+            DbPluginWindowsPermissions::Focus => JsPluginPermissionsWindows::Focus,
+            DbPluginWindowsPermissions::Close => JsPluginPermissionsWindows::Close,
+            DbPluginWindowsPermissions::Minimize => JsPluginPermissionsWindows::Minimize,
+        })
+        .collect();
+
+    PluginPermissions {
+        environment: permissions.environment,
+        network: permissions.network,
+        filesystem: JsPluginPermissionsFileSystem {
+            read: permissions.filesystem.read,
+            write: permissions.filesystem.write,
+        },
+        exec: JsPluginPermissionsExec {
+            command: permissions.exec.command,
+            executable: permissions.exec.executable,
+        },
+        system: permissions.system,
+        clipboard,
+        main_search_bar,
+        windows,
+    }
+}
+
+fn plugin_permissions_to_settings(permissions: &DbPluginPermissions) -> Vec<SettingsPluginPermission> {
+    let to_settings = |permission: &str, values: &[String]| {
+        values.iter()
+            .map(|value| SettingsPluginPermission { permission: permission.to_string(), value: value.to_string() })
+            .collect::<Vec<_>>()
+    };
+
+    let clipboard = permissions.clipboard
+        .iter()
+        .map(|permission| match permission {
+            DbPluginClipboardPermissions::Read => "read".to_string(),
+            DbPluginClipboardPermissions::Write => "write".to_string(),
+            DbPluginClipboardPermissions::Clear => "clear".to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    let main_search_bar = permissions.main_search_bar
+        .iter()
+        .map(|permission| match permission {
+            DbPluginMainSearchBarPermissions::Read => "read".to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    let windows = permissions.windows
+        .iter()
+        .map(|permission| match permission {
+            DbPluginWindowsPermissions::Read => "read".to_string(),
+            DbPluginWindowsPermissions::Focus => "focus".to_string(),
+            DbPluginWindowsPermissions::Close => "close".to_string(),
+            DbPluginWindowsPermissions::Minimize => "minimize".to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    [
+        to_settings("network", &permissions.network),
+        to_settings("filesystem.read", &permissions.filesystem.read),
+        to_settings("filesystem.write", &permissions.filesystem.write),
+        to_settings("exec.command", &permissions.exec.command),
+        to_settings("exec.executable", &permissions.exec.executable),
+        to_settings("environment", &permissions.environment),
+        to_settings("system", &permissions.system),
+        to_settings("clipboard", &clipboard),
+        to_settings("main_search_bar", &main_search_bar),
+        to_settings("windows", &windows),
+    ].concat()
+}
+
+// bundled plugins are part of application and dev plugins are written by user, so they are not reviewed
+fn unapproved_permissions(plugin: &DbReadPlugin) -> Vec<SettingsPluginPermission> {
+    match db_plugin_type_from_str(&plugin.plugin_type) {
+        DbPluginType::Bundled => return vec![],
+        DbPluginType::Normal | DbPluginType::Config => {
+            if plugin.id.starts_with("file://") {
+                return vec![]
+            }
+        }
+    }
+
+    let approved = plugin.approved_permissions
+        .as_ref()
+        .map(|approved| plugin_permissions_to_settings(&approved.0))
+        .unwrap_or_default();
+
+    plugin_permissions_to_settings(&plugin.permissions)
+        .into_iter()
+        .filter(|permission| !approved.contains(permission))
+        .collect()
+}
+
+// whole list is returned so that user can see what plugin will have access to, not only what was added
+fn permissions_to_review(plugin: &DbReadPlugin) -> Vec<SettingsPluginPermission> {
+    if unapproved_permissions(plugin).is_empty() {
+        vec![]
+    } else {
+        plugin_permissions_to_settings(&plugin.permissions)
+    }
+}
+
+// used to check whether persisted search index was created by the same code,
+// DefaultHasher is not guaranteed to be stable between rust versions but in that case index is just recreated
+fn plugin_code_version(code: &DbCode) -> String {
+    let mut hasher = DefaultHasher::new();
+
+    for (path, content) in code.js.iter().sorted_by_key(|(path, _)| *path) {
+        path.hash(&mut hasher);
+        content.hash(&mut hasher);
+    }
+
+    format!("{:016x}", hasher.finish())
+}
+
 fn plugin_preference_from_db(id: &str, value: DbPluginPreference) -> PluginPreference {
     match value {
         DbPluginPreference::Number { name, default, description } => {
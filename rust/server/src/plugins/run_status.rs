@@ -1,27 +1,42 @@
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
 
-use gauntlet_common::model::PluginId;
+use gauntlet_common::model::{PluginId, SettingsPluginRunStatus};
+
+// crashes older than that are not counted towards crash loop limit
+const CRASH_WINDOW: Duration = Duration::from_secs(5 * 60);
 
 pub struct RunStatusHolder {
-    running_plugins: Arc<Mutex<HashMap<PluginId, CancellationToken>>>
+    running_plugins: Arc<Mutex<HashMap<PluginId, CancellationToken>>>,
+    statuses: Arc<Mutex<HashMap<PluginId, SettingsPluginRunStatus>>>,
+    crashes: Arc<Mutex<HashMap<PluginId, Vec<Instant>>>>,
 }
 
 impl RunStatusHolder {
     pub fn new() -> Self {
         Self {
-            running_plugins: Arc::new(Mutex::new(HashMap::new()))
+            running_plugins: Arc::new(Mutex::new(HashMap::new())),
+            statuses: Arc::new(Mutex::new(HashMap::new())),
+            crashes: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
     pub fn start_block(&self, plugin_id: PluginId) -> RunStatusGuard {
         let mut running_plugins = self.running_plugins.lock().expect("lock is poisoned");
-        running_plugins.insert(plugin_id.clone(), CancellationToken::new());
+        let mut statuses = self.statuses.lock().expect("lock is poisoned");
+
+        let token = CancellationToken::new();
+        running_plugins.insert(plugin_id.clone(), token.clone());
+        statuses.insert(plugin_id.clone(), SettingsPluginRunStatus::Running);
+
         RunStatusGuard {
             running_plugins: self.running_plugins.clone(),
+            statuses: self.statuses.clone(),
             id: plugin_id,
+            token,
         }
     }
 
@@ -32,27 +47,162 @@ impl RunStatusHolder {
 
     pub fn stop_plugin(&self, plugin_id: &PluginId) {
         let mut running_plugins = self.running_plugins.lock().expect("lock is poisoned");
+        let mut statuses = self.statuses.lock().expect("lock is poisoned");
+
+        statuses.remove(plugin_id);
 
         running_plugins
             .remove(plugin_id)
             .expect("value should always exist for specified id")
             .cancel()
     }
+
+    pub fn plugin_status(&self, plugin_id: &PluginId) -> SettingsPluginRunStatus {
+        let statuses = self.statuses.lock().expect("lock is poisoned");
+
+        statuses.get(plugin_id)
+            .cloned()
+            .unwrap_or(SettingsPluginRunStatus::Stopped)
+    }
+
+    pub fn clear_status(&self, plugin_id: &PluginId) {
+        let mut statuses = self.statuses.lock().expect("lock is poisoned");
+
+        statuses.remove(plugin_id);
+    }
+
+    pub fn restarting(&self, plugin_id: &PluginId, exit_code: Option<i32>) {
+        let mut statuses = self.statuses.lock().expect("lock is poisoned");
+
+        statuses.insert(plugin_id.clone(), SettingsPluginRunStatus::Restarting { exit_code });
+    }
+
+    /// Returns number of crashes of the plugin that happened recently, including this one
+    pub fn record_crash(&self, plugin_id: &PluginId) -> usize {
+        self.record_crash_at(plugin_id, Instant::now())
+    }
+
+    fn record_crash_at(&self, plugin_id: &PluginId, now: Instant) -> usize {
+        let mut crashes = self.crashes.lock().expect("lock is poisoned");
+
+        let plugin_crashes = crashes.entry(plugin_id.clone())
+            .or_default();
+
+        plugin_crashes.retain(|crash| now.duration_since(*crash) < CRASH_WINDOW);
+        plugin_crashes.push(now);
+
+        plugin_crashes.len()
+    }
+
+    pub fn clear_crashes(&self, plugin_id: &PluginId) {
+        let mut crashes = self.crashes.lock().expect("lock is poisoned");
+
+        crashes.remove(plugin_id);
+    }
 }
 
 pub struct RunStatusGuard {
     id: PluginId,
+    token: CancellationToken,
     running_plugins: Arc<Mutex<HashMap<PluginId, CancellationToken>>>,
+    statuses: Arc<Mutex<HashMap<PluginId, SettingsPluginRunStatus>>>,
 }
 
 impl RunStatusGuard {
     pub fn stopped(&self) -> WaitForCancellationFutureOwned {
-        let mut running_plugins = self.running_plugins.lock().expect("lock is poisoned");
-
-        running_plugins
-            .get(&self.id)
-            .expect("value should always exist for specified id")
+        self.token
             .clone()
             .cancelled_owned()
     }
+
+    pub fn stop_requested(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Called when runtime process exits without being requested to stop
+    pub fn crashed(&self, exit_code: Option<i32>) {
+        let mut running_plugins = self.running_plugins.lock().expect("lock is poisoned");
+        let mut statuses = self.statuses.lock().expect("lock is poisoned");
+
+        // plugin could have been stopped after runtime process exited
+        if running_plugins.remove(&self.id).is_some() {
+            statuses.insert(self.id.clone(), SettingsPluginRunStatus::Crashed { exit_code });
+        }
+
+        // stops other runtimes of the same plugin, they are started again together with crashed one
+        self.token.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crashes_within_window_are_counted() {
+        let holder = RunStatusHolder::new();
+        let plugin_id = PluginId::from_string("test");
+
+        let start = Instant::now();
+
+        assert_eq!(holder.record_crash_at(&plugin_id, start), 1);
+        assert_eq!(holder.record_crash_at(&plugin_id, start + Duration::from_secs(10)), 2);
+        assert_eq!(holder.record_crash_at(&plugin_id, start + CRASH_WINDOW - Duration::from_secs(1)), 3);
+    }
+
+    #[test]
+    fn crashes_outside_window_are_forgotten() {
+        let holder = RunStatusHolder::new();
+        let plugin_id = PluginId::from_string("test");
+
+        let start = Instant::now();
+
+        holder.record_crash_at(&plugin_id, start);
+        holder.record_crash_at(&plugin_id, start + Duration::from_secs(60));
+
+        // first crash is out of window, second one is still in it
+        assert_eq!(holder.record_crash_at(&plugin_id, start + CRASH_WINDOW), 2);
+        assert_eq!(holder.record_crash_at(&plugin_id, start + CRASH_WINDOW * 3), 1);
+    }
+
+    #[test]
+    fn crashes_are_counted_per_plugin() {
+        let holder = RunStatusHolder::new();
+        let first = PluginId::from_string("first");
+        let second = PluginId::from_string("second");
+
+        let start = Instant::now();
+
+        holder.record_crash_at(&first, start);
+        holder.record_crash_at(&first, start);
+
+        assert_eq!(holder.record_crash_at(&second, start), 1);
+        assert_eq!(holder.record_crash_at(&first, start), 3);
+    }
+
+    #[test]
+    fn cleared_crashes_are_not_counted() {
+        let holder = RunStatusHolder::new();
+        let plugin_id = PluginId::from_string("test");
+
+        holder.record_crash(&plugin_id);
+        holder.record_crash(&plugin_id);
+        holder.clear_crashes(&plugin_id);
+
+        assert_eq!(holder.record_crash(&plugin_id), 1);
+    }
+
+    #[test]
+    fn crash_of_running_plugin_updates_status() {
+        let holder = RunStatusHolder::new();
+        let plugin_id = PluginId::from_string("test");
+
+        let guard = holder.start_block(plugin_id.clone());
+
+        guard.crashed(Some(1));
+
+        assert!(!holder.is_plugin_running(&plugin_id));
+        assert!(guard.stop_requested());
+        assert!(matches!(holder.plugin_status(&plugin_id), SettingsPluginRunStatus::Crashed { exit_code: Some(1) }));
+    }
 }
@@ -1,5 +1,6 @@
+use crate::plugins::config_reader::{ConfigReader, ThemeConfig};
 use crate::plugins::data_db_repository::{DataDbRepository, DbTheme};
-use crate::plugins::theme::{read_theme_file, BundledThemes};
+use crate::plugins::theme::{read_theme_file, try_read_theme_file, BundledThemes};
 use anyhow::anyhow;
 use dark_light::Mode;
 use gauntlet_common::dirs::Dirs;
@@ -11,15 +12,17 @@ pub struct Settings {
     dirs: Dirs,
     repository: DataDbRepository,
     frontend_api: FrontendApi,
+    config_reader: ConfigReader,
     themes: BundledThemes,
 }
 
 impl Settings {
-    pub fn new(dirs: Dirs, repository: DataDbRepository, frontend_api: FrontendApi) -> anyhow::Result<Self> {
+    pub fn new(dirs: Dirs, repository: DataDbRepository, frontend_api: FrontendApi, config_reader: ConfigReader) -> anyhow::Result<Self> {
         Ok(Self {
             dirs,
             repository,
             frontend_api,
+            config_reader,
             themes: BundledThemes::new()?
         })
     }
@@ -50,10 +53,26 @@ impl Settings {
     }
 
     pub async fn global_shortcut(&self) -> anyhow::Result<Option<(Option<PhysicalShortcut>, Option<String>)>> {
+        if self.config_reader.is_config_mode() {
+            let error = self.repository.get_global_shortcut()
+                .await?
+                .and_then(|(_, error)| error);
+
+            return match self.config_reader.global_shortcut() {
+                None => Ok(None),
+                Some(Ok(shortcut)) => Ok(Some((shortcut, error))),
+                Some(Err(err)) => Ok(Some((None, Some(format!("{:#}", err))))),
+            }
+        }
+
         self.repository.get_global_shortcut().await
     }
 
     pub async fn set_global_shortcut(&self, shortcut: Option<PhysicalShortcut>) -> anyhow::Result<()> {
+        if self.config_reader.is_settings_locked() {
+            return Err(anyhow!("Global shortcut is managed by config file and can not be changed"))
+        }
+
         let err = self.frontend_api.set_global_shortcut(shortcut.clone()).await;
 
         let db_err = err.as_ref().map_err(|err| format!("{:#}", err)).err();
@@ -64,6 +83,19 @@ impl Settings {
         err
     }
 
+    /// Re-registers global shortcut in frontend, used when config file changes
+    pub async fn reload_global_shortcut(&self) -> anyhow::Result<()> {
+        let shortcut = self.effective_global_shortcut().await?;
+
+        let err = self.frontend_api.set_global_shortcut(shortcut).await;
+
+        let db_err = err.as_ref().map_err(|err| format!("{:#}", err)).err();
+
+        self.set_global_shortcut_error(db_err).await?;
+
+        err
+    }
+
     pub async fn set_global_shortcut_error(&self, error: Option<String>) -> anyhow::Result<()> {
         match self.repository.get_global_shortcut().await? {
             None => {}
@@ -82,7 +114,16 @@ impl Settings {
             return Ok(theme);
         };
 
-        // TODO config
+        if self.config_reader.is_config_mode() {
+            let theme = match self.config_reader.theme() {
+                None | Some(ThemeConfig::AutoDetect) => self.autodetect_theme(),
+                Some(ThemeConfig::MacOSLight) => self.themes.macos_light_theme.clone(),
+                Some(ThemeConfig::MacOSDark) => self.themes.macos_dark_theme.clone(),
+                Some(ThemeConfig::Legacy) => self.themes.legacy_theme.clone(),
+            };
+
+            return Ok(theme)
+        }
 
         let settings = self.repository
             .get_settings()
@@ -100,12 +141,29 @@ impl Settings {
         Ok(theme)
     }
 
+    /// Sends current theme to frontend, used when config or theme file changes
+    pub async fn reload_theme(&self) -> anyhow::Result<()> {
+        let theme = self.effective_theme().await?;
+
+        self.frontend_api.set_theme(theme).await?;
+
+        Ok(())
+    }
+
+    /// Returns error if theme file exists but cannot be read or parsed
+    pub fn validate_theme_file(&self) -> anyhow::Result<()> {
+        try_read_theme_file(&self.dirs.theme_file())
+            .map(|_| ())
+    }
+
     pub async fn theme_setting(&self) -> anyhow::Result<SettingsTheme> {
         if let Some(_) = read_theme_file(self.dirs.theme_file()) {
             return Ok(SettingsTheme::ThemeFile);
         };
 
-        // TODO config
+        if self.config_reader.is_config_mode() {
+            return Ok(SettingsTheme::Config);
+        }
 
         let mut settings = self.repository
             .get_settings()
@@ -120,6 +178,9 @@ impl Settings {
     }
 
     pub async fn set_theme_setting(&self, theme: SettingsTheme) -> anyhow::Result<()> {
+        if self.config_reader.is_settings_locked() {
+            return Err(anyhow!("Theme is managed by config file and can not be changed"))
+        }
 
         let mut settings = self.repository
             .get_settings()
@@ -151,6 +212,28 @@ impl Settings {
         Ok(())
     }
 
+    pub async fn frecency_half_life(&self) -> anyhow::Result<f64> {
+        self.repository.get_frecency_half_life().await
+    }
+
+    pub async fn set_frecency_half_life(&self, half_life: f64) -> anyhow::Result<()> {
+        if !half_life.is_finite() || half_life <= 0.0 {
+            return Err(anyhow!("Frecency half life should be a positive number of seconds, got: {}", half_life))
+        }
+
+        let mut settings = self.repository
+            .get_settings()
+            .await?;
+
+        settings.frecency_half_life = Some(half_life);
+
+        self.repository.set_settings(settings).await?;
+
+        self.repository.set_frecency_half_life(half_life).await?;
+
+        Ok(())
+    }
+
     fn autodetect_theme(&self) -> UiTheme {
         match OS {
             "macos" => {
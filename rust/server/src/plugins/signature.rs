@@ -0,0 +1,267 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Context};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use walkdir::WalkDir;
+
+use gauntlet_common::model::PluginId;
+
+use crate::plugins::config_reader::PluginSignaturesConfig;
+
+pub const SIGNATURE_FILE_NAME: &str = "gauntlet-signature.toml";
+
+#[derive(Debug, Deserialize)]
+struct PluginSignatureFile {
+    // base64 encoded ed25519 public key
+    public_key: String,
+    // base64 encoded ed25519 signature of content hash
+    signature: String,
+}
+
+/// Sha256 over manifest, `js` and `assets` directories of built plugin.
+/// Each file contributes its path relative to plugin directory and its content, in file name order,
+/// both prefixed with their length so that different sets of files can not produce the same input
+pub fn plugin_content_hash(plugin_dir: &Path) -> anyhow::Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+
+    let manifest_path = plugin_dir.join("gauntlet.toml");
+    hash_file(&mut hasher, plugin_dir, &manifest_path)?;
+
+    for dir in ["js", "assets"] {
+        let dir = plugin_dir.join(dir);
+
+        if !dir.is_dir() {
+            continue;
+        }
+
+        let entries = WalkDir::new(&dir)
+            .sort_by_file_name()
+            .into_iter()
+            .collect::<walkdir::Result<Vec<_>>>()
+            .context(format!("Unable to read directory {:?}", dir))?;
+
+        for entry in entries {
+            if entry.file_type().is_file() {
+                hash_file(&mut hasher, plugin_dir, entry.path())?;
+            }
+        }
+    }
+
+    Ok(hasher.finalize().into())
+}
+
+fn hash_file(hasher: &mut Sha256, plugin_dir: &Path, path: &Path) -> anyhow::Result<()> {
+    let relative_path = path
+        .strip_prefix(plugin_dir)
+        .expect("plugin dir is a base of path")
+        .to_str()
+        .ok_or(anyhow!("filename is not a valid utf-8"))?
+        .replace('\\', "/");
+
+    let content = std::fs::read(path)
+        .context(format!("Unable to read {:?}", path))?;
+
+    hash_bytes(hasher, relative_path.as_bytes());
+    hash_bytes(hasher, &content);
+
+    Ok(())
+}
+
+fn hash_bytes(hasher: &mut Sha256, bytes: &[u8]) {
+    hasher.update((bytes.len() as u64).to_le_bytes());
+    hasher.update(bytes);
+}
+
+/// Verifies signature of the plugin if it is present or required by config, returns hex encoded content hash.
+/// Present but invalid or untrusted signature is always an error
+pub fn verify_plugin(plugin_dir: &Path, plugin_id: &PluginId, config: &PluginSignaturesConfig) -> anyhow::Result<String> {
+    let content_hash = plugin_content_hash(plugin_dir)?;
+
+    let signature_path = plugin_dir.join(SIGNATURE_FILE_NAME);
+
+    let signature_file = match std::fs::read_to_string(&signature_path) {
+        Ok(content) => Some(content),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        Err(err) => Err(err).context(format!("Unable to read {:?}", signature_path))?,
+    };
+
+    match signature_file {
+        None => {
+            if config.required.iter().any(|id| id == &plugin_id.to_string()) {
+                return Err(anyhow!("Plugin {} is required to be signed, but {} is missing", plugin_id.to_string(), SIGNATURE_FILE_NAME))
+            }
+
+            tracing::debug!("Plugin {:?} is not signed", plugin_id);
+        }
+        Some(signature_file) => {
+            let signature_file: PluginSignatureFile = toml::from_str(&signature_file)
+                .context(format!("Unable to read {}", SIGNATURE_FILE_NAME))?;
+
+            verify_signature(&signature_file, &content_hash, config)
+                .context(format!("Signature verification of plugin {} failed", plugin_id.to_string()))?;
+
+            tracing::info!("Plugin {:?} is signed by trusted key {}", plugin_id, signature_file.public_key);
+        }
+    }
+
+    Ok(hex::encode(content_hash))
+}
+
+fn verify_signature(signature_file: &PluginSignatureFile, content_hash: &[u8; 32], config: &PluginSignaturesConfig) -> anyhow::Result<()> {
+    let trusted = config.trusted_keys.iter()
+        .any(|key| key.trim() == signature_file.public_key.trim());
+
+    if !trusted {
+        return Err(anyhow!("Public key {} is not in the list of trusted keys", signature_file.public_key))
+    }
+
+    let public_key: [u8; 32] = STANDARD.decode(signature_file.public_key.trim())
+        .context("Public key is not a valid base64")?
+        .try_into()
+        .map_err(|_| anyhow!("Public key is expected to be 32 bytes long"))?;
+
+    let public_key = VerifyingKey::from_bytes(&public_key)
+        .context("Public key is not a valid ed25519 key")?;
+
+    let signature: [u8; 64] = STANDARD.decode(signature_file.signature.trim())
+        .context("Signature is not a valid base64")?
+        .try_into()
+        .map_err(|_| anyhow!("Signature is expected to be 64 bytes long"))?;
+
+    public_key.verify(content_hash, &Signature::from_bytes(&signature))
+        .context("Signature doesn't match plugin content")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    use super::*;
+
+    const PLUGIN_ID: &str = "https://github.com/project-gauntlet/test-plugin";
+
+    fn write_plugin(plugin_dir: &Path) {
+        std::fs::write(plugin_dir.join("gauntlet.toml"), "[gauntlet]\nname = 'Test'\n").unwrap();
+        std::fs::create_dir_all(plugin_dir.join("js")).unwrap();
+        std::fs::write(plugin_dir.join("js").join("index.js"), "export default {}").unwrap();
+        std::fs::create_dir_all(plugin_dir.join("assets")).unwrap();
+        std::fs::write(plugin_dir.join("assets").join("icon.png"), [1, 2, 3]).unwrap();
+    }
+
+    fn sign_plugin(plugin_dir: &Path, signing_key: &SigningKey) {
+        let content_hash = plugin_content_hash(plugin_dir).unwrap();
+
+        let signature = signing_key.sign(&content_hash);
+
+        let signature_file = format!(
+            "public_key = '{}'\nsignature = '{}'\n",
+            STANDARD.encode(signing_key.verifying_key().to_bytes()),
+            STANDARD.encode(signature.to_bytes())
+        );
+
+        std::fs::write(plugin_dir.join(SIGNATURE_FILE_NAME), signature_file).unwrap();
+    }
+
+    fn trusting(signing_key: &SigningKey) -> PluginSignaturesConfig {
+        PluginSignaturesConfig {
+            trusted_keys: vec![STANDARD.encode(signing_key.verifying_key().to_bytes())],
+            required: vec![PLUGIN_ID.to_string()],
+        }
+    }
+
+    fn signed_plugin() -> (tempfile::TempDir, SigningKey) {
+        let plugin_dir = tempfile::tempdir().unwrap();
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+
+        write_plugin(plugin_dir.path());
+        sign_plugin(plugin_dir.path(), &signing_key);
+
+        (plugin_dir, signing_key)
+    }
+
+    #[test]
+    fn valid_signature() {
+        let (plugin_dir, signing_key) = signed_plugin();
+
+        let content_hash = verify_plugin(plugin_dir.path(), &PluginId::from_string(PLUGIN_ID), &trusting(&signing_key)).unwrap();
+
+        assert_eq!(content_hash, hex::encode(plugin_content_hash(plugin_dir.path()).unwrap()));
+    }
+
+    #[test]
+    fn tampered_file() {
+        let (plugin_dir, signing_key) = signed_plugin();
+
+        std::fs::write(plugin_dir.path().join("js").join("index.js"), "export default { evil: true }").unwrap();
+
+        assert!(verify_plugin(plugin_dir.path(), &PluginId::from_string(PLUGIN_ID), &trusting(&signing_key)).is_err());
+    }
+
+    #[test]
+    fn added_file() {
+        let (plugin_dir, signing_key) = signed_plugin();
+
+        std::fs::write(plugin_dir.path().join("js").join("extra.js"), "").unwrap();
+
+        assert!(verify_plugin(plugin_dir.path(), &PluginId::from_string(PLUGIN_ID), &trusting(&signing_key)).is_err());
+    }
+
+    #[test]
+    fn untrusted_key() {
+        let (plugin_dir, _) = signed_plugin();
+
+        let other_key = SigningKey::from_bytes(&[8; 32]);
+
+        assert!(verify_plugin(plugin_dir.path(), &PluginId::from_string(PLUGIN_ID), &trusting(&other_key)).is_err());
+    }
+
+    #[test]
+    fn signed_with_other_key() {
+        let (plugin_dir, signing_key) = signed_plugin();
+
+        let other_key = SigningKey::from_bytes(&[8; 32]);
+
+        // trusted public key, but signature made with a different private key
+        let signature = other_key.sign(&plugin_content_hash(plugin_dir.path()).unwrap());
+        let signature_file = format!(
+            "public_key = '{}'\nsignature = '{}'\n",
+            STANDARD.encode(signing_key.verifying_key().to_bytes()),
+            STANDARD.encode(signature.to_bytes())
+        );
+        std::fs::write(plugin_dir.path().join(SIGNATURE_FILE_NAME), signature_file).unwrap();
+
+        assert!(verify_plugin(plugin_dir.path(), &PluginId::from_string(PLUGIN_ID), &trusting(&signing_key)).is_err());
+    }
+
+    #[test]
+    fn missing_required_signature() {
+        let plugin_dir = tempfile::tempdir().unwrap();
+        write_plugin(plugin_dir.path());
+
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+
+        assert!(verify_plugin(plugin_dir.path(), &PluginId::from_string(PLUGIN_ID), &trusting(&signing_key)).is_err());
+        assert!(verify_plugin(plugin_dir.path(), &PluginId::from_string(PLUGIN_ID), &PluginSignaturesConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn content_moved_between_files_changes_hash() {
+        let first = tempfile::tempdir().unwrap();
+        write_plugin(first.path());
+        std::fs::write(first.path().join("js").join("a.js"), "ab").unwrap();
+        std::fs::write(first.path().join("js").join("b.js"), "").unwrap();
+
+        let second = tempfile::tempdir().unwrap();
+        write_plugin(second.path());
+        std::fs::write(second.path().join("js").join("a.js"), "a").unwrap();
+        std::fs::write(second.path().join("js").join("b.js"), "b").unwrap();
+
+        assert_ne!(plugin_content_hash(first.path()).unwrap(), plugin_content_hash(second.path()).unwrap());
+    }
+}
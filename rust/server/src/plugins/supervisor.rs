@@ -0,0 +1,70 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use gauntlet_common::model::PluginId;
+use crate::plugins::ApplicationManager;
+
+/// Sent when plugin runtime process exits without being requested to stop
+#[derive(Debug)]
+pub struct PluginCrash {
+    pub plugin_id: PluginId,
+    pub exit_code: Option<i32>,
+}
+
+// plugin is disabled when it crashes that many times within crash window
+const MAX_PLUGIN_CRASHES: usize = 5;
+const PLUGIN_RESTART_BACKOFF: Duration = Duration::from_secs(1);
+
+#[derive(Debug, PartialEq)]
+pub enum CrashAction {
+    Restart {
+        backoff: Duration
+    },
+    Disable,
+}
+
+/// Decides what to do with crashed plugin, based on number of its recent crashes including the last one
+pub fn crash_action(crash_count: usize) -> CrashAction {
+    if crash_count >= MAX_PLUGIN_CRASHES {
+        return CrashAction::Disable
+    }
+
+    // 1s, 2s, 4s, ...
+    let backoff = PLUGIN_RESTART_BACKOFF * 2u32.pow(crash_count.saturating_sub(1) as u32);
+
+    CrashAction::Restart { backoff }
+}
+
+pub async fn supervise_plugin_runtimes(application_manager: Arc<ApplicationManager>, mut receiver: UnboundedReceiver<PluginCrash>) {
+    while let Some(PluginCrash { plugin_id, exit_code }) = receiver.recv().await {
+        let application_manager = application_manager.clone();
+
+        // restart waits for backoff, so it shouldn't block handling crashes of other plugins
+        tokio::spawn(async move {
+            if let Err(err) = application_manager.handle_plugin_crash(plugin_id.clone(), exit_code).await {
+                tracing::error!("Unable to restart plugin {:?} after crash: {:?}", plugin_id, err);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restart_with_exponential_backoff() {
+        assert_eq!(crash_action(1), CrashAction::Restart { backoff: Duration::from_secs(1) });
+        assert_eq!(crash_action(2), CrashAction::Restart { backoff: Duration::from_secs(2) });
+        assert_eq!(crash_action(3), CrashAction::Restart { backoff: Duration::from_secs(4) });
+        assert_eq!(crash_action(MAX_PLUGIN_CRASHES - 1), CrashAction::Restart { backoff: Duration::from_secs(8) });
+    }
+
+    #[test]
+    fn disable_at_threshold() {
+        assert_eq!(crash_action(MAX_PLUGIN_CRASHES), CrashAction::Disable);
+        assert_eq!(crash_action(MAX_PLUGIN_CRASHES + 1), CrashAction::Disable);
+    }
+}
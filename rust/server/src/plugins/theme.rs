@@ -1,6 +1,6 @@
 use std::env::consts::OS;
 use std::io::ErrorKind;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use anyhow::{anyhow, Context};
 use dark_light::Mode;
 use serde::{Deserialize, Serialize};
@@ -153,26 +153,31 @@ pub fn parse_theme(value: &str) -> anyhow::Result<UiTheme> {
 }
 
 pub fn read_theme_file(theme_file: PathBuf) -> Option<UiTheme> {
-    match std::fs::read_to_string(&theme_file) {
+    match try_read_theme_file(&theme_file) {
+        Ok(theme) => theme,
+        Err(err) => {
+            tracing::warn!("{:#}", err);
+            None
+        }
+    }
+}
+
+/// Returns `None` if there is no theme file and error if it cannot be read or parsed
+pub fn try_read_theme_file(theme_file: &Path) -> anyhow::Result<Option<UiTheme>> {
+    match std::fs::read_to_string(theme_file) {
         Ok(value) => {
-            match parse_theme(&value) {
-                Ok(value) => Some(value),
-                Err(err) => {
-                    tracing::warn!("Unable to parse theme file: {:?} - {:?}", theme_file, err);
-                    None
-                }
-            }
+            let theme = parse_theme(&value)
+                .with_context(|| format!("Unable to parse theme file: {:?}", theme_file))?;
+
+            Ok(Some(theme))
         },
         Err(err) => {
             match err.kind() {
                 ErrorKind::NotFound => {
                     tracing::debug!("No theme file was found");
-                    None
-                },
-                err @ _ => {
-                    tracing::warn!("Unable to read theme file: {}", err);
-                    None
+                    Ok(None)
                 },
+                _ => Err(anyhow!("Unable to read theme file: {}", err)),
             }
         }
     }
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::rc::Rc;
 use std::sync::Arc;
 use gauntlet_common::{settings_env_data_to_string, SettingsEnvData};
-use gauntlet_common::model::{DownloadStatus, EntrypointId, PluginId, PluginPreferenceUserData, SettingsPlugin, UiPropertyValue, SearchResult, UiWidgetId, PhysicalKey, PhysicalShortcut, LocalSaveData, SettingsTheme};
+use gauntlet_common::model::{DownloadStatus, EntrypointId, PluginId, PluginPreferenceUserData, PluginUpdate, SettingsPlugin, SettingsPluginPermission, UiPropertyValue, SearchResult, UiWidgetId, PhysicalKey, PhysicalShortcut, LocalSaveData, SettingsTheme, FrecencyStats, AuditLogCapability, AuditLogEntry};
 use gauntlet_common::rpc::backend_server::BackendServer;
 
 use crate::plugins::ApplicationManager;
@@ -45,6 +45,10 @@ impl BackendServer for BackendServerImpl {
         result
     }
 
+    async fn settings_read_only(&self) -> anyhow::Result<bool> {
+        Ok(self.application_manager.settings_read_only())
+    }
+
     async fn set_plugin_state(&self, plugin_id: PluginId, enabled: bool) -> anyhow::Result<()> {
         let result = self.application_manager.set_plugin_state(plugin_id, enabled)
             .await;
@@ -67,6 +71,17 @@ impl BackendServer for BackendServerImpl {
         Ok(())
     }
 
+    async fn set_entrypoint_aliases(&self, plugin_id: PluginId, entrypoint_id: EntrypointId, aliases: Vec<String>) -> anyhow::Result<()> {
+        let result = self.application_manager.set_entrypoint_aliases(plugin_id, entrypoint_id, aliases)
+            .await;
+
+        if let Err(err) = &result {
+            tracing::warn!(target = "rpc", "error occurred when handling 'set_entrypoint_aliases' request {:?}", err)
+        }
+
+        result
+    }
+
     async fn set_global_shortcut(&self, shortcut: Option<PhysicalShortcut>) -> anyhow::Result<()> {
         let result = self.application_manager.set_global_shortcut(shortcut)
             .await;
@@ -94,6 +109,47 @@ impl BackendServer for BackendServerImpl {
         self.application_manager.get_theme().await
     }
 
+    async fn set_frecency_half_life(&self, half_life_secs: f64) -> anyhow::Result<()> {
+        let result = self.application_manager.set_frecency_half_life(half_life_secs)
+            .await;
+
+        if let Err(err) = &result {
+            tracing::warn!(target = "rpc", "error occurred when handling 'set_frecency_half_life' request {:?}", err)
+        }
+
+        result
+    }
+
+    async fn get_frecency_half_life(&self) -> anyhow::Result<f64> {
+        self.application_manager.get_frecency_half_life().await
+    }
+
+    async fn frecency_stats(&self, plugin_id: PluginId, entrypoint_id: Option<EntrypointId>) -> anyhow::Result<Vec<FrecencyStats>> {
+        self.application_manager.frecency_stats(plugin_id, entrypoint_id).await
+    }
+
+    async fn reset_entrypoint_frecency(&self, plugin_id: PluginId, entrypoint_id: EntrypointId) -> anyhow::Result<()> {
+        let result = self.application_manager.reset_entrypoint_frecency(plugin_id, entrypoint_id)
+            .await;
+
+        if let Err(err) = &result {
+            tracing::warn!(target = "rpc", "error occurred when handling 'reset_entrypoint_frecency' request {:?}", err)
+        }
+
+        Ok(())
+    }
+
+    async fn reset_all_frecency(&self) -> anyhow::Result<()> {
+        let result = self.application_manager.reset_all_frecency()
+            .await;
+
+        if let Err(err) = &result {
+            tracing::warn!(target = "rpc", "error occurred when handling 'reset_all_frecency' request {:?}", err)
+        }
+
+        Ok(())
+    }
+
     async fn set_preference_value(&self, plugin_id: PluginId, entrypoint_id: Option<EntrypointId>, preference_id: String, preference_value: PluginPreferenceUserData) -> anyhow::Result<()> {
         let result = self.application_manager.set_preference_value(plugin_id, entrypoint_id, preference_id, preference_value)
             .await;
@@ -105,8 +161,8 @@ impl BackendServer for BackendServerImpl {
         Ok(())
     }
 
-    async fn download_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()> {
-        let result = self.application_manager.download_plugin(plugin_id)
+    async fn download_plugin(&self, plugin_id: PluginId, revision: Option<String>) -> anyhow::Result<()> {
+        let result = self.application_manager.download_plugin(plugin_id, revision)
             .await;
 
         if let Err(err) = &result {
@@ -116,6 +172,27 @@ impl BackendServer for BackendServerImpl {
         Ok(())
     }
 
+    async fn install_plugin(&self, path: String) -> anyhow::Result<PluginId> {
+        self.application_manager.install_plugin(&path)
+            .await
+    }
+
+    async fn check_updates(&self) -> anyhow::Result<Vec<PluginUpdate>> {
+        self.application_manager.check_updates()
+            .await
+    }
+
+    async fn update_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()> {
+        let result = self.application_manager.update_plugin(plugin_id)
+            .await;
+
+        if let Err(err) = &result {
+            tracing::warn!(target = "rpc", "error occurred when handling 'update_plugin' request {:?}", err)
+        }
+
+        Ok(())
+    }
+
     async fn download_status(&self) -> anyhow::Result<HashMap<PluginId, DownloadStatus>> {
         Ok(self.application_manager.download_status())
     }
@@ -131,6 +208,32 @@ impl BackendServer for BackendServerImpl {
         Ok(())
     }
 
+    async fn revoke_permission_grant(&self, plugin_id: PluginId, permission: String, value: String) -> anyhow::Result<()> {
+        let result = self.application_manager.revoke_permission_grant(plugin_id, permission, value)
+            .await;
+
+        if let Err(err) = &result {
+            tracing::warn!(target = "rpc", "error occurred when handling 'revoke_permission_grant' request {:?}", err)
+        }
+
+        Ok(())
+    }
+
+    async fn approve_plugin_permissions(&self, plugin_id: PluginId, permissions: Vec<SettingsPluginPermission>) -> anyhow::Result<()> {
+        let result = self.application_manager.approve_plugin_permissions(plugin_id, permissions)
+            .await;
+
+        if let Err(err) = &result {
+            tracing::warn!(target = "rpc", "error occurred when handling 'approve_plugin_permissions' request {:?}", err)
+        }
+
+        result
+    }
+
+    async fn audit_log(&self, plugin_id: Option<PluginId>, capability: Option<AuditLogCapability>) -> anyhow::Result<Vec<AuditLogEntry>> {
+        self.application_manager.audit_log(plugin_id, capability).await
+    }
+
     async fn save_local_plugin(&self, path: String) -> anyhow::Result<LocalSaveData> {
         let result = self.application_manager.save_local_plugin(&path)
             .await?;
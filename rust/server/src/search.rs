@@ -1,12 +1,16 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use tantivy::{doc, Index, IndexReader, IndexWriter, ReloadPolicy, Searcher};
-use tantivy::collector::TopDocs;
+use tantivy::collector::{DocSetCollector, TopDocs};
+use tantivy::directory::MmapDirectory;
 use tantivy::query::{AllQuery, BooleanQuery, FuzzyTermQuery, Query, RegexQuery, TermQuery};
 use tantivy::schema::*;
 use tantivy::tokenizer::TokenizerManager;
-use gauntlet_common::model::{EntrypointId, PhysicalShortcut, PluginId, SearchResult, SearchResultAccessory, SearchResultEntrypointAction, SearchResultEntrypointActionType, SearchResultEntrypointType};
+use gauntlet_common::model::{EntrypointId, PhysicalKey, PhysicalShortcut, PluginId, SearchResult, SearchResultAccessory, SearchResultEntrypointAction, SearchResultEntrypointActionType, SearchResultEntrypointType};
 use gauntlet_common::rpc::frontend_api::FrontendApi;
 
 #[derive(Clone)]
@@ -16,34 +20,64 @@ pub struct SearchIndex {
     index_reader: IndexReader,
     index_writer_mutex: Arc<Mutex<()>>,
 
-    entrypoint_data: Arc<Mutex<HashMap<PluginId, HashMap<EntrypointId, EntrypointData>>>>,
+    plugin_data: Arc<Mutex<HashMap<PluginId, PluginData>>>,
+    query_frecency: Arc<Mutex<HashMap<String, HashMap<(PluginId, EntrypointId), f64>>>>,
 
     entrypoint_name: Field,
+    entrypoint_name_initials: Field,
+    entrypoint_keywords: Field,
+    entrypoint_description: Field,
+    entrypoint_aliases: Field,
     entrypoint_id: Field,
+    entrypoint_data: Field,
     plugin_name: Field,
     plugin_id: Field,
+    plugin_code_version: Field,
 }
 
+struct PluginData {
+    code_version: String,
+    entrypoints: HashMap<EntrypointId, EntrypointData>,
+}
+
+// stored in the index as json, so it is available after restart when index is persisted on disk
+#[derive(Serialize, Deserialize)]
 struct EntrypointData {
     entrypoint_generator_name: Option<String>,
     entrypoint_type: SearchResultEntrypointType,
     icon_path: Option<String>,
+    #[serde(skip)] // always taken from db
     frecency: f64,
     actions: Vec<EntrypointActionData>,
     accessories: Vec<SearchResultAccessory>,
+    keywords: Vec<String>,
+    description: Option<String>,
+    // normalized, see normalize_alias
+    aliases: Vec<String>,
 }
 
+#[derive(Serialize, Deserialize)]
 struct EntrypointActionData {
     label: String,
     action_type: EntrypointActionType,
-    shortcut: Option<PhysicalShortcut>,
+    shortcut: Option<EntrypointActionShortcutData>,
 }
 
+#[derive(Serialize, Deserialize)]
 enum EntrypointActionType {
     Command,
     View,
 }
 
+#[derive(Serialize, Deserialize)]
+struct EntrypointActionShortcutData {
+    physical_key: String,
+    modifier_shift: bool,
+    modifier_control: bool,
+    modifier_alt: bool,
+    modifier_meta: bool,
+}
+
 #[derive(Clone, Debug)]
 pub struct SearchIndexItem {
     pub entrypoint_type: SearchResultEntrypointType,
@@ -54,6 +88,9 @@ pub struct SearchIndexItem {
     pub entrypoint_frecency: f64,
     pub entrypoint_actions: Vec<SearchIndexItemAction>,
     pub entrypoint_accessories: Vec<SearchResultAccessory>,
+    pub entrypoint_keywords: Vec<String>,
+    pub entrypoint_description: Option<String>,
+    pub entrypoint_aliases: Vec<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -71,47 +108,160 @@ pub enum SearchIndexItemActionActionType {
 
 
 impl SearchIndex {
-    pub fn create_index(frontend_api: FrontendApi) -> tantivy::Result<Self> {
+    /// If `index_dir` is provided index is persisted on disk and reused after restart
+    pub fn create_index(frontend_api: FrontendApi, index_dir: Option<PathBuf>) -> anyhow::Result<Self> {
         let schema = {
             let mut schema_builder = Schema::builder();
 
             schema_builder.add_text_field("entrypoint_name", TEXT | STORED);
+            schema_builder.add_text_field("entrypoint_name_initials", STRING);
+            schema_builder.add_text_field("entrypoint_keywords", TEXT);
+            schema_builder.add_text_field("entrypoint_description", TEXT);
+            schema_builder.add_text_field("entrypoint_aliases", STRING);
             schema_builder.add_text_field("entrypoint_id", STRING | STORED);
+            schema_builder.add_text_field("entrypoint_data", STORED);
             schema_builder.add_text_field("plugin_name", TEXT | STORED);
             schema_builder.add_text_field("plugin_id", STRING | STORED);
+            schema_builder.add_text_field("plugin_code_version", STRING | STORED);
 
             schema_builder.build()
         };
 
         let entrypoint_name = schema.get_field("entrypoint_name").expect("entrypoint_name field should exist");
+        let entrypoint_name_initials = schema.get_field("entrypoint_name_initials").expect("entrypoint_name_initials field should exist");
+        let entrypoint_keywords = schema.get_field("entrypoint_keywords").expect("entrypoint_keywords field should exist");
+        let entrypoint_description = schema.get_field("entrypoint_description").expect("entrypoint_description field should exist");
+        let entrypoint_aliases = schema.get_field("entrypoint_aliases").expect("entrypoint_aliases field should exist");
         let entrypoint_id = schema.get_field("entrypoint_id").expect("entrypoint_id field should exist");
+        let entrypoint_data = schema.get_field("entrypoint_data").expect("entrypoint_data field should exist");
         let plugin_name = schema.get_field("plugin_name").expect("plugin_name field should exist");
         let plugin_id = schema.get_field("plugin_id").expect("plugin_id field should exist");
+        let plugin_code_version = schema.get_field("plugin_code_version").expect("plugin_code_version field should exist");
 
-        let index = Index::create_in_ram(schema.clone());
+        let index = match index_dir {
+            None => Index::create_in_ram(schema.clone()),
+            Some(index_dir) => open_index_on_disk(&index_dir, schema.clone())?,
+        };
 
         let index_reader = index
             .reader_builder()
             .reload_policy(ReloadPolicy::Manual)
             .try_into()?;
 
-        Ok(Self {
+        let search_index = Self {
             frontend_api,
             index,
             index_reader,
             index_writer_mutex: Arc::new(Mutex::new(())),
-            entrypoint_data: Arc::new(Mutex::new(HashMap::new())),
+            plugin_data: Arc::new(Mutex::new(HashMap::new())),
+            query_frecency: Arc::new(Mutex::new(HashMap::new())),
             entrypoint_name,
+            entrypoint_name_initials,
+            entrypoint_keywords,
+            entrypoint_description,
+            entrypoint_aliases,
             entrypoint_id,
+            entrypoint_data,
             plugin_name,
             plugin_id,
-        })
+            plugin_code_version,
+        };
+
+        search_index.load_plugin_data()?;
+
+        Ok(search_index)
+    }
+
+    fn load_plugin_data(&self) -> anyhow::Result<()> {
+        let mut plugin_data = self.plugin_data.lock().expect("lock is poisoned");
+
+        let searcher = self.index_reader.searcher();
+
+        for doc_address in searcher.search(&AllQuery, &DocSetCollector)? {
+            let retrieved_doc = searcher.doc::<TantivyDocument>(doc_address)?;
+
+            let get_str_field = |field: Field| -> anyhow::Result<String> {
+                let value = retrieved_doc.get_first(field)
+                    .and_then(|value| value.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("field with name {:?} is missing in persisted search index", searcher.schema().get_field_name(field)))?
+                    .to_owned();
+
+                Ok(value)
+            };
+
+            let plugin_id = PluginId::from_string(get_str_field(self.plugin_id)?);
+            let entrypoint_id = EntrypointId::from_string(get_str_field(self.entrypoint_id)?);
+            let code_version = get_str_field(self.plugin_code_version)?;
+            let data: EntrypointData = serde_json::from_str(&get_str_field(self.entrypoint_data)?)?;
+
+            plugin_data.entry(plugin_id)
+                .or_insert_with(|| PluginData { code_version, entrypoints: HashMap::new() })
+                .entrypoints
+                .insert(entrypoint_id, data);
+        }
+
+        tracing::debug!("Loaded search index data for {} plugins", plugin_data.len());
+
+        Ok(())
+    }
+
+    pub fn plugin_ids(&self) -> Vec<PluginId> {
+        let plugin_data = self.plugin_data.lock().expect("lock is poisoned");
+
+        plugin_data.keys()
+            .cloned()
+            .collect()
+    }
+
+    /// Icons of entrypoints which are currently in the index
+    pub fn entrypoint_icon_paths(&self) -> Vec<String> {
+        let plugin_data = self.plugin_data.lock().expect("lock is poisoned");
+
+        plugin_data.values()
+            .flat_map(|data| data.entrypoints.values())
+            .filter_map(|entrypoint_data| entrypoint_data.icon_path.clone())
+            .collect()
+    }
+
+    /// Keeps entrypoints persisted from previous run if they were indexed with the same version of plugin code,
+    /// otherwise removes them so that search doesn't return entrypoints that may not exist anymore
+    pub fn retain_for_plugin(&self, plugin_id: PluginId, code_version: &str, frecency: HashMap<String, f64>) -> tantivy::Result<()> {
+        let mut plugin_data = self.plugin_data.lock().expect("lock is poisoned");
+
+        let outdated = match plugin_data.get_mut(&plugin_id) {
+            None => false,
+            Some(data) if data.code_version != code_version => true,
+            Some(data) => {
+                for (entrypoint_id, entrypoint_data) in data.entrypoints.iter_mut() {
+                    entrypoint_data.frecency = frecency.get(&entrypoint_id.to_string()).cloned().unwrap_or(0.0);
+
+                    // icons that were not used for a while are evicted from icon cache
+                    if let Some(icon_path) = &entrypoint_data.icon_path {
+                        if !Path::new(icon_path).exists() {
+                            entrypoint_data.icon_path = None;
+                        }
+                    }
+                }
+
+                false
+            }
+        };
+
+        drop(plugin_data);
+
+        if outdated {
+            tracing::debug!("Search index for plugin {:?} was created for different version of plugin code, removing", plugin_id);
+
+            self.remove_for_plugin(plugin_id)?;
+        }
+
+        Ok(())
     }
 
     pub fn remove_for_plugin(&self, plugin_id: PluginId) -> tantivy::Result<()> {
         // writer panics if another writer exists
         let _guard = self.index_writer_mutex.lock().expect("lock is poisoned");
-        let mut entrypoint_data = self.entrypoint_data.lock().expect("lock is poisoned");
+        let mut plugin_data = self.plugin_data.lock().expect("lock is poisoned");
 
         let mut index_writer = self.index.writer::<TantivyDocument>(15_000_000)?;
 
@@ -121,17 +271,17 @@ impl SearchIndex {
         index_writer.commit()?;
         self.index_reader.reload()?;
 
-        entrypoint_data.remove(&plugin_id);
+        plugin_data.remove(&plugin_id);
 
         Ok(())
     }
 
-    pub fn save_for_plugin(&self, plugin_id: PluginId, plugin_name: String, search_items: Vec<SearchIndexItem>, refresh_search_list: bool) -> tantivy::Result<()> {
+    pub fn save_for_plugin(&self, plugin_id: PluginId, plugin_name: String, code_version: String, search_items: Vec<SearchIndexItem>, refresh_search_list: bool) -> tantivy::Result<()> {
         tracing::debug!("Reloading search index for plugin {:?}", plugin_id);
 
         // writer panics if another writer exists
         let _guard = self.index_writer_mutex.lock().expect("lock is poisoned");
-        let mut entrypoint_data = self.entrypoint_data.lock().expect("lock is poisoned");
+        let mut plugin_data = self.plugin_data.lock().expect("lock is poisoned");
 
         let mut index_writer = self.index.writer::<TantivyDocument>(15_000_000)?;
 
@@ -139,19 +289,115 @@ impl SearchIndex {
             TermQuery::new(Term::from_field_text(self.plugin_id, &plugin_id.to_string()), IndexRecordOption::Basic)
         ))?;
 
-        for search_item in &search_items {
-            index_writer.add_document(doc!(
-                self.entrypoint_name => search_item.entrypoint_name.clone(),
-                self.entrypoint_id => search_item.entrypoint_id.to_string(),
-                self.plugin_name => plugin_name.clone(),
-                self.plugin_id => plugin_id.to_string(),
-            ))?;
+        let entrypoints = self.add_documents(&mut index_writer, &plugin_id, &plugin_name, &code_version, search_items)?;
+
+        index_writer.commit()?;
+        self.index_reader.reload()?;
+
+        plugin_data.insert(plugin_id.clone(), PluginData { code_version, entrypoints });
+
+        if refresh_search_list {
+            self.request_search_results_update(plugin_id);
         }
 
+        Ok(())
+    }
+
+    /// Adds or replaces provided entrypoints and removes entrypoints with provided ids,
+    /// rest of the entrypoints of the plugin are left untouched
+    pub fn update_for_plugin(
+        &self,
+        plugin_id: PluginId,
+        plugin_name: String,
+        code_version: String,
+        search_items: Vec<SearchIndexItem>,
+        removed_entrypoint_ids: Vec<EntrypointId>,
+        refresh_search_list: bool
+    ) -> tantivy::Result<()> {
+        tracing::debug!("Updating search index for plugin {:?}, updated: {}, removed: {}", plugin_id, search_items.len(), removed_entrypoint_ids.len());
+
+        // writer panics if another writer exists
+        let _guard = self.index_writer_mutex.lock().expect("lock is poisoned");
+        let mut plugin_data = self.plugin_data.lock().expect("lock is poisoned");
+
+        let mut index_writer = self.index.writer::<TantivyDocument>(15_000_000)?;
+
+        let changed_entrypoint_ids = search_items.iter()
+            .map(|item| &item.entrypoint_id)
+            .chain(removed_entrypoint_ids.iter());
+
+        for entrypoint_id in changed_entrypoint_ids {
+            index_writer.delete_query(Box::new(BooleanQuery::intersection(vec![
+                Box::new(TermQuery::new(Term::from_field_text(self.plugin_id, &plugin_id.to_string()), IndexRecordOption::Basic)),
+                Box::new(TermQuery::new(Term::from_field_text(self.entrypoint_id, &entrypoint_id.to_string()), IndexRecordOption::Basic)),
+            ])))?;
+        }
+
+        let entrypoints = self.add_documents(&mut index_writer, &plugin_id, &plugin_name, &code_version, search_items)?;
+
         index_writer.commit()?;
         self.index_reader.reload()?;
 
-        let data = search_items.into_iter()
+        let data = plugin_data.entry(plugin_id.clone())
+            .or_insert_with(|| PluginData { code_version: code_version.clone(), entrypoints: HashMap::new() });
+
+        data.code_version = code_version;
+
+        for entrypoint_id in &removed_entrypoint_ids {
+            data.entrypoints.remove(entrypoint_id);
+        }
+
+        data.entrypoints.extend(entrypoints);
+
+        if refresh_search_list {
+            self.request_search_results_update(plugin_id);
+        }
+
+        Ok(())
+    }
+
+    pub fn update_frecency(&self, plugin_id: &PluginId, frecency: HashMap<String, f64>) {
+        let mut plugin_data = self.plugin_data.lock().expect("lock is poisoned");
+
+        if let Some(data) = plugin_data.get_mut(plugin_id) {
+            for (entrypoint_id, entrypoint_data) in data.entrypoints.iter_mut() {
+                entrypoint_data.frecency = frecency.get(&entrypoint_id.to_string()).cloned().unwrap_or(0.0);
+            }
+        }
+    }
+
+    /// Frecency of entrypoints per query prefix they were selected with, see `query_prefixes`
+    pub fn set_query_frecency(&self, query_frecency: HashMap<String, HashMap<(PluginId, EntrypointId), f64>>) {
+        let mut current = self.query_frecency.lock().expect("lock is poisoned");
+
+        *current = query_frecency;
+    }
+
+    /// Replaces frecency of specified query prefixes, prefixes not present in `query_frecency` are removed
+    pub fn update_query_frecency(&self, query_prefixes: &[String], mut query_frecency: HashMap<String, HashMap<(PluginId, EntrypointId), f64>>) {
+        let mut current = self.query_frecency.lock().expect("lock is poisoned");
+
+        for query_prefix in query_prefixes {
+            match query_frecency.remove(query_prefix) {
+                Some(frecency) => {
+                    current.insert(query_prefix.clone(), frecency);
+                }
+                None => {
+                    current.remove(query_prefix);
+                }
+            }
+        }
+    }
+
+    fn add_documents(
+        &self,
+        index_writer: &mut IndexWriter,
+        plugin_id: &PluginId,
+        plugin_name: &str,
+        code_version: &str,
+        search_items: Vec<SearchIndexItem>
+    ) -> tantivy::Result<HashMap<EntrypointId, EntrypointData>> {
+        search_items.into_iter()
             .map(|item| {
                 let actions = item.entrypoint_actions.into_iter()
                     .map(|action| EntrypointActionData {
@@ -160,10 +406,21 @@ impl SearchIndex {
                             SearchIndexItemActionActionType::Command => EntrypointActionType::Command,
                             SearchIndexItemActionActionType::View => EntrypointActionType::View,
                         },
-                        shortcut: action.shortcut,
+                        shortcut: action.shortcut.map(|shortcut| EntrypointActionShortcutData {
+                            physical_key: shortcut.physical_key.to_value(),
+                            modifier_shift: shortcut.modifier_shift,
+                            modifier_control: shortcut.modifier_control,
+                            modifier_alt: shortcut.modifier_alt,
+                            modifier_meta: shortcut.modifier_meta,
+                        }),
                     })
                     .collect();
 
+                let aliases = item.entrypoint_aliases.iter()
+                    .map(|alias| normalize_alias(self.index.tokenizers(), alias))
+                    .filter(|alias| !alias.is_empty())
+                    .collect::<Vec<_>>();
+
                 let data = EntrypointData {
                     entrypoint_generator_name: item.entrypoint_generator_name,
                     entrypoint_type: item.entrypoint_type,
@@ -171,48 +428,89 @@ impl SearchIndex {
                     frecency: item.entrypoint_frecency,
                     actions,
                     accessories: item.entrypoint_accessories,
+                    keywords: item.entrypoint_keywords,
+                    description: item.entrypoint_description,
+                    aliases,
                 };
 
-                (item.entrypoint_id.clone(), data)
-            })
-            .collect();
+                let data_json = serde_json::to_string(&data)
+                    .expect("entrypoint data should always be serializable");
+
+                let mut document = doc!(
+                    self.entrypoint_name => item.entrypoint_name.clone(),
+                    self.entrypoint_name_initials => name_initials(&item.entrypoint_name),
+                    self.entrypoint_id => item.entrypoint_id.to_string(),
+                    self.entrypoint_data => data_json,
+                    self.plugin_name => plugin_name.to_string(),
+                    self.plugin_id => plugin_id.to_string(),
+                    self.plugin_code_version => code_version.to_string(),
+                );
+
+                for keyword in &data.keywords {
+                    document.add_text(self.entrypoint_keywords, keyword);
+                }
 
-        entrypoint_data.insert(plugin_id.clone(), data);
+                if let Some(description) = &data.description {
+                    document.add_text(self.entrypoint_description, description);
+                }
 
-        if refresh_search_list {
-            let mut frontend_api = self.frontend_api.clone();
-            tokio::spawn(async move {
-                tracing::info!("requesting search results update because search index update for plugin: {:?}", plugin_id);
+                for alias in &data.aliases {
+                    document.add_text(self.entrypoint_aliases, alias);
+                }
 
-                let result = frontend_api.request_search_results_update()
-                    .await;
+                index_writer.add_document(document)?;
 
-                if let Err(err) = &result {
-                    tracing::warn!("error occurred when requesting search results update {:?}", err)
-                }
-            });
-        }
+                Ok((item.entrypoint_id, data))
+            })
+            .collect()
+    }
 
-        Ok(())
+    fn request_search_results_update(&self, plugin_id: PluginId) {
+        let mut frontend_api = self.frontend_api.clone();
+        tokio::spawn(async move {
+            tracing::info!("requesting search results update because search index update for plugin: {:?}", plugin_id);
+
+            let result = frontend_api.request_search_results_update()
+                .await;
+
+            if let Err(err) = &result {
+                tracing::warn!("error occurred when requesting search results update {:?}", err)
+            }
+        });
     }
 
     pub fn search(&self, query: &str) -> anyhow::Result<Vec<SearchResult>> {
-        let entrypoint_data = self.entrypoint_data.lock().expect("lock is poisoned");
+        let plugin_data = self.plugin_data.lock().expect("lock is poisoned");
 
         let searcher = self.index_reader.searcher();
 
         let query_parser = QueryParser::new(
             self.index.tokenizers().clone(),
             self.entrypoint_name,
+            self.entrypoint_name_initials,
+            self.entrypoint_keywords,
+            self.entrypoint_description,
+            self.entrypoint_aliases,
             self.plugin_name,
         );
 
-        let query = query_parser.create_query(query);
+        let query_frecency = query_prefixes(query)
+            .pop()
+            .and_then(|query_prefix| {
+                let query_frecency = self.query_frecency.lock().expect("lock is poisoned");
+
+                query_frecency.get(&query_prefix).cloned()
+            })
+            .unwrap_or_default();
+
+        let query_terms = query_parser.tokenize(query);
+
+        let query = query_parser.create_query(&query_terms);
 
         let mut index = 0;
 
         let fetch = std::iter::from_fn(|| -> Option<anyhow::Result<Vec<(SearchResult, f64)>>> {
-            let result = self.fetch(&entrypoint_data, &query, TopDocs::with_limit(20).and_offset(index * 20), &searcher);
+            let result = self.fetch(&plugin_data, &query_frecency, &query, &query_terms, TopDocs::with_limit(20).and_offset(index * 20), &searcher);
 
             index += 1;
 
@@ -242,12 +540,20 @@ impl SearchIndex {
             .map(|(item, _)| item)
             .collect::<Vec<_>>();
 
-        drop(entrypoint_data);
+        drop(plugin_data);
 
         Ok(result)
     }
 
-    fn fetch(&self, entrypoint_data: &HashMap<PluginId, HashMap<EntrypointId, EntrypointData>>, query: &dyn Query, collector: TopDocs, searcher: &Searcher) -> anyhow::Result<Vec<(SearchResult, f64)>> {
+    fn fetch(
+        &self,
+        plugin_data: &HashMap<PluginId, PluginData>,
+        query_frecency: &HashMap<(PluginId, EntrypointId), f64>,
+        query: &dyn Query,
+        query_terms: &[String],
+        collector: TopDocs,
+        searcher: &Searcher
+    ) -> anyhow::Result<Vec<(SearchResult, f64)>> {
         let get_str_field = |retrieved_doc: &TantivyDocument, field: Field| -> String {
             retrieved_doc.get_first(field)
                 .unwrap_or_else(|| panic!("there should be a field with name {:?}", searcher.schema().get_field_name(field)))
@@ -258,7 +564,7 @@ impl SearchIndex {
 
         let result = searcher.search(query, &collector)?
             .into_iter()
-            .map(|(_score, doc_address)| {
+            .filter_map(|(_score, doc_address)| {
                 let retrieved_doc = searcher.doc::<TantivyDocument>(doc_address)
                     .expect("index should contain just searched results");
 
@@ -267,11 +573,14 @@ impl SearchIndex {
                 let entrypoint_name = get_str_field(&retrieved_doc, self.entrypoint_name);
                 let plugin_name = get_str_field(&retrieved_doc, self.plugin_name);
 
-                let entrypoint_data = entrypoint_data
+                // index on disk can contain documents of plugin or entrypoint which is already gone,
+                // e.g. if server was stopped between the db update and the index commit
+                let Some(entrypoint_data) = plugin_data
                     .get(&plugin_id)
-                    .expect("Plugin should always exist in entrypoint data")
-                    .get(&entrypoint_id)
-                    .expect("Entrypoint should always exist in plugin in entrypoint data");
+                    .and_then(|plugin_data| plugin_data.entrypoints.get(&entrypoint_id)) else {
+                    tracing::debug!("skipping stale search index item, plugin {:?}, entrypoint {:?}", plugin_id, entrypoint_id);
+                    return None
+                };
 
                 let entrypoint_actions = entrypoint_data.actions.iter()
                     .map(|data| SearchResultEntrypointAction {
@@ -280,7 +589,13 @@ impl SearchIndex {
                             EntrypointActionType::View => SearchResultEntrypointActionType::View
                         },
                         label: data.label.clone(),
-                        shortcut: data.shortcut.clone(),
+                        shortcut: data.shortcut.as_ref().map(|shortcut| PhysicalShortcut {
+                            physical_key: PhysicalKey::from_value(shortcut.physical_key.clone()),
+                            modifier_shift: shortcut.modifier_shift,
+                            modifier_control: shortcut.modifier_control,
+                            modifier_alt: shortcut.modifier_alt,
+                            modifier_meta: shortcut.modifier_meta,
+                        }),
                     })
                     .collect();
 
@@ -288,9 +603,21 @@ impl SearchIndex {
                     .cloned()
                     .collect();
 
+                let (match_score, entrypoint_name_match_indices) = match_score(query_terms, &entrypoint_name, &plugin_name, entrypoint_data);
+
+                let entrypoint_query_frecency = query_frecency
+                    .get(&(plugin_id.clone(), entrypoint_id.clone()))
+                    .cloned()
+                    .unwrap_or(0.0);
+
+                let score = match_score
+                    + FRECENCY_WEIGHT * entrypoint_data.frecency.ln_1p()
+                    + QUERY_FRECENCY_WEIGHT * entrypoint_query_frecency.ln_1p();
+
                 let result_item = SearchResult {
                     entrypoint_type: entrypoint_data.entrypoint_type.clone(),
                     entrypoint_name,
+                    entrypoint_name_match_indices,
                     entrypoint_generator_name: entrypoint_data.entrypoint_generator_name.clone(),
                     entrypoint_id,
                     entrypoint_icon: entrypoint_data.icon_path.clone(),
@@ -300,7 +627,7 @@ impl SearchIndex {
                     entrypoint_accessories,
                 };
 
-                (result_item, entrypoint_data.frecency)
+                Some((result_item, score))
             })
             .collect::<Vec<_>>();
 
@@ -308,70 +635,723 @@ impl SearchIndex {
     }
 }
 
+fn open_index_on_disk(index_dir: &Path, schema: Schema) -> anyhow::Result<Index> {
+    std::fs::create_dir_all(index_dir)?;
+
+    let directory = MmapDirectory::open(index_dir)?;
+
+    match Index::open_or_create(directory, schema.clone()) {
+        Ok(index) => Ok(index),
+        Err(err) => {
+            // index is only a cache, so if schema has changed or files are corrupted, start from scratch
+            tracing::warn!("Unable to open search index at {:?}, recreating it: {:?}", index_dir, err);
+
+            std::fs::remove_dir_all(index_dir)?;
+            std::fs::create_dir_all(index_dir)?;
+
+            Ok(Index::create_in_dir(index_dir, schema)?)
+        }
+    }
+}
+
 struct QueryParser {
     tokenizer_manager: TokenizerManager,
     entrypoint_name: Field,
+    entrypoint_name_initials: Field,
+    entrypoint_keywords: Field,
+    entrypoint_description: Field,
+    entrypoint_aliases: Field,
     plugin_name: Field,
 }
 
 impl QueryParser {
-    fn new(tokenizer_manager: TokenizerManager, entrypoint_name: Field, plugin_name: Field) -> Self {
+    fn new(
+        tokenizer_manager: TokenizerManager,
+        entrypoint_name: Field,
+        entrypoint_name_initials: Field,
+        entrypoint_keywords: Field,
+        entrypoint_description: Field,
+        entrypoint_aliases: Field,
+        plugin_name: Field
+    ) -> Self {
         Self {
             tokenizer_manager,
             entrypoint_name,
+            entrypoint_name_initials,
+            entrypoint_keywords,
+            entrypoint_description,
+            entrypoint_aliases,
             plugin_name,
         }
     }
 
-    fn create_query(&self, query: &str) -> Box<dyn Query> {
-        if query.is_empty() {
+    fn create_query(&self, terms: &[String]) -> Box<dyn Query> {
+        if terms.is_empty() {
             return Box::new(AllQuery);
         }
 
-        let contains_terms_fn = |field: Field| -> Box<dyn Query> {
-            let res = self.tokenize(query)
-                .into_iter()
-                .map(|term| -> Box<dyn Query> {
-                    Box::new(
-                        // basically a "contains" query
-                        RegexQuery::from_pattern(&format!(".*{}.*", regex::escape(&term)), field)
-                            .expect("there should not exist a situation where that regex is invalid")
-                    )
-                })
-                .collect::<Vec<_>>();
+        let term_fn = |field: Field, term: &str| -> Box<dyn Query> {
+            let mut queries: Vec<Box<dyn Query>> = vec![
+                Box::new(
+                    // basically a "contains" query
+                    RegexQuery::from_pattern(&format!(".*{}.*", regex::escape(term)), field)
+                        .expect("there should not exist a situation where that regex is invalid")
+                ),
+                Box::new(
+                    // all characters of the term in the same order, e.g. "frfx" for "firefox"
+                    RegexQuery::from_pattern(&subsequence_pattern(term), field)
+                        .expect("there should not exist a situation where that regex is invalid")
+                ),
+            ];
+
+            let distance = typo_distance(term);
+            if distance > 0 {
+                queries.push(Box::new(
+                    FuzzyTermQuery::new(Term::from_field_text(field, term), distance, true)
+                ));
+            }
 
-            Box::new(BooleanQuery::intersection(res))
+            Box::new(BooleanQuery::union(queries))
         };
 
         let terms_fn = |field: Field| -> Box<dyn Query> {
-            Box::new(
-                contains_terms_fn(field)
-            )
+            let res = terms.iter()
+                .map(|term| term_fn(field, term))
+                .collect::<Vec<_>>();
+
+            Box::new(BooleanQuery::intersection(res))
         };
 
         let entrypoint_name_terms = terms_fn(self.entrypoint_name);
+        let entrypoint_keywords_terms = terms_fn(self.entrypoint_keywords);
         let plugin_name_terms = terms_fn(self.plugin_name);
 
+        // descriptions are long, typo-tolerant matching there brings mostly noise
+        let entrypoint_description_terms = Box::new(BooleanQuery::intersection(
+            terms.iter()
+                .map(|term| -> Box<dyn Query> {
+                    Box::new(
+                        RegexQuery::from_pattern(&format!(".*{}.*", regex::escape(term)), self.entrypoint_description)
+                            .expect("there should not exist a situation where that regex is invalid")
+                    )
+                })
+                .collect()
+        ));
+
+        let entrypoint_name_initials = Box::new(
+            RegexQuery::from_pattern(&subsequence_pattern(&terms.concat()), self.entrypoint_name_initials)
+                .expect("there should not exist a situation where that regex is invalid")
+        );
+
+        let entrypoint_aliases = Box::new(
+            TermQuery::new(Term::from_field_text(self.entrypoint_aliases, &terms.join(" ")), IndexRecordOption::Basic)
+        );
+
         Box::new(
             BooleanQuery::union(vec![
-                Box::new(entrypoint_name_terms),
-                Box::new(plugin_name_terms),
+                entrypoint_name_terms,
+                entrypoint_keywords_terms,
+                entrypoint_description_terms,
+                plugin_name_terms,
+                entrypoint_name_initials,
+                entrypoint_aliases,
             ]),
         )
     }
 
     fn tokenize(&self, query: &str) -> Vec<String> {
-        let mut text_analyzer = self
-            .tokenizer_manager
-            .get("default")
-            .expect("default tokenizer should exist");
-
-        let mut terms: Vec<String> = Vec::new();
-        let mut token_stream = text_analyzer.token_stream(query);
-        token_stream.process(&mut |token| {
-            terms.push(token.text.to_string());
+        tokenize(&self.tokenizer_manager, query)
+    }
+}
+
+fn tokenize(tokenizer_manager: &TokenizerManager, text: &str) -> Vec<String> {
+    let mut text_analyzer = tokenizer_manager
+        .get("default")
+        .expect("default tokenizer should exist");
+
+    let mut terms: Vec<String> = Vec::new();
+    let mut token_stream = text_analyzer.token_stream(text);
+    token_stream.process(&mut |token| {
+        terms.push(token.text.to_string());
+    });
+
+    terms
+}
+
+/// Aliases are matched against the whole query, so they are tokenized the same way the query is
+fn normalize_alias(tokenizer_manager: &TokenizerManager, alias: &str) -> String {
+    tokenize(tokenizer_manager, alias).join(" ")
+}
+
+// how much frecency affects the final score compared to the match quality,
+// frecency of ~20 (used multiple times a day) is worth about half of the difference between prefix and fuzzy match
+const FRECENCY_WEIGHT: f64 = 0.1;
+
+// entrypoint picked for exactly this query prefix is very likely to be picked again,
+// single recent pick is enough to overcome the difference between prefix and word start match
+const QUERY_FRECENCY_WEIGHT: f64 = 0.5;
+
+// longer prefixes are rarely typed the same way twice, no need to store them
+const MAX_QUERY_PREFIX_LEN: usize = 16;
+
+/// Normalized prefixes of the query, from the shortest to the longest,
+/// used to remember which entrypoint was picked when specific query was typed
+pub fn query_prefixes(query: &str) -> Vec<String> {
+    let query = query.split_whitespace()
+        .join(" ")
+        .to_lowercase();
+
+    let chars = query.chars()
+        .take(MAX_QUERY_PREFIX_LEN)
+        .collect::<Vec<_>>();
+
+    (1..=chars.len())
+        .filter(|&len| !chars[len - 1].is_whitespace())
+        .map(|len| chars[..len].iter().collect())
+        .collect()
+}
+
+// user-defined alias is an explicit request to see that entrypoint first, so it is above any name match with any frecency
+const ALIAS_SCORE: f64 = 10.0;
+
+// keywords and description are less precise than the name, so matches there are scaled down
+const KEYWORD_WEIGHT: f64 = 0.6;
+const PLUGIN_NAME_WEIGHT: f64 = 0.5;
+const DESCRIPTION_WEIGHT: f64 = 0.4;
+
+// ordered from worst to best
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum MatchKind {
+    Fuzzy,
+    Substring,
+    Acronym,
+    WordStart,
+    Prefix,
+}
+
+impl MatchKind {
+    fn score(&self) -> f64 {
+        match self {
+            MatchKind::Prefix => 1.0,
+            MatchKind::WordStart => 0.8,
+            MatchKind::Acronym => 0.7,
+            MatchKind::Substring => 0.5,
+            MatchKind::Fuzzy => 0.3,
+        }
+    }
+}
+
+struct NameMatch {
+    kind: MatchKind,
+    indices: Vec<usize>,
+}
+
+/// Returns score of the match quality and indices of chars in entrypoint name that matched the query
+fn match_score(terms: &[String], entrypoint_name: &str, plugin_name: &str, entrypoint_data: &EntrypointData) -> (f64, Vec<usize>) {
+    if terms.is_empty() {
+        return (0.0, vec![]);
+    }
+
+    let query = terms.join(" ");
+    if entrypoint_data.aliases.contains(&query) {
+        return (ALIAS_SCORE, vec![]);
+    }
+
+    let name_len = entrypoint_name.chars().count();
+
+    if let Some(name_match) = match_name(terms, entrypoint_name) {
+        let coverage = name_match.indices.len() as f64 / name_len.max(1) as f64;
+
+        // prefer "Code" over "Codec Settings" when searching for "code"
+        let score = name_match.kind.score() + 0.1 * coverage;
+
+        return (score, name_match.indices);
+    }
+
+    let keyword_match = entrypoint_data.keywords.iter()
+        .filter_map(|keyword| match_name(terms, keyword))
+        .map(|keyword_match| keyword_match.kind)
+        .max();
+
+    if let Some(kind) = keyword_match {
+        return (kind.score() * KEYWORD_WEIGHT, vec![])
+    }
+
+    if let Some(plugin_match) = match_name(terms, plugin_name) {
+        return (plugin_match.kind.score() * PLUGIN_NAME_WEIGHT, vec![])
+    }
+
+    let description_match = entrypoint_data.description.as_ref()
+        .and_then(|description| match_name(terms, description))
+        .filter(|description_match| description_match.kind > MatchKind::Fuzzy);
+
+    if let Some(description_match) = description_match {
+        return (description_match.kind.score() * DESCRIPTION_WEIGHT, vec![])
+    }
+
+    // tantivy matched something that matcher here doesn't recognize, keep it but at the very bottom
+    (0.0, vec![])
+}
+
+fn match_name(terms: &[String], name: &str) -> Option<NameMatch> {
+    // lowercase char by char to keep indices the same as in original string
+    let chars = name.chars()
+        .map(|char| char.to_lowercase().next().unwrap_or(char))
+        .collect::<Vec<_>>();
+
+    let word_starts = word_starts(name);
+
+    let mut kind = MatchKind::Prefix;
+    let mut indices = vec![];
+
+    let mut terms_match = true;
+    for term in terms {
+        match match_term(term, &chars, &word_starts) {
+            Some(term_match) => {
+                kind = kind.min(term_match.kind);
+                indices.extend(term_match.indices);
+            }
+            None => {
+                terms_match = false;
+                break;
+            }
+        }
+    }
+
+    let terms_match = terms_match.then(|| {
+        indices.sort();
+        indices.dedup();
+
+        NameMatch {
+            kind,
+            indices,
+        }
+    });
+
+    let acronym_match = match_acronym(&terms.concat(), &chars, &word_starts);
+
+    match (terms_match, acronym_match) {
+        (Some(terms_match), Some(acronym_match)) => {
+            if acronym_match.kind > terms_match.kind {
+                Some(acronym_match)
+            } else {
+                Some(terms_match)
+            }
+        }
+        (terms_match, acronym_match) => terms_match.or(acronym_match)
+    }
+}
+
+fn match_term(term: &str, chars: &[char], word_starts: &[usize]) -> Option<NameMatch> {
+    let term = term.chars().collect::<Vec<_>>();
+
+    if term.is_empty() {
+        return None;
+    }
+
+    let occurrences = chars.windows(term.len())
+        .enumerate()
+        .filter(|(_, window)| *window == term.as_slice())
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+
+    let best_occurrence = occurrences.iter()
+        .map(|&start| {
+            let kind = if start == 0 {
+                MatchKind::Prefix
+            } else if word_starts.contains(&start) {
+                MatchKind::WordStart
+            } else {
+                MatchKind::Substring
+            };
+
+            (kind, start)
+        })
+        .max_by_key(|(kind, _)| *kind);
+
+    if let Some((kind, start)) = best_occurrence {
+        return Some(NameMatch {
+            kind,
+            indices: (start..start + term.len()).collect(),
         });
+    }
+
+    let words = words(chars, word_starts);
+
+    // same as subsequence regex in tantivy query, works only inside single word
+    for (word_start, word) in &words {
+        if let Some(indices) = match_subsequence(&term, word) {
+            return Some(NameMatch {
+                kind: MatchKind::Fuzzy,
+                indices: indices.into_iter().map(|index| word_start + index).collect(),
+            });
+        }
+    }
+
+    // same as fuzzy term query in tantivy query
+    let distance = typo_distance(&term.iter().collect::<String>()) as usize;
+    if distance > 0 {
+        for (word_start, word) in &words {
+            if edit_distance(&term, word) <= distance {
+                return Some(NameMatch {
+                    kind: MatchKind::Fuzzy,
+                    indices: (*word_start..word_start + word.len()).collect(),
+                });
+            }
+        }
+    }
+
+    None
+}
+
+fn match_acronym(query: &str, chars: &[char], word_starts: &[usize]) -> Option<NameMatch> {
+    let query = query.chars().collect::<Vec<_>>();
+
+    if query.len() < 2 {
+        return None;
+    }
+
+    let mut query_iter = query.iter().peekable();
+    let mut indices = vec![];
+
+    for &word_start in word_starts {
+        match query_iter.peek() {
+            Some(&&query_char) if chars[word_start] == query_char => {
+                indices.push(word_start);
+                query_iter.next();
+            }
+            Some(_) => {}
+            None => break,
+        }
+    }
+
+    if query_iter.peek().is_some() {
+        return None;
+    }
+
+    Some(NameMatch {
+        kind: MatchKind::Acronym,
+        indices,
+    })
+}
+
+fn match_subsequence(term: &[char], word: &[char]) -> Option<Vec<usize>> {
+    let mut indices = vec![];
+    let mut term_iter = term.iter().peekable();
+
+    for (index, char) in word.iter().enumerate() {
+        match term_iter.peek() {
+            Some(&term_char) if term_char == char => {
+                indices.push(index);
+                term_iter.next();
+            }
+            Some(_) => {}
+            None => break,
+        }
+    }
+
+    if term_iter.peek().is_some() {
+        None
+    } else {
+        Some(indices)
+    }
+}
+
+/// Optimal string alignment distance, same as levenshtein automaton used by tantivy with transposition cost of 1
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut matrix = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in matrix.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, value) in matrix[0].iter_mut().enumerate() {
+        *value = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+
+            let mut value = (matrix[i - 1][j] + 1)
+                .min(matrix[i][j - 1] + 1)
+                .min(matrix[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                value = value.min(matrix[i - 2][j - 2] + 1);
+            }
+
+            matrix[i][j] = value;
+        }
+    }
+
+    matrix[a.len()][b.len()]
+}
+
+fn typo_distance(term: &str) -> u8 {
+    match term.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+fn subsequence_pattern(term: &str) -> String {
+    let chars = term.chars()
+        .map(|char| regex::escape(&char.to_string()))
+        .collect::<Vec<_>>()
+        .join(".*");
+
+    format!(".*{}.*", chars)
+}
+
+/// Char indices where words start, word boundaries are non-alphanumeric chars and camelCase humps
+fn word_starts(name: &str) -> Vec<usize> {
+    let mut result = vec![];
+    let mut prev: Option<char> = None;
+
+    for (index, char) in name.chars().enumerate() {
+        if char.is_alphanumeric() {
+            let is_start = match prev {
+                None => true,
+                Some(prev) => !prev.is_alphanumeric() || (prev.is_lowercase() && char.is_uppercase())
+            };
+
+            if is_start {
+                result.push(index);
+            }
+        }
+
+        prev = Some(char);
+    }
+
+    result
+}
+
+fn words<'a>(chars: &'a [char], word_starts: &[usize]) -> Vec<(usize, &'a [char])> {
+    word_starts.iter()
+        .map(|&start| {
+            let len = chars[start..]
+                .iter()
+                .take_while(|char| char.is_alphanumeric())
+                .count();
+
+            (start, &chars[start..start + len])
+        })
+        .collect()
+}
+
+fn name_initials(name: &str) -> String {
+    let word_starts = word_starts(name);
+
+    name.chars()
+        .enumerate()
+        .filter(|(index, _)| word_starts.contains(index))
+        .flat_map(|(_, char)| char.to_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entrypoint_data(keywords: &[&str], description: Option<&str>, aliases: &[&str]) -> EntrypointData {
+        EntrypointData {
+            entrypoint_generator_name: None,
+            entrypoint_type: SearchResultEntrypointType::Command,
+            icon_path: None,
+            frecency: 0.0,
+            actions: vec![],
+            accessories: vec![],
+            keywords: keywords.iter().map(|keyword| keyword.to_string()).collect(),
+            description: description.map(|description| description.to_string()),
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+        }
+    }
+
+    fn terms(query: &str) -> Vec<String> {
+        query.split_whitespace()
+            .map(|term| term.to_lowercase())
+            .collect()
+    }
+
+    fn score(query: &str, entrypoint_name: &str) -> f64 {
+        match_score(&terms(query), entrypoint_name, "Plugin", &entrypoint_data(&[], None, &[])).0
+    }
+
+    fn kind(query: &str, name: &str) -> Option<MatchKind> {
+        match_name(&terms(query), name).map(|name_match| name_match.kind)
+    }
+
+    fn chars(text: &str) -> Vec<char> {
+        text.chars().collect()
+    }
+
+    #[test]
+    fn word_starts_split_on_separators_and_camel_case() {
+        assert_eq!(word_starts("Visual Studio Code"), vec![0, 7, 14]);
+        assert_eq!(word_starts("gnome-system-monitor"), vec![0, 6, 13]);
+        assert_eq!(word_starts("IntelliJ IDEA"), vec![0, 7, 9]);
+        assert_eq!(word_starts("  leading space"), vec![2, 10]);
+        assert_eq!(word_starts(""), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn prefix_match() {
+        let name_match = match_name(&terms("fire"), "Firefox").unwrap();
+
+        assert_eq!(name_match.kind, MatchKind::Prefix);
+        assert_eq!(name_match.indices, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn word_start_match() {
+        let name_match = match_name(&terms("code"), "Visual Studio Code").unwrap();
+
+        assert_eq!(name_match.kind, MatchKind::WordStart);
+        assert_eq!(name_match.indices, vec![14, 15, 16, 17]);
+
+        assert_eq!(kind("monitor", "gnome-system-monitor"), Some(MatchKind::WordStart));
+        assert_eq!(kind("studio", "VisualStudio"), Some(MatchKind::WordStart));
+    }
+
+    #[test]
+    fn substring_match() {
+        assert_eq!(kind("fox", "Firefox"), Some(MatchKind::Substring));
+    }
+
+    #[test]
+    fn best_occurrence_is_used() {
+        // first occurrence is in the middle of the word, second is at the word start
+        let name_match = match_name(&terms("set"), "Reset Settings").unwrap();
+
+        assert_eq!(name_match.kind, MatchKind::WordStart);
+        assert_eq!(name_match.indices, vec![6, 7, 8]);
+    }
+
+    #[test]
+    fn every_term_has_to_match() {
+        assert_eq!(kind("studio code", "Visual Studio Code"), Some(MatchKind::WordStart));
+        assert_eq!(kind("visual code", "Visual Studio Code"), Some(MatchKind::WordStart));
+        assert_eq!(kind("visual zzz", "Visual Studio Code"), None);
+    }
+
+    #[test]
+    fn acronym_match() {
+        let name_match = match_name(&terms("vsc"), "Visual Studio Code").unwrap();
+
+        assert_eq!(name_match.kind, MatchKind::Acronym);
+        assert_eq!(name_match.indices, vec![0, 7, 14]);
+
+        assert_eq!(kind("gsm", "gnome-system-monitor"), Some(MatchKind::Acronym));
+        assert_eq!(kind("ij", "IntelliJ IDEA"), Some(MatchKind::Acronym));
+    }
+
+    #[test]
+    fn acronym_requires_order_and_at_least_two_chars() {
+        let chars = chars("visual studio code");
+        let word_starts = word_starts("Visual Studio Code");
+
+        assert!(match_acronym("csv", &chars, &word_starts).is_none());
+        assert!(match_acronym("v", &chars, &word_starts).is_none());
+        assert!(match_acronym("vc", &chars, &word_starts).is_some());
+    }
+
+    #[test]
+    fn prefix_is_preferred_over_acronym() {
+        // "vi" is both prefix of "Visual" and acronym of "Visual Insiders"
+        assert_eq!(kind("vi", "Visual Insiders"), Some(MatchKind::Prefix));
+    }
+
+    #[test]
+    fn subsequence_match_inside_word() {
+        let name_match = match_name(&terms("frfx"), "Firefox").unwrap();
+
+        assert_eq!(name_match.kind, MatchKind::Fuzzy);
+        assert_eq!(name_match.indices, vec![0, 2, 4, 6]);
+    }
+
+    #[test]
+    fn typo_match() {
+        // transposition
+        assert_eq!(kind("fierfox", "Firefox"), Some(MatchKind::Fuzzy));
+        // substitution
+        assert_eq!(kind("tarminal", "Terminal"), Some(MatchKind::Fuzzy));
+        // too many typos for the term length
+        assert_eq!(kind("tarmanil", "Terminal"), None);
+        // short terms don't tolerate typos
+        assert_eq!(kind("cdo", "Code"), None);
+    }
+
+    #[test]
+    fn edit_distance_counts_transposition_as_one() {
+        assert_eq!(edit_distance(&chars("firefox"), &chars("firefox")), 0);
+        assert_eq!(edit_distance(&chars("fierfox"), &chars("firefox")), 1);
+        assert_eq!(edit_distance(&chars("firefx"), &chars("firefox")), 1);
+        assert_eq!(edit_distance(&chars("firefoxx"), &chars("firefox")), 1);
+        assert_eq!(edit_distance(&chars("tarmanil"), &chars("terminal")), 3);
+        assert_eq!(edit_distance(&chars(""), &chars("abc")), 3);
+    }
+
+    #[test]
+    fn better_match_kind_has_higher_score() {
+        let prefix = score("code", "Code");
+        let word_start = score("code", "Visual Studio Code");
+        let acronym = score("vsc", "Visual Studio Code");
+        let substring = score("ode", "Code");
+        let fuzzy = score("cdoe", "Code");
+
+        assert!(prefix > word_start);
+        assert!(word_start > acronym);
+        assert!(acronym > substring);
+        assert!(substring > fuzzy);
+        assert!(fuzzy > 0.0);
+    }
+
+    #[test]
+    fn shorter_name_is_preferred_for_the_same_match() {
+        assert!(score("code", "Code") > score("code", "Codec Settings"));
+    }
+
+    #[test]
+    fn alias_is_above_any_name_match() {
+        let data = entrypoint_data(&[], None, &["ff"]);
+
+        let (alias_score, indices) = match_score(&terms("ff"), "Firefox", "Plugin", &data);
+
+        assert_eq!(alias_score, ALIAS_SCORE);
+        assert!(indices.is_empty());
+        assert!(alias_score > score("firefox", "Firefox"));
+    }
+
+    #[test]
+    fn keywords_plugin_name_and_description_are_weighted_down() {
+        let data = entrypoint_data(&["browser"], Some("Surf the internet"), &[]);
+
+        let (name_score, _) = match_score(&terms("fire"), "Firefox", "Applications", &data);
+        let (keyword_score, keyword_indices) = match_score(&terms("browser"), "Firefox", "Applications", &data);
+        let (plugin_score, _) = match_score(&terms("applications"), "Firefox", "Applications", &data);
+        let (description_score, _) = match_score(&terms("internet"), "Firefox", "Applications", &data);
+
+        assert!(name_score > keyword_score);
+        assert!(keyword_score > plugin_score);
+        assert!(plugin_score > description_score);
+        assert!(description_score > 0.0);
+
+        // only name matches are highlighted
+        assert!(keyword_indices.is_empty());
+    }
+
+    #[test]
+    fn fuzzy_description_match_is_ignored() {
+        let data = entrypoint_data(&[], Some("Surf the internet"), &[]);
+
+        assert_eq!(match_score(&terms("intrnt"), "Firefox", "Applications", &data).0, 0.0);
+    }
 
-        terms
+    #[test]
+    fn query_prefixes_are_normalized() {
+        assert_eq!(query_prefixes("  Fire   Fox "), vec!["f", "fi", "fir", "fire", "fire f", "fire fo", "fire fox"]);
+        assert_eq!(query_prefixes("").len(), 0);
+        assert_eq!(query_prefixes(&"a".repeat(100)).len(), MAX_QUERY_PREFIX_LEN);
     }
 }
@@ -3,6 +3,9 @@ use thiserror::Error;
 use tokio::sync::{mpsc, oneshot};
 use tokio::time::error::Elapsed;
 
+/// How long [`RequestSender::send_receive`] waits for response before giving up
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[derive(Error, Debug)]
 pub enum RequestError {
     #[error("request timeout")]
@@ -65,9 +68,7 @@ impl<Req: std::fmt::Debug, Res: std::fmt::Debug> RequestSender<Req, Res> {
     pub async fn send_receive(&self, request: Req) -> Result<Res, RequestError> {
         let mut receiver = self.send(request)?;
 
-        let duration = Duration::from_secs(30);
-
-        let result = tokio::time::timeout(duration, receiver.recv()).await?;
+        let result = tokio::time::timeout(REQUEST_TIMEOUT, receiver.recv()).await?;
 
         Ok(result)
     }
@@ -109,6 +110,16 @@ impl<Res: std::fmt::Debug> Responder<Res> {
     pub fn respond(self, response: Res) {
         self.response_sender.send(response).expect("the receiver was closed")
     }
+
+    /// Same as [`Responder::respond`] but returns the response back if the receiver is already closed
+    pub fn try_respond(self, response: Res) -> Result<(), Res> {
+        self.response_sender.send(response)
+    }
+
+    /// Receiver is closed when request timed out before response was sent
+    pub fn is_closed(&self) -> bool {
+        self.response_sender.is_closed()
+    }
 }
 
 #[derive(Debug)]